@@ -36,10 +36,11 @@ async fn main() -> Result<()> {
 fn run(mut terminal: DefaultTerminal, ansi_content: String) -> Result<()> {
     loop {
         terminal.draw(|frame| draw(frame, &ansi_content))?;
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
-                return Ok(());
-            }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && key.code == KeyCode::Char('q')
+        {
+            return Ok(());
         }
     }
 }
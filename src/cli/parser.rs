@@ -1,14 +1,31 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 pub struct Cli {
     #[clap(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// List tasks
     Tasks {},
+    /// Compact the database and report its size before and after
+    Vacuum {},
+    /// Export every task to a JSON backup file
+    Backup {
+        /// File to write the backup to
+        path: PathBuf,
+    },
+    /// Print the effective configuration with secrets masked
+    Config {},
+    /// Import tasks from the integrations listed in the config file
+    Sync {},
+    /// Merge tasks from a JSON backup file into the database
+    Import {
+        /// Backup file to read
+        path: PathBuf,
+    },
 }
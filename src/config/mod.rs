@@ -1 +1,3 @@
+pub mod secrets;
 pub mod settings;
+pub mod state;
@@ -0,0 +1,86 @@
+use config::ConfigError;
+use std::fmt;
+
+/// Where a secret such as an access token lives. Only this reference is kept in
+/// the config file, never the secret itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretRef {
+    /// An environment variable
+    Env(String),
+    /// A generic password in the OS keyring
+    Keyring { service: String, account: String },
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecretRef::Env(name) => write!(f, "environment variable {name}"),
+            SecretRef::Keyring { service, account } => {
+                write!(f, "keyring entry {service}/{account}")
+            }
+        }
+    }
+}
+
+/// Backend that secret references are looked up in
+pub trait SecretStore {
+    fn env(&self, name: &str) -> Option<String>;
+    /// `Ok(None)` when there is no such entry, `Err` when the keyring could not be
+    /// read at all
+    fn keyring(&self, service: &str, account: &str) -> Result<Option<String>, String>;
+}
+
+/// The process environment and the platform keyring
+pub struct SystemSecrets;
+
+impl SecretStore for SystemSecrets {
+    fn env(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+
+    fn keyring(&self, service: &str, account: &str) -> Result<Option<String>, String> {
+        let entry = keyring::Entry::new(service, account).map_err(|e| e.to_string())?;
+        match entry.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+impl SecretRef {
+    /// Parse a keyring reference written as `service/account`
+    pub fn keyring(reference: &str) -> Result<Self, ConfigError> {
+        match reference.split_once('/') {
+            Some((service, account)) if !service.is_empty() && !account.is_empty() => {
+                Ok(SecretRef::Keyring {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                })
+            }
+            _ => Err(ConfigError::Message(format!(
+                "Invalid keyring reference '{reference}': expected service/account"
+            ))),
+        }
+    }
+
+    /// Look the secret up in the environment or the OS keyring
+    pub fn resolve(&self) -> Result<String, ConfigError> {
+        self.resolve_with(&SystemSecrets)
+    }
+
+    /// Look the secret up in `store`. Missing and blank secrets are both errors.
+    pub fn resolve_with(&self, store: &impl SecretStore) -> Result<String, ConfigError> {
+        let value = match self {
+            SecretRef::Env(name) => store.env(name),
+            SecretRef::Keyring { service, account } => store
+                .keyring(service, account)
+                .map_err(|e| ConfigError::Message(format!("Could not read {self}: {e}")))?,
+        };
+
+        match value {
+            Some(secret) if !secret.trim().is_empty() => Ok(secret),
+            _ => Err(ConfigError::Message(format!("Secret not found in {self}"))),
+        }
+    }
+}
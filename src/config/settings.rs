@@ -1,17 +1,57 @@
-use config::{Config, ConfigError, File};
+use super::secrets::{SecretRef, SecretStore, SystemSecrets};
+use crate::db::models::Priority;
+use config::{Config, ConfigBuilder, ConfigError, File, FileFormat, builder::DefaultState};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Base name of the config file, resolved with any extension the config crate supports
+pub const CONFIG_FILE: &str = "config/Settings";
+
+/// Field name fragments that mark a value as secret when displaying settings
+const SECRET_FIELD_MARKERS: [&str; 3] = ["token", "secret", "password"];
+
+/// A setting whose value differs between two loaded configurations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettingChange {
+    /// Dotted config key, e.g. `ui.stderr`
+    pub key: &'static str,
+    /// Whether the running app can apply the new value without a restart
+    pub live: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Settings {
-    pub github_token: Option<String>,
     pub database_path: Option<String>,
     pub history: HistoryConfig,
+    pub ui: UiConfig,
+    pub tasks: TasksConfig,
+    /// Shell that commands run in, e.g. `zsh` or `/usr/bin/fish`; unset uses `$SHELL`
+    #[serde(default)]
+    pub shell: Option<String>,
+    /// Run commands without shell syntax directly instead of through the shell
+    #[serde(default)]
+    pub direct_exec: bool,
+    /// Seconds a command may run before it is killed; unset or 0 lets it run until it exits
+    #[serde(default)]
+    pub command_timeout_secs: Option<u64>,
+    /// Repositories that tasks are imported from
+    #[serde(default)]
+    pub integrations: Vec<IntegrationTarget>,
+    /// Custom `/name` commands and the scripts they run, e.g. `deploy = "~/bin/deploy.sh"`.
+    /// Built-in commands take precedence over custom ones of the same name.
+    #[serde(default)]
+    pub commands: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct HistoryConfig {
     pub max_entries: usize,
     pub persist: bool,
+    /// Start in the working directory the last session left off in
+    #[serde(default)]
+    pub restore_directory: bool,
 }
 
 impl Default for HistoryConfig {
@@ -19,20 +59,428 @@ impl Default for HistoryConfig {
         Self {
             max_entries: 1000,
             persist: true,
+            restore_directory: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TasksConfig {
+    /// Maximum number of tasks loaded into memory at once
+    pub page_size: usize,
+}
+
+impl Default for TasksConfig {
+    fn default() -> Self {
+        Self { page_size: 100 }
+    }
+}
+
+/// Service an integration target is hosted on
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationKind {
+    Github,
+    Gitlab,
+    Jira,
+}
+
+/// A repository to import tasks from. The access token is never stored in the
+/// config file; it is referenced by environment variable name (`token_env`) or
+/// by OS keyring entry (`token_keyring = "service/account"`).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct IntegrationTarget {
+    #[serde(rename = "type")]
+    pub kind: IntegrationKind,
+    pub owner: String,
+    pub repo: String,
+    #[serde(default)]
+    pub token_env: Option<String>,
+    #[serde(default)]
+    pub token_keyring: Option<String>,
+    /// Stop importing after this many pages of results; unset uses the client default
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+    /// Times to wait out the API rate limit and retry before failing
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Priority given to issues carrying a label, e.g. `P0 = "High"`; labels match
+    /// case-insensitively and issues without a mapped label are `Medium`
+    #[serde(default)]
+    pub label_priorities: HashMap<String, Priority>,
+}
+
+impl IntegrationTarget {
+    /// Where this target's token is stored; the environment variable wins if both are set
+    pub fn token_ref(&self) -> Result<SecretRef, ConfigError> {
+        match (&self.token_env, &self.token_keyring) {
+            (Some(name), _) => Ok(SecretRef::Env(name.clone())),
+            (None, Some(reference)) => SecretRef::keyring(reference),
+            (None, None) => Err(ConfigError::Message(format!(
+                "No token configured for {}/{}: set token_env or token_keyring",
+                self.owner, self.repo
+            ))),
+        }
+    }
+
+    /// Resolve the access token from the environment or the OS keyring
+    pub fn resolve_token(&self) -> Result<String, ConfigError> {
+        self.resolve_token_with(&SystemSecrets)
+    }
+
+    /// Resolve the access token through `store`
+    pub fn resolve_token_with(&self, store: &impl SecretStore) -> Result<String, ConfigError> {
+        self.token_ref()?.resolve_with(store).map_err(|e| {
+            ConfigError::Message(format!("Token for {}/{}: {e}", self.owner, self.repo))
+        })
+    }
+}
+
+/// What a right click in the terminal does
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RightClickAction {
+    /// Clear all selections
+    #[default]
+    Clear,
+    /// Open a menu with copy, paste and clear entries
+    ContextMenu,
+    /// Paste from the clipboard
+    Paste,
+}
+
+/// How stderr output is arranged relative to stdout
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StderrMode {
+    /// Group stderr into a block after stdout
+    #[default]
+    Split,
+    /// Interleave stderr with stdout in the order it was written
+    Merge,
+}
+
+/// Where output search scrolls the current match to
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchScrollMode {
+    /// The middle of the history area
+    #[default]
+    Center,
+    /// The top of the history area
+    Top,
+    /// Only scroll when the match is off-screen, as little as needed
+    Minimal,
+}
+
+/// View shown when TaskHub starts
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StartMode {
+    /// The terminal with its command input
+    #[default]
+    Terminal,
+    /// The task list
+    TaskList,
+}
+
+/// Default for `ui.search_regex_size_limit`: 1 MiB
+pub const DEFAULT_SEARCH_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Default for `ui.max_scrollback_lines`
+pub const DEFAULT_MAX_SCROLLBACK_LINES: usize = 100_000;
+
+fn default_max_scrollback_lines() -> usize {
+    DEFAULT_MAX_SCROLLBACK_LINES
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UiConfig {
+    /// Map the 16 base ANSI color indices to the theme palette
+    pub theme_palette: bool,
+    /// Height of the input box in rows, including its borders
+    pub input_height: u16,
+    /// Maximum number of commands shown in the command-list popup
+    pub command_list_max_items: u16,
+    /// Behavior of a right click
+    pub right_click: RightClickAction,
+    /// Arrangement of stderr output
+    pub stderr: StderrMode,
+    /// Maximum compiled size in bytes of a regex typed into output search; larger
+    /// patterns are rejected as too complex
+    pub search_regex_size_limit: usize,
+    /// Where output search scrolls the current match to
+    #[serde(default)]
+    pub search_scroll_mode: SearchScrollMode,
+    /// Widest the content is drawn on wide terminals, centered; unset or 0 uses the
+    /// full width
+    #[serde(default)]
+    pub max_content_width: Option<u16>,
+    /// Ask before running a command whose program is not a builtin or on `PATH`
+    #[serde(default)]
+    pub confirm_unknown_commands: bool,
+    /// Show what the globs of a destructive command such as `rm *.log` match and ask
+    /// before running it
+    #[serde(default)]
+    pub preview_globs: bool,
+    /// View shown at startup
+    #[serde(default)]
+    pub start_mode: StartMode,
+    /// Most lines the command history holds, counting each command and its output;
+    /// the oldest output is trimmed beyond it
+    #[serde(default = "default_max_scrollback_lines")]
+    pub max_scrollback_lines: usize,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            theme_palette: false,
+            input_height: 3,
+            command_list_max_items: 8,
+            right_click: RightClickAction::default(),
+            stderr: StderrMode::default(),
+            search_regex_size_limit: DEFAULT_SEARCH_REGEX_SIZE_LIMIT,
+            search_scroll_mode: SearchScrollMode::default(),
+            max_content_width: None,
+            confirm_unknown_commands: false,
+            preview_globs: false,
+            start_mode: StartMode::default(),
+            max_scrollback_lines: DEFAULT_MAX_SCROLLBACK_LINES,
         }
     }
 }
 
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
-        let mut s =
-            Config::builder().add_source(File::with_name("config/Settings").required(false));
+        let s = Config::builder().add_source(File::with_name(CONFIG_FILE).required(false));
+        Self::with_defaults(s)?.build()?.try_deserialize()
+    }
 
-        // Set defaults
+    /// Build settings from TOML text instead of the config file
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        let s = Config::builder().add_source(File::from_str(contents, FileFormat::Toml));
+        Self::with_defaults(s)?.build()?.try_deserialize()
+    }
+
+    fn with_defaults(
+        mut s: ConfigBuilder<DefaultState>,
+    ) -> Result<ConfigBuilder<DefaultState>, ConfigError> {
         s = s.set_default("history.max_entries", 1000)?;
         s = s.set_default("history.persist", true)?;
+        s = s.set_default("history.restore_directory", false)?;
+        s = s.set_default("ui.theme_palette", false)?;
+        s = s.set_default("ui.input_height", 3)?;
+        s = s.set_default("ui.command_list_max_items", 8)?;
+        s = s.set_default("ui.right_click", "clear")?;
+        s = s.set_default("ui.stderr", "split")?;
+        s = s.set_default(
+            "ui.search_regex_size_limit",
+            DEFAULT_SEARCH_REGEX_SIZE_LIMIT as u64,
+        )?;
+        s = s.set_default("ui.search_scroll_mode", "center")?;
+        s = s.set_default("ui.confirm_unknown_commands", false)?;
+        s = s.set_default("ui.preview_globs", false)?;
+        s = s.set_default("ui.start_mode", "terminal")?;
+        s = s.set_default(
+            "ui.max_scrollback_lines",
+            DEFAULT_MAX_SCROLLBACK_LINES as u64,
+        )?;
+        s = s.set_default("tasks.page_size", 100)?;
+        Ok(s)
+    }
+
+    /// Settings that differ in `other`, in config file order
+    pub fn diff(&self, other: &Settings) -> Vec<SettingChange> {
+        let candidates = [
+            (
+                "database_path",
+                self.database_path != other.database_path,
+                false,
+            ),
+            (
+                "history.max_entries",
+                self.history.max_entries != other.history.max_entries,
+                false,
+            ),
+            (
+                "history.persist",
+                self.history.persist != other.history.persist,
+                false,
+            ),
+            (
+                "history.restore_directory",
+                self.history.restore_directory != other.history.restore_directory,
+                false,
+            ),
+            (
+                "ui.theme_palette",
+                self.ui.theme_palette != other.ui.theme_palette,
+                true,
+            ),
+            (
+                "ui.input_height",
+                self.ui.input_height != other.ui.input_height,
+                true,
+            ),
+            (
+                "ui.command_list_max_items",
+                self.ui.command_list_max_items != other.ui.command_list_max_items,
+                true,
+            ),
+            (
+                "ui.right_click",
+                self.ui.right_click != other.ui.right_click,
+                true,
+            ),
+            ("ui.stderr", self.ui.stderr != other.ui.stderr, true),
+            (
+                "ui.search_regex_size_limit",
+                self.ui.search_regex_size_limit != other.ui.search_regex_size_limit,
+                true,
+            ),
+            (
+                "ui.search_scroll_mode",
+                self.ui.search_scroll_mode != other.ui.search_scroll_mode,
+                true,
+            ),
+            (
+                "ui.max_content_width",
+                self.ui.max_content_width != other.ui.max_content_width,
+                true,
+            ),
+            (
+                "ui.confirm_unknown_commands",
+                self.ui.confirm_unknown_commands != other.ui.confirm_unknown_commands,
+                true,
+            ),
+            (
+                "ui.preview_globs",
+                self.ui.preview_globs != other.ui.preview_globs,
+                true,
+            ),
+            (
+                "ui.start_mode",
+                self.ui.start_mode != other.ui.start_mode,
+                false,
+            ),
+            (
+                "ui.max_scrollback_lines",
+                self.ui.max_scrollback_lines != other.ui.max_scrollback_lines,
+                true,
+            ),
+            (
+                "tasks.page_size",
+                self.tasks.page_size != other.tasks.page_size,
+                true,
+            ),
+            ("shell", self.shell != other.shell, true),
+            ("direct_exec", self.direct_exec != other.direct_exec, true),
+            (
+                "command_timeout_secs",
+                self.command_timeout_secs != other.command_timeout_secs,
+                true,
+            ),
+            (
+                "integrations",
+                self.integrations != other.integrations,
+                true,
+            ),
+            ("commands", self.commands != other.commands, true),
+        ];
+
+        candidates
+            .into_iter()
+            .filter(|(_, changed, _)| *changed)
+            .map(|(key, _, live)| SettingChange { key, live })
+            .collect()
+    }
+
+    /// How long a command may run before it is killed, if limited
+    pub fn command_timeout(&self) -> Option<Duration> {
+        self.command_timeout_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
 
-        let config = s.build()?;
-        config.try_deserialize()
+    /// Path of the config file that `new` reads, if one exists
+    pub fn config_file_path() -> Option<PathBuf> {
+        ["toml", "json", "yaml", "yml", "ini", "ron", "json5"]
+            .iter()
+            .map(|extension| PathBuf::from(format!("{CONFIG_FILE}.{extension}")))
+            .find(|path| path.exists())
+    }
+
+    /// Directory of the config file in use, or where one would be read from when
+    /// there is none
+    pub fn config_directory() -> PathBuf {
+        Self::config_file_path()
+            .unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// The effective settings as pretty-printed JSON with secret values masked,
+    /// preceded by the file they were loaded from
+    pub fn describe(&self) -> String {
+        let source = match Self::config_file_path() {
+            Some(path) => format!("Config file: {}", path.display()),
+            None => "Config file: none found, using defaults".to_string(),
+        };
+
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        redact_secrets(&mut value);
+        let body = serde_json::to_string_pretty(&value).unwrap_or_default();
+
+        format!("{source}\n{body}")
+    }
+}
+
+/// Describe reloaded settings, split into applied ones and ones that need a restart
+pub fn format_setting_changes(changes: &[SettingChange]) -> String {
+    if changes.is_empty() {
+        return "Config reloaded: no settings changed".to_string();
+    }
+
+    let keys = |live: bool| {
+        changes
+            .iter()
+            .filter(|change| change.live == live)
+            .map(|change| change.key)
+            .collect::<Vec<_>>()
+    };
+    let mut lines = vec!["Config reloaded".to_string()];
+    let applied = keys(true);
+    if !applied.is_empty() {
+        lines.push(format!("Applied: {}", applied.join(", ")));
+    }
+    let restart = keys(false);
+    if !restart.is_empty() {
+        lines.push(format!("Requires restart: {}", restart.join(", ")));
+    }
+    lines.join("\n")
+}
+
+/// Mask every value whose field name looks like a secret. Fields ending in `_env`
+/// or `_keyring` only name where a secret lives, so they are left readable.
+pub fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let is_reference = name.ends_with("_env") || name.ends_with("_keyring");
+                let is_secret = SECRET_FIELD_MARKERS
+                    .iter()
+                    .any(|marker| name.to_lowercase().contains(marker));
+
+                if is_secret && !is_reference && !field.is_null() {
+                    *field = serde_json::Value::String("********".to_string());
+                } else {
+                    redact_secrets(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
     }
 }
@@ -0,0 +1,33 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File remembering the working directory of the last session, next to the default
+/// database
+pub fn default_last_directory_path() -> Option<PathBuf> {
+    let mut path = dirs::data_dir().or_else(|| {
+        dirs::home_dir().map(|mut home| {
+            home.push(".local");
+            home.push("share");
+            home
+        })
+    })?;
+    path.push("taskhub");
+    path.push("last_directory");
+    Some(path)
+}
+
+/// Remember `dir` in `file` as the last working directory
+pub fn save_last_directory(file: &Path, dir: &Path) -> io::Result<()> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, dir.to_string_lossy().as_bytes())
+}
+
+/// The directory remembered in `file`, if there is one and it still exists
+pub fn load_last_directory(file: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(file).ok()?;
+    let dir = PathBuf::from(contents.trim_end_matches(['\n', '\r']));
+    dir.is_dir().then_some(dir)
+}
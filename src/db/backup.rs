@@ -0,0 +1,96 @@
+use super::models::Task;
+use super::operations;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::Path;
+
+/// Format version written into every backup file
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Portable snapshot of every task in the database
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskBackup {
+    pub version: u32,
+    pub exported_at: String,
+    pub tasks: Vec<Task>,
+}
+
+/// What happened to the tasks of a backup when it was imported
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+impl ImportReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "Imported tasks: {} added, {} updated, {} unchanged",
+            self.added, self.updated, self.unchanged
+        )
+    }
+}
+
+/// Collect every task into a backup snapshot
+pub async fn export_tasks(pool: &SqlitePool) -> Result<TaskBackup, sqlx::Error> {
+    Ok(TaskBackup {
+        version: BACKUP_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tasks: operations::list_tasks(pool).await?,
+    })
+}
+
+/// Merge a backup into the database. Unknown tasks are added, and tasks that
+/// already exist are only overwritten when the backup copy is newer.
+pub async fn import_tasks(
+    pool: &SqlitePool,
+    backup: &TaskBackup,
+) -> Result<ImportReport, sqlx::Error> {
+    let mut report = ImportReport::default();
+
+    for task in &backup.tasks {
+        match operations::get_task(pool, task.id).await {
+            Ok(existing) if existing.updated_at < task.updated_at => {
+                operations::update_task(pool, task).await?;
+                report.updated += 1;
+            }
+            Ok(_) => report.unchanged += 1,
+            Err(sqlx::Error::RowNotFound) => {
+                operations::create_task(pool, task).await?;
+                report.added += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Write all tasks to `path` as pretty-printed JSON and return how many were written
+pub async fn backup_to_file(
+    pool: &SqlitePool,
+    path: &Path,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let backup = export_tasks(pool).await?;
+    let json = serde_json::to_string_pretty(&backup)?;
+    tokio::fs::write(path, json).await?;
+    Ok(backup.tasks.len())
+}
+
+/// Read a JSON backup from `path` and merge it into the database
+pub async fn import_from_file(
+    pool: &SqlitePool,
+    path: &Path,
+) -> Result<ImportReport, Box<dyn std::error::Error>> {
+    let json = tokio::fs::read_to_string(path).await?;
+    let backup: TaskBackup = serde_json::from_str(&json)?;
+    if backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup format version {} is newer than supported version {BACKUP_VERSION}",
+            backup.version
+        )
+        .into());
+    }
+    Ok(import_tasks(pool, &backup).await?)
+}
@@ -1,11 +1,18 @@
 use dirs;
 use sqlx::SqlitePool;
-use std::path::PathBuf;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 use tokio::fs;
 
+pub mod backup;
 pub mod models;
 pub mod operations;
 
+/// How long a connection waits on a locked database before giving up
+pub const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
 pub async fn init_db(db_path: Option<PathBuf>) -> Result<SqlitePool, sqlx::Error> {
     let db_url = if let Some(path) = db_path {
         if path.to_str() == Some(":memory:") {
@@ -18,15 +25,22 @@ pub async fn init_db(db_path: Option<PathBuf>) -> Result<SqlitePool, sqlx::Error
         format!("sqlite://{}", path.to_str().unwrap())
     };
 
-    if !db_url.contains(":memory:") {
-        if let Some(parent) = PathBuf::from(db_url.strip_prefix("sqlite://").unwrap()).parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent).await.map_err(sqlx::Error::Io)?;
-            }
-        }
+    if !db_url.contains(":memory:")
+        && let Some(parent) = PathBuf::from(db_url.strip_prefix("sqlite://").unwrap()).parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent).await.map_err(sqlx::Error::Io)?;
     }
 
-    let pool = SqlitePool::connect(&format!("{db_url}?mode=rwc")).await?;
+    // WAL lets the UI keep reading while background work writes, and the busy
+    // timeout makes writers wait for each other instead of failing immediately
+    let options = SqliteConnectOptions::from_str(&db_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(BUSY_TIMEOUT)
+        .foreign_keys(true);
+
+    let pool = SqlitePool::connect_with(options).await?;
 
     // Run migrations
     run_migrations(&pool).await?;
@@ -64,12 +78,40 @@ async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Create the tasks table
     run_migration_create_table_tasks(pool).await?;
 
+    // Link subtasks to their parent
+    run_migration_add_task_parent_id(pool).await?;
+
+    // Speed up searching tasks by title
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_title ON tasks(title COLLATE NOCASE);")
+        .execute(pool)
+        .await?;
+
     // Handle command_history table migration
     run_migration_migrate_command_history_table(pool).await?;
 
     Ok(())
 }
 
+async fn run_migration_add_task_parent_id(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let exists: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pragma_table_info('tasks') WHERE name = 'parent_id'",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if exists == 0 {
+        sqlx::query("ALTER TABLE tasks ADD COLUMN parent_id TEXT")
+            .execute(pool)
+            .await?;
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_parent_id ON tasks(parent_id);")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn run_migration_migrate_command_history_table(
     pool: &SqlitePool,
 ) -> Result<(), sqlx::Error> {
@@ -94,9 +136,36 @@ pub async fn run_migration_migrate_command_history_table(
     .execute(pool)
     .await?;
 
+    // When each command was run, as RFC 3339; NULL for commands saved before it existed
+    let exists: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pragma_table_info('command_history') WHERE name = 'executed_at'",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if exists == 0 {
+        sqlx::query("ALTER TABLE command_history ADD COLUMN executed_at TEXT")
+            .execute(pool)
+            .await?;
+    }
+
     Ok(())
 }
 
+/// Directory holding the database at `db_path`, or at the default location when
+/// none is configured. None for an in-memory database.
+pub fn database_directory(db_path: Option<&Path>) -> Option<PathBuf> {
+    let path = match db_path {
+        Some(path) if path.to_str() == Some(":memory:") => return None,
+        Some(path) => path.to_path_buf(),
+        None => get_default_db_path()?,
+    };
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => Some(parent.to_path_buf()),
+        _ => Some(PathBuf::from(".")),
+    }
+}
+
 fn get_default_db_path() -> Option<PathBuf> {
     // Try data_dir first (preferred location)
     if let Some(mut path) = dirs::data_dir() {
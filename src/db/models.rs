@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -17,7 +18,7 @@ impl fmt::Display for TaskSource {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TaskStatus {
     Open,
     InProgress,
@@ -30,19 +31,53 @@ impl fmt::Display for TaskStatus {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Priority {
     High,
     Medium,
     Low,
 }
 
+impl Priority {
+    /// One step more urgent; High stays High
+    pub fn raised(self) -> Priority {
+        match self {
+            Priority::Low => Priority::Medium,
+            Priority::Medium | Priority::High => Priority::High,
+        }
+    }
+
+    /// One step less urgent; Low stays Low
+    pub fn lowered(self) -> Priority {
+        match self {
+            Priority::High => Priority::Medium,
+            Priority::Medium | Priority::Low => Priority::Low,
+        }
+    }
+}
+
 impl fmt::Display for Priority {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{self:?}")
     }
 }
 
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    /// Parse a priority name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high" => Ok(Priority::High),
+            "medium" => Ok(Priority::Medium),
+            "low" => Ok(Priority::Low),
+            _ => Err(format!(
+                "Unknown priority '{s}': expected high, medium or low"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Task {
     pub id: Uuid,
@@ -55,7 +90,61 @@ pub struct Task {
     pub assignee: Option<String>,
     pub labels: Vec<String>,
     pub due_date: Option<String>,
+    /// Task this one is a subtask of
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
     pub created_at: String,
     pub updated_at: String,
     pub custom_fields: HashMap<String, String>,
 }
+
+/// Key in `custom_fields` holding a task's free-form notes
+pub const NOTES_FIELD: &str = "notes";
+
+impl Task {
+    /// A new open, medium priority task with nothing but a title
+    pub fn new(title: impl Into<String>) -> Self {
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        Task {
+            id: Uuid::new_v4(),
+            external_id: None,
+            source: TaskSource::Markdown,
+            title: title.into(),
+            description: None,
+            status: TaskStatus::Open,
+            priority: Priority::Medium,
+            assignee: None,
+            labels: Vec::new(),
+            due_date: None,
+            parent_id: None,
+            created_at: now.clone(),
+            updated_at: now,
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    /// Notes jotted down for this task, empty if there are none
+    pub fn notes(&self) -> &str {
+        self.custom_fields
+            .get(NOTES_FIELD)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Replace the notes; blank notes remove the field
+    pub fn set_notes(&mut self, notes: &str) {
+        if notes.trim().is_empty() {
+            self.custom_fields.remove(NOTES_FIELD);
+        } else {
+            self.custom_fields
+                .insert(NOTES_FIELD.to_string(), notes.to_string());
+        }
+    }
+
+    /// Day the task is due. Accepts a bare `YYYY-MM-DD` date or a timestamp starting
+    /// with one; anything else counts as no due date.
+    pub fn due_day(&self) -> Option<NaiveDate> {
+        let due = self.due_date.as_deref()?.trim();
+        NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok()
+    }
+}
@@ -1,10 +1,12 @@
-use super::models::Task;
-use sqlx::{Row, SqlitePool};
+use super::models::{Task, TaskSource, TaskStatus};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 pub async fn create_task(pool: &SqlitePool, task: &Task) -> Result<(), sqlx::Error> {
     sqlx::query(
-        "INSERT INTO tasks (id, external_id, source, title, description, status, priority, assignee, labels, due_date, created_at, updated_at, custom_fields) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO tasks (id, external_id, source, title, description, status, priority, assignee, labels, due_date, parent_id, created_at, updated_at, custom_fields) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(task.id.to_string())
     .bind(&task.external_id)
@@ -16,6 +18,7 @@ pub async fn create_task(pool: &SqlitePool, task: &Task) -> Result<(), sqlx::Err
     .bind(&task.assignee)
     .bind(serde_json::to_string(&task.labels).unwrap())
     .bind(&task.due_date)
+    .bind(task.parent_id.map(|id| id.to_string()))
     .bind(&task.created_at)
     .bind(&task.updated_at)
     .bind(serde_json::to_string(&task.custom_fields).unwrap())
@@ -29,27 +32,12 @@ pub async fn get_task(pool: &SqlitePool, id: Uuid) -> Result<Task, sqlx::Error>
         .bind(id.to_string())
         .fetch_one(pool)
         .await?;
-    let task = Task {
-        id: Uuid::parse_str(row.get("id")).unwrap(),
-        external_id: row.get("external_id"),
-        source: serde_json::from_str(row.get("source")).unwrap(),
-        title: row.get("title"),
-        description: row.get("description"),
-        status: serde_json::from_str(row.get("status")).unwrap(),
-        priority: serde_json::from_str(row.get("priority")).unwrap(),
-        assignee: row.get("assignee"),
-        labels: serde_json::from_str(row.get("labels")).unwrap(),
-        due_date: row.get("due_date"),
-        created_at: row.get("created_at"),
-        updated_at: row.get("updated_at"),
-        custom_fields: serde_json::from_str(row.get("custom_fields")).unwrap(),
-    };
-    Ok(task)
+    Ok(task_from_row(&row))
 }
 
 pub async fn update_task(pool: &SqlitePool, task: &Task) -> Result<(), sqlx::Error> {
     sqlx::query(
-        "UPDATE tasks SET external_id = ?, source = ?, title = ?, description = ?, status = ?, priority = ?, assignee = ?, labels = ?, due_date = ?, updated_at = ?, custom_fields = ? WHERE id = ?",
+        "UPDATE tasks SET external_id = ?, source = ?, title = ?, description = ?, status = ?, priority = ?, assignee = ?, labels = ?, due_date = ?, parent_id = ?, updated_at = ?, custom_fields = ? WHERE id = ?",
     )
     .bind(&task.external_id)
     .bind(serde_json::to_string(&task.source).unwrap())
@@ -60,6 +48,7 @@ pub async fn update_task(pool: &SqlitePool, task: &Task) -> Result<(), sqlx::Err
     .bind(&task.assignee)
     .bind(serde_json::to_string(&task.labels).unwrap())
     .bind(&task.due_date)
+    .bind(task.parent_id.map(|id| id.to_string()))
     .bind(&task.updated_at)
     .bind(serde_json::to_string(&task.custom_fields).unwrap())
     .bind(task.id.to_string())
@@ -68,6 +57,46 @@ pub async fn update_task(pool: &SqlitePool, task: &Task) -> Result<(), sqlx::Err
     Ok(())
 }
 
+/// Set the status of task `id`, bumping its update time. Fails with `RowNotFound`
+/// if there is no such task.
+pub async fn update_task_status(
+    pool: &SqlitePool,
+    id: Uuid,
+    status: &TaskStatus,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query("UPDATE tasks SET status = ?, updated_at = ? WHERE id = ?")
+        .bind(serde_json::to_string(status).unwrap())
+        .bind(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(id.to_string())
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    Ok(())
+}
+
+/// Store `task` as a subtask of `parent_id`. Fails with `RowNotFound` if the parent
+/// does not exist.
+pub async fn create_subtask(
+    pool: &SqlitePool,
+    parent_id: Uuid,
+    task: &mut Task,
+) -> Result<(), sqlx::Error> {
+    get_task(pool, parent_id).await?;
+    task.parent_id = Some(parent_id);
+    create_task(pool, task).await
+}
+
+/// List the direct subtasks of `parent_id`, oldest first
+pub async fn list_subtasks(pool: &SqlitePool, parent_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+    let rows = sqlx::query("SELECT * FROM tasks WHERE parent_id = ? ORDER BY created_at, id")
+        .bind(parent_id.to_string())
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(task_from_row).collect())
+}
+
 pub async fn delete_task(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
     sqlx::query("DELETE FROM tasks WHERE id = ?")
         .bind(id.to_string())
@@ -76,27 +105,191 @@ pub async fn delete_task(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error>
     Ok(())
 }
 
+/// Tasks whose id starts with `prefix`, ordered by id. The prefix is matched
+/// literally, so LIKE wildcards in it match nothing.
+pub async fn find_tasks_by_id_prefix(
+    pool: &SqlitePool,
+    prefix: &str,
+) -> Result<Vec<Task>, sqlx::Error> {
+    let rows = sqlx::query("SELECT * FROM tasks WHERE substr(id, 1, length(?1)) = ?1 ORDER BY id")
+        .bind(prefix.to_lowercase())
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(task_from_row).collect())
+}
+
+/// Tasks whose title contains `query`, ignoring case, ordered by title. `%` and `_`
+/// in the query match themselves rather than acting as wildcards.
+pub async fn search_tasks(pool: &SqlitePool, query: &str) -> Result<Vec<Task>, sqlx::Error> {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let rows = sqlx::query(
+        "SELECT * FROM tasks WHERE title LIKE ? ESCAPE '\\' ORDER BY title COLLATE NOCASE",
+    )
+    .bind(format!("%{escaped}%"))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(task_from_row).collect())
+}
+
+/// Look up a task imported from `source` by its id in that source
+pub async fn find_task_by_external_id(
+    pool: &SqlitePool,
+    source: &TaskSource,
+    external_id: &str,
+) -> Result<Option<Task>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM tasks WHERE source = ? AND external_id = ?")
+        .bind(serde_json::to_string(source).unwrap())
+        .bind(external_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.as_ref().map(task_from_row))
+}
+
 pub async fn list_tasks(pool: &SqlitePool) -> Result<Vec<Task>, sqlx::Error> {
     let rows = sqlx::query("SELECT * FROM tasks").fetch_all(pool).await?;
 
-    let tasks: Vec<Task> = rows
-        .into_iter()
-        .map(|row| Task {
-            id: Uuid::parse_str(row.get("id")).unwrap(),
-            external_id: row.get("external_id"),
-            source: serde_json::from_str(row.get("source")).unwrap(),
-            title: row.get("title"),
-            description: row.get("description"),
-            status: serde_json::from_str(row.get("status")).unwrap(),
-            priority: serde_json::from_str(row.get("priority")).unwrap(),
-            assignee: row.get("assignee"),
-            labels: serde_json::from_str(row.get("labels")).unwrap(),
-            due_date: row.get("due_date"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            custom_fields: serde_json::from_str(row.get("custom_fields")).unwrap(),
-        })
-        .collect();
+    let tasks: Vec<Task> = rows.iter().map(task_from_row).collect();
 
     Ok(tasks)
 }
+
+/// Database size before and after a vacuum, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumReport {
+    pub size_before: i64,
+    pub size_after: i64,
+}
+
+impl VacuumReport {
+    /// Human-readable summary such as "Database size: 1.5 MiB -> 1.2 MiB"
+    pub fn summary(&self) -> String {
+        format!(
+            "Database size: {} -> {}",
+            format_bytes(self.size_before),
+            format_bytes(self.size_after)
+        )
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+
+    let value = bytes as f64;
+    if value >= MIB {
+        format!("{:.1} MiB", value / MIB)
+    } else if value >= KIB {
+        format!("{:.1} KiB", value / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Current size of the database in bytes
+pub async fn database_size(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()")
+        .fetch_one(pool)
+        .await
+}
+
+/// Rebuild the database file to reclaim free pages and refresh query planner statistics.
+/// For in-memory databases VACUUM still runs but there is no file to shrink.
+pub async fn vacuum(pool: &SqlitePool) -> Result<VacuumReport, sqlx::Error> {
+    let size_before = database_size(pool).await?;
+
+    sqlx::query("VACUUM").execute(pool).await?;
+    sqlx::query("PRAGMA optimize").execute(pool).await?;
+
+    let size_after = database_size(pool).await?;
+    Ok(VacuumReport {
+        size_before,
+        size_after,
+    })
+}
+
+/// Criteria for narrowing down listed tasks. Unset fields match every task.
+#[derive(Debug, Default)]
+pub struct TaskFilter {
+    pub status: Option<TaskStatus>,
+    pub source: Option<TaskSource>,
+}
+
+/// List one page of tasks matching `filter`, newest first
+pub async fn list_tasks_paged(
+    pool: &SqlitePool,
+    limit: i64,
+    offset: i64,
+    filter: &TaskFilter,
+) -> Result<Vec<Task>, sqlx::Error> {
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM tasks");
+    push_task_filter(&mut query, filter);
+    query
+        .push(" ORDER BY created_at DESC, id LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let rows = query.build().fetch_all(pool).await?;
+
+    Ok(rows.iter().map(task_from_row).collect())
+}
+
+/// Count the tasks matching `filter` without loading them
+pub async fn count_tasks(pool: &SqlitePool, filter: &TaskFilter) -> Result<i64, sqlx::Error> {
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM tasks");
+    push_task_filter(&mut query, filter);
+
+    let count: i64 = query.build_query_scalar().fetch_one(pool).await?;
+    Ok(count)
+}
+
+fn push_task_filter(query: &mut QueryBuilder<'_, Sqlite>, filter: &TaskFilter) {
+    let mut separator = " WHERE ";
+    if let Some(status) = &filter.status {
+        query
+            .push(separator)
+            .push("status = ")
+            .push_bind(serde_json::to_string(status).unwrap());
+        separator = " AND ";
+    }
+    if let Some(source) = &filter.source {
+        query
+            .push(separator)
+            .push("source = ")
+            .push_bind(serde_json::to_string(source).unwrap());
+    }
+}
+
+fn task_from_row(row: &SqliteRow) -> Task {
+    Task {
+        id: Uuid::parse_str(row.get("id")).unwrap(),
+        external_id: row.get("external_id"),
+        source: serde_json::from_str(row.get("source")).unwrap(),
+        title: row.get("title"),
+        description: row.get("description"),
+        status: serde_json::from_str(row.get("status")).unwrap(),
+        priority: serde_json::from_str(row.get("priority")).unwrap(),
+        assignee: row.get("assignee"),
+        labels: serde_json::from_str(row.get("labels")).unwrap(),
+        due_date: row.get("due_date"),
+        parent_id: row
+            .get::<Option<String>, _>("parent_id")
+            .and_then(|id| Uuid::parse_str(&id).ok()),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        custom_fields: custom_fields_from_column(row.get("custom_fields")),
+    }
+}
+
+/// Decode the JSON `custom_fields` column. NULL and empty columns are an empty map,
+/// and so is malformed JSON rather than failing the whole read. Nothing is printed
+/// about it, as the TUI owns the terminal while tasks are read.
+fn custom_fields_from_column(column: Option<String>) -> HashMap<String, String> {
+    match column.as_deref().map(str::trim) {
+        None | Some("") => HashMap::new(),
+        Some(json) => serde_json::from_str(json).unwrap_or_default(),
+    }
+}
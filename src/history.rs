@@ -1,4 +1,27 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, HashMap};
+
+/// A persisted command with its position in the history
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// 1-based position, counting from the oldest stored command
+    pub number: usize,
+    pub command: String,
+    /// When the command was run, if it was recorded
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+impl HistoryEntry {
+    /// `N  HH:MM:SS  command` in local time, with dashes for an unknown time
+    pub fn display(&self, number_width: usize) -> String {
+        let time = self
+            .executed_at
+            .map(|time| time.with_timezone(&Local).format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "--:--:--".to_string());
+        format!("{:>number_width$}  {time}  {}", self.number, self.command)
+    }
+}
 
 #[derive(Debug)]
 pub struct HistoryManager {
@@ -52,6 +75,25 @@ impl HistoryManager {
         // Use a transaction for atomic operations and better performance
         let mut tx = self.db_pool.begin().await?;
 
+        // Keep the recorded times of commands that are already stored, matching them
+        // up in order; commands not found in the table have no time
+        let stored: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT command, executed_at FROM command_history ORDER BY id ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let mut remaining = stored.into_iter();
+        let times: Vec<Option<String>> = entries_to_save
+            .iter()
+            .map(|command| {
+                let mut lookahead = remaining.clone();
+                let time = lookahead
+                    .find(|(stored_command, _)| stored_command == command)
+                    .map(|(_, time)| time)?;
+                remaining = lookahead;
+                time
+            })
+            .collect();
+
         // Clear existing history first
         sqlx::query("DELETE FROM command_history")
             .execute(&mut *tx)
@@ -59,12 +101,13 @@ impl HistoryManager {
 
         // Build a bulk insert query
         if !entries_to_save.is_empty() {
-            let placeholders = "(?)".repeat(entries_to_save.len()).replace(")(", "),(");
-            let query = format!("INSERT INTO command_history (command) VALUES {placeholders}");
+            let placeholders = "(?, ?)".repeat(entries_to_save.len()).replace(")(", "),(");
+            let query =
+                format!("INSERT INTO command_history (command, executed_at) VALUES {placeholders}");
 
             let mut query_builder = sqlx::query(&query);
-            for command in entries_to_save {
-                query_builder = query_builder.bind(command);
+            for (command, time) in entries_to_save.iter().zip(times) {
+                query_builder = query_builder.bind(command).bind(time);
             }
 
             query_builder.execute(&mut *tx).await?;
@@ -80,11 +123,12 @@ impl HistoryManager {
         // Insert the new command
         sqlx::query(
             r#"
-            INSERT INTO command_history (command)
-            VALUES (?)
+            INSERT INTO command_history (command, executed_at)
+            VALUES (?, ?)
         "#,
         )
         .bind(command)
+        .bind(Utc::now().to_rfc3339())
         .execute(&self.db_pool)
         .await?;
 
@@ -115,6 +159,45 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// The newest `limit` commands, oldest first
+    pub async fn recent_history(&self, limit: usize) -> Result<Vec<HistoryEntry>, sqlx::Error> {
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM command_history")
+            .fetch_one(&self.db_pool)
+            .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT command, executed_at
+                FROM command_history
+                ORDER BY id DESC
+                LIMIT ?
+        "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let first_number = total as usize - rows.len() + 1;
+        Ok(rows
+            .into_iter()
+            .rev()
+            .enumerate()
+            .map(|(i, row)| HistoryEntry {
+                number: first_number + i,
+                command: row.get("command"),
+                executed_at: row
+                    .get::<Option<String>, _>("executed_at")
+                    .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                    .map(|time| time.with_timezone(&Utc)),
+            })
+            .collect())
+    }
+
+    /// Every stored command, oldest first
+    pub async fn all_history(&self) -> Result<Vec<HistoryEntry>, sqlx::Error> {
+        self.recent_history(self.max_entries).await
+    }
+
     pub async fn clear_history(&self) -> Result<(), Box<dyn std::error::Error>> {
         sqlx::query("DELETE FROM command_history")
             .execute(&self.db_pool)
@@ -123,6 +206,49 @@ impl HistoryManager {
     }
 }
 
+/// The `limit` commands run most often with their counts, most frequent first.
+/// Ties are listed alphabetically.
+pub fn most_run_commands(entries: &[HistoryEntry], limit: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        *counts.entry(entry.command.trim()).or_default() += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(command, count)| (command.to_string(), count))
+        .collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts.truncate(limit);
+    counts
+}
+
+/// Number of commands run on each day in `tz`, oldest day first. Commands without a
+/// recorded time are not counted.
+pub fn commands_per_day<Tz: TimeZone>(
+    entries: &[HistoryEntry],
+    tz: &Tz,
+) -> Vec<(NaiveDate, usize)> {
+    let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for time in entries.iter().filter_map(|entry| entry.executed_at) {
+        *days.entry(time.with_timezone(tz).date_naive()).or_default() += 1;
+    }
+    days.into_iter().collect()
+}
+
+/// How many of the exit codes are successes and how many failures
+pub fn exit_code_counts(exit_codes: impl IntoIterator<Item = i32>) -> (usize, usize) {
+    exit_codes
+        .into_iter()
+        .fold((0, 0), |(succeeded, failed), code| {
+            if code == 0 {
+                (succeeded + 1, failed)
+            } else {
+                (succeeded, failed + 1)
+            }
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,18 +258,9 @@ mod tests {
         let pool = SqlitePool::connect(":memory:").await.unwrap();
 
         // Create the command_history table
-        sqlx::query(
-            r#"
-            CREATE TABLE command_history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                command TEXT NOT NULL,
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-        "#,
-        )
-        .execute(&pool)
-        .await
-        .unwrap();
+        crate::db::run_migration_migrate_command_history_table(&pool)
+            .await
+            .unwrap();
 
         pool
     }
@@ -193,6 +310,30 @@ mod tests {
         assert_eq!(history3[1], "echo overflow");
     }
 
+    #[tokio::test]
+    async fn test_recent_history_keeps_times_across_save() {
+        let pool = setup_test_db().await;
+        let manager = HistoryManager::new(pool, Some(100));
+
+        manager.append_command("ls").await.unwrap();
+        manager.append_command("pwd").await.unwrap();
+        let recent = manager.recent_history(10).await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!((recent[0].number, recent[0].command.as_str()), (1, "ls"));
+        assert_eq!((recent[1].number, recent[1].command.as_str()), (2, "pwd"));
+        assert!(recent.iter().all(|entry| entry.executed_at.is_some()));
+
+        // Saving the same history again keeps the recorded times; new entries have none
+        let history = vec!["ls".to_string(), "pwd".to_string(), "make".to_string()];
+        manager.save_history(&history).await.unwrap();
+        let saved = manager.recent_history(2).await.unwrap();
+        assert_eq!(saved[0].number, 2);
+        assert_eq!(saved[0].executed_at, recent[1].executed_at);
+        assert_eq!(saved[1].command, "make");
+        assert_eq!(saved[1].executed_at, None);
+        assert!(saved[1].display(1).starts_with("3  --:--:--  make"));
+    }
+
     #[tokio::test]
     async fn test_clear_history() {
         let pool = setup_test_db().await;
@@ -1,7 +1,11 @@
+use crate::config::secrets::SecretRef;
 use crate::db::models::{Priority, Task, TaskSource, TaskStatus};
-use reqwest::{Client, header};
+use config::ConfigError;
+use reqwest::{Client, Response, StatusCode, header};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -14,6 +18,9 @@ struct GitHubIssue {
     labels: Vec<GitHubLabel>,
     created_at: String,
     updated_at: String,
+    /// Present when the "issue" is really a pull request
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,9 +33,84 @@ struct GitHubLabel {
     name: String,
 }
 
+/// Default limit on the number of issue pages fetched per repository
+pub const DEFAULT_MAX_PAGES: usize = 50;
+
+/// Issues requested per page; the most the API allows
+const ISSUES_PER_PAGE: usize = 100;
+
+/// Longest wait for a rate limit to reset before giving up instead of retrying
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Why fetching from GitHub failed
+#[derive(Debug)]
+pub enum GitHubError {
+    Request(reqwest::Error),
+    /// The API rate limit is used up. `reset_at` is the Unix time at which it
+    /// resets, when GitHub reported it.
+    RateLimited {
+        reset_at: Option<u64>,
+    },
+}
+
+impl fmt::Display for GitHubError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GitHubError::Request(e) => write!(f, "{e}"),
+            GitHubError::RateLimited {
+                reset_at: Some(reset_at),
+            } => {
+                let wait = reset_at.saturating_sub(unix_now());
+                write!(f, "GitHub rate limit exceeded; retry in {wait}s")
+            }
+            GitHubError::RateLimited { reset_at: None } => {
+                write!(f, "GitHub rate limit exceeded")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GitHubError {}
+
+impl From<reqwest::Error> for GitHubError {
+    fn from(e: reqwest::Error) -> Self {
+        GitHubError::Request(e)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// Reset time of a response rejected for exceeding the rate limit, `None` for
+/// any other response. The inner `None` means GitHub did not say when it resets.
+fn rate_limit_reset(response: &Response) -> Option<Option<u64>> {
+    let limited = matches!(
+        response.status(),
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) && response
+        .headers()
+        .get("x-ratelimit-remaining")
+        .and_then(|remaining| remaining.to_str().ok())
+        == Some("0");
+    limited.then(|| {
+        response
+            .headers()
+            .get("x-ratelimit-reset")
+            .and_then(|reset| reset.to_str().ok()?.trim().parse().ok())
+    })
+}
+
 pub struct GitHubClient {
     client: Client,
     base_url: String,
+    max_pages: usize,
+    /// Times a rate-limited request is retried after waiting for the limit to reset
+    max_retries: u32,
+    /// Lowercased label name to the priority it implies
+    label_priorities: HashMap<String, Priority>,
 }
 
 impl GitHubClient {
@@ -48,15 +130,105 @@ impl GitHubClient {
         Self {
             client,
             base_url: "https://api.github.com".to_string(),
+            max_pages: DEFAULT_MAX_PAGES,
+            max_retries: 0,
+            label_priorities: HashMap::new(),
         }
     }
 
-    pub async fn fetch_issues(&self, owner: &str, repo: &str) -> Result<Vec<Task>, reqwest::Error> {
-        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
-        let issues: Vec<GitHubIssue> = self.client.get(&url).send().await?.json().await?;
+    /// Wait for the rate limit to reset and retry up to `max_retries` times when
+    /// GitHub rejects a request for exceeding it. Waits longer than
+    /// `MAX_RATE_LIMIT_WAIT` fail right away.
+    pub fn with_retry(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
 
+    /// Send requests to `base_url` instead of the public GitHub API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetch at most `max_pages` pages of issues; at least one page is always fetched
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Build a client whose token is looked up from `secret` at call time
+    pub fn from_secret(secret: &SecretRef) -> Result<Self, ConfigError> {
+        Ok(Self::new(&secret.resolve()?))
+    }
+
+    /// Derive task priority from issue labels; the first label with a mapping wins.
+    /// Label names match case-insensitively.
+    pub fn with_label_priorities(mut self, label_priorities: HashMap<String, Priority>) -> Self {
+        self.label_priorities = label_priorities
+            .into_iter()
+            .map(|(label, priority)| (label.to_lowercase(), priority))
+            .collect();
+        self
+    }
+
+    /// Priority implied by the first mapped label, `Medium` if none is mapped
+    fn priority_for_labels(&self, labels: &[String]) -> Priority {
+        labels
+            .iter()
+            .find_map(|label| self.label_priorities.get(&label.to_lowercase()).copied())
+            .unwrap_or(Priority::Medium)
+    }
+
+    /// GET `url`, retrying while rate limited as configured by `with_retry`
+    async fn get(&self, url: &str) -> Result<Response, GitHubError> {
+        let mut retries = 0;
+        loop {
+            let response = self.client.get(url).send().await?;
+            let Some(reset_at) = rate_limit_reset(&response) else {
+                return Ok(response.error_for_status()?);
+            };
+
+            let wait = Duration::from_secs(reset_at.unwrap_or(0).saturating_sub(unix_now()));
+            if retries >= self.max_retries || wait > MAX_RATE_LIMIT_WAIT {
+                return Err(GitHubError::RateLimited { reset_at });
+            }
+            retries += 1;
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Fetch the repository's issues, following the `Link` header from page to page
+    /// until a page comes back empty, there is no next page, or `max_pages` is reached
+    pub async fn fetch_issues(&self, owner: &str, repo: &str) -> Result<Vec<Task>, GitHubError> {
+        let mut url = format!(
+            "{}/repos/{}/{}/issues?per_page={ISSUES_PER_PAGE}",
+            self.base_url, owner, repo
+        );
+        let mut issues: Vec<GitHubIssue> = Vec::new();
+
+        for _ in 0..self.max_pages {
+            let response = self.get(&url).await?;
+            let next_url = response
+                .headers()
+                .get(header::LINK)
+                .and_then(|link| link.to_str().ok())
+                .and_then(next_page_url);
+            let page: Vec<GitHubIssue> = response.json().await?;
+            if page.is_empty() {
+                break;
+            }
+            issues.extend(page);
+
+            match next_url {
+                Some(next_url) => url = next_url,
+                None => break,
+            }
+        }
+
+        // The issues endpoint lists pull requests too; only real issues become tasks
         let tasks: Vec<Task> = issues
             .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
             .map(|issue| {
                 let status = match issue.state.as_str() {
                     "open" => TaskStatus::Open,
@@ -65,7 +237,8 @@ impl GitHubClient {
                 };
 
                 let assignee = issue.assignee.map(|u| u.login);
-                let labels = issue.labels.into_iter().map(|l| l.name).collect();
+                let labels: Vec<String> = issue.labels.into_iter().map(|l| l.name).collect();
+                let priority = self.priority_for_labels(&labels);
 
                 Task {
                     id: Uuid::new_v4(), // Generate a new UUID for internal ID
@@ -74,10 +247,11 @@ impl GitHubClient {
                     title: issue.title,
                     description: issue.body,
                     status,
-                    priority: Priority::Medium, // GitHub issues don't have direct priority, default to medium
+                    priority,
                     assignee,
                     labels,
                     due_date: None,
+                    parent_id: None,
                     created_at: issue.created_at,
                     updated_at: issue.updated_at,
                     custom_fields: HashMap::new(),
@@ -88,3 +262,15 @@ impl GitHubClient {
         Ok(tasks)
     }
 }
+
+/// URL of the `rel="next"` entry in a `Link` header, if any
+pub fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|entry| {
+        let (url, params) = entry.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim().replace(' ', "") == "rel=\"next\"");
+        let url = url.trim().strip_prefix('<')?.strip_suffix('>')?;
+        is_next.then(|| url.to_string())
+    })
+}
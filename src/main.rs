@@ -1,29 +1,110 @@
+use clap::Parser;
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use ratatui::Terminal;
 use std::io;
 use std::path::PathBuf;
+use taskhub::cli::parser::{Cli, Commands};
 use taskhub::config::settings::Settings;
-use taskhub::db::init_db;
+use taskhub::config::state;
+use taskhub::db::{backup, init_db, operations};
+use taskhub::sync::engine;
 use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::components::confirmation::draw_confirmation;
+use taskhub::tui::editor;
+use taskhub::tui::pty_input::InputTarget;
+use taskhub::tui::shell::Shell;
+use taskhub::tui::views::agenda::draw_agenda;
 use taskhub::tui::views::task_list::draw_task_list;
-use taskhub::tui::views::terminal::{TerminalDisplayState, draw_terminal};
-use taskhub::tui::{cleanup_terminal, setup_terminal};
+use taskhub::tui::views::terminal::{TerminalDisplayState, content_area, draw_terminal};
+use taskhub::tui::{cleanup_terminal, resume_terminal, setup_terminal, suspend_terminal};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
     let settings = Settings::new().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-    let db_path = settings.database_path.map(PathBuf::from);
+    let db_path = settings.database_path.clone().map(PathBuf::from);
     let db_pool = init_db(db_path)
         .await
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+
+    // Headless commands run without starting the TUI
+    match cli.command {
+        Some(Commands::Vacuum {}) => {
+            let report = operations::vacuum(&db_pool)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+            println!("{}", report.summary());
+            return Ok(());
+        }
+        Some(Commands::Backup { path }) => {
+            let count = backup::backup_to_file(&db_pool, &path).await?;
+            println!("Backed up {count} tasks to {}", path.display());
+            return Ok(());
+        }
+        Some(Commands::Import { path }) => {
+            let report = backup::import_from_file(&db_pool, &path).await?;
+            println!("{}", report.summary());
+            return Ok(());
+        }
+        Some(Commands::Config {}) => {
+            println!("{}", settings.describe());
+            return Ok(());
+        }
+        Some(Commands::Sync {}) => {
+            if settings.integrations.is_empty() {
+                println!("No integrations configured");
+                return Ok(());
+            }
+            let report = engine::sync_targets(&db_pool, &settings.integrations).await?;
+            println!("{}", report.summary());
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Resume in the directory the last session left off in
+    let last_directory_file = settings
+        .history
+        .restore_directory
+        .then(state::default_last_directory_path)
+        .flatten();
+    if let Some(dir) = last_directory_file
+        .as_deref()
+        .and_then(state::load_last_directory)
+    {
+        let _ = std::env::set_current_dir(dir);
+    }
+
     let mut terminal = setup_terminal()?;
 
     // Create app with history manager if persistence is enabled
-    let mut app = if settings.history.persist {
+    let app = if settings.history.persist {
         App::new(db_pool.clone()).with_history_manager(Some(settings.history.max_entries))
     } else {
         App::new(db_pool)
     };
+    let mut app = app
+        .with_theme_palette(settings.ui.theme_palette)
+        .with_layout(settings.ui.input_height, settings.ui.command_list_max_items)
+        .with_right_click_action(settings.ui.right_click)
+        .with_stderr_mode(settings.ui.stderr)
+        .with_search_scroll_mode(settings.ui.search_scroll_mode)
+        .with_start_mode(settings.ui.start_mode)
+        .with_shell(
+            Shell::from_setting(settings.shell.as_deref()).with_direct_exec(settings.direct_exec),
+        )
+        .with_search_regex_size_limit(settings.ui.search_regex_size_limit)
+        .with_max_content_width(settings.ui.max_content_width)
+        .with_confirm_unknown_commands(settings.ui.confirm_unknown_commands)
+        .with_preview_globs(settings.ui.preview_globs)
+        .with_custom_commands(settings.commands.clone())
+        .with_command_timeout(settings.command_timeout())
+        .with_max_scrollback_lines(settings.ui.max_scrollback_lines)
+        .with_task_page_size(settings.tasks.page_size)
+        .with_settings(settings);
+    if let Some(file) = last_directory_file {
+        app = app.with_last_directory_file(file);
+    }
 
     // Load persistent history if enabled
     app.load_persistent_history().await;
@@ -52,34 +133,38 @@ async fn run_app<B: ratatui::backend::Backend>(
         // Check if any running command has finished
         app.check_running_command().await;
 
+        // Show the next lines of an output replay
+        app.advance_replay();
+
         // Update spinner animation if command is running
         app.update_spinner();
 
         terminal.draw(|f| {
-            let size = f.area();
-            app.set_terminal_area_height(size.height);
+            app.set_terminal_area_height(f.area().height);
+            app.set_terminal_area_width(f.area().width);
+            let size = content_area(f.area(), app.max_content_width);
+            app.content_left = size.x;
 
             // Update layout areas for accurate mouse coordinate mapping
-            let command_list_size = if app.show_command_list {
-                app.get_filtered_commands().len().min(8) as u16 + 2
-            } else {
-                0
-            };
-            app.update_layout_areas(size.height, app.show_command_list, command_list_size);
+            let command_list_size = app.command_list_size();
+            app.update_layout_areas(size.height, command_list_size > 0, command_list_size);
 
             match app.mode {
-                AppMode::TaskList => {
+                AppMode::TaskList | AppMode::Agenda => {
                     let filtered_commands = app.get_filtered_commands();
+                    let completion_candidates = app.completion_candidates();
                     let reverse_search_prompt = app.get_reverse_search_prompt();
                     let state = TerminalDisplayState {
                         command_history: &app.command_history,
                         current_input: &app.current_input,
                         cursor_position: app.cursor_position,
                         scroll_offset: app.scroll_offset,
+                        horizontal_scroll_offset: app.horizontal_scroll_offset,
                         show_command_list: app.show_command_list,
                         filtered_commands: &filtered_commands,
                         selected_command_index: app.selected_command_index,
                         is_command_running: app.running_command.is_some(),
+                        waiting_for_input: app.command_may_be_waiting_for_input(),
                         prompt: app.get_prompt(),
                         selection_start: app.selection_start,
                         selection_end: app.selection_end,
@@ -89,26 +174,55 @@ async fn run_app<B: ratatui::backend::Backend>(
                         reverse_search_active: app.reverse_search_active,
                         reverse_search_prompt: &reverse_search_prompt,
                         current_search_result: app.get_current_search_result().map(|x| x.as_str()),
+                        reverse_search_preview: app.get_current_search_preview(),
+                        fuzzy_search: app.fuzzy_search.as_ref(),
                         output_search_active: app.output_search_active,
                         output_search_query: &app.output_search_query,
                         output_search_matches: app.get_output_search_matches(),
                         output_search_current_match: app.get_current_search_match(),
                         output_search_status: &app.get_output_search_status(),
+                        visual_selection_active: app.visual_selection_active,
+                        context_menu: app.context_menu.as_ref(),
+                        ansi_palette: app.ansi_palette(),
+                        theme: &app.theme,
+                        input_height: app.input_area_height,
+                        command_list_max_items: app.command_list_max_items,
+                        completion_candidates: &completion_candidates,
+                        selected_completion: app.completion_state.applied_index(),
+                        show_timestamps: app.show_timestamps,
+                        collapse_repeats: app.collapse_repeats,
+                        show_line_numbers: app.show_line_numbers,
                     };
-                    draw_task_list(f, size, &app.tasks, &state);
+                    if app.mode == AppMode::Agenda {
+                        let today = chrono::Local::now().date_naive();
+                        draw_agenda(f, size, &app.tasks, today, &state);
+                    } else {
+                        draw_task_list(
+                            f,
+                            size,
+                            &app.tasks,
+                            app.task_page,
+                            app.selected_task(),
+                            app.quick_add.as_ref(),
+                            &state,
+                        );
+                    }
                 }
                 AppMode::Terminal => {
                     let filtered_commands = app.get_filtered_commands();
+                    let completion_candidates = app.completion_candidates();
                     let reverse_search_prompt = app.get_reverse_search_prompt();
                     let state = TerminalDisplayState {
                         command_history: &app.command_history,
                         current_input: &app.current_input,
                         cursor_position: app.cursor_position,
                         scroll_offset: app.scroll_offset,
+                        horizontal_scroll_offset: app.horizontal_scroll_offset,
                         show_command_list: app.show_command_list,
                         filtered_commands: &filtered_commands,
                         selected_command_index: app.selected_command_index,
                         is_command_running: app.running_command.is_some(),
+                        waiting_for_input: app.command_may_be_waiting_for_input(),
                         prompt: app.get_prompt(),
                         selection_start: app.selection_start,
                         selection_end: app.selection_end,
@@ -118,22 +232,62 @@ async fn run_app<B: ratatui::backend::Backend>(
                         reverse_search_active: app.reverse_search_active,
                         reverse_search_prompt: &reverse_search_prompt,
                         current_search_result: app.get_current_search_result().map(|x| x.as_str()),
+                        reverse_search_preview: app.get_current_search_preview(),
+                        fuzzy_search: app.fuzzy_search.as_ref(),
                         output_search_active: app.output_search_active,
                         output_search_query: &app.output_search_query,
                         output_search_matches: app.get_output_search_matches(),
                         output_search_current_match: app.get_current_search_match(),
                         output_search_status: &app.get_output_search_status(),
+                        visual_selection_active: app.visual_selection_active,
+                        context_menu: app.context_menu.as_ref(),
+                        ansi_palette: app.ansi_palette(),
+                        theme: &app.theme,
+                        input_height: app.input_area_height,
+                        command_list_max_items: app.command_list_max_items,
+                        completion_candidates: &completion_candidates,
+                        selected_completion: app.completion_state.applied_index(),
+                        show_timestamps: app.show_timestamps,
+                        collapse_repeats: app.collapse_repeats,
+                        show_line_numbers: app.show_line_numbers,
                     };
                     draw_terminal(f, size, &state);
                 }
             }
+
+            // A question waiting for y/n floats above every view
+            if let Some(confirmation) = &app.confirmation {
+                draw_confirmation(f, confirmation);
+            }
         })?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             match event::read()? {
                 Event::Key(key) => {
-                    // Handle Ctrl-C to kill running commands
-                    if key.code == KeyCode::Char('c')
+                    // Commands running in a PTY get the keyboard, so they can be
+                    // typed into like in a normal terminal
+                    if app.key_input_target(key) == InputTarget::Child {
+                        app.forward_key_to_child(key);
+                    } else if key.code == KeyCode::Char('d')
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                        && app.running_command.is_some()
+                    {
+                        // Ctrl-D ends the input of a command reading stdin
+                        app.send_eof();
+                    } else if matches!(key.code, KeyCode::Char('c' | 'C'))
+                        && key
+                            .modifiers
+                            .contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+                    {
+                        // Ctrl-Shift-C copies the input line; some terminals report the
+                        // shifted key as an uppercase letter
+                        app.copy_input_line().await;
+                    } else if key.code == KeyCode::Char('m')
+                        && key.modifiers.contains(KeyModifiers::ALT)
+                    {
+                        // Alt-M copies the focused history entry for pasting into issues
+                        app.copy_focused_entry_as_markdown().await;
+                    } else if key.code == KeyCode::Char('c')
                         && key.modifiers.contains(KeyModifiers::CONTROL)
                     {
                         // Check if there's any text selection first
@@ -149,7 +303,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                         && key.modifiers.contains(KeyModifiers::CONTROL)
                     {
                         // Handle Ctrl-V for paste
-                        let _ = app.paste_from_clipboard();
+                        app.paste_and_report().await;
                     } else if key.code == KeyCode::Char('r')
                         && key.modifiers.contains(KeyModifiers::CONTROL)
                     {
@@ -177,10 +331,28 @@ async fn run_app<B: ratatui::backend::Backend>(
                 Event::Mouse(mouse) => {
                     app.on_mouse_event(mouse);
                 }
+                Event::Resize(width, height) => {
+                    app.handle_resize(width, height);
+                }
                 _ => {}
             }
         }
 
+        if std::mem::take(&mut app.pending_editor) {
+            // Hand the terminal to the editor, then take it back
+            suspend_terminal()?;
+            let result = editor::edit_in_external_editor(&app.current_input);
+            resume_terminal(terminal)?;
+            app.finish_external_edit(result).await;
+        }
+
+        if let Some(task) = app.pending_note_edit.take() {
+            suspend_terminal()?;
+            let result = editor::edit_in_external_editor(task.notes());
+            resume_terminal(terminal)?;
+            app.finish_note_edit(task, result).await;
+        }
+
         if app.should_quit {
             break;
         }
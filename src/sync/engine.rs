@@ -0,0 +1,73 @@
+use crate::config::settings::{IntegrationKind, IntegrationTarget};
+use crate::db::backup::ImportReport;
+use crate::db::models::Task;
+use crate::db::operations;
+use crate::integrations::github::GitHubClient;
+use sqlx::SqlitePool;
+
+/// Fetch the tasks of one configured target, resolving its token at call time
+pub async fn fetch_target_tasks(
+    target: &IntegrationTarget,
+) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+    match target.kind {
+        IntegrationKind::Github => {
+            let mut client = GitHubClient::from_secret(&target.token_ref()?)?
+                .with_label_priorities(target.label_priorities.clone())
+                .with_retry(target.max_retries);
+            if let Some(max_pages) = target.max_pages {
+                client = client.with_max_pages(max_pages);
+            }
+            Ok(client.fetch_issues(&target.owner, &target.repo).await?)
+        }
+        kind => Err(format!("{kind:?} integration is not supported yet").into()),
+    }
+}
+
+/// Store fetched tasks, matching them to earlier imports by source and external id
+/// so repeated syncs update tasks in place instead of duplicating them
+pub async fn store_fetched_tasks(
+    pool: &SqlitePool,
+    tasks: Vec<Task>,
+) -> Result<ImportReport, sqlx::Error> {
+    let mut report = ImportReport::default();
+
+    for mut task in tasks {
+        let existing = match &task.external_id {
+            Some(external_id) => {
+                operations::find_task_by_external_id(pool, &task.source, external_id).await?
+            }
+            None => None,
+        };
+
+        match existing {
+            Some(existing) if existing.updated_at != task.updated_at => {
+                task.id = existing.id;
+                operations::update_task(pool, &task).await?;
+                report.updated += 1;
+            }
+            Some(_) => report.unchanged += 1,
+            None => {
+                operations::create_task(pool, &task).await?;
+                report.added += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Import tasks from every configured target
+pub async fn sync_targets(
+    pool: &SqlitePool,
+    targets: &[IntegrationTarget],
+) -> Result<ImportReport, Box<dyn std::error::Error>> {
+    let mut total = ImportReport::default();
+    for target in targets {
+        let tasks = fetch_target_tasks(target).await?;
+        let report = store_fetched_tasks(pool, tasks).await?;
+        total.added += report.added;
+        total.updated += report.updated;
+        total.unchanged += report.unchanged;
+    }
+    Ok(total)
+}
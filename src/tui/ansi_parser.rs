@@ -37,6 +37,10 @@ pub struct TerminalState {
     pub italic: bool,
     pub underline: bool,
     pub reverse: bool,
+    /// Faint text (SGR 2)
+    pub dim: bool,
+    /// Crossed-out text (SGR 9)
+    pub strikethrough: bool,
     pub alternate_screen: bool,
     pub screen_buffer: Vec<Vec<StyledChar>>,
     pub alternate_buffer: Vec<Vec<StyledChar>>,
@@ -53,6 +57,13 @@ pub struct TerminalState {
     pub cursor_visible: bool,
     pub screen_cleared: bool,
     pub final_output: Vec<String>,
+    /// Optional RGB palette used for the 16 base indices of 256-color sequences
+    pub palette: Option<[Color; 16]>,
+    /// Window title last requested with OSC 0 or OSC 2
+    pub title: Option<String>,
+    /// Rows scrolled off the top of the main screen, oldest first; None when they
+    /// are not kept
+    pub scrollback: Option<Vec<Vec<StyledChar>>>,
 }
 
 impl Default for TerminalState {
@@ -66,6 +77,8 @@ impl Default for TerminalState {
             italic: false,
             underline: false,
             reverse: false,
+            dim: false,
+            strikethrough: false,
             alternate_screen: false,
             screen_buffer: vec![vec![StyledChar::space_with_style(Style::default()); 80]; 24],
             alternate_buffer: vec![vec![StyledChar::space_with_style(Style::default()); 80]; 24],
@@ -82,6 +95,9 @@ impl Default for TerminalState {
             cursor_visible: true,
             screen_cleared: false,
             final_output: Vec::new(),
+            palette: None,
+            title: None,
+            scrollback: None,
         }
     }
 }
@@ -207,6 +223,12 @@ impl TerminalState {
         if self.reverse {
             style = style.add_modifier(Modifier::REVERSED);
         }
+        if self.dim {
+            style = style.add_modifier(Modifier::DIM);
+        }
+        if self.strikethrough {
+            style = style.add_modifier(Modifier::CROSSED_OUT);
+        }
 
         style
     }
@@ -240,12 +262,42 @@ impl TerminalState {
     pub fn switch_to_main_screen(&mut self) {
         self.alternate_screen = false;
         self.cursor = CursorPosition { row: 0, col: 0 };
+        // A scroll region set by a full-screen app does not apply to the main screen
+        self.scroll_region = None;
+    }
+
+    /// Soft terminal reset (DECSTR): restore default modes and attributes while
+    /// keeping the screen contents and cursor position
+    pub fn soft_reset(&mut self) {
+        self.foreground_color = None;
+        self.background_color = None;
+        self.bold = false;
+        self.italic = false;
+        self.underline = false;
+        self.reverse = false;
+        self.dim = false;
+        self.strikethrough = false;
+        self.scroll_region = None;
+        self.saved_cursor = None;
+        self.charset = 0;
+        self.insert_mode = false;
+        self.application_keypad = false;
+        self.origin_mode = false;
+        self.auto_wrap = true;
+        self.cursor_visible = true;
     }
 
     pub fn scroll_up(&mut self, lines: usize) {
         let scroll_region = self.scroll_region;
         let start_row = scroll_region.map_or(0, |(start, _)| start);
         let end_row = scroll_region.map_or(self.height, |(_, end)| end);
+        if !self.alternate_screen
+            && start_row == 0
+            && let Some(scrollback) = self.scrollback.as_mut()
+        {
+            let scrolled_off = lines.min(end_row);
+            scrollback.extend(self.screen_buffer[..scrolled_off].iter().cloned());
+        }
         let buffer = self.current_buffer_mut();
 
         for _ in 0..lines {
@@ -310,6 +362,19 @@ pub struct AnsiParser {
     animation_detected: bool,
     screen_changes: usize,
     last_screen_state: Option<Vec<Vec<StyledChar>>>,
+    /// Complete (newline-terminated) lines already parsed by `parse_incremental`
+    cached_source: String,
+    /// Parsed form of `cached_source`
+    cached_lines: Vec<Line<'static>>,
+    /// Lines parsed line by line so far, to tell cache hits from re-parses
+    lines_parsed: usize,
+    /// Rows that scrolled off the screen while feeding output, as styled lines
+    scrollback_lines: Vec<Line<'static>>,
+    /// The last line fed ended without a carriage return, so the next one starts
+    /// on a new row
+    line_pending: bool,
+    /// Fed output has moved the cursor back over text it already wrote
+    output_rewritten: bool,
 }
 
 impl AnsiParser {
@@ -321,6 +386,12 @@ impl AnsiParser {
             animation_detected: false,
             screen_changes: 0,
             last_screen_state: None,
+            cached_source: String::new(),
+            cached_lines: Vec::new(),
+            lines_parsed: 0,
+            scrollback_lines: Vec::new(),
+            line_pending: false,
+            output_rewritten: false,
         }
     }
 
@@ -333,6 +404,11 @@ impl AnsiParser {
         &self.state
     }
 
+    /// Window title the output has asked for, if any
+    pub fn current_title(&self) -> Option<&str> {
+        self.state.title.as_deref()
+    }
+
     pub fn parse(&mut self, input: &str) -> Vec<Line<'static>> {
         // For most commands, we should just parse the text line by line
         // and only use the full terminal state for complex applications
@@ -352,6 +428,130 @@ impl AnsiParser {
         self.convert_to_lines()
     }
 
+    /// Parse output that grows between calls, such as a running command's. Lines
+    /// parsed by an earlier call whose input this one extends are reused, so only
+    /// the appended lines and the unfinished last line are parsed. The cache is
+    /// dropped when the input no longer extends it, or when it clears the screen,
+    /// moves the cursor home or switches screens, in which case everything is
+    /// parsed again from a fresh terminal state.
+    pub fn parse_incremental(&mut self, input: &str) -> Vec<Line<'static>> {
+        if !self.should_use_simple_parsing(input) {
+            self.reset();
+            return self.parse(input);
+        }
+
+        if !input.starts_with(&self.cached_source) {
+            self.cached_source.clear();
+            self.cached_lines.clear();
+        }
+
+        // Only lines ending in a newline are final; the last one may still grow
+        let complete_len = input.rfind('\n').map_or(0, |index| index + 1);
+        let appended = &input[self.cached_source.len()..complete_len];
+        let appended_lines = self.parse_simple_text(appended);
+        self.cached_lines.extend(appended_lines);
+        self.cached_source.push_str(appended);
+
+        let mut lines = self.cached_lines.clone();
+        lines.extend(self.parse_simple_text(&input[complete_len..]));
+        lines
+    }
+
+    /// Feed a chunk of a running command's output. Terminal state carries over
+    /// between calls, so carriage returns, cursor movement and escape sequences
+    /// split across chunks act on what earlier chunks wrote.
+    pub fn feed(&mut self, input: &str) {
+        self.output_rewritten |= rewrites_output(input);
+        self.state.scrollback.get_or_insert_with(Vec::new);
+
+        let mut handler = VtActionHandler::new(&mut self.state);
+        for byte in input.bytes() {
+            self.parser.parse_byte(byte, &mut handler);
+        }
+
+        let scrolled_off = self
+            .state
+            .scrollback
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        for row in &scrolled_off {
+            let line = self.convert_styled_row_to_line(row);
+            self.scrollback_lines.push(line);
+        }
+    }
+
+    /// Feed one line of output as split by the command reader, which drops the
+    /// newline. A line that ends in a carriage return is redrawn by the next one,
+    /// as is one followed by a line starting with a carriage return, so progress
+    /// bars update in place instead of adding a row per update.
+    pub fn feed_line(&mut self, line: &str) {
+        if self.line_pending && !line.starts_with('\r') {
+            self.feed("\n");
+        }
+        self.feed(line);
+        self.line_pending = !line.ends_with('\r');
+    }
+
+    /// Feed more of the line last fed with `feed_line`, sent by the reader before
+    /// the rest of the line had arrived
+    pub fn extend_line(&mut self, rest: &str) {
+        self.feed(rest);
+        if !rest.is_empty() {
+            self.line_pending = !rest.ends_with('\r');
+        }
+    }
+
+    /// Whether fed output has redrawn text it already wrote, with a carriage
+    /// return, backspace or cursor movement, so it has to be shown as laid out on
+    /// the screen rather than line by line
+    pub fn output_rewritten(&self) -> bool {
+        self.output_rewritten
+    }
+
+    /// What the fed output shows: the rows that scrolled off followed by the screen
+    /// up to its last written row, or just the screen while a full-screen program
+    /// has the alternate screen
+    pub fn screen_lines(&self) -> Vec<Line<'static>> {
+        let buffer = self.state.current_buffer();
+        let is_blank = |row: &Vec<StyledChar>| row.iter().all(|c| c.ch == ' ');
+        let mut end = buffer
+            .iter()
+            .rposition(|row| !is_blank(row))
+            .map_or(0, |i| i + 1);
+
+        let scrollback: &[Line<'static>] = if self.state.alternate_screen {
+            &[]
+        } else {
+            // Blank lines the output moved past are part of it
+            end = end.max(self.state.cursor.row.min(buffer.len()));
+            &self.scrollback_lines
+        };
+        scrollback
+            .iter()
+            .cloned()
+            .chain(
+                buffer[..end]
+                    .iter()
+                    .map(|row| self.convert_styled_row_to_line(row)),
+            )
+            .collect()
+    }
+
+    /// Rows of what `screen_lines` shows that fed output has written up to the
+    /// cursor, counting the cursor's row once text is on it and it is not about
+    /// to be redrawn
+    pub fn rows_written(&self) -> usize {
+        let cursor = &self.state.cursor;
+        let on_row = self.line_pending || cursor.col > 0;
+        self.scrollback_lines.len() + cursor.row + usize::from(on_row)
+    }
+
+    /// Number of lines parsed line by line since the parser was created
+    pub fn lines_parsed(&self) -> usize {
+        self.lines_parsed
+    }
+
     fn should_use_simple_parsing(&self, input: &str) -> bool {
         // Use simple parsing for most text output
         // Only use full terminal emulation for complex escape sequences
@@ -370,6 +570,7 @@ impl AnsiParser {
         input
             .lines()
             .map(|line| {
+                self.lines_parsed += 1;
                 if line.contains('\x1b') || line.contains('\t') || line.contains('\r') {
                     // Use vtparse for this line if it has ANSI sequences or control characters
                     self.parse_line_with_vtparse(line)
@@ -382,60 +583,41 @@ impl AnsiParser {
     }
 
     pub fn parse_line_with_vtparse(&mut self, line: &str) -> Line<'static> {
-        // Handle tabs by converting them to spaces before processing
-        let processed_line = self.expand_tabs(line);
-
         // Get actual terminal size for proper display
         let (term_cols, _term_rows) = crossterm::terminal::size().unwrap_or((80, 24));
 
         // Create a fresh parser and state for single line parsing with actual terminal width
         let mut parser = VTParser::new();
         let mut state = TerminalState::new(term_cols as usize, 1);
+        state.palette = self.state.palette;
 
         // Parse the line
         let mut handler = VtActionHandler::new(&mut state);
-        for byte in processed_line.bytes() {
+        for byte in line.bytes() {
             parser.parse_byte(byte, &mut handler);
         }
+        if state.title.is_some() {
+            self.state.title = state.title.take();
+        }
 
         // Extract the result as styled line
         let buffer = state.current_buffer();
         if let Some(row) = buffer.first() {
             self.convert_styled_row_to_line(row)
         } else {
-            Line::from(processed_line)
+            Line::from(line.to_string())
         }
     }
 
-    fn expand_tabs(&self, line: &str) -> String {
-        // Convert tabs to spaces, respecting 8-character tab stops
-        let mut result = String::new();
-        let mut column = 0;
-
-        for ch in line.chars() {
-            if ch == '\t' {
-                // Calculate spaces needed to reach next tab stop
-                let spaces_to_add = 8 - (column % 8);
-                result.push_str(&" ".repeat(spaces_to_add));
-                column += spaces_to_add;
-            } else {
-                result.push(ch);
-                column += 1;
-            }
-        }
-
-        result
-    }
-
     fn detect_animation(&mut self) {
         let current_state = self.state.current_buffer().clone();
 
-        if let Some(ref last_state) = self.last_screen_state {
-            if current_state != *last_state {
-                self.screen_changes += 1;
-                if self.screen_changes > 5 {
-                    self.animation_detected = true;
-                }
+        if let Some(ref last_state) = self.last_screen_state
+            && current_state != *last_state
+        {
+            self.screen_changes += 1;
+            if self.screen_changes > 5 {
+                self.animation_detected = true;
             }
         }
 
@@ -444,9 +626,19 @@ impl AnsiParser {
 
     fn convert_to_lines(&mut self) -> Vec<Line<'static>> {
         if self.animation_detected || self.state.screen_cleared {
-            // For animations or screen clearing, only return final state
-            let final_lines = self.state.extract_final_output();
-            return final_lines.into_iter().map(Line::from).collect();
+            // For animations or screen clearing, only return the final screen, without
+            // the blank rows around it but keeping its colors
+            let buffer = self.state.current_buffer();
+            let is_blank = |row: &Vec<StyledChar>| row.iter().all(|c| c.ch.is_whitespace());
+            let start = buffer.iter().position(|row| !is_blank(row)).unwrap_or(0);
+            let end = buffer
+                .iter()
+                .rposition(|row| !is_blank(row))
+                .map_or(0, |i| i + 1);
+            return buffer[start..end]
+                .iter()
+                .map(|row| self.convert_styled_row_to_line(row))
+                .collect();
         }
 
         // If we're not in alternate screen mode, return the main screen buffer
@@ -548,15 +740,50 @@ impl AnsiParser {
         }
     }
 
+    /// Map the 16 base color indices to the given palette instead of named colors
+    pub fn set_palette(&mut self, palette: Option<[Color; 16]>) {
+        self.state.palette = palette;
+    }
+
     pub fn reset(&mut self) {
+        let palette = self.state.palette;
         self.state = TerminalState::new(self.state.width, self.state.height);
+        self.state.palette = palette;
         self.accumulated_output.clear();
         self.animation_detected = false;
         self.screen_changes = 0;
         self.last_screen_state = None;
+        self.cached_source.clear();
+        self.cached_lines.clear();
+        self.scrollback_lines.clear();
+        self.line_pending = false;
+        self.output_rewritten = false;
     }
 }
 
+/// Split CSI parameters into `;`-separated groups of `:`-separated sub-parameters.
+/// Omitted parameters are represented as `None`; private markers such as `?` are skipped.
+fn group_csi_params(params: &[CsiParam]) -> Vec<Vec<Option<i64>>> {
+    let mut groups = Vec::new();
+    let mut current = vec![None];
+
+    for param in params {
+        match param {
+            CsiParam::Integer(n) => {
+                if let Some(slot) = current.last_mut() {
+                    *slot = Some(*n);
+                }
+            }
+            CsiParam::P(b':') => current.push(None),
+            CsiParam::P(b';') => groups.push(std::mem::replace(&mut current, vec![None])),
+            CsiParam::P(_) => {}
+        }
+    }
+
+    groups.push(current);
+    groups
+}
+
 struct VtActionHandler<'a> {
     state: &'a mut TerminalState,
 }
@@ -567,15 +794,11 @@ impl<'a> VtActionHandler<'a> {
     }
 
     fn handle_sgr(&mut self, params: &[CsiParam]) {
+        let groups = group_csi_params(params);
         let mut i = 0;
-        while i < params.len() {
-            let value = match params[i] {
-                CsiParam::Integer(n) => n,
-                _ => {
-                    i += 1;
-                    continue;
-                }
-            };
+        while i < groups.len() {
+            // An omitted parameter is treated as 0 (reset)
+            let value = groups[i].first().copied().flatten().unwrap_or(0);
             match value {
                 0 => {
                     // Reset all attributes
@@ -583,132 +806,146 @@ impl<'a> VtActionHandler<'a> {
                     self.state.italic = false;
                     self.state.underline = false;
                     self.state.reverse = false;
+                    self.state.dim = false;
+                    self.state.strikethrough = false;
                     self.state.foreground_color = None;
                     self.state.background_color = None;
                 }
                 1 => self.state.bold = true,
+                2 => self.state.dim = true,
                 3 => self.state.italic = true,
                 4 => self.state.underline = true,
                 7 => self.state.reverse = true,
-                22 => self.state.bold = false,
+                9 => self.state.strikethrough = true,
+                22 => {
+                    // Normal intensity: neither bold nor faint
+                    self.state.bold = false;
+                    self.state.dim = false;
+                }
                 23 => self.state.italic = false,
                 24 => self.state.underline = false,
                 27 => self.state.reverse = false,
-                30 => self.state.foreground_color = Some(Color::Black),
-                31 => self.state.foreground_color = Some(Color::Red),
-                32 => self.state.foreground_color = Some(Color::Green),
-                33 => self.state.foreground_color = Some(Color::Yellow),
-                34 => self.state.foreground_color = Some(Color::Blue),
-                35 => self.state.foreground_color = Some(Color::Magenta),
-                36 => self.state.foreground_color = Some(Color::Cyan),
-                37 => self.state.foreground_color = Some(Color::White),
+                29 => self.state.strikethrough = false,
+                // Base colors go through the palette like `38;5;0-15`
+                30..=37 => {
+                    self.state.foreground_color = Some(self.index_to_color(value as u8 - 30))
+                }
                 38 => {
                     // Extended foreground color
-                    if i + 2 < params.len() {
-                        if let CsiParam::Integer(color_type) = params[i + 2] {
-                            match color_type {
-                                2 => {
-                                    // 24-bit RGB: 38;2;R;G;B (accounting for semicolons as separate params)
-                                    if i + 8 < params.len() {
-                                        if let (
-                                            CsiParam::Integer(r),
-                                            CsiParam::Integer(g),
-                                            CsiParam::Integer(b),
-                                        ) = (&params[i + 4], &params[i + 6], &params[i + 8])
-                                        {
-                                            self.state.foreground_color =
-                                                Some(Color::Rgb(*r as u8, *g as u8, *b as u8));
-                                            i += 8; // Skip all RGB parameters including semicolons
-                                        } else {
-                                            i += 2; // Skip to color_type param
-                                        }
-                                    } else {
-                                        i += 2; // Skip to color_type param
-                                    }
-                                }
-                                5 => {
-                                    // 256-color: 38;5;n
-                                    if i + 4 < params.len() {
-                                        if let CsiParam::Integer(color_index) = params[i + 4] {
-                                            self.state.foreground_color =
-                                                Some(self.index_to_color(color_index as u8));
-                                            i += 4; // Skip all 256-color parameters including semicolons
-                                        } else {
-                                            i += 2; // Skip to color_type param
-                                        }
-                                    } else {
-                                        i += 2; // Skip to color_type param
-                                    }
-                                }
-                                _ => i += 2, // Skip unknown color type
-                            }
-                        } else {
-                            i += 2; // Skip if color_type is not an integer
-                        }
+                    let (color, consumed) = self.parse_extended_color(&groups, i);
+                    if let Some(color) = color {
+                        self.state.foreground_color = Some(color);
                     }
+                    i += consumed;
                 }
                 39 => self.state.foreground_color = None,
-                40 => self.state.background_color = Some(Color::Black),
-                41 => self.state.background_color = Some(Color::Red),
-                42 => self.state.background_color = Some(Color::Green),
-                43 => self.state.background_color = Some(Color::Yellow),
-                44 => self.state.background_color = Some(Color::Blue),
-                45 => self.state.background_color = Some(Color::Magenta),
-                46 => self.state.background_color = Some(Color::Cyan),
-                47 => self.state.background_color = Some(Color::White),
+                40..=47 => {
+                    self.state.background_color = Some(self.index_to_color(value as u8 - 40))
+                }
                 48 => {
                     // Extended background color
-                    if i + 2 < params.len() {
-                        if let CsiParam::Integer(color_type) = params[i + 2] {
-                            match color_type {
-                                2 => {
-                                    // 24-bit RGB: 48;2;R;G;B (accounting for semicolons as separate params)
-                                    if i + 8 < params.len() {
-                                        if let (
-                                            CsiParam::Integer(r),
-                                            CsiParam::Integer(g),
-                                            CsiParam::Integer(b),
-                                        ) = (&params[i + 4], &params[i + 6], &params[i + 8])
-                                        {
-                                            self.state.background_color =
-                                                Some(Color::Rgb(*r as u8, *g as u8, *b as u8));
-                                            i += 8; // Skip all RGB parameters including semicolons
-                                        } else {
-                                            i += 2; // Skip to color_type param
-                                        }
-                                    } else {
-                                        i += 2; // Skip to color_type param
-                                    }
-                                }
-                                5 => {
-                                    // 256-color: 48;5;n
-                                    if i + 4 < params.len() {
-                                        if let CsiParam::Integer(color_index) = params[i + 4] {
-                                            self.state.background_color =
-                                                Some(self.index_to_color(color_index as u8));
-                                            i += 4; // Skip all 256-color parameters including semicolons
-                                        } else {
-                                            i += 2; // Skip to color_type param
-                                        }
-                                    } else {
-                                        i += 2; // Skip to color_type param
-                                    }
-                                }
-                                _ => i += 2, // Skip unknown color type
-                            }
-                        } else {
-                            i += 2; // Skip if color_type is not an integer
-                        }
+                    let (color, consumed) = self.parse_extended_color(&groups, i);
+                    if let Some(color) = color {
+                        self.state.background_color = Some(color);
                     }
+                    i += consumed;
                 }
                 49 => self.state.background_color = None,
+                // Bright colors are indices 8-15
+                90..=97 => {
+                    self.state.foreground_color = Some(self.index_to_color(value as u8 - 82))
+                }
+                100..=107 => {
+                    self.state.background_color = Some(self.index_to_color(value as u8 - 92))
+                }
                 _ => {}
             }
             i += 1;
         }
     }
 
+    /// Parse an extended color (SGR 38/48) starting at `groups[index]`.
+    ///
+    /// Supports the colon form (`38:5:N`, `38:2::R:G:B`, `38:2:R:G:B`) where the
+    /// color is contained in a single group, and the semicolon form (`38;5;N`,
+    /// `38;2;R;G;B`) where it spans the following groups. An omitted color
+    /// component, as in `38:2::255::0`, is 0. Returns the color and the number of
+    /// additional groups consumed.
+    fn parse_extended_color(
+        &self,
+        groups: &[Vec<Option<i64>>],
+        index: usize,
+    ) -> (Option<Color>, usize) {
+        let group = &groups[index];
+
+        if group.len() > 1 {
+            // Colon form: everything is in sub-parameters of this group
+            let color = match group[1] {
+                Some(5) => group
+                    .get(2)
+                    .copied()
+                    .flatten()
+                    .map(|n| self.index_to_color(n as u8)),
+                Some(2) => {
+                    // With a color space id the RGB values are shifted by one
+                    let rgb = if group.len() >= 6 {
+                        &group[3..6]
+                    } else if group.len() == 5 {
+                        &group[2..5]
+                    } else {
+                        &[][..]
+                    };
+                    match rgb {
+                        [r, g, b] => Some(Color::Rgb(
+                            r.unwrap_or(0) as u8,
+                            g.unwrap_or(0) as u8,
+                            b.unwrap_or(0) as u8,
+                        )),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            return (color, 0);
+        }
+
+        // Semicolon form: the color type and values are the following groups
+        let value_at = |offset: usize| -> Option<i64> {
+            groups
+                .get(index + offset)
+                .and_then(|g| g.first().copied().flatten())
+        };
+        let component_at = |offset: usize| -> Option<i64> {
+            groups
+                .get(index + offset)
+                .map(|g| g.first().copied().flatten().unwrap_or(0))
+        };
+        let remaining = groups.len() - index - 1;
+
+        match value_at(1) {
+            Some(5) => {
+                let color = value_at(2).map(|n| self.index_to_color(n as u8));
+                (color, remaining.min(2))
+            }
+            Some(2) => {
+                let color = match (component_at(2), component_at(3), component_at(4)) {
+                    (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r as u8, g as u8, b as u8)),
+                    _ => None,
+                };
+                (color, remaining.min(4))
+            }
+            _ => (None, remaining.min(1)),
+        }
+    }
+
     fn index_to_color(&self, index: u8) -> Color {
+        // Use the themed palette for the base colors when one is configured
+        if let Some(palette) = &self.state.palette
+            && index < 16
+        {
+            return palette[index as usize];
+        }
+
         // Convert 256-color index to RGB
         match index {
             // Standard colors (0-15)
@@ -736,7 +973,7 @@ impl<'a> VtActionHandler<'a> {
                 let b = (n % 6) * 51;
                 Color::Rgb(r, g, b)
             }
-            // Grayscale ramp (232-255)
+            // Grayscale ramp (232-255): 24 steps from 8 to 238
             232..=255 => {
                 let gray = (index - 232) * 10 + 8;
                 Color::Rgb(gray, gray, gray)
@@ -749,11 +986,13 @@ impl<'a> VtActionHandler<'a> {
             return;
         }
 
+        // vtparse reports `;` separators and private markers as parameters of their own
+        let groups = group_csi_params(params);
         let get_param = |i: usize| -> i64 {
-            match params.get(i) {
-                Some(CsiParam::Integer(n)) => *n,
-                _ => 0,
-            }
+            groups
+                .get(i)
+                .and_then(|group| group.first().copied().flatten())
+                .unwrap_or(0)
         };
 
         match final_byte {
@@ -853,42 +1092,47 @@ impl<'a> VtActionHandler<'a> {
                 // Restore cursor position
                 self.state.restore_cursor();
             }
+            b'p' if params.contains(&CsiParam::P(b'!')) => {
+                // DECSTR - soft terminal reset
+                self.state.soft_reset();
+            }
             b'r' => {
                 // Set scroll region
                 let top = get_param(0).max(1) as usize - 1;
-                let bottom = get_param(1).max(self.state.height as i64) as usize - 1;
-                self.state.scroll_region = Some((top, bottom.min(self.state.height - 1)));
+                // An omitted bottom margin means the last row
+                let bottom = match get_param(1) {
+                    n if n < 1 => self.state.height,
+                    n => n as usize,
+                };
+                let bottom = bottom.min(self.state.height) - 1;
+                self.state.scroll_region = Some((top.min(bottom), bottom));
             }
-            b'h' => {
-                // Set modes
-                if !params.is_empty() {
-                    match get_param(0) {
-                        1049 => {
-                            // Enable alternate screen buffer
-                            self.state.switch_to_alternate_screen();
-                        }
-                        1047 => {
-                            // Enable alternate screen buffer (xterm)
-                            self.state.switch_to_alternate_screen();
-                        }
-                        _ => {}
+            // Set modes
+            b'h' if !params.is_empty() => {
+                match get_param(0) {
+                    1049 => {
+                        // Enable alternate screen buffer
+                        self.state.switch_to_alternate_screen();
                     }
+                    1047 => {
+                        // Enable alternate screen buffer (xterm)
+                        self.state.switch_to_alternate_screen();
+                    }
+                    _ => {}
                 }
             }
-            b'l' => {
-                // Reset modes
-                if !params.is_empty() {
-                    match get_param(0) {
-                        1049 => {
-                            // Disable alternate screen buffer
-                            self.state.switch_to_main_screen();
-                        }
-                        1047 => {
-                            // Disable alternate screen buffer (xterm)
-                            self.state.switch_to_main_screen();
-                        }
-                        _ => {}
+            // Reset modes
+            b'l' if !params.is_empty() => {
+                match get_param(0) {
+                    1049 => {
+                        // Disable alternate screen buffer
+                        self.state.switch_to_main_screen();
+                    }
+                    1047 => {
+                        // Disable alternate screen buffer (xterm)
+                        self.state.switch_to_main_screen();
                     }
+                    _ => {}
                 }
             }
             _ => {}
@@ -913,7 +1157,9 @@ impl<'a> VTActor for VtActionHandler<'a> {
             b'8' => self.state.restore_cursor(),
             b'c' => {
                 // Reset terminal
+                let palette = self.state.palette;
                 *self.state = TerminalState::new(self.state.width, self.state.height);
+                self.state.palette = palette;
             }
             b'D' => {
                 // Index (cursor down with scroll)
@@ -972,16 +1218,18 @@ impl<'a> VTActor for VtActionHandler<'a> {
     fn execute_c0_or_c1(&mut self, byte: u8) {
         match byte {
             0x07 => {} // BEL - Bell
-            0x08 => {
-                // BS - Backspace
-                if self.state.cursor.col > 0 {
-                    self.state.cursor.col -= 1;
-                }
+            // BS - Backspace
+            0x08 if self.state.cursor.col > 0 => {
+                self.state.cursor.col -= 1;
             }
             0x0C => {
                 // FF - Form Feed
                 self.state.clear_screen();
             }
+            // HT, LF, VT and CR arrive as control codes rather than printed characters
+            0x09 => self.print('\t'),
+            0x0A | 0x0B => self.print('\n'),
+            0x0D => self.print('\r'),
             _ => {}
         }
     }
@@ -998,8 +1246,14 @@ impl<'a> VTActor for VtActionHandler<'a> {
         // Not implemented for now
     }
 
-    fn osc_dispatch(&mut self, _params: &[&[u8]]) {
-        // Not implemented for now
+    fn osc_dispatch(&mut self, params: &[&[u8]]) {
+        // OSC 0 sets the icon name and window title, OSC 2 only the title. The title
+        // may itself contain `;`, which splits it into several params.
+        if let [code, title @ ..] = params
+            && matches!(*code, b"0" | b"2")
+        {
+            self.state.title = Some(String::from_utf8_lossy(&title.join(&b';')).into_owned());
+        }
     }
 
     fn apc_dispatch(&mut self, _data: Vec<u8>) {
@@ -1007,6 +1261,144 @@ impl<'a> VTActor for VtActionHandler<'a> {
     }
 }
 
+/// Collects the printable text of a line, dropping escape sequences
+struct TextCollector {
+    text: String,
+}
+
+impl VTActor for TextCollector {
+    fn print(&mut self, ch: char) {
+        self.text.push(ch);
+    }
+
+    fn execute_c0_or_c1(&mut self, byte: u8) {
+        if byte == b'\t' {
+            self.text.push('\t');
+        }
+    }
+
+    fn csi_dispatch(&mut self, _params: &[CsiParam], _ignore: bool, _final_byte: u8) {}
+
+    fn esc_dispatch(
+        &mut self,
+        _params: &[i64],
+        _intermediate_bytes: &[u8],
+        _ignore: bool,
+        _final_byte: u8,
+    ) {
+    }
+
+    fn dcs_hook(&mut self, _byte: u8, _params: &[i64], _intermediate_bytes: &[u8], _ignore: bool) {}
+
+    fn dcs_put(&mut self, _byte: u8) {}
+
+    fn dcs_unhook(&mut self) {}
+
+    fn osc_dispatch(&mut self, _params: &[&[u8]]) {}
+
+    fn apc_dispatch(&mut self, _data: Vec<u8>) {}
+}
+
+/// Spots control codes that move the cursor back over written text
+struct RewriteDetector {
+    rewrites: bool,
+}
+
+impl VTActor for RewriteDetector {
+    fn print(&mut self, _ch: char) {}
+
+    fn execute_c0_or_c1(&mut self, byte: u8) {
+        // Carriage return and backspace
+        if byte == 0x0D || byte == 0x08 {
+            self.rewrites = true;
+        }
+    }
+
+    fn csi_dispatch(&mut self, _params: &[CsiParam], _ignore: bool, final_byte: u8) {
+        // Cursor movement and erasing
+        if b"ABCDEFGHJKdf".contains(&final_byte) {
+            self.rewrites = true;
+        }
+    }
+
+    fn esc_dispatch(
+        &mut self,
+        _params: &[i64],
+        _intermediate_bytes: &[u8],
+        _ignore: bool,
+        _final_byte: u8,
+    ) {
+    }
+
+    fn dcs_hook(&mut self, _byte: u8, _params: &[i64], _intermediate_bytes: &[u8], _ignore: bool) {}
+
+    fn dcs_put(&mut self, _byte: u8) {}
+
+    fn dcs_unhook(&mut self) {}
+
+    fn osc_dispatch(&mut self, _params: &[&[u8]]) {}
+
+    fn apc_dispatch(&mut self, _data: Vec<u8>) {}
+}
+
+/// Whether `text` moves the cursor back over what it wrote (carriage return,
+/// backspace, cursor movement or erasing), so it cannot be shown line by line
+pub fn rewrites_output(text: &str) -> bool {
+    let mut parser = VTParser::new();
+    let mut detector = RewriteDetector { rewrites: false };
+    parser.parse(text.as_bytes(), &mut detector);
+    detector.rewrites
+}
+
+/// Text of `line` as it appears on screen: escape sequences are removed, so
+/// column offsets into the result line up with the rendered spans
+pub fn strip_ansi(line: &str) -> String {
+    let mut parser = VTParser::new();
+    let mut collector = TextCollector {
+        text: String::new(),
+    };
+    parser.parse(line.as_bytes(), &mut collector);
+    collector.text
+}
+
+/// Sequences that switch to the alternate screen buffer
+const ALTERNATE_SCREEN_ENTER: [&str; 2] = ["\x1b[?1049h", "\x1b[?1047h"];
+/// Sequences that switch back to the main screen buffer
+const ALTERNATE_SCREEN_EXIT: [&str; 2] = ["\x1b[?1049l", "\x1b[?1047l"];
+
+/// Earliest occurrence of any of `patterns` in `text`, with the pattern's length
+fn find_first(text: &str, patterns: &[&str]) -> Option<(usize, usize)> {
+    patterns
+        .iter()
+        .filter_map(|pattern| text.find(pattern).map(|index| (index, pattern.len())))
+        .min()
+}
+
+/// Remove everything drawn on the alternate screen, keeping main-screen output
+/// before and after it. An alternate screen that has not been left yet is kept,
+/// so a program that is still running remains visible.
+pub fn strip_alternate_screen(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((start, enter_len)) = find_first(rest, &ALTERNATE_SCREEN_ENTER) {
+        let inside = &rest[start + enter_len..];
+        let Some((end, exit_len)) = find_first(inside, &ALTERNATE_SCREEN_EXIT) else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        rest = &inside[end + exit_len..];
+        // A segment that took up whole lines should not leave an empty line behind
+        if result.is_empty() || result.ends_with('\n') {
+            rest = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+                .unwrap_or(rest);
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1107,7 +1499,7 @@ mod tests {
         // Test cursor up/down/left/right
         let cursor_moves = "\x1b[2AUp\x1b[2BDown\x1b[2CRight\x1b[2DLeft";
         let lines = parser.parse(cursor_moves);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1134,12 +1526,12 @@ mod tests {
         // Test scroll up
         let scroll_up = "\x1b[3SScrolled up";
         let lines = parser.parse(scroll_up);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
 
         // Test scroll down
         let scroll_down = "\x1b[3TScrolled down";
         let lines = parser.parse(scroll_down);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1149,12 +1541,12 @@ mod tests {
         // Test cursor save and restore
         let save_restore = "\x1b[10;10H\x1b[sHello\x1b[uWorld";
         let lines = parser.parse(save_restore);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
 
         // Test ESC 7 and ESC 8
         let esc_save_restore = "\x1b7Position\x1b8Restored";
         let lines = parser.parse(esc_save_restore);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1164,12 +1556,12 @@ mod tests {
         // Test alternate screen enable/disable
         let alt_screen = "\x1b[?1049hAlternate screen\x1b[?1049lMain screen";
         let lines = parser.parse(alt_screen);
-        assert!(lines.len() >= 1);
+        assert!(!lines.is_empty());
 
         // Test xterm alternate screen
         let xterm_alt = "\x1b[?1047hXterm alternate\x1b[?1047lXterm main";
         let lines = parser.parse(xterm_alt);
-        assert!(lines.len() >= 1);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1179,7 +1571,7 @@ mod tests {
         // Test terminal reset
         let reset = "\x1bcReset terminal";
         let lines = parser.parse(reset);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1206,7 +1598,7 @@ mod tests {
         // Test backspace
         let bs = "ABC\x08D";
         let lines = parser.parse(bs);
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
     }
 
     #[test]
@@ -1253,6 +1645,32 @@ mod tests {
         assert_eq!(lines[2].to_string(), "Line 3");
     }
 
+    #[test]
+    fn test_osc_title_sequences() {
+        let mut parser = AnsiParser::new(80, 24);
+        assert_eq!(parser.current_title(), None);
+
+        // OSC 2 terminated by ST
+        let lines = parser.parse("\x1b]2;building…\x1b\\done");
+        assert_eq!(parser.current_title(), Some("building…"));
+        assert_eq!(lines[0].to_string().trim_end(), "done");
+
+        // OSC 0 terminated by BEL; a `;` in the title is kept
+        parser.parse("\x1b]0;user@host: ~/src; vim\x07");
+        assert_eq!(parser.current_title(), Some("user@host: ~/src; vim"));
+
+        // OSC 1 only sets the icon name
+        parser.parse("\x1b]1;icon\x07");
+        assert_eq!(parser.current_title(), Some("user@host: ~/src; vim"));
+
+        // Full-screen output sets it through the terminal state too
+        parser.parse("\x1b[?1049h\x1b]2;top\x07\x1b[H");
+        assert_eq!(parser.get_terminal_state().title.as_deref(), Some("top"));
+
+        parser.reset();
+        assert_eq!(parser.current_title(), None);
+    }
+
     #[test]
     fn test_parser_reset() {
         let mut parser = AnsiParser::new(80, 24);
@@ -1510,6 +1928,53 @@ mod tests {
         assert_eq!(state.foreground_color, None);
     }
 
+    #[test]
+    fn test_line_break_controls_move_the_cursor() {
+        // Cursor home makes the parser emulate the terminal instead of splitting lines
+        let rows = |input: &str| -> Vec<String> {
+            let mut parser = AnsiParser::new(80, 24);
+            let mut rows: Vec<String> = parser
+                .parse(&format!("\x1b[H{input}"))
+                .iter()
+                .map(|line| {
+                    line.spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect()
+                })
+                .map(|row: String| row.trim_end().to_string())
+                .collect();
+            while rows.last().is_some_and(|row| row.is_empty()) {
+                rows.pop();
+            }
+            rows
+        };
+
+        // LF and VT move down a row, CR returns to the first column
+        assert_eq!(rows("one\r\ntwo"), ["one", "two"]);
+        assert_eq!(rows("one\r\x0btwo"), ["one", "two"]);
+        assert_eq!(rows("abc\rX\r\nend"), ["Xbc", "end"]);
+    }
+
+    #[test]
+    fn test_cleared_screen_keeps_colors() {
+        let mut parser = AnsiParser::new(80, 24);
+        let lines =
+            parser.parse("\x1b[31mred\x1b[0m\x1b[2J\x1b[H\x1b[32mgreen\x1b[0m\r\n\r\nplain");
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].spans[0].content, "green");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Green));
+        assert!(
+            lines[1]
+                .spans
+                .iter()
+                .all(|span| span.content.trim().is_empty())
+        );
+        assert_eq!(lines[2].spans[0].content, "plain");
+        assert_eq!(lines[2].spans[0].style.fg, None);
+    }
+
     #[test]
     fn test_extract_final_output() {
         let mut state = TerminalState::new(80, 24);
@@ -1906,6 +2371,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dim_and_strikethrough() {
+        use ratatui::style::Modifier;
+        use vtparse::CsiParam;
+
+        let mut parser = AnsiParser::new(80, 24);
+        let lines = parser.parse("\x1b[2mfaint\x1b[0m");
+        let span = lines[0]
+            .spans
+            .iter()
+            .find(|span| span.content.contains("faint"))
+            .unwrap();
+        assert!(span.style.add_modifier.contains(Modifier::DIM));
+
+        let lines = parser.parse("\x1b[9mgone\x1b[29m kept");
+        let gone = lines[0].spans.iter().find(|span| span.content == "gone");
+        assert!(
+            gone.unwrap()
+                .style
+                .add_modifier
+                .contains(Modifier::CROSSED_OUT)
+        );
+        let kept = lines[0]
+            .spans
+            .iter()
+            .find(|span| span.content.contains("kept"))
+            .unwrap();
+        assert!(!kept.style.add_modifier.contains(Modifier::CROSSED_OUT));
+
+        // SGR 22 clears faint along with bold
+        let mut state = TerminalState::new(80, 1);
+        {
+            let mut handler = VtActionHandler::new(&mut state);
+            handler.handle_sgr(&[CsiParam::Integer(2)]);
+            assert!(handler.state.dim);
+            handler.handle_sgr(&[CsiParam::Integer(22)]);
+        }
+        assert!(!state.dim);
+    }
+
     #[test]
     fn test_all_text_modifiers() {
         use vtparse::CsiParam;
@@ -2177,6 +2682,78 @@ mod tests {
         assert_eq!(handler.index_to_color(255), Color::Rgb(238, 238, 238));
     }
 
+    #[test]
+    fn test_grayscale_ramp_steps() {
+        let mut state = TerminalState::new(80, 24);
+        let handler = VtActionHandler::new(&mut state);
+
+        // xterm's ramp has 24 evenly spaced steps: 8, 18, ..., 238
+        for (step, index) in (232..=255u8).enumerate() {
+            let gray = 8 + 10 * step as u8;
+            assert_eq!(
+                handler.index_to_color(index),
+                Color::Rgb(gray, gray, gray),
+                "Failed for grayscale index {}",
+                index
+            );
+        }
+    }
+
+    #[test]
+    fn test_themed_vs_default_base_color_mapping() {
+        use crate::tui::theme::Theme;
+
+        let theme = Theme::default();
+        let input = "\x1b[38;5;1mRed\x1b[0m";
+
+        // Default mapping uses ratatui's named colors
+        let mut default_parser = AnsiParser::new(80, 24);
+        let lines = default_parser.parse(input);
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Red));
+
+        // Themed mapping uses the palette's RGB value
+        let mut themed_parser = AnsiParser::new(80, 24);
+        themed_parser.set_palette(Some(theme.palette));
+        let lines = themed_parser.parse(input);
+        assert_eq!(lines[0].spans[0].style.fg, Some(theme.palette[1]));
+
+        // Indices outside the base range are unaffected by the palette
+        let lines = themed_parser.parse("\x1b[38;5;196mBright\x1b[0m");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+
+        // The palette survives a parser reset
+        themed_parser.reset();
+        let lines = themed_parser.parse(input);
+        assert_eq!(lines[0].spans[0].style.fg, Some(theme.palette[1]));
+    }
+
+    #[test]
+    fn test_themed_sgr_base_and_bright_colors() {
+        use crate::tui::theme::Theme;
+
+        let theme = Theme::default();
+        let mut parser = AnsiParser::new(80, 24);
+        parser.set_palette(Some(theme.palette));
+
+        for (input, fg, bg) in [
+            ("\x1b[31;42mX\x1b[0m", 1, 2),
+            ("\x1b[37;40mX\x1b[0m", 7, 0),
+            ("\x1b[91;102mX\x1b[0m", 9, 10),
+            ("\x1b[97;107mX\x1b[0m", 15, 15),
+        ] {
+            let lines = parser.parse(input);
+            let style = lines[0].spans[0].style;
+            assert_eq!(style.fg, Some(theme.palette[fg]), "foreground of {input:?}");
+            assert_eq!(style.bg, Some(theme.palette[bg]), "background of {input:?}");
+        }
+
+        // Without a palette the named colors are used
+        let mut parser = AnsiParser::new(80, 24);
+        let lines = parser.parse("\x1b[32;100mX\x1b[0m");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Green));
+        assert_eq!(lines[0].spans[0].style.bg, Some(Color::DarkGray));
+    }
+
     #[test]
     fn test_original_bug_fix_integration() {
         // Test the original issue from color-1.txt file
@@ -2312,4 +2889,279 @@ mod tests {
         assert!(rgb_green, "Should have RGB green color");
         assert!(basic_blue, "Should have basic blue color");
     }
+
+    fn first_span_style(input: &str) -> Style {
+        let mut parser = AnsiParser::new(80, 24);
+        let lines = parser.parse(input);
+        assert_eq!(lines.len(), 1);
+        lines[0].spans[0].style
+    }
+
+    #[test]
+    fn test_colon_rgb_with_empty_colorspace() {
+        let style = first_span_style("\x1b[38:2::255:0:0mred\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn test_colon_rgb_with_colorspace_id() {
+        let style = first_span_style("\x1b[38:2:0:10:20:30mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(10, 20, 30)));
+    }
+
+    #[test]
+    fn test_colon_rgb_without_colorspace() {
+        let style = first_span_style("\x1b[48:2:1:2:3mtext\x1b[0m");
+        assert_eq!(style.bg, Some(Color::Rgb(1, 2, 3)));
+    }
+
+    #[test]
+    fn test_colon_256_color() {
+        let style = first_span_style("\x1b[38:5:196;48:5:21mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(style.bg, Some(Color::Rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn test_colon_color_followed_by_other_attributes() {
+        // Sub-parameters must not swallow the following `;`-separated attributes
+        let style = first_span_style("\x1b[38:2::255:0:0;1;4mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+        assert!(style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn test_semicolon_color_followed_by_other_attributes() {
+        let style = first_span_style("\x1b[38;2;1;2;3;48;5;9;1mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(style.bg, Some(Color::LightRed));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_semicolon_and_colon_forms_agree() {
+        let pairs = [
+            ("\x1b[38;2;12;34;56m", "\x1b[38:2::12:34:56m"),
+            ("\x1b[38;2;12;34;56m", "\x1b[38:2:12:34:56m"),
+            ("\x1b[48;2;200;100;0m", "\x1b[48:2::200:100:0m"),
+            ("\x1b[38;5;208m", "\x1b[38:5:208m"),
+            ("\x1b[48;5;33m", "\x1b[48:5:33m"),
+        ];
+        for (semicolon, colon) in pairs {
+            let semicolon_style = first_span_style(&format!("{semicolon}text\x1b[0m"));
+            let colon_style = first_span_style(&format!("{colon}text\x1b[0m"));
+            assert_eq!(semicolon_style, colon_style, "{colon:?}");
+        }
+
+        let style = first_span_style("\x1b[38;2;12;34;56mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(12, 34, 56)));
+    }
+
+    #[test]
+    fn test_omitted_rgb_components_are_zero() {
+        let style = first_span_style("\x1b[38:2::255::7mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 7)));
+        let style = first_span_style("\x1b[38;2;;128;mtext\x1b[0m");
+        assert_eq!(style.fg, Some(Color::Rgb(0, 128, 0)));
+    }
+
+    #[test]
+    fn test_incomplete_extended_color_is_ignored() {
+        let style = first_span_style("\x1b[38:2:255mtext");
+        assert_eq!(style.fg, None);
+        let style = first_span_style("\x1b[38;5mtext");
+        assert_eq!(style.fg, None);
+    }
+
+    #[test]
+    fn test_strip_ansi_keeps_only_visible_text() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(
+            strip_ansi("\x1b[1;31merror\x1b[0m: \x1b[38:2::1:2:3mfile\x1b[0m"),
+            "error: file"
+        );
+        assert_eq!(strip_ansi("\x1b]0;title\x07a\tb"), "a\tb");
+    }
+
+    /// Feed `input` through the full terminal emulator into `state`
+    fn feed(state: &mut TerminalState, input: &str) {
+        let mut parser = VTParser::new();
+        let mut handler = VtActionHandler::new(state);
+        parser.parse(input.as_bytes(), &mut handler);
+    }
+
+    #[test]
+    fn test_decstr_resets_attributes_but_keeps_buffer() {
+        let mut state = TerminalState::new(80, 24);
+        feed(
+            &mut state,
+            "\x1b[1;4;31;44mstyled\x1b[5;20r\x1b[4h\x1b[?25l\x1b7\x1b[!p",
+        );
+
+        assert!(!state.bold);
+        assert!(!state.underline);
+        assert_eq!(state.foreground_color, None);
+        assert_eq!(state.background_color, None);
+        assert_eq!(state.scroll_region, None);
+        assert!(state.saved_cursor.is_none());
+        assert!(state.cursor_visible);
+        // Screen contents and cursor position survive a soft reset
+        let text: String = state.current_buffer()[0][..6]
+            .iter()
+            .map(|cell| cell.ch)
+            .collect();
+        assert_eq!(text, "styled");
+        assert_eq!(state.cursor.col, 6);
+
+        // Text written after the reset uses default attributes
+        feed(&mut state, "x");
+        assert_eq!(state.current_buffer()[0][6].style, Style::default());
+    }
+
+    #[test]
+    fn test_main_screen_switch_resets_scroll_region() {
+        let mut state = TerminalState::new(80, 24);
+        feed(&mut state, "\x1b[?1049h\x1b[3;10r");
+        assert!(state.alternate_screen);
+        assert_eq!(state.scroll_region, Some((2, 9)));
+
+        feed(&mut state, "\x1b[?1049l");
+        assert!(!state.alternate_screen);
+        assert_eq!(state.scroll_region, None);
+    }
+
+    #[test]
+    fn test_cursor_position_reads_both_parameters() {
+        let mut state = TerminalState::new(80, 24);
+        feed(&mut state, "\x1b[5;12H");
+        assert_eq!((state.cursor.row, state.cursor.col), (4, 11));
+    }
+
+    #[test]
+    fn test_incremental_parse_reuses_earlier_lines() {
+        let mut parser = AnsiParser::new(80, 24);
+        let mut output = String::new();
+
+        // 10k lines appended in batches, as a running command produces them
+        for batch in 0..100 {
+            for i in 0..100 {
+                output.push_str(&format!("\x1b[32mline {}\x1b[0m\n", batch * 100 + i));
+            }
+            let lines = parser.parse_incremental(&output);
+            assert_eq!(lines.len(), (batch + 1) * 100);
+        }
+
+        // Every line was parsed exactly once
+        assert_eq!(parser.lines_parsed(), 10_000);
+
+        let mut fresh = AnsiParser::new(80, 24);
+        assert_eq!(parser.parse_incremental(&output), fresh.parse(&output));
+    }
+
+    #[test]
+    fn test_incremental_parse_reparses_unfinished_line() {
+        let mut parser = AnsiParser::new(80, 24);
+        parser.parse_incremental("one\ntw");
+        assert_eq!(parser.lines_parsed(), 2);
+
+        let lines = parser.parse_incremental("one\ntwo\nthree");
+        assert_eq!(
+            lines,
+            vec![Line::from("one"), Line::from("two"), Line::from("three")]
+        );
+        // "one" was kept; "two" and "three" were parsed
+        assert_eq!(parser.lines_parsed(), 4);
+    }
+
+    #[test]
+    fn test_incremental_parse_cache_invalidation() {
+        let mut parser = AnsiParser::new(80, 24);
+        parser.parse_incremental("first\nsecond\n");
+        assert_eq!(parser.lines_parsed(), 2);
+
+        // Input that does not extend the cached text is parsed from the start
+        let lines = parser.parse_incremental("other\nsecond\n");
+        assert_eq!(lines, vec![Line::from("other"), Line::from("second")]);
+        assert_eq!(parser.lines_parsed(), 4);
+
+        // Clearing the screen drops the cache and parses with full emulation
+        let lines = parser.parse_incremental("other\nsecond\n\x1b[2J\x1b[Hfinal");
+        assert_eq!(lines, vec![Line::from("final")]);
+        assert_eq!(parser.lines_parsed(), 4);
+
+        let lines = parser.parse_incremental("next\n");
+        assert_eq!(lines, vec![Line::from("next")]);
+        assert_eq!(parser.lines_parsed(), 5);
+    }
+
+    fn line_texts(lines: &[Line<'static>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_feed_keeps_state_across_chunks() {
+        let mut parser = AnsiParser::new(80, 24);
+        // An escape sequence and a redraw split over several chunks
+        parser.feed("50%\x1b[3");
+        parser.feed("1mfailed\x1b[0m\r");
+        parser.feed("ok");
+
+        let lines = parser.screen_lines();
+        assert_eq!(line_texts(&lines), vec!["ok%failed"]);
+        assert_eq!(lines[0].spans[1].style.fg, Some(Color::Red));
+        assert!(parser.output_rewritten());
+    }
+
+    #[test]
+    fn test_feed_line_redraws_progress() {
+        let mut parser = AnsiParser::new(80, 24);
+        parser.feed_line("start");
+        assert!(!parser.output_rewritten());
+        parser.feed_line("10%\r");
+        parser.feed_line("20%\r");
+        parser.feed_line("30%");
+        parser.feed_line("\r40%");
+        parser.feed_line("");
+        parser.feed_line("end");
+
+        assert_eq!(
+            line_texts(&parser.screen_lines()),
+            vec!["start", "40%", "", "end"]
+        );
+        assert!(parser.output_rewritten());
+    }
+
+    #[test]
+    fn test_screen_lines_keep_scrolled_off_rows() {
+        let mut parser = AnsiParser::new(20, 3);
+        for i in 0..10 {
+            parser.feed_line(&format!("line {i}"));
+        }
+
+        let expected: Vec<String> = (0..10).map(|i| format!("line {i}")).collect();
+        assert_eq!(line_texts(&parser.screen_lines()), expected);
+
+        // The alternate screen shows only what is drawn on it
+        parser.feed("\x1b[?1049h\x1b[Hmenu");
+        assert_eq!(line_texts(&parser.screen_lines()), vec!["menu"]);
+        parser.feed("\x1b[?1049l");
+        assert_eq!(line_texts(&parser.screen_lines()), expected);
+    }
+
+    #[test]
+    fn test_rewrites_output() {
+        assert!(!rewrites_output("plain \x1b[31mred\x1b[0m text\n"));
+        assert!(rewrites_output("10%\r20%"));
+        assert!(rewrites_output("abc\x08d"));
+        assert!(rewrites_output("\x1b[2K\x1b[1Gdone"));
+    }
 }
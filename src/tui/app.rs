@@ -1,22 +1,76 @@
-use crate::db::models::{Priority, Task, TaskSource, TaskStatus};
-use crate::db::operations;
-use crate::history::HistoryManager;
-use crate::tui::ansi_parser::AnsiParser;
-use crate::tui::completion::{CompletionEngine, CompletionState};
-use crate::tui::views::terminal::CommandEntry;
-use portable_pty::{CommandBuilder, PtySize};
-use regex::Regex;
+use crate::config::settings::{
+    DEFAULT_MAX_SCROLLBACK_LINES, DEFAULT_SEARCH_REGEX_SIZE_LIMIT, RightClickAction,
+    SearchScrollMode, SettingChange, Settings, StartMode, StderrMode, format_setting_changes,
+};
+use crate::config::state;
+use crate::db::backup;
+use crate::db::database_directory;
+use crate::db::models::{Priority, Task, TaskStatus};
+use crate::db::operations::{self, TaskFilter};
+use crate::history::{HistoryManager, commands_per_day, exit_code_counts, most_run_commands};
+use crate::tui::ansi_parser::{AnsiParser, strip_alternate_screen, strip_ansi};
+use crate::tui::commands::{Builtin, command_list, find_builtin};
+use crate::tui::completion::{
+    CompletionEngine, CompletionState, closest_command, command_program, is_known_program,
+};
+use crate::tui::components::confirmation::{ConfirmAction, Confirmation};
+use crate::tui::components::context_menu::{ContextMenu, ContextMenuAction};
+use crate::tui::components::quick_add::QuickAdd;
+use crate::tui::fuzzy::FuzzySearch;
+use crate::tui::glob_preview::glob_preview;
+use crate::tui::help;
+use crate::tui::html_export::history_to_html;
+use crate::tui::pty_input::{InputTarget, key_to_pty_bytes, route_key};
+use crate::tui::pty_output::{LineSplitter, OutputPiece, Utf8Decoder};
+use crate::tui::replay::Replay;
+use crate::tui::shell::Shell;
+use crate::tui::theme::Theme;
+use crate::tui::views::task_list::{TaskPage, order_by_parent};
+use crate::tui::views::terminal::{
+    CommandEntry, OutputStream, content_area, display_output_lines, entry_markdown, expand_tabs,
+    line_number_width,
+};
+use portable_pty::PtySize;
+use regex::{Regex, RegexBuilder};
 use sqlx::SqlitePool;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
+use tokio::process::{Child, ChildStdin};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+/// Receives text copied to the clipboard
+pub type ClipboardWriter = Box<dyn FnMut(&str) -> Result<(), String> + Send>;
+/// Supplies clipboard text for pasting; called on a background thread
+pub type ClipboardReader = Arc<dyn Fn() -> Result<String, String> + Send + Sync>;
+
+/// Number of columns moved per horizontal scroll key press
+const HORIZONTAL_SCROLL_STEP: usize = 4;
+/// Default input box height: one line of text plus borders
+const DEFAULT_INPUT_AREA_HEIGHT: u16 = 3;
+/// Default number of commands shown in the command-list popup
+const DEFAULT_COMMAND_LIST_MAX_ITEMS: u16 = 8;
+/// Number of history lines scrolled per mouse wheel step
+const MOUSE_SCROLL_LINES: usize = 3;
+/// Number of commands /history lists when no count is given
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+/// Number of most-run commands /stats lists
+const STATS_TOP_COMMANDS: usize = 10;
+/// Number of most recent days /stats counts commands for
+const STATS_DAYS: usize = 7;
+/// How long a paste waits for the clipboard before giving up
+pub const CLIPBOARD_READ_TIMEOUT: Duration = Duration::from_millis(500);
+
 #[derive(Debug, PartialEq)]
 pub enum AppMode {
     TaskList,
+    /// Open tasks grouped by due date
+    Agenda,
     Terminal,
 }
 
@@ -25,6 +79,8 @@ pub enum SearchMode {
     CaseInsensitive,
     CaseSensitive,
     Regex,
+    /// Case-insensitive, matching only where no letter or digit is next to the match
+    WholeWord,
 }
 
 pub struct App {
@@ -38,6 +94,7 @@ pub struct App {
     pub cursor_position: usize,
     pub pending_command: Option<String>,
     pub scroll_offset: usize,
+    pub horizontal_scroll_offset: usize,
     pub show_command_list: bool,
     pub command_filter: String,
     pub selected_command_index: usize,
@@ -54,25 +111,140 @@ pub struct App {
     pub selection_start: Option<(usize, usize)>,
     pub selection_end: Option<(usize, usize)>,
     pub is_selecting: bool,
+    /// Last mouse position seen while dragging a history selection
+    pub last_drag_position: Option<(u16, u16)>,
+    /// Keyboard-driven history selection mode
+    pub visual_selection_active: bool,
+    /// Caret (content line, column) moved by the arrow keys in visual selection mode
+    pub visual_cursor: (usize, usize),
+    /// Ctrl+X was pressed and the next key may complete a chord
+    pub ctrl_x_pending: bool,
+    /// The input should be opened in the external editor by the main loop
+    pub pending_editor: bool,
+    /// Task whose notes should be opened in the external editor by the main loop
+    pub pending_note_edit: Option<Task>,
     pub input_selection_start: Option<usize>,
     pub input_selection_end: Option<usize>,
     pub is_selecting_input: bool,
     pub terminal_area_height: u16,
+    pub terminal_area_width: u16,
     pub clipboard: Option<arboard::Clipboard>,
+    /// Replaces the system clipboard when copying, e.g. to capture copies in tests
+    pub clipboard_writer: Option<ClipboardWriter>,
+    /// Replaces the system clipboard when pasting
+    pub clipboard_reader: Option<ClipboardReader>,
+    /// The input may span several lines, so pasted newlines are kept
+    pub multiline_input: bool,
     pub history_area_start: u16,
     pub history_area_height: u16,
     pub input_area_start: u16,
+    /// Widest the content is drawn, centered in the terminal
+    pub max_content_width: Option<u16>,
+    /// Screen column where the content starts; mouse columns are relative to it
+    pub content_left: u16,
     pub auto_suggestion: Option<String>,
     pub reverse_search_active: bool,
     pub reverse_search_query: String,
     pub reverse_search_results: Vec<String>,
+    /// Output line each result matched, for results found by their output
+    pub reverse_search_previews: Vec<Option<String>>,
     pub reverse_search_index: usize,
+    /// Reverse search also matches the output of this session's commands
+    pub reverse_search_output: bool,
+    /// Fuzzy history search overlay opened with Ctrl+Shift+R
+    pub fuzzy_search: Option<FuzzySearch>,
     pub output_search_active: bool,
     pub output_search_query: String,
     pub output_search_matches: Vec<(usize, usize, usize)>, // (line_index, start_col, end_col)
     pub output_search_current_match: usize,
     pub output_search_mode: SearchMode,
+    /// Why the current regex search query could not be used
+    pub output_search_error: Option<String>,
+    /// Maximum compiled size of an output search regex
+    pub search_regex_size_limit: usize,
+    /// Where output search scrolls the current match to
+    pub search_scroll_mode: SearchScrollMode,
     pub ansi_parser: AnsiParser,
+    pub theme: Theme,
+    pub use_theme_palette: bool,
+    pub input_area_height: u16,
+    pub command_list_max_items: u16,
+    pub right_click_action: RightClickAction,
+    pub context_menu: Option<ContextMenu>,
+    pub stderr_mode: StderrMode,
+    /// Shell that commands run in
+    pub shell: Shell,
+    /// Prefix output lines with the time since their command started
+    pub show_timestamps: bool,
+    /// Show runs of identical output lines once, with a repeat count
+    pub collapse_repeats: bool,
+    /// Number the output lines of each command
+    pub show_line_numbers: bool,
+    /// Output replay started by /replay, advanced by the main loop
+    pub replay: Option<Replay>,
+    pub task_page: TaskPage,
+    pub task_filter: TaskFilter,
+    /// Settings the app was started with, shown by /config
+    pub settings: Settings,
+    /// Page of tasks to load on the next update
+    pub pending_task_page: Option<usize>,
+    /// Task highlighted in the task list, counted in display order
+    pub selected_task_index: usize,
+    /// Priority change to apply to the selected task on the next update
+    pub pending_priority_change: Option<fn(Priority) -> Priority>,
+    /// Task title being typed in the task list after pressing `a`
+    pub quick_add: Option<QuickAdd>,
+    /// File /cd saves the new working directory to, so the next session can start there
+    pub last_directory_file: Option<PathBuf>,
+    /// Directory commands run in; `cd` and /cd change it
+    pub current_working_dir: PathBuf,
+    /// Directory before the last change, for `cd -`
+    pub previous_working_dir: Option<PathBuf>,
+    /// Ask before running a command whose program is not a builtin or on `PATH`
+    pub confirm_unknown_commands: bool,
+    /// Ask before running a destructive command, showing what its globs match
+    pub preview_globs: bool,
+    /// Custom `/name` commands from the config and the scripts they run
+    pub custom_commands: BTreeMap<String, String>,
+    /// Running commands are killed once they have run this long
+    pub command_timeout: Option<Duration>,
+    /// Most lines the command history holds before the oldest output is trimmed
+    pub max_scrollback_lines: usize,
+    /// Number of history entries before the last whose lines were counted towards
+    /// `max_scrollback_lines`, and how many lines they hold. Only the last entry
+    /// changes as output arrives, so the others are counted once.
+    scrollback_count: (usize, usize),
+    /// Widest line among the history entries before the last, measured once like
+    /// `scrollback_count` since parsing every line on each horizontal scroll is slow
+    history_width: HistoryWidth,
+    /// Question waiting for a y/n answer before its action runs, e.g. a command whose
+    /// program was not found or whose globs match files it would change
+    pub confirmation: Option<Confirmation>,
+    /// Action the user confirmed, run on the next update
+    confirmed_action: Option<ConfirmAction>,
+    /// `!N` reference that matched no history entry, and why, to report as an entry
+    history_reference_error: Option<(String, String)>,
+}
+
+/// Widest line found in the first `measured` history entries, with the line number
+/// and collapse settings it was measured under
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct HistoryWidth {
+    measured: usize,
+    widest: usize,
+    show_line_numbers: bool,
+    collapse_repeats: bool,
+}
+
+/// Output of a running command gathered from its stream buffers
+struct CombinedOutput {
+    text: String,
+    /// Stream of each line in `text`
+    streams: Vec<OutputStream>,
+    /// Arrival time of each line in `text`
+    times: Vec<Duration>,
+    /// Lines laid out by the terminal emulator, when the output needed one
+    styled: Option<Vec<ratatui::text::Line<'static>>>,
 }
 
 pub struct RunningCommand {
@@ -81,29 +253,57 @@ pub struct RunningCommand {
     pub pty_child: Option<Box<dyn portable_pty::Child + Send + Sync>>,
     pub stdout_buffer: Vec<String>,
     pub stderr_buffer: Vec<String>,
+    /// Stream of each buffered line, in the order the lines arrived
+    pub output_order: Vec<OutputStream>,
     pub output_changed: bool,
     pub output_receiver: Option<mpsc::UnboundedReceiver<OutputLine>>,
     pub uses_alternate_screen: bool,
     pub live_ansi_parser: Option<crate::tui::ansi_parser::AnsiParser>,
+    /// Input side of the command's PTY; keys are forwarded here while the command
+    /// is showing a full-screen program
+    pub pty_writer: Option<Box<dyn Write + Send>>,
+    /// Stdin of a command running through pipes; dropped to send it EOF
+    pub stdin: Option<ChildStdin>,
+    /// When the command was spawned
+    pub started_at: Instant,
+    /// When the command last produced output, or was spawned if it has not yet
+    pub last_output_at: Instant,
+    /// Time since `started_at` at which each `stdout_buffer` entry was read
+    pub stdout_times: Vec<Duration>,
+    /// Time since `started_at` at which each `stderr_buffer` entry was read
+    pub stderr_times: Vec<Duration>,
+    /// Rows the live terminal had written when each `stderr_buffer` entry was read
+    pub stderr_rows: Vec<usize>,
+    /// The last `stdout_buffer` entry is a line whose end has not arrived yet
+    pub stdout_line_open: bool,
+}
+
+impl RunningCommand {
+    /// Write `bytes` to the command's terminal input, as if typed into it. Fails
+    /// if the command is not running under a PTY.
+    pub fn write_to_pty(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let writer = self.pty_writer.as_mut().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "command is not running in a PTY",
+            )
+        })?;
+        writer.write_all(bytes)?;
+        writer.flush()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum OutputLine {
     Stdout(String),
+    /// More of a stdout line whose end has not arrived yet
+    PartialStdout(String),
     Stderr(String),
 }
 
 impl App {
     pub fn new(db_pool: SqlitePool) -> Self {
-        let available_commands = vec![
-            "/quit".to_string(),
-            "/task".to_string(),
-            "/task add".to_string(),
-            "/task list".to_string(),
-            "/help".to_string(),
-            "/help keys".to_string(),
-            "/clear".to_string(),
-        ];
+        let available_commands = command_list();
 
         let completion_engine = CompletionEngine::new(available_commands.clone());
 
@@ -118,6 +318,7 @@ impl App {
             cursor_position: 0,
             pending_command: None,
             scroll_offset: 0,
+            horizontal_scroll_offset: 0,
             show_command_list: false,
             command_filter: String::new(),
             selected_command_index: 0,
@@ -134,25 +335,75 @@ impl App {
             selection_start: None,
             selection_end: None,
             is_selecting: false,
+            last_drag_position: None,
+            visual_selection_active: false,
+            visual_cursor: (0, 0),
+            ctrl_x_pending: false,
+            pending_editor: false,
+            pending_note_edit: None,
             input_selection_start: None,
             input_selection_end: None,
             is_selecting_input: false,
             terminal_area_height: 24,
+            terminal_area_width: 80,
             clipboard: None,
+            clipboard_writer: None,
+            clipboard_reader: None,
+            multiline_input: false,
             history_area_start: 0,
             history_area_height: 21,
             input_area_start: 21,
+            max_content_width: None,
+            content_left: 0,
             auto_suggestion: None,
             reverse_search_active: false,
             reverse_search_query: String::new(),
             reverse_search_results: Vec::new(),
+            reverse_search_previews: Vec::new(),
             reverse_search_index: 0,
+            reverse_search_output: false,
+            fuzzy_search: None,
             output_search_active: false,
             output_search_query: String::new(),
             output_search_matches: Vec::new(),
             output_search_current_match: 0,
             output_search_mode: SearchMode::CaseInsensitive,
+            output_search_error: None,
+            search_regex_size_limit: DEFAULT_SEARCH_REGEX_SIZE_LIMIT,
+            search_scroll_mode: SearchScrollMode::default(),
             ansi_parser: AnsiParser::new_with_terminal_size(),
+            theme: Theme::default(),
+            use_theme_palette: false,
+            input_area_height: DEFAULT_INPUT_AREA_HEIGHT,
+            command_list_max_items: DEFAULT_COMMAND_LIST_MAX_ITEMS,
+            right_click_action: RightClickAction::default(),
+            context_menu: None,
+            stderr_mode: StderrMode::default(),
+            shell: Shell::from_setting(None),
+            show_timestamps: false,
+            collapse_repeats: false,
+            show_line_numbers: false,
+            replay: None,
+            task_page: TaskPage::default(),
+            task_filter: TaskFilter::default(),
+            settings: Settings::default(),
+            pending_task_page: None,
+            selected_task_index: 0,
+            quick_add: None,
+            pending_priority_change: None,
+            last_directory_file: None,
+            current_working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            previous_working_dir: None,
+            confirm_unknown_commands: false,
+            preview_globs: false,
+            custom_commands: BTreeMap::new(),
+            command_timeout: None,
+            max_scrollback_lines: DEFAULT_MAX_SCROLLBACK_LINES,
+            scrollback_count: (0, 0),
+            history_width: HistoryWidth::default(),
+            confirmation: None,
+            confirmed_action: None,
+            history_reference_error: None,
         }
     }
 
@@ -161,6 +412,236 @@ impl App {
         self
     }
 
+    pub fn with_theme_palette(mut self, enabled: bool) -> Self {
+        self.use_theme_palette = enabled;
+        self.ansi_parser.set_palette(self.ansi_palette());
+        self
+    }
+
+    /// Configure the input box height and the number of commands shown in the command list.
+    /// The input box keeps room for its borders and at least one line of text.
+    pub fn with_layout(mut self, input_height: u16, command_list_max_items: u16) -> Self {
+        self.input_area_height = input_height.max(DEFAULT_INPUT_AREA_HEIGHT);
+        self.command_list_max_items = command_list_max_items.max(1);
+        self
+    }
+
+    /// Cap the content width on wide terminals; None or 0 uses the full width
+    pub fn with_max_content_width(mut self, max_width: Option<u16>) -> Self {
+        self.max_content_width = max_width;
+        self
+    }
+
+    pub fn with_search_regex_size_limit(mut self, limit: usize) -> Self {
+        self.search_regex_size_limit = limit;
+        self
+    }
+
+    pub fn with_right_click_action(mut self, action: RightClickAction) -> Self {
+        self.right_click_action = action;
+        self
+    }
+
+    pub fn with_stderr_mode(mut self, mode: StderrMode) -> Self {
+        self.stderr_mode = mode;
+        self
+    }
+
+    pub fn with_search_scroll_mode(mut self, mode: SearchScrollMode) -> Self {
+        self.search_scroll_mode = mode;
+        self
+    }
+
+    /// Start in the view chosen by `ui.start_mode`
+    pub fn with_start_mode(mut self, start_mode: StartMode) -> Self {
+        self.mode = match start_mode {
+            StartMode::Terminal => AppMode::Terminal,
+            StartMode::TaskList => AppMode::TaskList,
+        };
+        self
+    }
+
+    pub fn with_timestamps(mut self, enabled: bool) -> Self {
+        self.show_timestamps = enabled;
+        self
+    }
+
+    pub fn with_line_numbers(mut self, enabled: bool) -> Self {
+        self.show_line_numbers = enabled;
+        self
+    }
+
+    pub fn with_task_page_size(mut self, page_size: usize) -> Self {
+        self.task_page.size = page_size.max(1);
+        self
+    }
+
+    /// Save the working directory to `file` whenever /cd changes it
+    pub fn with_last_directory_file(mut self, file: PathBuf) -> Self {
+        self.last_directory_file = Some(file);
+        self
+    }
+
+    /// Run commands in `shell` instead of the default one
+    pub fn with_shell(mut self, shell: Shell) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    /// Ask before running commands whose program is not found
+    pub fn with_confirm_unknown_commands(mut self, enabled: bool) -> Self {
+        self.confirm_unknown_commands = enabled;
+        self
+    }
+
+    /// Ask before running destructive commands, listing the files their globs match
+    pub fn with_preview_globs(mut self, enabled: bool) -> Self {
+        self.preview_globs = enabled;
+        self
+    }
+
+    pub fn with_custom_commands(mut self, commands: BTreeMap<String, String>) -> Self {
+        self.set_custom_commands(commands);
+        self
+    }
+
+    /// Replace the custom commands, listing them after the built-in ones
+    fn set_custom_commands(&mut self, commands: BTreeMap<String, String>) {
+        self.custom_commands = commands;
+        self.available_commands = command_list();
+        self.available_commands
+            .extend(self.custom_commands.keys().map(|name| format!("/{name}")));
+        self.completion_engine
+            .update_commands(self.available_commands.clone());
+    }
+
+    /// Kill commands that run longer than `timeout`; None lets them run until they exit
+    pub fn with_command_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
+
+    /// Trim the oldest history output once the history is longer than `limit` lines
+    pub fn with_max_scrollback_lines(mut self, limit: usize) -> Self {
+        self.max_scrollback_lines = limit;
+        self
+    }
+
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_clipboard_writer(mut self, writer: ClipboardWriter) -> Self {
+        self.clipboard_writer = Some(writer);
+        self
+    }
+
+    pub fn with_clipboard_reader(mut self, reader: ClipboardReader) -> Self {
+        self.clipboard_reader = Some(reader);
+        self
+    }
+
+    pub fn with_multiline_input(mut self, enabled: bool) -> Self {
+        self.multiline_input = enabled;
+        self
+    }
+
+    /// Apply reloaded settings to the running app and return what changed. Values
+    /// that can only take effect on restart keep their current value.
+    pub fn apply_settings(&mut self, mut settings: Settings) -> Vec<SettingChange> {
+        let changes = self.settings.diff(&settings);
+
+        self.use_theme_palette = settings.ui.theme_palette;
+        self.ansi_parser.set_palette(self.ansi_palette());
+        self.input_area_height = settings.ui.input_height.max(DEFAULT_INPUT_AREA_HEIGHT);
+        self.command_list_max_items = settings.ui.command_list_max_items.max(1);
+        self.max_content_width = settings.ui.max_content_width;
+        self.right_click_action = settings.ui.right_click;
+        self.stderr_mode = settings.ui.stderr;
+        self.search_regex_size_limit = settings.ui.search_regex_size_limit;
+        self.search_scroll_mode = settings.ui.search_scroll_mode;
+        self.confirm_unknown_commands = settings.ui.confirm_unknown_commands;
+        self.preview_globs = settings.ui.preview_globs;
+        self.set_custom_commands(settings.commands.clone());
+        self.command_timeout = settings.command_timeout();
+        self.max_scrollback_lines = settings.ui.max_scrollback_lines;
+        self.trim_scrollback();
+        self.shell =
+            Shell::from_setting(settings.shell.as_deref()).with_direct_exec(settings.direct_exec);
+
+        let page_size = settings.tasks.page_size.max(1);
+        if page_size != self.task_page.size {
+            self.task_page.size = page_size;
+            self.task_page.index = 0;
+            self.pending_task_page = Some(0);
+        }
+
+        settings.database_path = self.settings.database_path.clone();
+        settings.history = self.settings.history.clone();
+        self.settings = settings;
+
+        changes
+    }
+
+    /// Re-read the config file and apply it, reporting applied and pending settings
+    pub async fn handle_reload_config_command(&mut self, command: &str) {
+        let (output, success) = match Settings::new() {
+            Ok(settings) => (format_setting_changes(&self.apply_settings(settings)), true),
+            Err(e) => (format!("Error reloading config: {e}"), false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Height of the command-list popup including borders, or 0 when it is hidden.
+    /// The popup shows either built-in commands or the candidates of an ambiguous Tab completion.
+    pub fn command_list_size(&self) -> u16 {
+        let items = if self.show_command_list {
+            self.get_filtered_commands().len()
+        } else {
+            match self.completion_candidates().len() {
+                0 => return 0,
+                count => count,
+            }
+        };
+
+        items.min(self.command_list_max_items as usize) as u16 + 2
+    }
+
+    /// Labels of the active completions when Tab matched more than one candidate
+    pub fn completion_candidates(&self) -> Vec<String> {
+        if !self.completion_state.is_active || self.completion_state.completions.len() < 2 {
+            return Vec::new();
+        }
+
+        self.completion_state
+            .completions
+            .iter()
+            .map(|completion| {
+                completion
+                    .display_text
+                    .clone()
+                    .unwrap_or_else(|| completion.text.clone())
+            })
+            .collect()
+    }
+
+    /// Palette for the 16 base ANSI colors, if themed mapping is enabled
+    pub fn ansi_palette(&self) -> Option<[ratatui::style::Color; 16]> {
+        if self.use_theme_palette {
+            Some(self.theme.palette)
+        } else {
+            None
+        }
+    }
+
     pub async fn load_persistent_history(&mut self) {
         if let Some(ref history_manager) = self.history_manager {
             self.persistent_command_history = history_manager.load_history().await;
@@ -168,13 +649,12 @@ impl App {
     }
 
     pub async fn save_persistent_history(&self) {
-        if let Some(ref history_manager) = self.history_manager {
-            if let Err(e) = history_manager
+        if let Some(ref history_manager) = self.history_manager
+            && let Err(e) = history_manager
                 .save_history(&self.persistent_command_history)
                 .await
-            {
-                eprintln!("Warning: Failed to save command history: {e}");
-            }
+        {
+            eprintln!("Warning: Failed to save command history: {e}");
         }
     }
 
@@ -200,7 +680,10 @@ impl App {
         // Keep only the last 1000 commands in memory
         if self.command_history.len() > 1000 {
             self.command_history.drain(0..100);
+            self.scrollback_count = (0, 0);
+            self.history_width = HistoryWidth::default();
         }
+        self.trim_scrollback();
 
         // Persist only the command if history manager is enabled
         self.append_to_persistent_history(&entry.command).await;
@@ -209,8 +692,139 @@ impl App {
         self.scroll_offset = 0;
     }
 
+    /// Load the current page of tasks along with the total number of matching tasks
     pub async fn load_tasks(&mut self) -> Result<(), sqlx::Error> {
-        self.tasks = operations::list_tasks(&self.db_pool).await?;
+        self.task_page.total =
+            operations::count_tasks(&self.db_pool, &self.task_filter).await? as usize;
+        self.tasks = operations::list_tasks_paged(
+            &self.db_pool,
+            self.task_page.size as i64,
+            self.task_page.offset() as i64,
+            &self.task_filter,
+        )
+        .await?;
+        self.selected_task_index = self
+            .selected_task_index
+            .min(self.tasks.len().saturating_sub(1));
+        Ok(())
+    }
+
+    /// Index of the highlighted task in display order, while the task list shows any
+    pub fn selected_task(&self) -> Option<usize> {
+        (self.mode == AppMode::TaskList && !self.tasks.is_empty())
+            .then_some(self.selected_task_index)
+    }
+
+    /// Whether ↑/↓ move the task selection rather than browse command history
+    fn task_selection_keys_active(&self) -> bool {
+        self.mode == AppMode::TaskList
+            && self.current_input.is_empty()
+            && !self.show_command_list
+            && !self.reverse_search_active
+            && !self.output_search_active
+    }
+
+    /// Open the quick-add input in the task list
+    pub fn open_quick_add(&mut self) {
+        if self.mode == AppMode::TaskList {
+            self.quick_add = Some(QuickAdd::default());
+        }
+    }
+
+    /// Add a task titled with the quick-add input on the next update and close it.
+    /// An empty title is rejected and the input stays open to show why.
+    pub fn submit_quick_add(&mut self) {
+        let Some(quick_add) = self.quick_add.as_mut() else {
+            return;
+        };
+        if let Ok(title) = quick_add.submit() {
+            self.pending_task_add = Some(Task::new(title));
+            self.quick_add = None;
+        }
+    }
+
+    /// Close the quick-add input without adding a task
+    pub fn cancel_quick_add(&mut self) {
+        self.quick_add = None;
+    }
+
+    /// Highlight the task below the selected one
+    pub fn select_next_task(&mut self) {
+        if self.selected_task_index + 1 < self.tasks.len() {
+            self.selected_task_index += 1;
+        }
+    }
+
+    /// Highlight the task above the selected one
+    pub fn select_previous_task(&mut self) {
+        self.selected_task_index = self.selected_task_index.saturating_sub(1);
+    }
+
+    /// Apply a priority change requested with +/- to the selected task and store
+    /// it. Nothing changes at the ends of the scale.
+    pub async fn handle_pending_priority_change(&mut self) {
+        let Some(change) = self.pending_priority_change.take() else {
+            return;
+        };
+        let Some(id) = order_by_parent(&self.tasks)
+            .get(self.selected_task_index)
+            .map(|(task, _)| task.id)
+        else {
+            return;
+        };
+        let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) else {
+            return;
+        };
+
+        let priority = change(task.priority);
+        if priority == task.priority {
+            return;
+        }
+        let previous = task.priority;
+        task.priority = priority;
+        task.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        if let Err(e) = operations::update_task(&self.db_pool, task).await {
+            task.priority = previous;
+            let entry = CommandEntry {
+                command: "update_task".to_string(),
+                output: format!("Error changing task priority: {e}"),
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+        }
+    }
+
+    /// Request the next page of tasks; ignored on the last page
+    pub fn next_task_page(&mut self) {
+        if self.task_page.index + 1 < self.task_page.page_count() {
+            self.pending_task_page = Some(self.task_page.index + 1);
+        }
+    }
+
+    /// Request the previous page of tasks
+    pub fn previous_task_page(&mut self) {
+        if self.task_page.index > 0 {
+            self.pending_task_page = Some(self.task_page.index - 1);
+        }
+    }
+
+    /// Load a page requested by `next_task_page`/`previous_task_page`
+    pub async fn handle_pending_task_page(&mut self) -> Result<(), sqlx::Error> {
+        let Some(index) = self.pending_task_page.take() else {
+            return Ok(());
+        };
+
+        let previous_index = self.task_page.index;
+        self.task_page.index = index;
+        self.load_tasks().await?;
+
+        // Tasks may have been removed since they were counted; stay where we were
+        if self.tasks.is_empty() && index > 0 {
+            self.task_page.index = previous_index;
+            self.load_tasks().await?;
+        }
         Ok(())
     }
 
@@ -240,10 +854,27 @@ impl App {
     }
 
     pub fn on_key(&mut self, key: char) {
+        self.ctrl_x_pending = false;
+        if self.confirmation.is_some() {
+            self.handle_confirmation_key(key);
+            return;
+        }
         match self.mode {
-            AppMode::TaskList => {
-                if key == 'q' || key == 't' {
+            AppMode::TaskList | AppMode::Agenda => {
+                if let Some(quick_add) = self.quick_add.as_mut() {
+                    if !key.is_control() {
+                        quick_add.push(key);
+                    }
+                } else if key == 'q' || key == 't' {
                     self.mode = AppMode::Terminal;
+                } else if self.task_selection_keys_active() && key == 'a' {
+                    self.open_quick_add();
+                } else if self.task_selection_keys_active() && (key == '+' || key == '-') {
+                    self.pending_priority_change = Some(if key == '+' {
+                        Priority::raised
+                    } else {
+                        Priority::lowered
+                    });
                 } else {
                     self.handle_terminal_input(key);
                 }
@@ -267,6 +898,69 @@ impl App {
             return;
         }
 
+        // A confirmation's action runs on y; Enter and Esc decline
+        if self.confirmation.is_some() {
+            if matches!(key_code, KeyCode::Enter | KeyCode::Esc) {
+                self.answer_confirmation(false);
+            }
+            return;
+        }
+
+        // The context menu captures navigation keys while open
+        if self.context_menu.is_some() {
+            self.handle_context_menu_key(key_code);
+            return;
+        }
+
+        // The quick-add input captures keys while open
+        if let Some(quick_add) = self.quick_add.as_mut() {
+            match key_code {
+                KeyCode::Enter => self.submit_quick_add(),
+                KeyCode::Esc => self.cancel_quick_add(),
+                KeyCode::Backspace => quick_add.backspace(),
+                _ => {}
+            }
+            return;
+        }
+
+        // The fuzzy history search captures navigation keys while open
+        if self.fuzzy_search.is_some() {
+            self.handle_fuzzy_search_key(key_code, modifiers);
+            return;
+        }
+
+        // Keyboard selection takes over navigation keys while active
+        if self.visual_selection_active {
+            self.handle_visual_selection_key(key_code);
+            return;
+        }
+
+        // Ctrl+X Ctrl+E opens the input in $EDITOR (readline convention)
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        if std::mem::take(&mut self.ctrl_x_pending) && ctrl && key_code == KeyCode::Char('e') {
+            self.pending_editor = true;
+            return;
+        }
+        if ctrl && key_code == KeyCode::Char('x') {
+            self.ctrl_x_pending = true;
+            return;
+        }
+
+        // Handle Ctrl-S for keyboard selection in the history
+        if key_code == KeyCode::Char('s') && modifiers.contains(KeyModifiers::CONTROL) {
+            self.start_visual_selection();
+            return;
+        }
+
+        // Ctrl+Shift+R opens the fuzzy history search; some terminals report the
+        // shifted key as an uppercase letter
+        if matches!(key_code, KeyCode::Char('r' | 'R'))
+            && modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+        {
+            self.start_fuzzy_search();
+            return;
+        }
+
         // Handle Ctrl-R for reverse search
         if key_code == KeyCode::Char('r') && modifiers.contains(KeyModifiers::CONTROL) {
             self.start_reverse_search();
@@ -279,6 +973,19 @@ impl App {
             return;
         }
 
+        // Alt+W inserts the working directory and Alt+G the git repository root
+        if modifiers.contains(KeyModifiers::ALT) {
+            let dir = match key_code {
+                KeyCode::Char('w') => Some(self.current_working_dir.clone()),
+                KeyCode::Char('g') => find_git_root(&self.current_working_dir),
+                _ => None,
+            };
+            if let Some(dir) = dir {
+                self.insert_at_cursor(&shell_quote(&dir.to_string_lossy()));
+                return;
+            }
+        }
+
         // Handle advanced cursor movement shortcuts
         if modifiers.contains(KeyModifiers::CONTROL) {
             match key_code {
@@ -307,6 +1014,11 @@ impl App {
                     }
                     return;
                 }
+                KeyCode::Char('g') => {
+                    // Ctrl+G: Clear the whole input line
+                    self.clear_input();
+                    return;
+                }
                 KeyCode::Char('k') => {
                     // Ctrl+K: Kill (delete) from cursor to end of line
                     let chars: Vec<char> = self.current_input.chars().collect();
@@ -335,7 +1047,7 @@ impl App {
 
         // Handle key codes for both modes
         match self.mode {
-            AppMode::Terminal | AppMode::TaskList => {
+            AppMode::Terminal | AppMode::TaskList | AppMode::Agenda => {
                 match key_code {
                     KeyCode::Esc => {
                         if self.output_search_active {
@@ -378,13 +1090,16 @@ impl App {
                                     // User never navigated - execute typed command if it's complete
                                     let is_complete_command =
                                         self.available_commands.contains(&command)
+                                            || self.available_commands.iter().any(|available| {
+                                                command.starts_with(&format!("{available} "))
+                                            })
                                             || command.starts_with("/task add ")
                                             || command.starts_with("/help")
                                             || command.starts_with("/quit");
 
                                     if is_complete_command {
                                         // Execute the command directly
-                                        if self.running_command.is_none() {
+                                        if self.can_submit(&command) {
                                             self.current_input.clear();
                                             self.cursor_position = 0;
                                             self.show_command_list = false;
@@ -416,11 +1131,20 @@ impl App {
                                 }
                             } else {
                                 // No command list showing, execute the command
-                                // But only if no command is currently running
-                                if self.running_command.is_none() {
+                                // But only /quit while a command is running
+                                let command = submitted_command(&self.current_input);
+                                if self.can_submit(&command) {
+                                    // `!N` and `!!` re-run a command from the history
+                                    let history = self.get_combined_command_history();
+                                    match expand_history_reference(&command, &history) {
+                                        Some(Ok(expanded)) => self.pending_command = Some(expanded),
+                                        Some(Err(e)) => {
+                                            self.history_reference_error = Some((command, e))
+                                        }
+                                        None => self.pending_command = Some(command),
+                                    }
                                     self.current_input.clear();
                                     self.cursor_position = 0;
-                                    self.pending_command = Some(command);
                                     self.scroll_offset = 0;
                                     self.reset_history_navigation();
                                 }
@@ -431,6 +1155,8 @@ impl App {
                         if self.output_search_active {
                             // Toggle search mode in output search mode
                             self.toggle_output_search_mode();
+                        } else if self.reverse_search_active {
+                            self.toggle_reverse_search_output();
                         } else {
                             self.handle_tab_completion();
                         }
@@ -485,12 +1211,25 @@ impl App {
                             self.update_auto_suggestion();
                         }
                     }
-                    KeyCode::Left => {
-                        if self.cursor_position > 0 {
-                            self.cursor_position -= 1;
-                            // Update auto-suggestion based on new cursor position
-                            self.update_auto_suggestion();
-                        }
+                    KeyCode::Left
+                        if modifiers.contains(KeyModifiers::SHIFT)
+                            || self.current_input.is_empty() =>
+                    {
+                        // Shift+Left, or Left with no input: scroll history left
+                        self.scroll_horizontally(-(HORIZONTAL_SCROLL_STEP as isize));
+                    }
+                    KeyCode::Right
+                        if modifiers.contains(KeyModifiers::SHIFT)
+                            || (self.current_input.is_empty()
+                                && self.auto_suggestion.is_none()) =>
+                    {
+                        // Shift+Right, or Right with no input: scroll history right
+                        self.scroll_horizontally(HORIZONTAL_SCROLL_STEP as isize);
+                    }
+                    KeyCode::Left if self.cursor_position > 0 => {
+                        self.cursor_position -= 1;
+                        // Update auto-suggestion based on new cursor position
+                        self.update_auto_suggestion();
                     }
                     KeyCode::Right => {
                         let char_count = self.current_input.chars().count();
@@ -503,11 +1242,19 @@ impl App {
                             self.accept_next_suggestion_char();
                         }
                     }
+                    KeyCode::Up if self.task_selection_keys_active() => {
+                        self.select_previous_task();
+                    }
+                    KeyCode::Down if self.task_selection_keys_active() => {
+                        self.select_next_task();
+                    }
                     KeyCode::Up => {
                         if self.output_search_active {
                             self.output_search_previous_match();
                         } else if self.reverse_search_active {
                             self.reverse_search_previous();
+                        } else if modifiers.contains(KeyModifiers::ALT) {
+                            self.jump_to_previous_command();
                         } else if modifiers.contains(KeyModifiers::SHIFT) {
                             // Shift+Up: Scroll up through history (show older content)
                             let max_scroll = self.get_total_history_lines().saturating_sub(1);
@@ -554,6 +1301,8 @@ impl App {
                             self.output_search_next_match();
                         } else if self.reverse_search_active {
                             self.reverse_search_next();
+                        } else if modifiers.contains(KeyModifiers::ALT) {
+                            self.jump_to_next_command();
                         } else if modifiers.contains(KeyModifiers::SHIFT) {
                             // Shift+Down: Scroll down through history (show newer content)
                             if self.scroll_offset > 0 {
@@ -587,6 +1336,12 @@ impl App {
                             }
                         }
                     }
+                    KeyCode::PageUp if self.mode == AppMode::TaskList => {
+                        self.previous_task_page();
+                    }
+                    KeyCode::PageDown if self.mode == AppMode::TaskList => {
+                        self.next_task_page();
+                    }
                     KeyCode::PageUp => {
                         // Scroll up by 10 lines
                         let max_scroll = self.get_total_history_lines().saturating_sub(1);
@@ -623,6 +1378,28 @@ impl App {
             return;
         }
 
+        if self.confirmation.is_some() {
+            self.handle_confirmation_key(ch);
+            return;
+        }
+
+        if self.context_menu.is_some() {
+            // Typing dismisses the context menu
+            self.context_menu = None;
+        }
+
+        if self.visual_selection_active {
+            // vi-style keys: 'v' restarts the selection at the caret, 'y' copies it
+            match ch {
+                'v' => self.selection_start = Some(self.visual_cursor),
+                'y' => {
+                    let _ = self.copy_visual_selection();
+                }
+                _ => {}
+            }
+            return;
+        }
+
         if self.output_search_active {
             // Handle output search input
             self.output_search_query.push(ch);
@@ -630,6 +1407,12 @@ impl App {
             return;
         }
 
+        if let Some(search) = &mut self.fuzzy_search {
+            search.query.push(ch);
+            self.update_fuzzy_search();
+            return;
+        }
+
         if self.reverse_search_active {
             // Handle search input
             self.reverse_search_query.push(ch);
@@ -664,19 +1447,70 @@ impl App {
         self.update_auto_suggestion();
     }
 
-    pub fn handle_tab_completion(&mut self) {
-        // If there's an auto-suggestion and cursor is at the end, accept it completely
-        if let Some(suggestion) = &self.auto_suggestion {
-            if self.cursor_position == self.current_input.chars().count() {
-                self.current_input = suggestion.clone();
+    /// Insert `text` into the input line at the cursor
+    pub fn insert_at_cursor(&mut self, text: &str) {
+        let mut chars: Vec<char> = self.current_input.chars().collect();
+        let cursor_pos = self.cursor_position.min(chars.len());
+        chars.splice(cursor_pos..cursor_pos, text.chars());
+        self.current_input = chars.into_iter().collect();
+        self.cursor_position = cursor_pos + text.chars().count();
+        self.completion_state.reset();
+        self.reset_history_navigation();
+        self.update_command_filtering();
+        self.update_auto_suggestion();
+    }
+
+    /// Empty the input line and drop any state derived from it. Scrollback and
+    /// command history are left alone, unlike `/clear`.
+    pub fn clear_input(&mut self) {
+        self.current_input.clear();
+        self.cursor_position = 0;
+        self.clear_input_selection();
+        self.completion_state.reset();
+        self.reset_history_navigation();
+        self.show_command_list = false;
+        self.command_filter.clear();
+        self.selected_command_index = 0;
+        self.user_navigated_command_list = false;
+        self.auto_suggestion = None;
+    }
+
+    /// Replace the input with the result of an external edit. A cancelled edit keeps
+    /// the input as it was.
+    pub async fn finish_external_edit(&mut self, result: std::io::Result<Option<String>>) {
+        match result {
+            Ok(Some(edited)) => {
+                self.current_input = edited;
                 self.cursor_position = self.current_input.chars().count();
-                self.auto_suggestion = None;
-                return;
+                self.completion_state.reset();
+                self.update_auto_suggestion();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                let entry = CommandEntry {
+                    command: "edit".to_string(),
+                    output: format!("Could not open editor: {e}"),
+                    success: false,
+                    ..Default::default()
+                };
+                self.add_command_entry(entry).await;
             }
         }
+    }
 
-        // If completion is already active, cycle to next completion
-        if self.completion_state.is_active {
+    pub fn handle_tab_completion(&mut self) {
+        // If there's an auto-suggestion and cursor is at the end, accept it completely
+        if let Some(suggestion) = &self.auto_suggestion
+            && self.cursor_position == self.current_input.chars().count()
+        {
+            self.current_input = suggestion.clone();
+            self.cursor_position = self.current_input.chars().count();
+            self.auto_suggestion = None;
+            return;
+        }
+
+        // If completion is already active, cycle to next completion
+        if self.completion_state.is_active {
             if let Some(completed_text) = self.completion_state.cycle_next() {
                 self.current_input = completed_text;
                 self.cursor_position = self.current_input.chars().count();
@@ -695,6 +1529,7 @@ impl App {
             let word_start = self
                 .completion_engine
                 .find_word_start(&self.current_input, self.cursor_position);
+            let descend = completions.len() == 1 && completions[0].is_directory();
             self.completion_state
                 .start(&self.current_input, completions, word_start);
 
@@ -703,10 +1538,29 @@ impl App {
                 self.current_input = completed_text;
                 self.cursor_position = self.current_input.chars().count();
             }
+
+            // A lone directory match is final, so the next Tab lists its contents
+            if descend {
+                self.completion_state.reset();
+            }
         }
     }
 
     pub async fn handle_pending_commands(&mut self) {
+        if let Some(action) = self.confirmed_action.take() {
+            self.run_confirmed_action(action).await;
+        }
+
+        if let Some((command, output)) = self.history_reference_error.take() {
+            let entry = CommandEntry {
+                command,
+                output,
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+        }
+
         if let Some(command) = self.pending_command.take() {
             // Handle built-in commands first, then shell commands
             if self.handle_builtin_command(&command).await {
@@ -714,14 +1568,187 @@ impl App {
                 self.handle_pending_task_add().await;
                 return;
             }
+            // A plain `cd` changes the directory later commands run in
+            if cd_argument(&command).is_some() {
+                self.handle_cd_command(&command).await;
+                return;
+            }
+            if let Some(output) = self.unknown_builtin_message(&command) {
+                let entry = CommandEntry {
+                    command,
+                    output,
+                    success: false,
+                    ..Default::default()
+                };
+                self.add_command_entry(entry).await;
+                return;
+            }
+            if self.confirm_unknown_commands
+                && let Some(program) = self.unknown_program(&command)
+            {
+                self.ask_to_run_unknown_command(command, &program).await;
+                return;
+            }
+            if self.preview_globs
+                && let Some(preview) = glob_preview(&command, &self.current_working_dir)
+            {
+                self.ask_to_confirm(
+                    format!("{preview} - run anyway? [y/N]"),
+                    ConfirmAction::RunDangerousCommand(command),
+                )
+                .await;
+                return;
+            }
             self.execute_command(command).await;
         }
 
         // Also handle pending task add if no command was processed
         self.handle_pending_task_add().await;
+        self.handle_pending_priority_change().await;
+
+        if let Err(e) = self.handle_pending_task_page().await {
+            let entry = CommandEntry {
+                command: "load_tasks".to_string(),
+                output: format!("Error loading tasks: {e}"),
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+        }
+    }
+
+    /// Error for a `/` command that names no built-in, suggesting the closest one.
+    /// None for anything else, including absolute paths of existing programs.
+    pub fn unknown_builtin_message(&self, command: &str) -> Option<String> {
+        let name = command.split_whitespace().next()?;
+        if !name.starts_with('/') || Path::new(name).exists() {
+            return None;
+        }
+
+        let mut builtins: Vec<String> = self
+            .available_commands
+            .iter()
+            .filter_map(|available| available.split_whitespace().next())
+            .map(str::to_string)
+            .collect();
+        builtins.dedup();
+        if builtins.contains(&name.to_string()) {
+            return None;
+        }
+
+        Some(match closest_command(name, &builtins) {
+            Some(suggestion) => format!("Unknown command {name}. Did you mean {suggestion}?"),
+            None => format!("Unknown command {name}. Type /help for a list of commands"),
+        })
+    }
+
+    /// The program `command` starts, if it is neither a shell builtin nor on `PATH`
+    pub fn unknown_program(&self, command: &str) -> Option<String> {
+        let program = command_program(command)?;
+        let path_commands = self.completion_engine.get_path_commands();
+        (!is_known_program(program, &path_commands)).then(|| program.to_string())
+    }
+
+    /// Hold `command` until the user confirms it, suggesting the closest known command
+    async fn ask_to_run_unknown_command(&mut self, command: String, program: &str) {
+        let path_commands = self.completion_engine.get_path_commands();
+        let mut output = format!("Command '{program}' not found");
+        if let Some(suggestion) = closest_command(program, &path_commands) {
+            output.push_str(&format!(" (did you mean '{suggestion}'?)"));
+        }
+        output.push_str(" - run anyway? [y/N]");
+        self.ask_to_confirm(output, ConfirmAction::RunCommand(command))
+            .await;
+    }
+
+    /// Show `question` under the command that asked for `action`, if one did, and
+    /// hold the action until the user answers it with y or n
+    async fn ask_to_confirm(&mut self, question: String, action: ConfirmAction) {
+        if let Some(command) = action.command() {
+            let entry = CommandEntry {
+                command: command.to_string(),
+                output: question.clone(),
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+        }
+        self.confirm(question, action);
+    }
+
+    /// Whether `command` can be submitted now: any command while none is running,
+    /// otherwise only /quit, which asks before killing the one that is
+    fn can_submit(&self, command: &str) -> bool {
+        self.running_command.is_none()
+            || find_builtin(command).is_some_and(|builtin| builtin.builtin == Builtin::Quit)
+    }
+
+    /// Ask `prompt` as a yes/no question and hold `action` until it is answered
+    pub fn confirm(&mut self, prompt: impl Into<String>, action: ConfirmAction) {
+        self.confirmation = Some(Confirmation::new(prompt, action));
+    }
+
+    /// y confirms the open question and n declines it; other keys are ignored
+    fn handle_confirmation_key(&mut self, ch: char) {
+        match ch {
+            'y' | 'Y' => self.answer_confirmation(true),
+            'n' | 'N' => self.answer_confirmation(false),
+            _ => {}
+        }
+    }
+
+    /// Close the open question, running its action on the next update if
+    /// `confirmed`. A declined command goes back in the input to edit.
+    pub fn answer_confirmation(&mut self, confirmed: bool) {
+        let Some(confirmation) = self.confirmation.take() else {
+            return;
+        };
+        if confirmed {
+            self.confirmed_action = Some(confirmation.action);
+            return;
+        }
+        if let Some(command) = confirmation.command()
+            && self.current_input.is_empty()
+        {
+            self.cursor_position = command.chars().count();
+            self.current_input = command.to_string();
+        }
+    }
+
+    async fn run_confirmed_action(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::RunCommand(command) | ConfirmAction::RunDangerousCommand(command) => {
+                self.execute_command(command).await
+            }
+            ConfirmAction::Quit => {
+                self.kill_running_command().await;
+                self.should_quit = true;
+            }
+            ConfirmAction::DeleteTask { command, id, title } => {
+                self.delete_task(&command, id, &title).await
+            }
+            ConfirmAction::UpdateTaskStatuses {
+                command,
+                ids,
+                status,
+            } => self.update_task_statuses(&command, &ids, status).await,
+        }
     }
 
     pub async fn execute_command(&mut self, command: String) {
+        // Run in the current working directory to preserve the user's location
+        let cwd = self.current_working_dir.clone();
+        self.spawn_command(command.clone(), &command, Some(&cwd))
+            .await;
+    }
+
+    /// Run `shell_command` in `cwd`, recording it in the history as `entry_command`
+    async fn spawn_command(
+        &mut self,
+        entry_command: String,
+        shell_command: &str,
+        cwd: Option<&Path>,
+    ) {
         // Don't start a new command if one is already running
         if self.running_command.is_some() {
             return;
@@ -731,31 +1758,124 @@ impl App {
         self.ansi_parser.reset();
 
         // Try PTY execution first for better color support
-        let running_cmd = if let Ok(running_cmd) = self.execute_command_with_pty(&command).await {
-            running_cmd
-        } else {
-            // Fallback to regular pipes if PTY fails
-            match self.execute_command_with_pipes(&command).await {
-                Ok(running_cmd) => running_cmd,
-                Err(_) => {
-                    let entry = CommandEntry {
-                        command,
-                        output: "Error: Failed to execute command".to_string(),
-                        success: false,
-                    };
-                    self.add_command_entry(entry).await;
-                    return;
+        let mut running_cmd =
+            if let Ok(running_cmd) = self.execute_command_with_pty(shell_command, cwd).await {
+                running_cmd
+            } else {
+                // Fallback to regular pipes if PTY fails
+                match self.execute_command_with_pipes(shell_command, cwd).await {
+                    Ok(running_cmd) => running_cmd,
+                    Err(_) => {
+                        let entry = CommandEntry {
+                            command: entry_command,
+                            output: "Error: Failed to execute command".to_string(),
+                            success: false,
+                            ..Default::default()
+                        };
+                        self.add_command_entry(entry).await;
+                        return;
+                    }
                 }
-            }
-        };
+            };
 
+        // Output is matched back to the history entry by this text
+        running_cmd.command = entry_command.clone();
         self.running_command = Some(running_cmd);
 
         // Add initial entry to command history
         let entry = CommandEntry {
-            command,
+            command: entry_command,
             output: "Running...".to_string(),
             success: true,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /cd and plain shell `cd`: change the session's working directory,
+    /// remembering it for the next session when configured to. No directory goes
+    /// home and `-` goes back to the previous one.
+    pub async fn handle_cd_command(&mut self, command: &str) {
+        let dir = match command.strip_prefix("/cd") {
+            Some(dir) => dir.trim(),
+            None => cd_argument(command).unwrap_or_default(),
+        };
+        let result = self.change_directory(dir);
+
+        let (output, success) = match result {
+            Ok(path) => match &self.last_directory_file {
+                Some(file) => match state::save_last_directory(file, &path) {
+                    Ok(()) => (path.display().to_string(), true),
+                    Err(e) => (
+                        format!("{} (could not remember it: {e})", path.display()),
+                        true,
+                    ),
+                },
+                None => (path.display().to_string(), true),
+            },
+            Err(e) => (e, false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Make `dir` the working directory of the session and of later commands
+    fn change_directory(&mut self, dir: &str) -> Result<PathBuf, String> {
+        let path = match dir {
+            "" => resolve_directory_in(&self.current_working_dir, "~")?,
+            "-" => {
+                let previous = self
+                    .previous_working_dir
+                    .clone()
+                    .ok_or("No previous directory")?;
+                if !previous.is_dir() {
+                    return Err(format!("Not a directory: {}", previous.display()));
+                }
+                previous
+            }
+            _ => resolve_directory_in(&self.current_working_dir, dir)?,
+        };
+
+        // The process follows too, so completion and Alt+W see the same directory
+        std::env::set_current_dir(&path).map_err(|e| format!("Could not change directory: {e}"))?;
+        self.previous_working_dir = Some(std::mem::replace(
+            &mut self.current_working_dir,
+            path.clone(),
+        ));
+        Ok(path)
+    }
+
+    /// Handle /in command: run one command in another directory without changing
+    /// the session's working directory
+    pub async fn handle_in_command(&mut self, command: &str) {
+        let rest = command.trim_start_matches("/in").trim_start();
+        let (dir, shell_command) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let shell_command = shell_command.trim();
+
+        let error = if dir.is_empty() || shell_command.is_empty() {
+            "Usage: /in <dir> <command>".to_string()
+        } else {
+            match resolve_directory_in(&self.current_working_dir, dir) {
+                Ok(cwd) => {
+                    self.spawn_command(command.to_string(), shell_command, Some(&cwd))
+                        .await;
+                    return;
+                }
+                Err(e) => e,
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output: error,
+            success: false,
+            ..Default::default()
         };
         self.add_command_entry(entry).await;
     }
@@ -763,12 +1883,18 @@ impl App {
     async fn execute_command_with_pty(
         &mut self,
         command: &str,
+        cwd: Option<&Path>,
     ) -> Result<RunningCommand, Box<dyn std::error::Error + Send + Sync>> {
         // Create a PTY system
         let pty_system = portable_pty::native_pty_system();
 
-        // Get actual terminal size for proper display
+        // Get actual terminal size for proper display, so programs wrap their output to
+        // the width it is shown in
         let (term_cols, term_rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let term_cols = match self.max_content_width {
+            Some(max_width) if max_width > 0 => term_cols.min(max_width),
+            _ => term_cols,
+        };
 
         // Create a PTY with actual terminal size
         let pty_pair = pty_system.openpty(PtySize {
@@ -779,22 +1905,7 @@ impl App {
         })?;
 
         // Create command builder
-        let mut cmd = if cfg!(target_os = "windows") {
-            CommandBuilder::new("cmd")
-        } else {
-            CommandBuilder::new("sh")
-        };
-
-        if cfg!(target_os = "windows") {
-            cmd.args(["/C", command]);
-        } else {
-            cmd.args(["-c", command]);
-        }
-
-        // Set current working directory to preserve user's location
-        if let Ok(current_dir) = std::env::current_dir() {
-            cmd.cwd(current_dir);
-        }
+        let mut cmd = self.shell.pty_command(command, cwd);
 
         // Set environment variables to encourage color output
         cmd.env("TERM", "xterm-256color");
@@ -804,8 +1915,9 @@ impl App {
         // Spawn the child process in the PTY
         let pty_child = pty_pair.slave.spawn_command(cmd)?;
 
-        // Get the reader for PTY output
+        // Get the reader for PTY output and the writer for forwarded keys
         let mut reader = pty_pair.master.try_clone_reader()?;
+        let pty_writer = pty_pair.master.take_writer()?;
 
         // Create channel for receiving streaming output
         let (output_sender, output_receiver) = mpsc::unbounded_channel();
@@ -813,28 +1925,34 @@ impl App {
         // Start background task for streaming PTY output
         tokio::task::spawn_blocking(move || {
             let mut buffer = [0u8; 8192];
+            let mut decoder = Utf8Decoder::new();
+            let mut splitter = LineSplitter::new();
             loop {
                 match reader.read(&mut buffer) {
                     Ok(0) => break, // EOF
                     Ok(n) => {
-                        // Convert bytes to string, handling potential invalid UTF-8
-                        let output = String::from_utf8_lossy(&buffer[..n]);
-
-                        // Split by lines and send each line
-                        for line in output.lines() {
-                            let _ = output_sender.send(OutputLine::Stdout(line.to_string()));
-                        }
-
-                        // Handle partial lines (data without newline at end)
-                        if !output.ends_with('\n') && !output.is_empty() {
-                            if let Some(_last_line) = output.lines().last() {
-                                // This was already sent above, so we don't need to resend
-                            }
+                        // Convert bytes to string, holding back a character split
+                        // across reads until the rest of it arrives
+                        let output = decoder.decode(&buffer[..n]);
+
+                        // Send each line, and the start of an unfinished one so that
+                        // prompts without a newline show up before it arrives
+                        for piece in splitter.split(&output) {
+                            let _ = output_sender.send(match piece {
+                                OutputPiece::Line(line) => OutputLine::Stdout(line),
+                                OutputPiece::Partial(text) => OutputLine::PartialStdout(text),
+                            });
                         }
                     }
                     Err(_) => break,
                 }
             }
+
+            // A character cut off by the end of the output
+            let rest = decoder.finish();
+            if !rest.is_empty() {
+                let _ = output_sender.send(OutputLine::PartialStdout(rest));
+            }
         });
 
         Ok(RunningCommand {
@@ -843,35 +1961,35 @@ impl App {
             pty_child: Some(pty_child),
             stdout_buffer: Vec::new(),
             stderr_buffer: Vec::new(),
+            output_order: Vec::new(),
             output_changed: false,
             output_receiver: Some(output_receiver),
             uses_alternate_screen: false,
             live_ansi_parser: Some(crate::tui::ansi_parser::AnsiParser::new_with_terminal_size()),
+            pty_writer: Some(pty_writer),
+            stdin: None,
+            started_at: Instant::now(),
+            last_output_at: Instant::now(),
+            stdout_times: Vec::new(),
+            stderr_times: Vec::new(),
+            stderr_rows: Vec::new(),
+            stdout_line_open: false,
         })
     }
 
     async fn execute_command_with_pipes(
         &mut self,
         command: &str,
+        cwd: Option<&Path>,
     ) -> Result<RunningCommand, Box<dyn std::error::Error + Send + Sync>> {
-        let mut cmd = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-        } else {
-            Command::new("sh")
-        };
-
-        if cfg!(target_os = "windows") {
-            cmd.args(["/C", command]);
-        } else {
-            cmd.args(["-c", command]);
-        }
+        let mut cmd = self.shell.pipe_command(command, cwd);
 
-        // Set current working directory to preserve user's location
-        if let Ok(current_dir) = std::env::current_dir() {
-            cmd.current_dir(current_dir);
-        }
-
-        let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take();
 
         // Take stdout and stderr for streaming
         let stdout = child.stdout.take();
@@ -923,10 +2041,19 @@ impl App {
             pty_child: None,
             stdout_buffer: Vec::new(),
             stderr_buffer: Vec::new(),
+            output_order: Vec::new(),
             output_changed: false,
             output_receiver: Some(output_receiver),
             uses_alternate_screen: false,
             live_ansi_parser: Some(crate::tui::ansi_parser::AnsiParser::new_with_terminal_size()),
+            pty_writer: None,
+            stdin,
+            started_at: Instant::now(),
+            last_output_at: Instant::now(),
+            stdout_times: Vec::new(),
+            stderr_times: Vec::new(),
+            stderr_rows: Vec::new(),
+            stdout_line_open: false,
         })
     }
 
@@ -935,22 +2062,40 @@ impl App {
             // Try to read any new output from stdout/stderr
             self.read_streaming_output(&mut running).await;
 
+            if let Some(timeout) = self.command_timeout
+                && running.started_at.elapsed() >= timeout
+            {
+                self.running_command = Some(running);
+                let note = format!("Timed out after {}s", timeout.as_secs());
+                self.stop_running_command(&note).await;
+                return;
+            }
+
             // Check if command is finished (different types for PTY vs regular processes)
-            let (command_finished, command_success) = if let Some(ref mut child) = running.child {
-                match child.try_wait() {
-                    Ok(Some(status)) => (true, status.success()),
-                    Ok(None) => (false, true),
-                    Err(_) => (true, false),
-                }
-            } else if let Some(ref mut pty_child) = running.pty_child {
-                match pty_child.try_wait() {
-                    Ok(Some(status)) => (true, status.success()),
-                    Ok(None) => (false, true),
-                    Err(_) => (true, false),
-                }
-            } else {
-                (false, true) // Should not happen, but handle gracefully
-            };
+            // Processes killed by a signal have no exit code
+            let (command_finished, command_success, exit_code) =
+                if let Some(ref mut child) = running.child {
+                    match child.try_wait() {
+                        Ok(Some(status)) => (true, status.success(), status.code()),
+                        Ok(None) => (false, true, None),
+                        Err(_) => (true, false, None),
+                    }
+                } else if let Some(ref mut pty_child) = running.pty_child {
+                    match pty_child.try_wait() {
+                        Ok(Some(status)) => {
+                            let code = status
+                                .signal()
+                                .is_none()
+                                .then(|| i32::try_from(status.exit_code()).ok())
+                                .flatten();
+                            (true, status.success(), code)
+                        }
+                        Ok(None) => (false, true, None),
+                        Err(_) => (true, false, None),
+                    }
+                } else {
+                    (false, true, None) // Should not happen, but handle gracefully
+                };
 
             if command_finished {
                 // Command finished, do multiple final output reads to ensure all data is captured
@@ -962,33 +2107,48 @@ impl App {
                 }
 
                 // Combine all buffered output
-                let combined_output = self.combine_streamed_output(&running);
+                let combined = self.running_output(&running);
 
                 // Update the last entry in history
-                if let Some(last_entry) = self.command_history.last_mut() {
-                    if last_entry.command == running.command {
-                        last_entry.output = if combined_output.trim().is_empty() {
-                            "(no output)".to_string()
-                        } else {
-                            combined_output
-                        };
-                        last_entry.success = command_success;
+                if let Some(last_entry) = self.command_history.last_mut()
+                    && last_entry.command == running.command
+                {
+                    if combined.text.trim().is_empty() {
+                        last_entry.output = "(no output)".to_string();
+                        last_entry.output_streams.clear();
+                        last_entry.output_times.clear();
+                        last_entry.styled_output = None;
+                    } else {
+                        last_entry.output = combined.text;
+                        last_entry.output_streams = combined.streams;
+                        last_entry.output_times = combined.times;
+                        last_entry.styled_output = combined.styled;
                     }
+                    last_entry.success = command_success;
+                    last_entry.exit_code = exit_code;
                 }
+                self.trim_scrollback();
             } else {
                 // Command still running, update output if new data available
                 if running.output_changed {
-                    let combined_output = self.combine_streamed_output(&running);
-                    if let Some(last_entry) = self.command_history.last_mut() {
-                        if last_entry.command == running.command {
-                            last_entry.output = if combined_output.trim().is_empty() {
-                                "Running...".to_string()
-                            } else {
-                                format!("{combined_output}\nRunning...")
-                            };
+                    let combined = self.running_output(&running);
+                    if let Some(last_entry) = self.command_history.last_mut()
+                        && last_entry.command == running.command
+                    {
+                        if combined.text.trim().is_empty() {
+                            last_entry.output = "Running...".to_string();
+                            last_entry.output_streams.clear();
+                            last_entry.output_times.clear();
+                            last_entry.styled_output = None;
+                        } else {
+                            last_entry.output = format!("{}\nRunning...", combined.text);
+                            last_entry.output_streams = combined.streams;
+                            last_entry.output_times = combined.times;
+                            last_entry.styled_output = combined.styled;
                         }
                     }
                     running.output_changed = false;
+                    self.trim_scrollback();
                 }
                 // Put the command back to continue monitoring
                 self.running_command = Some(running);
@@ -996,48 +2156,256 @@ impl App {
         }
     }
 
-    async fn read_streaming_output(&self, running: &mut RunningCommand) {
-        // Read all available output from the channel
-        if let Some(ref mut receiver) = running.output_receiver {
-            let mut new_output = false;
-
-            // Read all available messages without blocking
-            while let Ok(output_line) = receiver.try_recv() {
-                new_output = true;
-                match output_line {
-                    OutputLine::Stdout(line) => {
-                        // Check for alternate screen buffer usage
-                        if line.contains("\x1b[?1049h") || line.contains("\x1b[?1047h") {
-                            running.uses_alternate_screen = true;
-                        }
+    /// Whether the running command has gone quiet long enough, with its input still
+    /// open, that it is probably waiting for the user to type something
+    pub fn command_may_be_waiting_for_input(&self) -> bool {
+        self.running_command.as_ref().is_some_and(|running| {
+            likely_waiting_for_input(
+                running.last_output_at.elapsed(),
+                running.pty_writer.is_some() || running.stdin.is_some(),
+            )
+        })
+    }
 
-                        // Check for exit from alternate screen buffer
-                        if line.contains("\x1b[?1049l") || line.contains("\x1b[?1047l") {
-                            running.uses_alternate_screen = false;
-                        }
+    /// Where `key` goes: to the running command's terminal while it runs under a
+    /// PTY, except for the app's own control keys, otherwise to the app
+    pub fn key_input_target(&self, key: crossterm::event::KeyEvent) -> InputTarget {
+        match &self.running_command {
+            Some(running) => route_key(
+                key,
+                running.pty_writer.is_some(),
+                running.uses_alternate_screen,
+            ),
+            None => InputTarget::App,
+        }
+    }
+
+    /// Send a key press to the running command's terminal. Returns false if the
+    /// key has no terminal encoding or could not be written.
+    pub fn forward_key_to_child(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        key_to_pty_bytes(key).is_some_and(|bytes| self.send_input(&bytes))
+    }
+
+    /// Signal end of input to the running command, like Ctrl-D in a terminal: a PTY
+    /// gets its EOF character and a piped stdin is closed. Returns false if there is
+    /// no command input to end.
+    pub fn send_eof(&mut self) -> bool {
+        let Some(running) = self.running_command.as_mut() else {
+            return false;
+        };
+        if running.pty_writer.is_some() {
+            // Closing our end would not reach the program; the terminal turns
+            // Ctrl-D into end of file itself
+            self.send_input(&[0x04])
+        } else {
+            running.stdin.take().is_some()
+        }
+    }
 
-                        // Store output for real-time display, but handle alternate screen applications specially
-                        running.stdout_buffer.push(line);
+    /// Write `bytes` to the running command's terminal input. Returns false if no
+    /// command is running under a PTY or the write failed.
+    pub fn send_input(&mut self, bytes: &[u8]) -> bool {
+        self.running_command
+            .as_mut()
+            .is_some_and(|running| running.write_to_pty(bytes).is_ok())
+    }
 
-                        // For alternate screen applications, we'll clean up the buffer when they exit
-                        // This allows the animation to be visible in real-time but cleaned up afterwards
-                    }
-                    OutputLine::Stderr(line) => {
-                        running.stderr_buffer.push(line);
+    async fn read_streaming_output(&self, running: &mut RunningCommand) {
+        // Read all available messages from the channel without blocking
+        let mut new_output = false;
+        while let Some(output_line) = running
+            .output_receiver
+            .as_mut()
+            .and_then(|receiver| receiver.try_recv().ok())
+        {
+            new_output = true;
+            match output_line {
+                OutputLine::Stdout(line) => Self::receive_stdout(running, line, true),
+                OutputLine::PartialStdout(text) => Self::receive_stdout(running, text, false),
+                OutputLine::Stderr(line) => {
+                    running.stderr_buffer.push(line);
+                    running.stderr_times.push(running.started_at.elapsed());
+                    if let Some(parser) = running.live_ansi_parser.as_ref() {
+                        running.stderr_rows.push(parser.rows_written());
                     }
+                    running.output_order.push(OutputStream::Stderr);
+                }
+            }
+        }
+
+        if new_output {
+            running.output_changed = true;
+            running.last_output_at = Instant::now();
+            Self::cap_running_output(running, self.max_scrollback_lines);
+        }
+    }
+
+    /// Buffer stdout text read from a running command. Text continuing an unfinished
+    /// line is added to it, so the provisional line is replaced by the longer one.
+    fn receive_stdout(running: &mut RunningCommand, text: String, line_finished: bool) {
+        // Check for alternate screen buffer usage
+        if text.contains("\x1b[?1049h") || text.contains("\x1b[?1047h") {
+            running.uses_alternate_screen = true;
+        }
+
+        // Check for exit from alternate screen buffer
+        if text.contains("\x1b[?1049l") || text.contains("\x1b[?1047l") {
+            running.uses_alternate_screen = false;
+        }
+
+        let continues_line = running.stdout_line_open && !running.stdout_buffer.is_empty();
+
+        // Keep the live terminal state up to date across chunks
+        if let Some(parser) = running.live_ansi_parser.as_mut() {
+            if continues_line {
+                parser.extend_line(&text);
+            } else {
+                parser.feed_line(&text);
+            }
+        }
+
+        // Store output for real-time display, but handle alternate screen applications specially.
+        // For alternate screen applications, we'll clean up the buffer when they exit
+        // This allows the animation to be visible in real-time but cleaned up afterwards
+        match running.stdout_buffer.last_mut() {
+            Some(line) if continues_line => line.push_str(&text),
+            _ => {
+                running.stdout_buffer.push(text);
+                running.stdout_times.push(running.started_at.elapsed());
+                running.output_order.push(OutputStream::Stdout);
+            }
+        }
+        running.stdout_line_open = !line_finished;
+    }
+
+    /// Output to show for a running or just finished command: as its live terminal
+    /// lays it out when the command redraws what it wrote, such as a progress bar,
+    /// otherwise combined from its buffered lines
+    fn running_output(&mut self, running: &RunningCommand) -> CombinedOutput {
+        match running.live_ansi_parser.as_ref() {
+            Some(parser) if parser.output_rewritten() => self.live_output(running, parser),
+            _ => self.combine_streamed_output(running),
+        }
+    }
+
+    /// Stdout as laid out on the live terminal's screen, with stderr placed as
+    /// `stderr_mode` says: after the screen lines when split, or when merged after
+    /// the rows the terminal had written when it arrived. Redrawn lines have no
+    /// single arrival time, so times are left out.
+    fn live_output(&mut self, running: &RunningCommand, parser: &AnsiParser) -> CombinedOutput {
+        let screen = parser.screen_lines();
+        let stderr_mode = self.stderr_mode;
+        let stderr_row = |index: usize| match stderr_mode {
+            StderrMode::Split => screen.len(),
+            StderrMode::Merge => running
+                .stderr_rows
+                .get(index)
+                .map_or(screen.len(), |row| (*row).min(screen.len())),
+        };
+        let mut stderr = running
+            .stderr_buffer
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| (stderr_row(index), chunk))
+            .peekable();
+
+        let mut lines = Vec::new();
+        let mut streams = Vec::new();
+        let mut styled = Vec::new();
+        for row in 0..=screen.len() {
+            while let Some((_, chunk)) = stderr.next_if(|(at, _)| *at <= row) {
+                for line in chunk.lines() {
+                    lines.push(line.to_string());
+                    streams.push(OutputStream::Stderr);
+                    styled.push(self.ansi_parser.parse_line_with_vtparse(line));
                 }
             }
+            if let Some(line) = screen.get(row) {
+                lines.push(line_text(line));
+                streams.push(OutputStream::Stdout);
+                styled.push(line.clone());
+            }
+        }
+
+        CombinedOutput {
+            text: lines.join("\n"),
+            streams,
+            times: Vec::new(),
+            styled: Some(styled),
+        }
+    }
 
-            if new_output {
-                running.output_changed = true;
+    /// Combine buffered stdout and stderr into the entry output, along with the
+    /// stream each output line came from and when it arrived
+    fn combine_streamed_output(&mut self, running: &RunningCommand) -> CombinedOutput {
+        let (combined_text, output_streams, output_times) = match self.stderr_mode {
+            StderrMode::Split => Self::split_streamed_output(running),
+            StderrMode::Merge => Self::merge_streamed_output(running),
+        };
+
+        // Drop what applications drew on the alternate screen once they have left it,
+        // since the main screen is restored to its earlier state. Main-screen output
+        // around it is kept, but no longer lines up with the recorded streams and times.
+        let stripped = strip_alternate_screen(&combined_text);
+        let (combined_text, output_streams, output_times) = if stripped == combined_text {
+            (combined_text, output_streams, output_times)
+        } else {
+            (stripped, Vec::new(), Vec::new())
+        };
+
+        // Only use ANSI parser for complex sequences that need filtering (animations, screen clearing)
+        // For simple color codes, pass through directly to preserve them efficiently
+        if !combined_text.is_empty() && self.needs_animation_filtering(&combined_text) {
+            // Use ANSI parser to filter out animation sequences but preserve final state.
+            // Lines parsed on earlier updates of this command are reused from its cache.
+            let parsed_lines = self.ansi_parser.parse_incremental(&combined_text);
+
+            // The parsed lines keep their styles for rendering; the text only holds
+            // what they show. Lines are re-laid out by the parser, so the stream and
+            // time of each line is lost.
+            let text = parsed_lines
+                .iter()
+                .map(line_text)
+                .collect::<Vec<String>>()
+                .join("\n");
+            CombinedOutput {
+                text,
+                streams: Vec::new(),
+                times: Vec::new(),
+                styled: Some(parsed_lines),
+            }
+        } else {
+            // Pass through directly to preserve ANSI color codes efficiently
+            CombinedOutput {
+                text: combined_text,
+                streams: output_streams,
+                times: output_times,
+                styled: None,
             }
         }
     }
 
-    fn combine_streamed_output(&mut self, running: &RunningCommand) -> String {
+    /// Stdout lines followed by a block of stderr lines
+    fn split_streamed_output(
+        running: &RunningCommand,
+    ) -> (String, Vec<OutputStream>, Vec<Duration>) {
         let stdout_text = running.stdout_buffer.join("\n");
         let stderr_text = running.stderr_buffer.join("\n");
 
+        let output_streams = std::iter::repeat_n(OutputStream::Stdout, stdout_text.lines().count())
+            .chain(std::iter::repeat_n(
+                OutputStream::Stderr,
+                stderr_text.lines().count(),
+            ))
+            .collect();
+
+        let output_times = Self::line_times(&running.stdout_buffer, &running.stdout_times)
+            .chain(Self::line_times(
+                &running.stderr_buffer,
+                &running.stderr_times,
+            ))
+            .collect();
+
         let combined_text = if stderr_text.is_empty() {
             stdout_text
         } else if stdout_text.is_empty() {
@@ -1046,288 +2414,1309 @@ impl App {
             format!("{stdout_text}\n{stderr_text}")
         };
 
-        // For applications that used alternate screen buffer and have exited it,
-        // return empty output since the screen should be restored to its original state
-        if combined_text.contains("\x1b[?1049l") || combined_text.contains("\x1b[?1047l") {
-            return String::new();
+        (combined_text, output_streams, output_times)
+    }
+
+    /// Stdout and stderr lines interleaved in the order they were written
+    fn merge_streamed_output(
+        running: &RunningCommand,
+    ) -> (String, Vec<OutputStream>, Vec<Duration>) {
+        let mut stdout_lines = running.stdout_buffer.iter().zip(&running.stdout_times);
+        let mut stderr_lines = running.stderr_buffer.iter().zip(&running.stderr_times);
+        let mut lines = Vec::new();
+        let mut output_streams = Vec::new();
+        let mut output_times = Vec::new();
+
+        for stream in &running.output_order {
+            let line = match stream {
+                OutputStream::Stdout => stdout_lines.next(),
+                OutputStream::Stderr => stderr_lines.next(),
+            };
+            if let Some((line, time)) = line {
+                // A buffered chunk may hold several lines that all share its stream
+                let line_count = line.lines().count().max(1);
+                output_streams.extend(std::iter::repeat_n(*stream, line_count));
+                output_times.extend(std::iter::repeat_n(*time, line_count));
+                lines.push(line.as_str());
+            }
         }
 
-        // Only use ANSI parser for complex sequences that need filtering (animations, screen clearing)
-        // For simple color codes, pass through directly to preserve them efficiently
-        if !combined_text.is_empty() && self.needs_animation_filtering(&combined_text) {
-            // Use ANSI parser to filter out animation sequences but preserve final state
-            self.ansi_parser.reset();
-            let parsed_lines = self.ansi_parser.parse(&combined_text);
-
-            // Convert back to ANSI but with a simpler approach than before
-            parsed_lines
-                .into_iter()
-                .map(|line| {
-                    line.spans
-                        .into_iter()
-                        .map(|span| self.span_to_ansi_string(span))
-                        .collect::<String>()
-                })
-                .collect::<Vec<String>>()
-                .join("\n")
-        } else {
-            // Pass through directly to preserve ANSI color codes efficiently
-            combined_text
-        }
-    }
-
-    fn span_to_ansi_string(&self, span: ratatui::text::Span) -> String {
-        let mut result = String::new();
-
-        // Add foreground color if present
-        if let Some(color) = span.style.fg {
-            match color {
-                ratatui::style::Color::Black => result.push_str("\x1b[30m"),
-                ratatui::style::Color::Red => result.push_str("\x1b[31m"),
-                ratatui::style::Color::Green => result.push_str("\x1b[32m"),
-                ratatui::style::Color::Yellow => result.push_str("\x1b[33m"),
-                ratatui::style::Color::Blue => result.push_str("\x1b[34m"),
-                ratatui::style::Color::Magenta => result.push_str("\x1b[35m"),
-                ratatui::style::Color::Cyan => result.push_str("\x1b[36m"),
-                ratatui::style::Color::White => result.push_str("\x1b[37m"),
-                ratatui::style::Color::Gray | ratatui::style::Color::DarkGray => {
-                    result.push_str("\x1b[90m")
-                }
-                ratatui::style::Color::LightRed => result.push_str("\x1b[91m"),
-                ratatui::style::Color::LightGreen => result.push_str("\x1b[92m"),
-                ratatui::style::Color::LightYellow => result.push_str("\x1b[93m"),
-                ratatui::style::Color::LightBlue => result.push_str("\x1b[94m"),
-                ratatui::style::Color::LightMagenta => result.push_str("\x1b[95m"),
-                ratatui::style::Color::LightCyan => result.push_str("\x1b[96m"),
-                ratatui::style::Color::Rgb(r, g, b) => {
-                    result.push_str(&format!("\x1b[38;2;{r};{g};{b}m"))
-                }
-                ratatui::style::Color::Indexed(i) => result.push_str(&format!("\x1b[38;5;{i}m")),
-                _ => {}
+        (lines.join("\n"), output_streams, output_times)
+    }
+
+    /// Arrival time of every line in `chunks`; a chunk holding several lines gives
+    /// each of them its own arrival time
+    fn line_times<'a>(
+        chunks: &'a [String],
+        times: &'a [Duration],
+    ) -> impl Iterator<Item = Duration> + 'a {
+        chunks
+            .iter()
+            .zip(times)
+            .flat_map(|(chunk, time)| std::iter::repeat_n(*time, chunk.lines().count().max(1)))
+    }
+
+    fn needs_animation_filtering(&self, text: &str) -> bool {
+        // Only use ANSI parser for applications that need filtering of complex sequences:
+        // 1. Screen clearing and cursor positioning (animations)
+        // 2. Lots of escape sequences that might be animations
+        // Note: Simple color codes are passed through directly for efficiency
+        text.contains("\x1b[2J") ||  // Clear screen
+        text.contains("\x1b[H") ||   // Cursor home
+        text.contains("\x1b[?1049h") || // Alternative screen buffer
+        text.contains("\x1b[?1047h") || // Alternative screen buffer
+        (text.matches('\x1b').count() > 10) // Lots of escape sequences (likely animation)
+    }
+
+    pub async fn kill_running_command(&mut self) {
+        self.stop_running_command("Killed by user (Ctrl-C)").await;
+    }
+
+    /// Kill the running command and end its output with `note`, marking it failed
+    async fn stop_running_command(&mut self, note: &str) {
+        if let Some(mut running) = self.running_command.take() {
+            // Kill the appropriate process type
+            if let Some(ref mut child) = running.child {
+                let _ = child.kill().await;
+            } else if let Some(ref mut pty_child) = running.pty_child {
+                let _ = pty_child.kill();
+            }
+
+            // Get any remaining output before killing
+            let combined = self.running_output(&running);
+
+            // Update the last entry in history
+            if let Some(last_entry) = self.command_history.last_mut()
+                && last_entry.command == running.command
+            {
+                if combined.text.trim().is_empty() {
+                    last_entry.output = note.to_string();
+                    last_entry.output_streams.clear();
+                    last_entry.output_times.clear();
+                    last_entry.styled_output = None;
+                } else {
+                    last_entry.output = format!("{}\n{note}", combined.text);
+                    last_entry.output_streams = combined.streams;
+                    last_entry.output_times = combined.times;
+                    last_entry.styled_output = combined.styled;
+                }
+                last_entry.success = false;
+            }
+            self.trim_scrollback();
+        }
+    }
+
+    /// Drop or trim the oldest history entries until the history fits in
+    /// `max_scrollback_lines`. Older entries are dropped whole; the entry the limit
+    /// falls in keeps its newest output lines under a marker saying how many were
+    /// trimmed. The newest entry is never dropped.
+    fn trim_scrollback(&mut self) {
+        let entry_lines = |entry: &CommandEntry| 1 + entry.output.lines().count() + 1;
+        let settled = self.command_history.len().saturating_sub(1);
+        let (mut counted, mut settled_lines) = self.scrollback_count;
+        if counted > settled {
+            // Entries were removed since they were counted
+            (counted, settled_lines) = (0, 0);
+        }
+        settled_lines += self.command_history[counted..settled]
+            .iter()
+            .map(entry_lines)
+            .sum::<usize>();
+        let last_lines = self.command_history.last().map_or(0, entry_lines);
+        let mut excess = (settled_lines + last_lines).saturating_sub(self.max_scrollback_lines);
+        if excess > 0 {
+            self.history_width = HistoryWidth::default();
+        }
+
+        let mut index = 0;
+        while excess > 0 && index < self.command_history.len() {
+            let is_last = index + 1 == self.command_history.len();
+            let output_lines = self.command_history[index].output.lines().count();
+            // Trimming leaves at least the marker, so an entry that would be left
+            // with nothing else goes as a whole
+            let removed = if excess >= output_lines.saturating_sub(1) && !is_last {
+                self.command_history.remove(index);
+                1 + output_lines + 1
+            } else {
+                index += 1;
+                trim_oldest_output(&mut self.command_history[index - 1], excess)
+            };
+            excess = excess.saturating_sub(removed);
+            if !is_last {
+                settled_lines -= removed;
+            }
+        }
+        self.scrollback_count = (self.command_history.len().saturating_sub(1), settled_lines);
+    }
+
+    /// Drop the oldest buffered lines of a running command once it holds more than
+    /// `limit`, so that its output stops growing. A marker line counting them takes
+    /// their place, and later drops add to its count.
+    fn cap_running_output(running: &mut RunningCommand, limit: usize) {
+        // Keep at least one line besides the marker
+        let limit = limit.max(2);
+        let buffered = running.output_order.len();
+        if buffered <= limit {
+            return;
+        }
+
+        let earlier = match running.output_order.first() {
+            Some(OutputStream::Stdout) => running
+                .stdout_buffer
+                .first()
+                .and_then(|line| trimmed_lines_count(line)),
+            _ => None,
+        };
+        // Lines after a marker left by an earlier drop
+        let start = usize::from(earlier.is_some());
+        let remove = buffered - limit + 1 - start;
+
+        let dropped: Vec<OutputStream> =
+            running.output_order.drain(start..start + remove).collect();
+        let stdout_count = dropped
+            .iter()
+            .filter(|stream| **stream == OutputStream::Stdout)
+            .count();
+        let stderr_count = remove - stdout_count;
+        let drain_range =
+            |len: usize, from: usize, count: usize| from.min(len)..(from + count).min(len);
+
+        // The marker takes the time of the last stdout line it replaces
+        let times_kept = running.stdout_times.len() == running.stdout_buffer.len();
+        let marker_time = running
+            .stdout_times
+            .get((start + stdout_count).saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
+        let range = drain_range(running.stdout_buffer.len(), start, stdout_count);
+        running.stdout_buffer.drain(range);
+        let range = drain_range(running.stdout_times.len(), start, stdout_count);
+        running.stdout_times.drain(range);
+        let range = drain_range(running.stderr_buffer.len(), 0, stderr_count);
+        running.stderr_buffer.drain(range);
+        let range = drain_range(running.stderr_times.len(), 0, stderr_count);
+        running.stderr_times.drain(range);
+        let range = drain_range(running.stderr_rows.len(), 0, stderr_count);
+        running.stderr_rows.drain(range);
+        if running.stdout_buffer.len() == start {
+            // An unfinished line was dropped; what follows of it starts a new one
+            running.stdout_line_open = false;
+        }
+
+        let marker = trimmed_lines_marker(earlier.unwrap_or(0) + remove);
+        if earlier.is_some() {
+            running.stdout_buffer[0] = marker;
+        } else {
+            running.stdout_buffer.insert(0, marker);
+            running.output_order.insert(0, OutputStream::Stdout);
+            if times_kept {
+                running.stdout_times.insert(0, marker_time);
+            }
+        }
+    }
+
+    /// Calculate total number of lines in command history
+    pub fn get_total_history_lines(&self) -> usize {
+        let mut total_lines = 0;
+        for entry in &self.command_history {
+            // Command line
+            total_lines += 1;
+            // Output lines
+            if !entry.output.is_empty() {
+                total_lines += self.output_line_count(&entry.output);
+            }
+            // Empty spacing line
+            total_lines += 1;
+        }
+        total_lines
+    }
+
+    /// History line on which each command entry starts, counted the same way as
+    /// `get_total_history_lines`
+    fn command_start_lines(&self) -> Vec<usize> {
+        let mut starts = Vec::with_capacity(self.command_history.len());
+        let mut line = 0;
+        for entry in &self.command_history {
+            starts.push(line);
+            line += 1 + self.output_line_count(&entry.output) + 1;
+        }
+        starts
+    }
+
+    /// Scroll so that the command line of the `index`th history entry is at the top
+    /// of the history area. Output longer than the area runs off the bottom; entries
+    /// near the end scroll to the bottom instead. Out of range indexes jump to the last
+    /// entry.
+    pub fn jump_to_command(&mut self, index: usize) {
+        let starts = self.command_start_lines();
+        let Some(&start) = starts.get(index.min(starts.len().saturating_sub(1))) else {
+            return;
+        };
+
+        let total_lines = self.get_total_history_lines();
+        let available_height = self.history_area_height.saturating_sub(2) as usize;
+        self.scroll_offset = if total_lines <= available_height {
+            0
+        } else {
+            total_lines.saturating_sub(available_height + start)
+        };
+    }
+
+    /// Jump to the last command starting above the top of the history area
+    pub fn jump_to_previous_command(&mut self) {
+        let first_visible = self.visible_history_start();
+        let starts = self.command_start_lines();
+        let index = starts.partition_point(|start| *start < first_visible);
+        if index > 0 {
+            self.jump_to_command(index - 1);
+        }
+    }
+
+    /// Jump to the first command starting below the top of the history area, or to
+    /// the bottom when there is none
+    pub fn jump_to_next_command(&mut self) {
+        let first_visible = self.visible_history_start();
+        let starts = self.command_start_lines();
+        let index = starts.partition_point(|start| *start <= first_visible);
+        if index < starts.len() {
+            self.jump_to_command(index);
+        } else {
+            self.scroll_offset = 0;
+        }
+    }
+
+    /// Number of lines `output` takes up in the history
+    fn output_line_count(&self, output: &str) -> usize {
+        if self.collapse_repeats {
+            display_output_lines(output, true).len()
+        } else {
+            output.lines().count()
+        }
+    }
+
+    /// Width in columns of the widest line in the command history
+    pub fn get_max_history_line_width(&mut self) -> usize {
+        let settled = self.command_history.len().saturating_sub(1);
+        let mut width = self.history_width;
+        if width.measured > settled
+            || width.show_line_numbers != self.show_line_numbers
+            || width.collapse_repeats != self.collapse_repeats
+        {
+            width = HistoryWidth {
+                show_line_numbers: self.show_line_numbers,
+                collapse_repeats: self.collapse_repeats,
+                ..HistoryWidth::default()
+            };
+        }
+        for entry in &self.command_history[width.measured..settled] {
+            width.widest = width.widest.max(self.entry_line_width(entry));
+        }
+        width.measured = settled;
+        self.history_width = width;
+
+        let last = self
+            .command_history
+            .last()
+            .map_or(0, |entry| self.entry_line_width(entry));
+        width.widest.max(last)
+    }
+
+    /// Width in columns of the widest line of one history entry
+    fn entry_line_width(&self, entry: &CommandEntry) -> usize {
+        // Command line including the "> " prompt
+        let mut max_width = entry.command.chars().count() + 2;
+
+        let gutter = if self.show_line_numbers {
+            line_number_width(&entry.output)
+        } else {
+            0
+        };
+        for (_, line) in display_output_lines(&entry.output, self.collapse_repeats) {
+            // Measure the visible text, ignoring ANSI escape sequences
+            let mut parser = AnsiParser::new(80, 1);
+            let width = parser
+                .parse(&line)
+                .first()
+                .map(|parsed| parsed.width())
+                .unwrap_or(0);
+            max_width = max_width.max(gutter + width);
+        }
+        max_width
+    }
+
+    /// Columns taken by the line number gutter on a history line; only output lines
+    /// are numbered
+    fn line_number_gutter(&self, content_line: usize) -> usize {
+        if !self.show_line_numbers {
+            return 0;
+        }
+
+        let mut start = 0;
+        for entry in &self.command_history {
+            let output_lines = self.output_line_count(&entry.output);
+            if content_line < start + 1 + output_lines + 1 {
+                let is_output = content_line > start && content_line <= start + output_lines;
+                return if is_output {
+                    line_number_width(&entry.output)
+                } else {
+                    0
+                };
+            }
+            start += 1 + output_lines + 1;
+        }
+        0
+    }
+
+    /// Scroll the history horizontally by `delta` columns, clamped to the widest line
+    pub fn scroll_horizontally(&mut self, delta: isize) {
+        let max_offset = self.get_max_history_line_width().saturating_sub(1);
+        self.horizontal_scroll_offset = self
+            .horizontal_scroll_offset
+            .saturating_add_signed(delta)
+            .min(max_offset);
+    }
+
+    /// Update command filtering based on current input
+    pub fn update_command_filtering(&mut self) {
+        if self.current_input.starts_with('/') {
+            self.command_filter = self.current_input[1..].to_string();
+            self.show_command_list = true;
+            self.selected_command_index = 0;
+            self.user_navigated_command_list = false;
+        } else {
+            self.show_command_list = false;
+            self.command_filter.clear();
+            self.user_navigated_command_list = false;
+        }
+    }
+
+    /// Get filtered commands based on current filter
+    pub fn get_filtered_commands(&self) -> Vec<String> {
+        if self.command_filter.is_empty() {
+            // Show only top-level commands when no filter
+            self.available_commands
+                .iter()
+                .filter(|cmd| !cmd[1..].contains(' '))
+                .cloned()
+                .collect()
+        } else {
+            // Show matching commands including subcommands
+            let filter = &self.command_filter;
+            self.available_commands
+                .iter()
+                .filter(|cmd| {
+                    let cmd_without_slash = &cmd[1..];
+                    cmd_without_slash.starts_with(filter)
+                })
+                .cloned()
+                .collect()
+        }
+    }
+
+    /// Handle built-in commands that don't need shell execution
+    pub async fn handle_builtin_command(&mut self, command: &str) -> bool {
+        let Some(builtin) = find_builtin(command) else {
+            let Some(shell_command) = self.custom_command_line(command) else {
+                return false;
+            };
+            let cwd = self.current_working_dir.clone();
+            self.spawn_command(command.to_string(), &shell_command, Some(&cwd))
+                .await;
+            return true;
+        };
+
+        match builtin.builtin {
+            Builtin::Quit => match &self.running_command {
+                Some(running) => {
+                    let question = format!(
+                        "'{}' is still running - quit anyway? [y/N]",
+                        running.command
+                    );
+                    self.ask_to_confirm(question, ConfirmAction::Quit).await;
+                }
+                None => self.should_quit = true,
+            },
+            Builtin::TaskList => {
+                self.mode = AppMode::TaskList;
+            }
+            Builtin::TaskAgenda => {
+                self.mode = AppMode::Agenda;
+            }
+            Builtin::Clear => {
+                self.clear_screen();
+            }
+            Builtin::Vacuum => {
+                let (output, success) = match operations::vacuum(&self.db_pool).await {
+                    Ok(report) => (report.summary(), true),
+                    Err(e) => (format!("Error vacuuming database: {e}"), false),
+                };
+                let entry = CommandEntry {
+                    command: command.to_string(),
+                    output,
+                    success,
+                    ..Default::default()
+                };
+                self.add_command_entry(entry).await;
+            }
+            Builtin::Config => {
+                let entry = CommandEntry {
+                    command: command.to_string(),
+                    output: self.settings.describe(),
+                    success: true,
+                    ..Default::default()
+                };
+                self.add_command_entry(entry).await;
+            }
+            Builtin::ReloadConfig => self.handle_reload_config_command(command).await,
+            Builtin::OpenConfig => {
+                self.open_in_file_manager(command, Some(Settings::config_directory()))
+                    .await
+            }
+            Builtin::OpenData => {
+                let db_path = self.settings.database_path.as_deref().map(Path::new);
+                self.open_in_file_manager(command, database_directory(db_path))
+                    .await
+            }
+            Builtin::Done => {
+                self.handle_task_status_command(command, TaskStatus::Done)
+                    .await
+            }
+            Builtin::Progress => {
+                self.handle_task_status_command(command, TaskStatus::InProgress)
+                    .await
+            }
+            Builtin::TaskSearch => self.handle_task_search_command(command).await,
+            Builtin::TaskDelete => self.handle_task_delete_command(command).await,
+            Builtin::TaskSub => self.handle_task_sub_command(command).await,
+            Builtin::TaskNotes => self.handle_task_notes_command(command).await,
+            Builtin::TaskLabel => self.handle_task_label_command(command).await,
+            Builtin::TaskAdd => self.handle_task_add_command(command).await,
+            Builtin::Cd => self.handle_cd_command(command).await,
+            Builtin::In => self.handle_in_command(command).await,
+            Builtin::Backup => self.handle_backup_command(command).await,
+            Builtin::Stderr => self.handle_stderr_command(command).await,
+            Builtin::Collapse => self.handle_collapse_command(command).await,
+            Builtin::LineNumbers => self.handle_line_numbers_command(command).await,
+            Builtin::Timestamps => self.handle_timestamps_command(command).await,
+            Builtin::Replay => self.handle_replay_command(command).await,
+            Builtin::ExportHtml => self.handle_export_html_command(command).await,
+            Builtin::Help => self.handle_help_command(command).await,
+            Builtin::History => self.handle_history_command(command).await,
+            Builtin::Stats => self.handle_stats_command(command).await,
+        }
+        true
+    }
+
+    /// Show `dir` in the system file manager
+    async fn open_in_file_manager(&mut self, command: &str, dir: Option<PathBuf>) {
+        let (output, success) = match dir {
+            None => ("The database is in memory".to_string(), false),
+            Some(dir) if !dir.is_dir() => {
+                (format!("Directory not found: {}", dir.display()), false)
+            }
+            Some(dir) => {
+                let dir = std::path::absolute(&dir).unwrap_or(dir);
+                match open::that_detached(&dir) {
+                    Ok(()) => (format!("Opened {}", dir.display()), true),
+                    Err(e) => (format!("Could not open {}: {e}", dir.display()), false),
+                }
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Shell command running the script of the custom command `command` names, with
+    /// the arguments typed after the name. None when it names no custom command.
+    pub fn custom_command_line(&self, command: &str) -> Option<String> {
+        let (name, arguments) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        let script = self.custom_commands.get(name.strip_prefix('/')?)?;
+        let script = match script.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest).to_string_lossy().into_owned())
+                .unwrap_or_else(|| script.clone()),
+            None => script.clone(),
+        };
+
+        let arguments = arguments.trim();
+        Some(if arguments.is_empty() {
+            shell_quote(&script)
+        } else {
+            format!("{} {arguments}", shell_quote(&script))
+        })
+    }
+
+    /// Switch how stderr is arranged for subsequent commands
+    pub async fn handle_stderr_command(&mut self, command: &str) {
+        let (output, success) = match command.trim_start_matches("/stderr").trim() {
+            "split" => {
+                self.stderr_mode = StderrMode::Split;
+                ("Stderr will be shown after stdout".to_string(), true)
+            }
+            "merge" => {
+                self.stderr_mode = StderrMode::Merge;
+                ("Stderr will be interleaved with stdout".to_string(), true)
+            }
+            "" => {
+                let current = match self.stderr_mode {
+                    StderrMode::Split => "split",
+                    StderrMode::Merge => "merge",
+                };
+                (
+                    format!("Stderr mode: {current}\nUsage: /stderr split|merge"),
+                    true,
+                )
+            }
+            other => (
+                format!("Unknown stderr mode '{other}'\nUsage: /stderr split|merge"),
+                false,
+            ),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Turn collapsing of repeated output lines on or off
+    pub async fn handle_collapse_command(&mut self, command: &str) {
+        let (output, success) = match command.trim_start_matches("/collapse").trim() {
+            "on" => {
+                self.collapse_repeats = true;
+                ("Repeated output lines will be shown once".to_string(), true)
+            }
+            "off" => {
+                self.collapse_repeats = false;
+                ("Repeated output lines shown in full".to_string(), true)
+            }
+            "" => {
+                let current = if self.collapse_repeats { "on" } else { "off" };
+                (
+                    format!("Collapse repeats: {current}\nUsage: /collapse on|off"),
+                    true,
+                )
+            }
+            other => (
+                format!("Unknown collapse setting '{other}'\nUsage: /collapse on|off"),
+                false,
+            ),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Turn output line numbers on or off
+    pub async fn handle_line_numbers_command(&mut self, command: &str) {
+        let (output, success) = match command.trim_start_matches("/line-numbers").trim() {
+            "on" => {
+                self.show_line_numbers = true;
+                ("Output lines will be numbered".to_string(), true)
+            }
+            "off" => {
+                self.show_line_numbers = false;
+                ("Output line numbers hidden".to_string(), true)
+            }
+            "" => {
+                let current = if self.show_line_numbers { "on" } else { "off" };
+                (
+                    format!("Line numbers: {current}\nUsage: /line-numbers on|off"),
+                    true,
+                )
+            }
+            other => (
+                format!("Unknown line numbers setting '{other}'\nUsage: /line-numbers on|off"),
+                false,
+            ),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Turn per-line output timestamps on or off
+    pub async fn handle_timestamps_command(&mut self, command: &str) {
+        let (output, success) = match command.trim_start_matches("/timestamps").trim() {
+            "on" => {
+                self.show_timestamps = true;
+                ("Output lines will show when they arrived".to_string(), true)
+            }
+            "off" => {
+                self.show_timestamps = false;
+                ("Output timestamps hidden".to_string(), true)
+            }
+            "" => {
+                let current = if self.show_timestamps { "on" } else { "off" };
+                (
+                    format!("Timestamps: {current}\nUsage: /timestamps on|off"),
+                    true,
+                )
+            }
+            other => (
+                format!("Unknown timestamps setting '{other}'\nUsage: /timestamps on|off"),
+                false,
+            ),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// /help [filter] and /help keys [filter]
+    pub async fn handle_help_command(&mut self, command: &str) {
+        let args = command.trim_start_matches("/help").trim();
+        let (keys, filter) = match args.strip_prefix("keys") {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => (true, rest.trim()),
+            _ => (false, args),
+        };
+        let filter_option = (!filter.is_empty()).then_some(filter);
+        let text = if keys {
+            help::key_help(filter_option)
+        } else {
+            help::command_help(filter_option)
+        };
+
+        let (output, success) = match text {
+            Some(text) => (text, true),
+            None => (format!("No help entries match '{filter}'"), false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// List the most recent persisted commands, numbered, with the time they ran
+    pub async fn handle_history_command(&mut self, command: &str) {
+        let count = command.trim_start_matches("/history").trim();
+        let limit = if count.is_empty() {
+            Ok(DEFAULT_HISTORY_LIMIT)
+        } else {
+            count
+                .parse::<usize>()
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| format!("Invalid count '{count}'\nUsage: /history [N]"))
+        };
+
+        let result = match (limit, &self.history_manager) {
+            (Err(e), _) => Err(e),
+            (Ok(_), None) => {
+                Err("Command history is not saved (history.persist is off)".to_string())
+            }
+            (Ok(limit), Some(history_manager)) => history_manager
+                .recent_history(limit)
+                .await
+                .map_err(|e| format!("Error reading command history: {e}")),
+        };
+
+        let (output, success) = match result {
+            Ok(entries) if entries.is_empty() => ("No commands in history".to_string(), true),
+            Ok(entries) => {
+                let width = entries
+                    .last()
+                    .map_or(1, |entry| entry.number.to_string().len());
+                let lines: Vec<String> = entries.iter().map(|entry| entry.display(width)).collect();
+                (lines.join("\n"), true)
+            }
+            Err(e) => (e, false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /stats: summarize the saved history and the exit codes of this session
+    pub async fn handle_stats_command(&mut self, command: &str) {
+        let result = match &self.history_manager {
+            None => Err("Command history is not saved (history.persist is off)".to_string()),
+            Some(history_manager) => history_manager
+                .all_history()
+                .await
+                .map_err(|e| format!("Error reading command history: {e}")),
+        };
+
+        let (output, success) = match result {
+            Ok(entries) if entries.is_empty() => ("No commands in history".to_string(), true),
+            Ok(entries) => {
+                let mut lines = vec![format!("Commands in history: {}", entries.len())];
+
+                lines.push(String::new());
+                lines.push("Most run:".to_string());
+                let top = most_run_commands(&entries, STATS_TOP_COMMANDS);
+                let width = top.first().map_or(1, |(_, count)| count.to_string().len());
+                for (command, count) in top {
+                    lines.push(format!("  {count:>width$}  {command}"));
+                }
+
+                let days = commands_per_day(&entries, &chrono::Local);
+                if !days.is_empty() {
+                    lines.push(String::new());
+                    lines.push("Per day:".to_string());
+                    for (day, count) in &days[days.len().saturating_sub(STATS_DAYS)..] {
+                        lines.push(format!("  {day}  {count}"));
+                    }
+                }
+
+                let exit_codes = self
+                    .command_history
+                    .iter()
+                    .filter_map(|entry| entry.exit_code);
+                let (succeeded, failed) = exit_code_counts(exit_codes);
+                if let Some(rate) = (succeeded * 100).checked_div(succeeded + failed) {
+                    lines.push(String::new());
+                    lines.push(format!(
+                        "This session: {succeeded} succeeded, {failed} failed ({rate}% success)"
+                    ));
+                }
+                (lines.join("\n"), true)
+            }
+            Err(e) => (e, false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Start replaying the output of the most recent command with recorded timing.
+    /// An optional speed multiplier such as `2` or `0.5x` changes the pace.
+    pub async fn handle_replay_command(&mut self, command: &str) {
+        let speed = command.trim_start_matches("/replay").trim();
+        let speed = if speed.is_empty() {
+            Ok(1.0)
+        } else {
+            speed
+                .trim_end_matches('x')
+                .parse::<f64>()
+                .ok()
+                .filter(|speed| speed.is_finite() && *speed > 0.0)
+                .ok_or_else(|| format!("Invalid speed '{speed}'\nUsage: /replay [speed]"))
+        };
+
+        let replay = speed.and_then(|speed| {
+            if self.running_command.is_some() {
+                return Err("Wait for the running command to finish before replaying".to_string());
+            }
+            self.command_history
+                .iter()
+                .rev()
+                .find_map(|entry| Replay::new(command.to_string(), entry, speed))
+                .ok_or_else(|| "No command output to replay".to_string())
+        });
+
+        let entry = match replay {
+            Ok(replay) => {
+                self.replay = Some(replay);
+                CommandEntry {
+                    command: command.to_string(),
+                    output: String::new(),
+                    success: true,
+                    ..Default::default()
+                }
+            }
+            Err(output) => CommandEntry {
+                command: command.to_string(),
+                output,
+                success: false,
+                ..Default::default()
+            },
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Reveal the replayed lines that are due. The replay ends when every line is
+    /// shown or another entry has been added after it.
+    pub fn advance_replay(&mut self) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let in_progress = match self.command_history.last_mut() {
+            Some(entry) if entry.command == replay.command => replay.update(entry),
+            _ => false,
+        };
+        if !in_progress {
+            self.replay = None;
+        }
+    }
+
+    pub async fn handle_backup_command(&mut self, command: &str) {
+        let path = command.trim_start_matches("/backup").trim();
+        let (output, success) = if path.is_empty() {
+            ("Usage: /backup <path>".to_string(), false)
+        } else {
+            match backup::backup_to_file(&self.db_pool, Path::new(path)).await {
+                Ok(count) => (format!("Backed up {count} tasks to {path}"), true),
+                Err(e) => (format!("Error writing backup: {e}"), false),
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /export-html, writing the history so far with its colors to a file
+    pub async fn handle_export_html_command(&mut self, command: &str) {
+        let path = command.trim_start_matches("/export-html").trim();
+        let (output, success) = if path.is_empty() {
+            ("Usage: /export-html <path>".to_string(), false)
+        } else {
+            let html = history_to_html(&self.command_history, &self.theme);
+            match std::fs::write(path, html) {
+                Ok(()) => (
+                    format!("Exported {} commands to {path}", self.command_history.len()),
+                    true,
+                ),
+                Err(e) => (format!("Error writing {path}: {e}"), false),
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /task add command
+    pub async fn handle_task_add_command(&mut self, command: &str) {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        if parts.len() < 3 {
+            let entry = CommandEntry {
+                command: command.to_string(),
+                output: "Usage: /task add <title>".to_string(),
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+            return;
+        }
+
+        let task = match parse_new_task(&parts[2..]) {
+            Ok(task) => task,
+            Err(error) => {
+                let entry = CommandEntry {
+                    command: command.to_string(),
+                    output: error,
+                    success: false,
+                    ..Default::default()
+                };
+                self.add_command_entry(entry).await;
+                return;
+            }
+        };
+
+        // Store the task to add asynchronously
+        self.pending_task_add = Some(task);
+        self.mode = AppMode::TaskList;
+    }
+
+    /// The one task whose id starts with `prefix`, or a message saying why there
+    /// is not exactly one
+    async fn resolve_task_prefix(&self, prefix: &str) -> Result<Task, String> {
+        if !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return Err(format!("Invalid task id '{prefix}'"));
+        }
+        let mut matches = operations::find_tasks_by_id_prefix(&self.db_pool, prefix)
+            .await
+            .map_err(|e| format!("Error looking up task: {e}"))?;
+        match matches.len() {
+            0 => Err(format!("No task id starts with '{prefix}'")),
+            1 => Ok(matches.remove(0)),
+            count => {
+                let candidates: Vec<String> = matches
+                    .iter()
+                    .map(|task| format!("  {}  {}", task.id, task.title))
+                    .collect();
+                Err(format!(
+                    "Task id prefix '{prefix}' matches {count} tasks:\n{}",
+                    candidates.join("\n")
+                ))
+            }
+        }
+    }
+
+    /// Handle /task delete, asking before removing the one task whose id starts
+    /// with the given prefix
+    pub async fn handle_task_delete_command(&mut self, command: &str) {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let output = match parts.get(2) {
+            None => "Usage: /task delete <id-prefix>".to_string(),
+            Some(prefix) => match self.resolve_task_prefix(prefix).await {
+                Ok(task) => {
+                    let action = ConfirmAction::DeleteTask {
+                        command: command.to_string(),
+                        id: task.id,
+                        title: task.title.clone(),
+                    };
+                    let question = format!("Delete task '{}'? [y/N]", task.title);
+                    self.ask_to_confirm(question, action).await;
+                    return;
+                }
+                Err(error) => error,
+            },
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success: false,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Delete the task with `id`, confirmed after `command` asked for it
+    async fn delete_task(&mut self, command: &str, id: Uuid, title: &str) {
+        let (output, success) = match operations::delete_task(&self.db_pool, id).await {
+            Ok(()) => match self.load_tasks().await {
+                Ok(()) => (format!("Deleted task '{title}' ({id})"), true),
+                Err(e) => (format!("Error reloading tasks: {e}"), false),
+            },
+            Err(e) => (format!("Error deleting task: {e}"), false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /task search, listing the tasks whose title contains the query
+    pub async fn handle_task_search_command(&mut self, command: &str) {
+        let query = command.trim_start_matches("/task search").trim();
+        let (output, success) = if query.is_empty() {
+            ("Usage: /task search <query>".to_string(), false)
+        } else {
+            match operations::search_tasks(&self.db_pool, query).await {
+                Ok(tasks) if tasks.is_empty() => (format!("No tasks match '{query}'"), true),
+                Ok(tasks) => {
+                    let lines: Vec<String> = tasks
+                        .iter()
+                        .map(|task| format!("  {}  [{}] {}", task.id, task.status, task.title))
+                        .collect();
+                    (
+                        format!(
+                            "{} task(s) match '{query}':\n{}",
+                            tasks.len(),
+                            lines.join("\n")
+                        ),
+                        true,
+                    )
+                }
+                Err(e) => (format!("Error searching tasks: {e}"), false),
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Handle /done and /progress, setting the status of the task matching an id prefix
+    pub async fn handle_task_status_command(&mut self, command: &str, status: TaskStatus) {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let (output, success) = match &parts[1..] {
+            [] => (format!("Usage: {} <id-prefix>...", parts[0]), false),
+            [prefix] => match self.resolve_task_prefix(prefix).await {
+                Ok(task) => {
+                    match operations::update_task_status(&self.db_pool, task.id, &status).await {
+                        Ok(()) => match self.load_tasks().await {
+                            Ok(()) => (format!("Task '{}' is now {status}", task.title), true),
+                            Err(e) => (format!("Error reloading tasks: {e}"), false),
+                        },
+                        Err(e) => (format!("Error updating task: {e}"), false),
+                    }
+                }
+                Err(error) => (error, false),
+            },
+            // Changing several tasks at once is asked about first
+            prefixes => {
+                let mut ids = Vec::new();
+                let mut error = None;
+                for prefix in prefixes {
+                    match self.resolve_task_prefix(prefix).await {
+                        Ok(task) => ids.push(task.id),
+                        Err(e) => {
+                            error = Some(e);
+                            break;
+                        }
+                    }
+                }
+                match error {
+                    Some(error) => (error, false),
+                    None => {
+                        let question = format!("Mark {} tasks as {status}? [y/N]", ids.len());
+                        let action = ConfirmAction::UpdateTaskStatuses {
+                            command: command.to_string(),
+                            ids,
+                            status,
+                        };
+                        self.ask_to_confirm(question, action).await;
+                        return;
+                    }
+                }
+            }
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
+
+    /// Set the status of the tasks with `ids`, confirmed after `command` asked for it
+    async fn update_task_statuses(&mut self, command: &str, ids: &[Uuid], status: TaskStatus) {
+        let mut result = Ok(());
+        for id in ids {
+            result = operations::update_task_status(&self.db_pool, *id, &status).await;
+            if result.is_err() {
+                break;
             }
         }
+        let (output, success) = match result {
+            Ok(()) => match self.load_tasks().await {
+                Ok(()) => (format!("{} tasks are now {status}", ids.len()), true),
+                Err(e) => (format!("Error reloading tasks: {e}"), false),
+            },
+            Err(e) => (format!("Error updating task: {e}"), false),
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
 
-        // Add background color if present
-        if let Some(bg_color) = span.style.bg {
-            match bg_color {
-                ratatui::style::Color::Black => result.push_str("\x1b[40m"),
-                ratatui::style::Color::Red => result.push_str("\x1b[41m"),
-                ratatui::style::Color::Green => result.push_str("\x1b[42m"),
-                ratatui::style::Color::Yellow => result.push_str("\x1b[43m"),
-                ratatui::style::Color::Blue => result.push_str("\x1b[44m"),
-                ratatui::style::Color::Magenta => result.push_str("\x1b[45m"),
-                ratatui::style::Color::Cyan => result.push_str("\x1b[46m"),
-                ratatui::style::Color::White => result.push_str("\x1b[47m"),
-                ratatui::style::Color::Rgb(r, g, b) => {
-                    result.push_str(&format!("\x1b[48;2;{r};{g};{b}m"))
+    /// Handle /task sub command, adding a subtask under an existing task
+    pub async fn handle_task_sub_command(&mut self, command: &str) {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let (output, success) = if parts.len() < 4 {
+            (
+                "Usage: /task sub <parent-id-prefix> <title>".to_string(),
+                false,
+            )
+        } else {
+            match self.resolve_task_prefix(parts[2]).await {
+                Ok(parent) => {
+                    let mut task = Task::new(parts[3..].join(" "));
+                    match operations::create_subtask(&self.db_pool, parent.id, &mut task).await {
+                        Ok(()) => {
+                            self.mode = AppMode::TaskList;
+                            match self.load_tasks().await {
+                                Ok(()) => (format!("Subtask '{}' added", task.title), true),
+                                Err(e) => (format!("Error reloading tasks: {e}"), false),
+                            }
+                        }
+                        Err(sqlx::Error::RowNotFound) => {
+                            (format!("Task {} not found", parent.id), false)
+                        }
+                        Err(e) => (format!("Error adding subtask: {e}"), false),
+                    }
                 }
-                ratatui::style::Color::Indexed(i) => result.push_str(&format!("\x1b[48;5;{i}m")),
-                _ => {}
+                Err(message) => (message, false),
             }
-        }
+        };
 
-        // Add modifiers
-        if span
-            .style
-            .add_modifier
-            .contains(ratatui::style::Modifier::BOLD)
-        {
-            result.push_str("\x1b[1m");
-        }
-        if span
-            .style
-            .add_modifier
-            .contains(ratatui::style::Modifier::ITALIC)
-        {
-            result.push_str("\x1b[3m");
-        }
-        if span
-            .style
-            .add_modifier
-            .contains(ratatui::style::Modifier::UNDERLINED)
-        {
-            result.push_str("\x1b[4m");
-        }
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
 
-        // Add the text content
-        result.push_str(&span.content);
+    /// Handle /task label add|rm, adding or removing a label on a stored task
+    pub async fn handle_task_label_command(&mut self, command: &str) {
+        const USAGE: &str = "Usage: /task label add|rm <id-prefix> <label>";
 
-        // Add reset if we added any styling
-        if result != span.content {
-            result.push_str("\x1b[0m");
-        }
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let (output, success) = if parts.len() < 5 || !matches!(parts[2], "add" | "rm") {
+            (USAGE.to_string(), false)
+        } else {
+            let label = parts[4..].join(" ");
+            match self.resolve_task_prefix(parts[3]).await {
+                Ok(task) => {
+                    self.update_task_labels(task, &label, parts[2] == "add")
+                        .await
+                }
+                Err(message) => (message, false),
+            }
+        };
 
-        result
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
     }
 
-    fn needs_animation_filtering(&self, text: &str) -> bool {
-        // Only use ANSI parser for applications that need filtering of complex sequences:
-        // 1. Screen clearing and cursor positioning (animations)
-        // 2. Lots of escape sequences that might be animations
-        // Note: Simple color codes are passed through directly for efficiency
-        text.contains("\x1b[2J") ||  // Clear screen
-        text.contains("\x1b[H") ||   // Cursor home
-        text.contains("\x1b[?1049h") || // Alternative screen buffer
-        text.contains("\x1b[?1047h") || // Alternative screen buffer
-        (text.matches('\x1b').count() > 10) // Lots of escape sequences (likely animation)
+    /// Queue a task's notes for editing; the main loop opens them in the external editor
+    pub async fn handle_task_notes_command(&mut self, command: &str) {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let error = match parts.get(2) {
+            None => "Usage: /task notes <id-prefix>".to_string(),
+            Some(prefix) => match self.resolve_task_prefix(prefix).await {
+                Ok(task) => {
+                    self.pending_note_edit = Some(task);
+                    return;
+                }
+                Err(message) => message,
+            },
+        };
+
+        let entry = CommandEntry {
+            command: command.to_string(),
+            output: error,
+            success: false,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
     }
 
-    pub async fn kill_running_command(&mut self) {
-        if let Some(mut running) = self.running_command.take() {
-            // Kill the appropriate process type
-            if let Some(ref mut child) = running.child {
-                let _ = child.kill().await;
-            } else if let Some(ref mut pty_child) = running.pty_child {
-                let _ = pty_child.kill();
+    /// Save notes edited in the external editor. A cancelled edit changes nothing.
+    pub async fn finish_note_edit(
+        &mut self,
+        mut task: Task,
+        result: std::io::Result<Option<String>>,
+    ) {
+        let command = format!("/task notes {}", task.id);
+        let (output, success) = match result {
+            Ok(Some(notes)) if notes == task.notes() => {
+                (format!("Notes for '{}' unchanged", task.title), true)
             }
-
-            // Get any remaining output before killing
-            let final_output = self.combine_streamed_output(&running);
-
-            // Update the last entry in history
-            if let Some(last_entry) = self.command_history.last_mut() {
-                if last_entry.command == running.command {
-                    let output = if final_output.trim().is_empty() {
-                        "Killed by user (Ctrl-C)".to_string()
-                    } else {
-                        format!("{final_output}\nKilled by user (Ctrl-C)")
-                    };
-                    last_entry.output = output;
-                    last_entry.success = false;
+            Ok(Some(notes)) => {
+                task.set_notes(&notes);
+                task.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                match operations::update_task(&self.db_pool, &task).await {
+                    Ok(()) => {
+                        if let Err(e) = self.load_tasks().await {
+                            (format!("Error reloading tasks: {e}"), false)
+                        } else {
+                            (format!("Saved notes for '{}'", task.title), true)
+                        }
+                    }
+                    Err(e) => (format!("Error saving notes: {e}"), false),
                 }
             }
-        }
-    }
+            Ok(None) => (format!("Notes for '{}' not saved", task.title), false),
+            Err(e) => (format!("Could not open editor: {e}"), false),
+        };
 
-    /// Calculate total number of lines in command history
-    pub fn get_total_history_lines(&self) -> usize {
-        let mut total_lines = 0;
-        for entry in &self.command_history {
-            // Command line
-            total_lines += 1;
-            // Output lines
-            if !entry.output.is_empty() {
-                total_lines += entry.output.lines().count();
-            }
-            // Empty spacing line
-            total_lines += 1;
-        }
-        total_lines
+        let entry = CommandEntry {
+            command,
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
     }
 
-    /// Update command filtering based on current input
-    pub fn update_command_filtering(&mut self) {
-        if self.current_input.starts_with('/') {
-            self.command_filter = self.current_input[1..].to_string();
-            self.show_command_list = true;
-            self.selected_command_index = 0;
-            self.user_navigated_command_list = false;
-        } else {
-            self.show_command_list = false;
-            self.command_filter.clear();
-            self.user_navigated_command_list = false;
+    /// Add or remove `label` on a stored task, returning the message to show and
+    /// whether anything changed
+    async fn update_task_labels(
+        &mut self,
+        mut task: Task,
+        label: &str,
+        add: bool,
+    ) -> (String, bool) {
+        let has_label = task.labels.iter().any(|existing| existing == label);
+        if add && has_label {
+            return (
+                format!("Task '{}' already has label '{label}'", task.title),
+                false,
+            );
+        }
+        if !add && !has_label {
+            return (
+                format!("Task '{}' has no label '{label}'", task.title),
+                false,
+            );
         }
-    }
 
-    /// Get filtered commands based on current filter
-    pub fn get_filtered_commands(&self) -> Vec<String> {
-        if self.command_filter.is_empty() {
-            // Show only top-level commands when no filter
-            self.available_commands
-                .iter()
-                .filter(|cmd| !cmd[1..].contains(' '))
-                .cloned()
-                .collect()
+        if add {
+            task.labels.push(label.to_string());
         } else {
-            // Show matching commands including subcommands
-            let filter = &self.command_filter;
-            self.available_commands
-                .iter()
-                .filter(|cmd| {
-                    let cmd_without_slash = &cmd[1..];
-                    cmd_without_slash.starts_with(filter)
-                })
-                .cloned()
-                .collect()
+            task.labels.retain(|existing| existing != label);
         }
-    }
+        task.updated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
-    /// Handle built-in commands that don't need shell execution
-    pub async fn handle_builtin_command(&mut self, command: &str) -> bool {
-        match command {
-            "/quit" => {
-                self.should_quit = true;
-                true
-            }
-            "/task" | "/task list" => {
-                self.mode = AppMode::TaskList;
-                true
-            }
-            "/help" => {
-                let help_text = "Available commands:\n/quit - Exit the application\n/task - Switch to task list view\n/task add - Add a new task\n/task list - Show task list\n/clear - Clear terminal screen (Ctrl+L)\n/help - Show this help message\n/help keys - Show keyboard shortcuts";
-                let entry = CommandEntry {
-                    command: command.to_string(),
-                    output: help_text.to_string(),
-                    success: true,
-                };
-                self.add_command_entry(entry).await;
-                true
-            }
-            "/help keys" => {
-                let keys_help = "\n📋 TaskHub Keyboard Shortcuts\n\n🔄 Mode Switching:\n  q                 Switch to Terminal mode (from TaskList)\n  /task            Switch to TaskList mode\n\n📝 Text Editing:\n  Ctrl+A           Move cursor to beginning of line\n  Ctrl+E           Move cursor to end of line\n  Ctrl+B           Move cursor backward one character\n  Ctrl+K           Delete from cursor to end of line\n  Backspace        Delete character before cursor\n  Delete           Delete character at cursor\n\n🧭 Navigation:\n  ↑/↓ arrows       Navigate command history\n  ←/→ arrows       Move cursor left/right\n  Ctrl+←/→         Move cursor by word\n  Home/End         Move to beginning/end (or scroll history if empty)\n\n📜 Scrolling:\n  Shift+↑/↓        Scroll through terminal history\n  Page Up/Down     Scroll by 10 lines\n\n🔍 Search & Completion:\n  Ctrl+R           Reverse search through history\n  Ctrl+F           Search terminal output\n  Tab              Accept auto-suggestion or cycle completions\n  Right arrow      Accept next character from suggestion\n\n📋 Copy & Paste:\n  Ctrl+C           Copy selected text or interrupt command\n  Ctrl+V           Paste from clipboard\n  Middle Click     Paste from clipboard\n\n🖱️ Mouse:\n  Left Click       Start text selection\n  Left Drag        Extend text selection\n  Right Click      Clear selections\n\n⌨️ Command List (when typing /):\n  ↑/↓ arrows       Navigate command list\n  Enter            Select command\n  Esc              Cancel command selection\n\n🔍 Reverse Search (Ctrl+R):\n  ↑/↓ arrows       Navigate search results\n  Enter            Accept search result\n  Esc              Cancel reverse search\n\n🔍 Output Search (Ctrl+F):\n  Type text        Search terminal output\n  ↑/↓ arrows       Navigate between matches\n  Tab              Toggle case sensitivity ([Aa]/[aa])\n  Enter/Esc        Exit search mode\n\n🚪 Exit:\n  /quit            Exit application\n  Ctrl+C           Interrupt running command\n  Ctrl+L           Clear terminal screen";
-                let entry = CommandEntry {
-                    command: command.to_string(),
-                    output: keys_help.to_string(),
-                    success: true,
-                };
-                self.add_command_entry(entry).await;
-                true
-            }
-            "/clear" => {
-                self.clear_screen();
-                true
-            }
-            _ if command.starts_with("/task add") => {
-                self.handle_task_add_command(command).await;
-                true
-            }
-            _ => false,
+        if let Err(e) = operations::update_task(&self.db_pool, &task).await {
+            return (format!("Error updating task: {e}"), false);
         }
-    }
-
-    /// Handle /task add command
-    pub async fn handle_task_add_command(&mut self, command: &str) {
-        let parts: Vec<&str> = command.split_whitespace().collect();
-        if parts.len() < 3 {
-            let entry = CommandEntry {
-                command: command.to_string(),
-                output: "Usage: /task add <title>".to_string(),
-                success: false,
-            };
-            self.add_command_entry(entry).await;
-            return;
+        if let Err(e) = self.load_tasks().await {
+            return (format!("Error reloading tasks: {e}"), false);
         }
 
-        let title = parts[2..].join(" ");
-        let task = Task {
-            id: Uuid::new_v4(),
-            external_id: None,
-            source: TaskSource::Markdown,
-            title,
-            description: None,
-            status: TaskStatus::Open,
-            priority: Priority::Medium,
-            assignee: None,
-            labels: Vec::new(),
-            due_date: None,
-            created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            updated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            custom_fields: HashMap::new(),
+        let message = if add {
+            format!("Added label '{label}' to task '{}'", task.title)
+        } else {
+            format!("Removed label '{label}' from task '{}'", task.title)
         };
-
-        // Store the task to add asynchronously
-        self.pending_task_add = Some(task);
-        self.mode = AppMode::TaskList;
+        (message, true)
     }
 
     /// Handle adding a task asynchronously
@@ -1339,6 +3728,7 @@ impl App {
                         command: format!("/task add {}", task.title),
                         output: format!("Task '{}' added successfully", task.title),
                         success: true,
+                        ..Default::default()
                     };
                     self.add_command_entry(entry).await;
                     // Reload tasks to show the new task
@@ -1347,6 +3737,7 @@ impl App {
                             command: "reload_tasks".to_string(),
                             output: format!("Error reloading tasks: {e}"),
                             success: false,
+                            ..Default::default()
                         };
                         self.add_command_entry(error_entry).await;
                     }
@@ -1356,6 +3747,7 @@ impl App {
                         command: format!("/task add {}", task.title),
                         output: format!("Error adding task: {e}"),
                         success: false,
+                        ..Default::default()
                     };
                     self.add_command_entry(entry).await;
                 }
@@ -1405,25 +3797,25 @@ impl App {
 
     /// Accept the next character from auto-suggestion
     pub fn accept_next_suggestion_char(&mut self) {
-        if let Some(suggestion) = &self.auto_suggestion.clone() {
-            if self.cursor_position == self.current_input.chars().count() {
-                let suggestion_chars: Vec<char> = suggestion.chars().collect();
-                let input_chars: Vec<char> = self.current_input.chars().collect();
-
-                // Find the next character to accept
-                if input_chars.len() < suggestion_chars.len() {
-                    let next_char = suggestion_chars[input_chars.len()];
-                    self.current_input.push(next_char);
-                    self.cursor_position += 1;
-
-                    // Keep the same suggestion if there are more characters to accept
-                    if self.current_input.len() < suggestion.len() {
-                        // Keep the current suggestion
-                        self.auto_suggestion = Some(suggestion.clone());
-                    } else {
-                        // We've accepted the entire suggestion, clear it
-                        self.auto_suggestion = None;
-                    }
+        if let Some(suggestion) = &self.auto_suggestion.clone()
+            && self.cursor_position == self.current_input.chars().count()
+        {
+            let suggestion_chars: Vec<char> = suggestion.chars().collect();
+            let input_chars: Vec<char> = self.current_input.chars().collect();
+
+            // Find the next character to accept
+            if input_chars.len() < suggestion_chars.len() {
+                let next_char = suggestion_chars[input_chars.len()];
+                self.current_input.push(next_char);
+                self.cursor_position += 1;
+
+                // Keep the same suggestion if there are more characters to accept
+                if self.current_input.len() < suggestion.len() {
+                    // Keep the current suggestion
+                    self.auto_suggestion = Some(suggestion.clone());
+                } else {
+                    // We've accepted the entire suggestion, clear it
+                    self.auto_suggestion = None;
                 }
             }
         }
@@ -1434,6 +3826,7 @@ impl App {
         self.selection_start = Some((line, col));
         self.selection_end = Some((line, col));
         self.is_selecting = true;
+        self.last_drag_position = None;
     }
 
     /// Update text selection end position
@@ -1446,6 +3839,7 @@ impl App {
     /// End text selection
     pub fn end_selection(&mut self) {
         self.is_selecting = false;
+        self.last_drag_position = None;
     }
 
     /// Clear text selection
@@ -1453,6 +3847,101 @@ impl App {
         self.selection_start = None;
         self.selection_end = None;
         self.is_selecting = false;
+        self.last_drag_position = None;
+        self.visual_selection_active = false;
+    }
+
+    /// Enter keyboard selection mode with the caret on the last visible history line
+    pub fn start_visual_selection(&mut self) {
+        if self.command_history.is_empty() {
+            return;
+        }
+
+        let line = self.visible_history_start() + self.visible_history_line_count().max(1) - 1;
+        self.clear_input_selection();
+        self.visual_cursor = (line, 0);
+        self.selection_start = Some(self.visual_cursor);
+        self.selection_end = Some(self.visual_cursor);
+        self.is_selecting = false;
+        self.visual_selection_active = true;
+    }
+
+    /// Leave keyboard selection mode and drop the selection
+    pub fn cancel_visual_selection(&mut self) {
+        self.clear_selection();
+    }
+
+    /// Copy the keyboard selection to the clipboard and leave selection mode
+    pub fn copy_visual_selection(&mut self) -> Result<(), String> {
+        self.visual_selection_active = false;
+        self.copy_selected_text()
+    }
+
+    fn handle_visual_selection_key(&mut self, key_code: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key_code {
+            KeyCode::Esc => self.cancel_visual_selection(),
+            KeyCode::Enter => {
+                let _ = self.copy_visual_selection();
+            }
+            KeyCode::Up => self.move_visual_cursor(-1, 0),
+            KeyCode::Down => self.move_visual_cursor(1, 0),
+            KeyCode::Left => self.move_visual_cursor(0, -1),
+            KeyCode::Right => self.move_visual_cursor(0, 1),
+            KeyCode::Home => self.move_visual_cursor(0, isize::MIN),
+            KeyCode::End => self.move_visual_cursor(0, isize::MAX),
+            _ => {}
+        }
+    }
+
+    /// Move the selection caret, extending the selection from its anchor
+    pub fn move_visual_cursor(&mut self, line_delta: isize, col_delta: isize) {
+        let lines = self.history_lines();
+        if lines.is_empty() {
+            return;
+        }
+
+        let (line, col) = self.visual_cursor;
+        let line = line.saturating_add_signed(line_delta).min(lines.len() - 1);
+        let col = col
+            .saturating_add_signed(col_delta)
+            .min(lines[line].chars().count());
+
+        self.visual_cursor = (line, col);
+        self.selection_end = Some(self.visual_cursor);
+        self.scroll_to_history_line(line);
+    }
+
+    /// Adjust the scroll offset so that the given content line is visible
+    fn scroll_to_history_line(&mut self, line: usize) {
+        let available_height = self.history_area_height.saturating_sub(2) as usize;
+        if available_height == 0 || self.get_total_history_lines() <= available_height {
+            return;
+        }
+
+        let first_visible = self.visible_history_start();
+        let last_visible = first_visible + available_height - 1;
+        if line < first_visible {
+            self.scroll_offset += first_visible - line;
+        } else if line > last_visible {
+            self.scroll_offset = self.scroll_offset.saturating_sub(line - last_visible);
+        }
+    }
+
+    /// Plain text of every history line, in the order they are rendered
+    fn history_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for entry in &self.command_history {
+            lines.push(format!("> {}", entry.command));
+            if !entry.output.is_empty() {
+                for (_, line) in display_output_lines(&entry.output, self.collapse_repeats) {
+                    lines.push(line.into_owned());
+                }
+            }
+            lines.push(String::new()); // Empty line for spacing
+        }
+        lines
     }
 
     /// Clear input selection
@@ -1499,16 +3988,7 @@ impl App {
             };
 
             // Convert command history to lines
-            let mut lines = Vec::new();
-            for entry in &self.command_history {
-                lines.push(format!("> {}", entry.command));
-                if !entry.output.is_empty() {
-                    for line in entry.output.lines() {
-                        lines.push(line.to_string());
-                    }
-                }
-                lines.push(String::new()); // Empty line for spacing
-            }
+            let lines = self.history_lines();
 
             // Extract selected text
             let mut selected_text = String::new();
@@ -1579,39 +4059,90 @@ impl App {
         };
 
         if let Some(text) = text_to_copy {
-            // Initialize clipboard if not already done
-            if self.clipboard.is_none() {
-                match arboard::Clipboard::new() {
-                    Ok(clipboard) => self.clipboard = Some(clipboard),
-                    Err(_) => return Err("Failed to access clipboard".to_string()),
-                }
+            self.write_clipboard(&text)?;
+            self.clear_selection();
+            self.clear_input_selection();
+            Ok(())
+        } else {
+            Err("No text selected".to_string())
+        }
+    }
+
+    /// Copy the whole input line to the clipboard and report the result
+    pub async fn copy_input_line(&mut self) {
+        let (output, success) = if self.current_input.is_empty() {
+            ("Nothing to copy: the input is empty".to_string(), false)
+        } else {
+            let input = self.current_input.clone();
+            match self.write_clipboard(&input) {
+                Ok(()) => (
+                    format!(
+                        "Copied {} characters to the clipboard",
+                        input.chars().count()
+                    ),
+                    true,
+                ),
+                Err(e) => (e, false),
             }
+        };
+
+        let entry = CommandEntry {
+            command: "copy input".to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
+    }
 
-            // Use the persistent clipboard instance
-            if let Some(ref mut clipboard) = self.clipboard {
-                match clipboard.set_text(&text) {
-                    Ok(_) => {
-                        // Keep the clipboard instance alive and add a delay
-                        // to ensure clipboard managers can register the content.
-                        // This solves the "Clipboard was dropped very quickly" issue
-                        std::thread::sleep(std::time::Duration::from_millis(50));
+    /// Index of the history entry being looked at: the one shown at the top of the
+    /// history area while scrolled up, otherwise the last one
+    pub fn focused_entry_index(&self) -> Option<usize> {
+        if self.command_history.is_empty() {
+            return None;
+        }
+        if self.scroll_offset == 0 {
+            return Some(self.command_history.len() - 1);
+        }
+        let first_visible = self.visible_history_start();
+        let starts = self.command_start_lines();
+        Some(
+            starts
+                .partition_point(|start| *start <= first_visible)
+                .saturating_sub(1),
+        )
+    }
 
-                        self.clear_selection();
-                        self.clear_input_selection();
-                        Ok(())
-                    }
-                    Err(_) => Err("Failed to set clipboard contents".to_string()),
+    /// Copy the focused history entry to the clipboard as markdown and report the
+    /// result
+    pub async fn copy_focused_entry_as_markdown(&mut self) {
+        let (output, success) = match self.focused_entry_index() {
+            None => ("Nothing to copy: the history is empty".to_string(), false),
+            Some(index) => {
+                let entry = &self.command_history[index];
+                let command = entry.command.clone();
+                let markdown = entry_markdown(entry);
+                match self.write_clipboard(&markdown) {
+                    Ok(()) => (format!("Copied '{command}' as markdown"), true),
+                    Err(e) => (e, false),
                 }
-            } else {
-                Err("Failed to access clipboard".to_string())
             }
-        } else {
-            Err("No text selected".to_string())
-        }
+        };
+
+        let entry = CommandEntry {
+            command: "copy markdown".to_string(),
+            output,
+            success,
+            ..Default::default()
+        };
+        self.add_command_entry(entry).await;
     }
 
-    /// Paste text from clipboard
-    pub fn paste_from_clipboard(&mut self) -> Result<(), String> {
+    fn write_clipboard(&mut self, text: &str) -> Result<(), String> {
+        if let Some(writer) = self.clipboard_writer.as_mut() {
+            return writer(text);
+        }
+
         // Initialize clipboard if not already done
         if self.clipboard.is_none() {
             match arboard::Clipboard::new() {
@@ -1622,40 +4153,81 @@ impl App {
 
         // Use the persistent clipboard instance
         if let Some(ref mut clipboard) = self.clipboard {
-            match clipboard.get_text() {
-                Ok(text) => {
-                    // Insert text at current cursor position
-                    let mut chars: Vec<char> = self.current_input.chars().collect();
-                    let cursor_pos = self.cursor_position.min(chars.len());
-
-                    // Insert clipboard text character by character
-                    let mut char_count = 0;
-                    for ch in text.chars() {
-                        // Skip newlines and other control characters for single-line input
-                        if ch.is_control() && ch != '\t' {
-                            continue;
-                        }
-                        chars.insert(cursor_pos + char_count, ch);
-                        char_count += 1;
-                    }
-
-                    self.current_input = chars.into_iter().collect();
-                    self.cursor_position = cursor_pos + char_count;
-
+            match clipboard.set_text(text) {
+                Ok(_) => {
+                    // Keep the clipboard instance alive and add a delay
+                    // to ensure clipboard managers can register the content.
+                    // This solves the "Clipboard was dropped very quickly" issue
+                    std::thread::sleep(std::time::Duration::from_millis(50));
                     Ok(())
                 }
-                Err(_) => Err("Failed to get clipboard contents".to_string()),
+                Err(_) => Err("Failed to set clipboard contents".to_string()),
             }
         } else {
             Err("Failed to access clipboard".to_string())
         }
     }
 
+    /// Paste text from clipboard. The read runs off the UI thread and gives up after
+    /// `CLIPBOARD_READ_TIMEOUT` so a stuck clipboard cannot freeze the app.
+    pub fn paste_from_clipboard(&mut self) -> Result<(), String> {
+        let reader = self.clipboard_reader.clone();
+        let text = read_clipboard_with_timeout(
+            move || match reader {
+                Some(read) => read(),
+                None => arboard::Clipboard::new()
+                    .map_err(|_| "Failed to access clipboard".to_string())?
+                    .get_text()
+                    .map_err(|_| "Failed to get clipboard contents".to_string()),
+            },
+            CLIPBOARD_READ_TIMEOUT,
+        )?;
+
+        // Insert text at current cursor position
+        let mut chars: Vec<char> = self.current_input.chars().collect();
+        let cursor_pos = self.cursor_position.min(chars.len());
+
+        // Insert clipboard text character by character
+        let mut char_count = 0;
+        for ch in text.chars() {
+            // Skip control characters; newlines are kept only when the input
+            // can hold several lines
+            if ch.is_control() && ch != '\t' && !(ch == '\n' && self.multiline_input) {
+                continue;
+            }
+            chars.insert(cursor_pos + char_count, ch);
+            char_count += 1;
+        }
+
+        self.current_input = chars.into_iter().collect();
+        self.cursor_position = cursor_pos + char_count;
+
+        Ok(())
+    }
+
+    /// Paste from the clipboard, reporting a failed or timed-out read in the history
+    pub async fn paste_and_report(&mut self) {
+        if let Err(e) = self.paste_from_clipboard() {
+            let entry = CommandEntry {
+                command: "paste".to_string(),
+                output: e,
+                success: false,
+                ..Default::default()
+            };
+            self.add_command_entry(entry).await;
+        }
+    }
+
     /// Update terminal area height
     pub fn set_terminal_area_height(&mut self, height: u16) {
         self.terminal_area_height = height;
     }
 
+    /// Update terminal area width
+    pub fn set_terminal_area_width(&mut self, width: u16) {
+        self.terminal_area_width = width;
+    }
+
     /// Update layout areas for proper mouse coordinate mapping
     pub fn update_layout_areas(
         &mut self,
@@ -1665,23 +4237,73 @@ impl App {
     ) {
         self.terminal_area_height = total_height;
 
+        let input_height = self.input_area_height;
+
         if show_command_list {
             // Three-area layout: history, command list, input
             self.history_area_start = 0;
-            self.history_area_height = total_height.saturating_sub(command_list_size + 3);
-            self.input_area_start = total_height.saturating_sub(3);
+            self.history_area_height =
+                total_height.saturating_sub(command_list_size + input_height);
+            self.input_area_start = total_height.saturating_sub(input_height);
         } else {
             // Two-area layout: history, input
             self.history_area_start = 0;
-            self.history_area_height = total_height.saturating_sub(3);
-            self.input_area_start = total_height.saturating_sub(3);
+            self.history_area_height = total_height.saturating_sub(input_height);
+            self.input_area_start = total_height.saturating_sub(input_height);
+        }
+    }
+
+    /// Handle the terminal being resized to `width` x `height`. The areas are laid out
+    /// for the new size and the scroll offset is re-clamped to it; while scrolled back,
+    /// the top visible history line stays in place. Selections and the context menu
+    /// were made against the old layout, so they are cleared.
+    pub fn handle_resize(&mut self, width: u16, height: u16) {
+        let first_visible = self.visible_history_start();
+
+        let area = content_area(
+            ratatui::layout::Rect::new(0, 0, width, height),
+            self.max_content_width,
+        );
+        self.set_terminal_area_width(width);
+        self.set_terminal_area_height(height);
+        self.content_left = area.x;
+        let command_list_size = self.command_list_size();
+        self.update_layout_areas(area.height, command_list_size > 0, command_list_size);
+
+        let total_lines = self.get_total_history_lines();
+        let available_height = self.history_area_height.saturating_sub(2) as usize;
+        if self.scroll_offset > 0 {
+            self.scroll_offset = total_lines.saturating_sub(available_height + first_visible);
         }
+        // Scrolling further back than the first line at the top would leave the
+        // bottom of the history area empty
+        self.scroll_offset = self
+            .scroll_offset
+            .min(total_lines.saturating_sub(available_height));
+
+        self.clear_selection();
+        self.context_menu = None;
     }
 
     /// Handle mouse events with proper coordinate mapping
     pub fn on_mouse_event(&mut self, mouse: crossterm::event::MouseEvent) {
         use crossterm::event::{MouseButton, MouseEventKind};
 
+        // While the context menu is open, a left click either picks an entry or dismisses it
+        if let Some(menu) = &self.context_menu
+            && mouse.kind == MouseEventKind::Down(MouseButton::Left)
+        {
+            let action = menu.action_at(mouse.column, mouse.row);
+            self.context_menu = None;
+            if let Some(action) = action {
+                self.run_context_menu_action(action);
+            }
+            return;
+        }
+
+        // Everything but the context menu is laid out relative to the content area
+        let column = mouse.column.saturating_sub(self.content_left);
+
         match mouse.kind {
             MouseEventKind::Down(MouseButton::Left) => {
                 let mouse_row = mouse.row;
@@ -1689,7 +4311,7 @@ impl App {
                 // Determine which area was clicked
                 if mouse_row >= self.input_area_start {
                     // Clicked in input area - start input selection
-                    let input_pos = self.mouse_col_to_input_pos(mouse.column as usize);
+                    let input_pos = self.mouse_col_to_input_pos(column as usize);
                     self.clear_selection(); // Clear any terminal selection
                     self.start_input_selection(input_pos);
                 } else if mouse_row >= self.history_area_start {
@@ -1697,7 +4319,7 @@ impl App {
                     self.clear_input_selection(); // Clear any input selection
 
                     // Map mouse coordinates to visible content line
-                    let content_row = self.map_mouse_to_content_line(mouse_row, mouse.column);
+                    let content_row = self.map_mouse_to_content_line(mouse_row, column);
                     if let Some((line, col)) = content_row {
                         self.start_selection(line, col);
                     }
@@ -1706,41 +4328,162 @@ impl App {
             MouseEventKind::Drag(MouseButton::Left) => {
                 let mouse_row = mouse.row;
 
-                if mouse_row >= self.input_area_start {
+                if self.is_selecting {
+                    // Dragging a history selection, possibly past the edge of the history area
+                    self.last_drag_position = Some((mouse_row, column));
+                    self.drag_history_selection(mouse_row, column);
+                } else if mouse_row >= self.input_area_start {
                     // Dragging in input area
                     if self.is_selecting_input {
-                        let input_pos = self.mouse_col_to_input_pos(mouse.column as usize);
+                        let input_pos = self.mouse_col_to_input_pos(column as usize);
                         self.update_input_selection(input_pos);
                     }
-                } else if mouse_row >= self.history_area_start {
-                    // Dragging in terminal history area
-                    if self.is_selecting {
-                        let content_row = self.map_mouse_to_content_line(mouse_row, mouse.column);
-                        if let Some((line, col)) = content_row {
-                            self.update_selection(line, col);
-                        }
-                    }
                 }
             }
-            MouseEventKind::Up(MouseButton::Left) => {
-                // End selection and automatically copy
-                if self.is_selecting || self.is_selecting_input {
-                    self.end_selection();
-                    self.end_input_selection();
+            MouseEventKind::ScrollUp => {
+                self.scroll_history_by(MOUSE_SCROLL_LINES as isize);
+            }
+            MouseEventKind::ScrollDown => {
+                self.scroll_history_by(-(MOUSE_SCROLL_LINES as isize));
+            }
+            // End selection and automatically copy
+            MouseEventKind::Up(MouseButton::Left)
+                if self.is_selecting || self.is_selecting_input =>
+            {
+                self.end_selection();
+                self.end_input_selection();
 
-                    // Automatically copy selected text
-                    let _ = self.copy_selected_text();
-                }
+                // Automatically copy selected text
+                let _ = self.copy_selected_text();
             }
             MouseEventKind::Down(MouseButton::Middle) => {
                 // Middle mouse button paste
                 let _ = self.paste_from_clipboard();
             }
             MouseEventKind::Down(MouseButton::Right) => {
-                // Right click - clear all selections
+                self.on_right_click(mouse.column, mouse.row);
+            }
+            _ => {}
+        }
+    }
+
+    /// Scroll the history by `delta` lines (positive shows older content).
+    /// While a selection is being dragged, its end is re-mapped against the new viewport
+    /// so it keeps following the mouse instead of the content that scrolled away.
+    pub fn scroll_history_by(&mut self, delta: isize) {
+        let max_scroll = self.get_total_history_lines().saturating_sub(1);
+        self.scroll_offset = self
+            .scroll_offset
+            .saturating_add_signed(delta)
+            .min(max_scroll);
+
+        if self.is_selecting
+            && let Some((row, col)) = self.last_drag_position
+            && let Some((line, col)) = self.map_mouse_to_content_line(row, col)
+        {
+            self.update_selection(line, col);
+        }
+    }
+
+    /// Extend the history selection to the mouse position, auto-scrolling when the
+    /// mouse is dragged onto or past the top or bottom edge of the history area
+    fn drag_history_selection(&mut self, mouse_row: u16, mouse_col: u16) {
+        let top_border = self.history_area_start;
+        let bottom_border = (self.history_area_start + self.history_area_height).saturating_sub(1);
+
+        if mouse_row <= top_border {
+            // Reveal older content and extend to the first visible line
+            self.scroll_history_by(1);
+            if let Some((line, col)) = self.map_mouse_to_content_line(top_border + 1, mouse_col) {
+                self.update_selection(line, col);
+            }
+        } else if mouse_row >= bottom_border {
+            // Reveal newer content and extend to the last visible line
+            self.scroll_history_by(-1);
+            let last_row = self.history_area_start + self.visible_history_line_count() as u16;
+            if let Some((line, col)) = self.map_mouse_to_content_line(last_row, mouse_col) {
+                self.update_selection(line, col);
+            }
+        } else if let Some((line, col)) = self.map_mouse_to_content_line(mouse_row, mouse_col) {
+            self.update_selection(line, col);
+        }
+    }
+
+    /// Index of the first content line shown in the history area
+    fn visible_history_start(&self) -> usize {
+        let total_lines = self.get_total_history_lines();
+        let available_height = self.history_area_height.saturating_sub(2) as usize;
+
+        if total_lines <= available_height || self.scroll_offset >= total_lines {
+            0
+        } else {
+            total_lines.saturating_sub(available_height + self.scroll_offset)
+        }
+    }
+
+    /// Number of content lines currently shown in the history area
+    fn visible_history_line_count(&self) -> usize {
+        let available_height = self.history_area_height.saturating_sub(2) as usize;
+        let remaining = self
+            .get_total_history_lines()
+            .saturating_sub(self.visible_history_start());
+        remaining.min(available_height)
+    }
+
+    /// Perform the configured right-click action
+    pub fn on_right_click(&mut self, column: u16, row: u16) {
+        match self.right_click_action {
+            RightClickAction::Clear => {
+                self.clear_selection();
+                self.clear_input_selection();
+            }
+            RightClickAction::Paste => {
+                let _ = self.paste_from_clipboard();
+            }
+            RightClickAction::ContextMenu => {
+                let bounds = ratatui::layout::Rect::new(
+                    0,
+                    0,
+                    self.terminal_area_width,
+                    self.terminal_area_height,
+                );
+                self.context_menu = Some(ContextMenu::new(column, row, bounds));
+            }
+        }
+    }
+
+    /// Run an entry picked from the context menu
+    pub fn run_context_menu_action(&mut self, action: ContextMenuAction) {
+        match action {
+            ContextMenuAction::Copy => {
+                let _ = self.copy_selected_text();
+            }
+            ContextMenuAction::Paste => {
+                let _ = self.paste_from_clipboard();
+            }
+            ContextMenuAction::Clear => {
                 self.clear_selection();
                 self.clear_input_selection();
             }
+        }
+    }
+
+    fn handle_context_menu_key(&mut self, key_code: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        let Some(menu) = self.context_menu.as_mut() else {
+            return;
+        };
+
+        match key_code {
+            KeyCode::Up => menu.select_previous(),
+            KeyCode::Down => menu.select_next(),
+            KeyCode::Enter => {
+                let action = menu.selected_action();
+                self.context_menu = None;
+                self.run_context_menu_action(action);
+            }
+            KeyCode::Esc => self.context_menu = None,
             _ => {}
         }
     }
@@ -1766,47 +4509,25 @@ impl App {
         mouse_col: u16,
     ) -> Option<(usize, usize)> {
         // Calculate which line in the visible area was clicked
-        let history_relative_row = mouse_row - self.history_area_start;
+        let history_relative_row = mouse_row.checked_sub(self.history_area_start)?;
 
         // Account for top border - the first visible row is the border
         if history_relative_row == 0 {
             return None; // Clicked on border
         }
 
-        let visible_line_index = (history_relative_row - 1) as usize; // -1 for top border
-
-        // Now we need to map this visible line back to the actual content line
-        // This requires reproducing the same logic as in draw_command_history
-
-        // First, build the same all_items structure to understand the mapping
-        let mut all_items_count = 0;
-        for entry in &self.command_history {
-            // Command line
-            all_items_count += 1;
-
-            // Output lines
-            if !entry.output.is_empty() {
-                all_items_count += entry.output.lines().count();
-            }
-
-            // Empty line for spacing
-            all_items_count += 1;
+        // Rows at or past the bottom border belong to the command list or input area,
+        // which sit below a shorter history area when the command list is shown
+        if history_relative_row >= self.history_area_height.saturating_sub(1) {
+            return None;
         }
 
-        // Calculate available height (same as in draw_command_history)
-        let available_height = self.history_area_height.saturating_sub(2) as usize; // -2 for borders
+        let visible_line_index = (history_relative_row - 1) as usize; // -1 for top border
 
-        // Calculate which items are visible (same logic as in draw_command_history)
-        let visible_start_index = if all_items_count <= available_height {
-            0 // All items fit
-        } else {
-            // Need to scroll - show from bottom up with offset
-            if self.scroll_offset >= all_items_count {
-                0
-            } else {
-                all_items_count.saturating_sub(available_height + self.scroll_offset)
-            }
-        };
+        // Map the visible line back to the content line using the same viewport
+        // calculation as draw_command_history
+        let all_items_count = self.get_total_history_lines();
+        let visible_start_index = self.visible_history_start();
 
         // The actual content line is the visible line index plus the start offset
         let content_line = visible_start_index + visible_line_index;
@@ -1816,12 +4537,13 @@ impl App {
             return None;
         }
 
-        // Account for left border in column
-        let content_col = if mouse_col > 0 {
+        // Account for left border in column, the horizontal scroll and line numbers
+        let content_col = (if mouse_col > 0 {
             (mouse_col - 1) as usize
         } else {
             0
-        };
+        } + self.horizontal_scroll_offset)
+            .saturating_sub(self.line_number_gutter(content_line));
 
         Some((content_line, content_col))
     }
@@ -1829,8 +4551,10 @@ impl App {
     /// Start reverse search mode
     pub fn start_reverse_search(&mut self) {
         self.reverse_search_active = true;
+        self.reverse_search_output = false;
         self.reverse_search_query.clear();
         self.reverse_search_results.clear();
+        self.reverse_search_previews.clear();
         self.reverse_search_index = 0;
         self.update_reverse_search();
     }
@@ -1840,6 +4564,7 @@ impl App {
         self.reverse_search_active = false;
         self.reverse_search_query.clear();
         self.reverse_search_results.clear();
+        self.reverse_search_previews.clear();
         self.reverse_search_index = 0;
     }
 
@@ -1854,35 +4579,50 @@ impl App {
         self.reverse_search_active = false;
         self.reverse_search_query.clear();
         self.reverse_search_results.clear();
+        self.reverse_search_previews.clear();
         self.reverse_search_index = 0;
     }
 
+    /// Switch reverse search between matching commands and matching commands or output
+    pub fn toggle_reverse_search_output(&mut self) {
+        self.reverse_search_output = !self.reverse_search_output;
+        self.update_reverse_search();
+    }
+
     /// Update reverse search results based on current query
     pub fn update_reverse_search(&mut self) {
+        self.reverse_search_results.clear();
+        self.reverse_search_previews.clear();
+        self.reverse_search_index = 0;
         if self.reverse_search_query.is_empty() {
-            self.reverse_search_results.clear();
-            self.reverse_search_index = 0;
             return;
         }
 
-        // Get combined history
-        let combined_history = self.get_combined_command_history();
+        let query = self.reverse_search_query.to_lowercase();
+        let matches_command = |command: &str| command.to_lowercase().contains(&query);
 
-        // Filter history based on search query
-        self.reverse_search_results = combined_history
-            .iter()
-            .filter(|cmd| {
-                cmd.to_lowercase()
-                    .contains(&self.reverse_search_query.to_lowercase())
-            })
-            .cloned()
-            .collect();
+        // Saved commands have no output, so only their text can match
+        for command in &self.persistent_command_history {
+            if matches_command(command) {
+                self.reverse_search_results.push(command.clone());
+                self.reverse_search_previews.push(None);
+            }
+        }
+        for entry in &self.command_history {
+            let preview = if self.reverse_search_output {
+                matching_output_line(&entry.output, &query)
+            } else {
+                None
+            };
+            if matches_command(&entry.command) || preview.is_some() {
+                self.reverse_search_results.push(entry.command.clone());
+                self.reverse_search_previews.push(preview);
+            }
+        }
 
         // Reverse the results so most recent matches come first
         self.reverse_search_results.reverse();
-
-        // Reset index to first result
-        self.reverse_search_index = 0;
+        self.reverse_search_previews.reverse();
     }
 
     /// Navigate to previous search result
@@ -1912,6 +4652,13 @@ impl App {
         }
     }
 
+    /// Output line the current search result matched, if it was found by its output
+    pub fn get_current_search_preview(&self) -> Option<&str> {
+        self.reverse_search_previews
+            .get(self.reverse_search_index)?
+            .as_deref()
+    }
+
     /// Get reverse search prompt text
     pub fn get_reverse_search_prompt(&self) -> String {
         if self.reverse_search_active {
@@ -1924,8 +4671,13 @@ impl App {
                     self.reverse_search_results.len()
                 )
             };
+            let scope = if self.reverse_search_output {
+                " in output"
+            } else {
+                ""
+            };
             format!(
-                "(reverse-i-search `{}`): {}",
+                "(reverse-i-search{scope} `{}`): {}",
                 self.reverse_search_query, match_info
             )
         } else {
@@ -1933,6 +4685,67 @@ impl App {
         }
     }
 
+    /// Open the fuzzy history search with an empty query, listing recent commands
+    pub fn start_fuzzy_search(&mut self) {
+        self.fuzzy_search = Some(FuzzySearch::default());
+        self.update_fuzzy_search();
+    }
+
+    /// Re-rank the history against the fuzzy search query
+    pub fn update_fuzzy_search(&mut self) {
+        let history = self.get_combined_command_history();
+        if let Some(search) = &mut self.fuzzy_search {
+            search.update(&history);
+        }
+    }
+
+    /// Close the fuzzy search, putting the selected command in the input if `accept`
+    pub fn close_fuzzy_search(&mut self, accept: bool) {
+        let Some(search) = self.fuzzy_search.take() else {
+            return;
+        };
+        if accept && let Some(result) = search.selected_result() {
+            self.current_input = result.command.clone();
+            self.cursor_position = self.current_input.chars().count();
+            self.reset_history_navigation();
+            self.update_auto_suggestion();
+        }
+    }
+
+    fn handle_fuzzy_search_key(
+        &mut self,
+        key_code: crossterm::event::KeyCode,
+        modifiers: crossterm::event::KeyModifiers,
+    ) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match key_code {
+            KeyCode::Esc => self.close_fuzzy_search(false),
+            KeyCode::Char('g') if ctrl => self.close_fuzzy_search(false),
+            KeyCode::Enter => self.close_fuzzy_search(true),
+            KeyCode::Backspace => {
+                if let Some(search) = &mut self.fuzzy_search
+                    && search.query.pop().is_some()
+                {
+                    self.update_fuzzy_search();
+                }
+            }
+            _ => {
+                let Some(search) = &mut self.fuzzy_search else {
+                    return;
+                };
+                match key_code {
+                    KeyCode::Up => search.select_previous(),
+                    KeyCode::Char('p') if ctrl => search.select_previous(),
+                    KeyCode::Down => search.select_next(),
+                    KeyCode::Char('n') if ctrl => search.select_next(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
     /// Move cursor backward by word (Ctrl+Left)
     pub fn move_cursor_word_backward(&mut self) {
         if self.cursor_position == 0 {
@@ -1988,9 +4801,12 @@ impl App {
     pub fn clear_screen(&mut self) {
         // Clear entire command history (no scroll-back access)
         self.command_history.clear();
+        self.scrollback_count = (0, 0);
+        self.history_width = HistoryWidth::default();
 
         // Reset display state to show a clean screen
         self.scroll_offset = 0; // Reset to bottom
+        self.horizontal_scroll_offset = 0; // Reset to left edge
         self.current_input.clear(); // Clear current input
         self.cursor_position = 0; // Reset cursor
         self.show_command_list = false; // Hide command list
@@ -1999,6 +4815,7 @@ impl App {
         self.reverse_search_active = false; // Exit reverse search mode
         self.reverse_search_query.clear(); // Clear search query
         self.reverse_search_results.clear(); // Clear search results
+        self.reverse_search_previews.clear();
         self.reverse_search_index = 0; // Reset search index
         self.output_search_active = false; // Exit output search mode
         self.output_search_query.clear(); // Clear output search query
@@ -2033,6 +4850,7 @@ impl App {
     pub fn update_output_search(&mut self) {
         self.output_search_matches.clear();
         self.output_search_current_match = 0;
+        self.output_search_error = None;
 
         if self.output_search_query.is_empty() {
             return;
@@ -2040,6 +4858,18 @@ impl App {
 
         let search_query = self.output_search_query.clone();
         let search_mode = self.output_search_mode.clone();
+        // Compile the pattern once, not per line, and refuse ones that are too big
+        let regex = if search_mode == SearchMode::Regex {
+            match build_search_regex(&search_query, self.search_regex_size_limit) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    self.output_search_error = Some(e);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
         let mut matches = Vec::new();
         let mut line_index = 0;
 
@@ -2052,18 +4882,20 @@ impl App {
                 line_index,
                 &search_query,
                 &search_mode,
+                regex.as_ref(),
                 &mut matches,
             );
             line_index += 1;
 
             // Search in output lines
             if !entry.output.is_empty() {
-                for line in entry.output.lines() {
+                for (_, line) in display_output_lines(&entry.output, self.collapse_repeats) {
                     Self::search_in_text_static(
-                        line,
+                        &line,
                         line_index,
                         &search_query,
                         &search_mode,
+                        regex.as_ref(),
                         &mut matches,
                     );
                     line_index += 1;
@@ -2074,6 +4906,16 @@ impl App {
             line_index += 1;
         }
 
+        // The line being typed is searched too; it counts as the line after the history
+        Self::search_in_text_static(
+            &self.current_input,
+            line_index,
+            &search_query,
+            &search_mode,
+            regex.as_ref(),
+            &mut matches,
+        );
+
         self.output_search_matches = matches;
 
         // Auto-scroll to first match if any
@@ -2082,43 +4924,70 @@ impl App {
         }
     }
 
+    /// Find `search_query` in the visible text of `text` and record each match as
+    /// `(line_index, start_col, end_col)` in characters, the columns the line is
+    /// rendered at
     fn search_in_text_static(
         text: &str,
         line_index: usize,
         search_query: &str,
         search_mode: &SearchMode,
+        regex: Option<&Regex>,
         matches: &mut Vec<(usize, usize, usize)>,
     ) {
+        let visible = expand_tabs(&strip_ansi(text), 8);
+        let mut byte_ranges = Vec::new();
         match search_mode {
-            SearchMode::CaseInsensitive => {
-                let search_query = search_query.to_lowercase();
-                let search_text = text.to_lowercase();
-                let mut start = 0;
-                while let Some(pos) = search_text[start..].find(&search_query) {
-                    let actual_pos = start + pos;
-                    let end_pos = actual_pos + search_query.len();
-                    matches.push((line_index, actual_pos, end_pos));
-                    start = actual_pos + 1;
+            SearchMode::CaseInsensitive | SearchMode::WholeWord => {
+                // Fold case one char at a time so columns stay aligned with the text
+                let fold = |text: &str| -> Vec<char> {
+                    text.chars()
+                        .map(|ch| ch.to_lowercase().next().unwrap_or(ch))
+                        .collect()
+                };
+                let query = fold(search_query);
+                let chars = fold(&visible);
+                let whole_word = *search_mode == SearchMode::WholeWord;
+                let is_boundary = |ch: Option<&char>| ch.is_none_or(|ch| !ch.is_alphanumeric());
+                for start in 0..chars.len() {
+                    let end = start + query.len();
+                    if chars[start..].starts_with(&query)
+                        && (!whole_word
+                            || (is_boundary(start.checked_sub(1).and_then(|i| chars.get(i)))
+                                && is_boundary(chars.get(end))))
+                    {
+                        matches.push((line_index, start, end));
+                    }
                 }
+                return;
             }
             SearchMode::CaseSensitive => {
+                // Step one character past each match so overlapping matches are found
                 let mut start = 0;
-                while let Some(pos) = text[start..].find(search_query) {
+                while let Some(pos) = visible[start..].find(search_query) {
                     let actual_pos = start + pos;
-                    let end_pos = actual_pos + search_query.len();
-                    matches.push((line_index, actual_pos, end_pos));
-                    start = actual_pos + 1;
+                    byte_ranges.push((actual_pos, actual_pos + search_query.len()));
+                    match visible[actual_pos..].chars().next() {
+                        Some(ch) => start = actual_pos + ch.len_utf8(),
+                        None => break,
+                    }
                 }
             }
             SearchMode::Regex => {
-                if let Ok(regex) = Regex::new(search_query) {
-                    for mat in regex.find_iter(text) {
-                        matches.push((line_index, mat.start(), mat.end()));
-                    }
+                if let Some(regex) = regex {
+                    byte_ranges.extend(
+                        regex
+                            .find_iter(&visible)
+                            .map(|mat| (mat.start(), mat.end())),
+                    );
                 }
-                // If regex compilation fails, silently continue (matches will be empty)
             }
         }
+
+        let column = |byte: usize| visible[..byte].chars().count();
+        for (start, end) in byte_ranges {
+            matches.push((line_index, column(start), column(end)));
+        }
     }
 
     /// Navigate to previous search match
@@ -2152,6 +5021,18 @@ impl App {
     }
 
     /// Scroll to make the specified search match visible
+    /// Line index output search uses for the input line, one past the history
+    pub fn input_search_line_index(&self) -> usize {
+        self.get_total_history_lines()
+    }
+
+    /// Whether the current output search match is in the input line
+    pub fn current_search_match_in_input(&self) -> bool {
+        self.output_search_matches
+            .get(self.output_search_current_match)
+            .is_some_and(|(line, _, _)| *line == self.input_search_line_index())
+    }
+
     fn scroll_to_search_match(&mut self, match_index: usize) {
         if match_index >= self.output_search_matches.len() {
             return;
@@ -2161,15 +5042,32 @@ impl App {
         let total_lines = self.get_total_history_lines();
         let available_height = self.history_area_height.saturating_sub(2) as usize;
 
-        // Calculate scroll offset to center the target line
+        // Offset that puts the target line at the top
+        let at_top = total_lines.saturating_sub(available_height + target_line);
         let desired_scroll = if total_lines <= available_height {
             0 // All content fits, no scrolling needed
         } else {
-            let center_offset = available_height / 2;
-            if target_line >= center_offset {
-                total_lines.saturating_sub(target_line + center_offset)
-            } else {
-                total_lines.saturating_sub(available_height)
+            match self.search_scroll_mode {
+                SearchScrollMode::Center => {
+                    let center_offset = available_height / 2;
+                    if target_line >= center_offset {
+                        total_lines.saturating_sub(target_line + center_offset)
+                    } else {
+                        total_lines.saturating_sub(available_height)
+                    }
+                }
+                SearchScrollMode::Top => at_top,
+                SearchScrollMode::Minimal => {
+                    let first_visible = self.visible_history_start();
+                    if target_line < first_visible {
+                        at_top
+                    } else if target_line >= first_visible + available_height {
+                        // Bring it in at the bottom
+                        total_lines.saturating_sub(target_line + 1)
+                    } else {
+                        self.scroll_offset
+                    }
+                }
             }
         };
 
@@ -2183,9 +5081,15 @@ impl App {
                 SearchMode::CaseInsensitive => "[aa]",
                 SearchMode::CaseSensitive => "[Aa]",
                 SearchMode::Regex => "[.*]",
+                SearchMode::WholeWord => "[W]",
             };
 
-            if self.output_search_matches.is_empty() {
+            if let Some(error) = &self.output_search_error {
+                format!(
+                    "Search {} '{}' - {}",
+                    mode_indicator, self.output_search_query, error
+                )
+            } else if self.output_search_matches.is_empty() {
                 if self.output_search_query.is_empty() {
                     format!("Search {mode_indicator}: (Type to search output, Tab to toggle mode)")
                 } else {
@@ -2195,12 +5099,18 @@ impl App {
                     )
                 }
             } else {
+                let location = if self.current_search_match_in_input() {
+                    " (in input)"
+                } else {
+                    ""
+                };
                 format!(
-                    "Search {} '{}' - Match {}/{}",
+                    "Search {} '{}' - Match {}/{}{}",
                     mode_indicator,
                     self.output_search_query,
                     self.output_search_current_match + 1,
-                    self.output_search_matches.len()
+                    self.output_search_matches.len(),
+                    location
                 )
             }
         } else {
@@ -2208,12 +5118,14 @@ impl App {
         }
     }
 
-    /// Toggle search mode for output search (case-insensitive -> case-sensitive -> regex)
+    /// Toggle search mode for output search (case-insensitive -> case-sensitive -> regex
+    /// -> whole word)
     pub fn toggle_output_search_mode(&mut self) {
         self.output_search_mode = match self.output_search_mode {
             SearchMode::CaseInsensitive => SearchMode::CaseSensitive,
             SearchMode::CaseSensitive => SearchMode::Regex,
-            SearchMode::Regex => SearchMode::CaseInsensitive,
+            SearchMode::Regex => SearchMode::WholeWord,
+            SearchMode::WholeWord => SearchMode::CaseInsensitive,
         };
         self.update_output_search();
     }
@@ -2228,3 +5140,288 @@ impl App {
         self.output_search_current_match
     }
 }
+
+/// The command to run for the typed `input`. Trailing whitespace is dropped, but
+/// leading whitespace is passed on to the shell, where it can be significant; a
+/// builtin is recognised with it stripped.
+pub fn submitted_command(input: &str) -> String {
+    let command = input.trim_end();
+    let builtin = command.trim_start();
+    if builtin.starts_with('/') {
+        builtin.to_string()
+    } else {
+        command.to_string()
+    }
+}
+
+/// The first line of `output` containing `query`, which must be lowercase, with
+/// escape sequences removed and surrounding whitespace trimmed
+fn matching_output_line(output: &str, query: &str) -> Option<String> {
+    output
+        .lines()
+        .map(strip_ansi)
+        .find(|line| line.to_lowercase().contains(query))
+        .map(|line| line.trim().to_string())
+}
+
+/// Line put above an entry's output once `count` of its oldest lines were trimmed
+fn trimmed_lines_marker(count: usize) -> String {
+    format!("[{count} earlier lines trimmed]")
+}
+
+/// Number of lines a marker from `trimmed_lines_marker` says were trimmed
+fn trimmed_lines_count(line: &str) -> Option<usize> {
+    line.strip_prefix('[')?
+        .strip_suffix(" earlier lines trimmed]")?
+        .parse()
+        .ok()
+}
+
+/// Remove the oldest output lines of `entry` so that it gets up to `excess` lines
+/// shorter, counting the marker that replaces them. Returns how many lines shorter
+/// it got.
+fn trim_oldest_output(entry: &mut CommandEntry, excess: usize) -> usize {
+    let lines: Vec<&str> = entry.output.lines().collect();
+    let earlier = lines.first().and_then(|line| trimmed_lines_count(line));
+    // Output lines after a marker left by an earlier trim
+    let start = usize::from(earlier.is_some());
+    let kept = lines.len() - start;
+    // A new marker takes a line of its own
+    let remove = (excess + 1 - start).min(kept);
+    if remove + start <= 1 {
+        return 0;
+    }
+
+    let first_kept = start + remove;
+    let marker = trimmed_lines_marker(earlier.unwrap_or(0) + remove);
+    let output = std::iter::once(marker.as_str())
+        .chain(lines[first_kept..].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if !entry.output_streams.is_empty() {
+        let rest = entry.output_streams.get(first_kept..).unwrap_or_default();
+        entry.output_streams = std::iter::once(OutputStream::Stdout)
+            .chain(rest.iter().copied())
+            .collect();
+    }
+    if !entry.output_times.is_empty() {
+        // The marker takes the time of the last line it replaces
+        let marker_time = entry.output_times[(first_kept - 1).min(entry.output_times.len() - 1)];
+        let rest = entry.output_times.get(first_kept..).unwrap_or_default();
+        entry.output_times = std::iter::once(marker_time)
+            .chain(rest.iter().copied())
+            .collect();
+    }
+    if let Some(styled) = &mut entry.styled_output {
+        let rest = styled.get(first_kept..).unwrap_or_default().to_vec();
+        let marker_line = ratatui::text::Line::styled(
+            marker.clone(),
+            ratatui::style::Style::default().add_modifier(ratatui::style::Modifier::DIM),
+        );
+        *styled = std::iter::once(marker_line).chain(rest).collect();
+    }
+    entry.output = output;
+    remove + start - 1
+}
+
+/// Text a styled line shows, without its styles
+fn line_text(line: &ratatui::text::Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Expand a bash-style history reference at the start of `command`: `!!` is the
+/// last command in `history` and `!N` the Nth, counting from 1. Words after the
+/// reference are appended. None when the command does not start with one; an error
+/// when the referenced command does not exist.
+pub fn expand_history_reference(
+    command: &str,
+    history: &[String],
+) -> Option<Result<String, String>> {
+    let trimmed = command.trim_start();
+    let (reference, rest) = trimmed
+        .split_once(char::is_whitespace)
+        .unwrap_or((trimmed, ""));
+    let number = reference.strip_prefix('!')?;
+
+    let resolved = if number == "!" {
+        history
+            .last()
+            .ok_or_else(|| "!!: no previous command".to_string())
+    } else if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+        number
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .and_then(|n| history.get(n - 1))
+            .ok_or_else(|| match history.len() {
+                0 => format!("!{number}: history is empty"),
+                len => format!("!{number}: no such command in history (1-{len})"),
+            })
+    } else {
+        return None;
+    };
+
+    Some(resolved.map(|resolved| {
+        let rest = rest.trim();
+        if rest.is_empty() {
+            resolved.clone()
+        } else {
+            format!("{resolved} {rest}")
+        }
+    }))
+}
+
+/// A new task from the words typed after `/task add`. `due:YYYY-MM-DD`,
+/// `priority:<level>` and `label:<name>` words set those fields wherever they appear
+/// and the remaining words, in order, form the title. A repeated `due:` or
+/// `priority:` overrides the earlier one. Invalid dates and priorities are errors,
+/// as is a title left empty.
+fn parse_new_task(words: &[&str]) -> Result<Task, String> {
+    let mut task = Task::new(String::new());
+    let mut title = Vec::new();
+    for word in words {
+        if let Some(date) = word.strip_prefix("due:") {
+            let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid due date '{date}': expected YYYY-MM-DD"))?;
+            task.due_date = Some(day.format("%Y-%m-%d").to_string());
+        } else if let Some(priority) = word.strip_prefix("priority:") {
+            task.priority = priority.parse()?;
+        } else if let Some(label) = word.strip_prefix("label:").filter(|l| !l.is_empty()) {
+            if !task.labels.iter().any(|existing| existing == label) {
+                task.labels.push(label.to_string());
+            }
+        } else {
+            title.push(*word);
+        }
+    }
+
+    if title.is_empty() {
+        return Err(
+            "Usage: /task add <title> [due:YYYY-MM-DD] [priority:high|medium|low] [label:<name>]"
+                .to_string(),
+        );
+    }
+    task.title = title.join(" ");
+    Ok(task)
+}
+
+/// Compile an output search pattern, rejecting ones whose compiled form would
+/// exceed `size_limit` bytes so a pathological pattern cannot stall the UI
+pub fn build_search_regex(pattern: &str, size_limit: usize) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(size_limit)
+        .dfa_size_limit(size_limit)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(_) => "pattern too complex".to_string(),
+            _ => "invalid pattern".to_string(),
+        })
+}
+
+/// Run `read` on a background thread and wait at most `timeout` for its result.
+/// A read that is still running when the timeout expires is left to finish on its
+/// own and its result is dropped.
+pub fn read_clipboard_with_timeout<F>(read: F, timeout: Duration) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String> + Send + 'static,
+{
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(read());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+            Err("Timed out reading the clipboard".to_string())
+        }
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+            Err("Failed to get clipboard contents".to_string())
+        }
+    }
+}
+
+/// How long a command with open input must stay silent before it is assumed to be
+/// waiting for input
+pub const INPUT_WAIT_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// Heuristic for a command blocked reading its input: it accepts input and has
+/// produced no output for at least `INPUT_WAIT_THRESHOLD`
+pub fn likely_waiting_for_input(idle: Duration, accepts_input: bool) -> bool {
+    accepts_input && idle >= INPUT_WAIT_THRESHOLD
+}
+
+/// Root of the git repository containing `dir`: the nearest directory, starting
+/// with `dir` itself, that has a `.git` entry
+pub fn find_git_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// `text` as a single shell word, single-quoted only when it contains characters
+/// the shell would treat specially
+pub fn shell_quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || "/._-+:,@%=".contains(c));
+    if plain {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// The directory a plain shell `cd` command changes to, with surrounding quotes
+/// removed; empty when none is given. None for anything the shell has to run, such
+/// as `cd dir && make`, `cd $VAR` or more than one argument.
+pub fn cd_argument(command: &str) -> Option<&str> {
+    let rest = match command.trim().strip_prefix("cd") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim(),
+        _ => return None,
+    };
+
+    let single_quoted = rest
+        .strip_prefix('\'')
+        .and_then(|dir| dir.strip_suffix('\''))
+        .filter(|dir| !dir.contains('\''));
+    let double_quoted = rest
+        .strip_prefix('"')
+        .and_then(|dir| dir.strip_suffix('"'))
+        .filter(|dir| !dir.contains(['"', '$', '`', '\\']));
+    if let Some(dir) = single_quoted.or(double_quoted) {
+        return Some(dir);
+    }
+
+    let needs_shell = rest.contains(|c: char| c.is_whitespace() || ";&|<>()$`\\'\"*?".contains(c));
+    (!needs_shell).then_some(rest)
+}
+
+/// Resolve a directory typed by the user: `~` expands to the home directory and
+/// relative paths are taken from the current working directory
+pub fn resolve_directory(dir: &str) -> Result<PathBuf, String> {
+    let cwd = std::env::current_dir()
+        .map_err(|e| format!("Could not determine the current directory: {e}"))?;
+    resolve_directory_in(&cwd, dir)
+}
+
+/// Resolve a directory typed by the user, taking relative paths from `base`
+pub fn resolve_directory_in(base: &Path, dir: &str) -> Result<PathBuf, String> {
+    let path = if dir == "~" || dir.starts_with("~/") {
+        let home = dirs::home_dir().ok_or("Could not determine the home directory")?;
+        home.join(dir.trim_start_matches('~').trim_start_matches('/'))
+    } else {
+        base.join(dir)
+    };
+
+    if path.is_dir() {
+        Ok(path)
+    } else {
+        Err(format!("Not a directory: {dir}"))
+    }
+}
@@ -0,0 +1,373 @@
+/// What a built-in command does, dispatched by `App::handle_builtin_command`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    Quit,
+    TaskList,
+    TaskAdd,
+    TaskLabel,
+    TaskNotes,
+    TaskSub,
+    TaskAgenda,
+    TaskDelete,
+    TaskSearch,
+    Done,
+    Progress,
+    Help,
+    Clear,
+    Stderr,
+    Timestamps,
+    Collapse,
+    LineNumbers,
+    Replay,
+    ExportHtml,
+    History,
+    Stats,
+    Vacuum,
+    Backup,
+    Config,
+    ReloadConfig,
+    OpenConfig,
+    OpenData,
+    In,
+    Cd,
+}
+
+/// Heading a built-in command is listed under in /help
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandSection {
+    Tasks,
+    Terminal,
+    History,
+    Data,
+    General,
+}
+
+impl CommandSection {
+    /// Every section, in the order /help lists them
+    pub const ALL: [CommandSection; 5] = [
+        CommandSection::Tasks,
+        CommandSection::Terminal,
+        CommandSection::History,
+        CommandSection::Data,
+        CommandSection::General,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            CommandSection::Tasks => "Tasks",
+            CommandSection::Terminal => "Terminal",
+            CommandSection::History => "History",
+            CommandSection::Data => "Data & Configuration",
+            CommandSection::General => "General",
+        }
+    }
+}
+
+/// A `/` command. The command list, completion, /help and dispatch are all built
+/// from `BUILTIN_COMMANDS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinCommand {
+    /// What is typed to run it, e.g. `/task add`
+    pub name: &'static str,
+    /// The name with its arguments, as shown in /help
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub section: CommandSection,
+    pub builtin: Builtin,
+    /// Values of the first argument offered in the command list, each as a command
+    /// of its own, e.g. `/stderr split`
+    pub arguments: &'static [&'static str],
+    /// Whether anything may follow the name
+    pub takes_arguments: bool,
+}
+
+const fn command(
+    name: &'static str,
+    usage: &'static str,
+    description: &'static str,
+    section: CommandSection,
+    builtin: Builtin,
+) -> BuiltinCommand {
+    BuiltinCommand {
+        name,
+        usage,
+        description,
+        section,
+        builtin,
+        arguments: &[],
+        // The usage names arguments after the command name
+        takes_arguments: usage.len() > name.len(),
+    }
+}
+
+const fn with_arguments(
+    name: &'static str,
+    usage: &'static str,
+    description: &'static str,
+    section: CommandSection,
+    builtin: Builtin,
+    arguments: &'static [&'static str],
+) -> BuiltinCommand {
+    BuiltinCommand {
+        arguments,
+        ..command(name, usage, description, section, builtin)
+    }
+}
+
+/// Every built-in command, in command-list order
+pub const BUILTIN_COMMANDS: &[BuiltinCommand] = &[
+    command(
+        "/quit",
+        "/quit",
+        "Exit the application",
+        CommandSection::General,
+        Builtin::Quit,
+    ),
+    command(
+        "/task",
+        "/task",
+        "Switch to task list view",
+        CommandSection::Tasks,
+        Builtin::TaskList,
+    ),
+    command(
+        "/task add",
+        "/task add <title> [due:YYYY-MM-DD] [priority:high|medium|low] [label:<name>]",
+        "Add a new task",
+        CommandSection::Tasks,
+        Builtin::TaskAdd,
+    ),
+    command(
+        "/task list",
+        "/task list",
+        "Show task list",
+        CommandSection::Tasks,
+        Builtin::TaskList,
+    ),
+    with_arguments(
+        "/task label",
+        "/task label add|rm <id-prefix> <label>",
+        "Add or remove a task label",
+        CommandSection::Tasks,
+        Builtin::TaskLabel,
+        &["add", "rm"],
+    ),
+    command(
+        "/task notes",
+        "/task notes <id-prefix>",
+        "Edit a task's notes in $EDITOR",
+        CommandSection::Tasks,
+        Builtin::TaskNotes,
+    ),
+    command(
+        "/task sub",
+        "/task sub <parent-id-prefix> <title>",
+        "Add a subtask",
+        CommandSection::Tasks,
+        Builtin::TaskSub,
+    ),
+    command(
+        "/task agenda",
+        "/task agenda",
+        "Show open tasks grouped by due date",
+        CommandSection::Tasks,
+        Builtin::TaskAgenda,
+    ),
+    command(
+        "/task delete",
+        "/task delete <id-prefix>",
+        "Delete a task",
+        CommandSection::Tasks,
+        Builtin::TaskDelete,
+    ),
+    command(
+        "/task search",
+        "/task search <query>",
+        "Find tasks whose title contains the query",
+        CommandSection::Tasks,
+        Builtin::TaskSearch,
+    ),
+    command(
+        "/done",
+        "/done <id-prefix>...",
+        "Mark tasks done",
+        CommandSection::Tasks,
+        Builtin::Done,
+    ),
+    command(
+        "/progress",
+        "/progress <id-prefix>...",
+        "Mark tasks in progress",
+        CommandSection::Tasks,
+        Builtin::Progress,
+    ),
+    command(
+        "/help",
+        "/help [filter]",
+        "Show this help message",
+        CommandSection::General,
+        Builtin::Help,
+    ),
+    command(
+        "/help keys",
+        "/help keys [filter]",
+        "Show keyboard shortcuts",
+        CommandSection::General,
+        Builtin::Help,
+    ),
+    command(
+        "/clear",
+        "/clear",
+        "Clear terminal screen (Ctrl+L)",
+        CommandSection::Terminal,
+        Builtin::Clear,
+    ),
+    with_arguments(
+        "/stderr",
+        "/stderr split|merge",
+        "Show stderr after stdout or interleaved",
+        CommandSection::Terminal,
+        Builtin::Stderr,
+        &["split", "merge"],
+    ),
+    with_arguments(
+        "/timestamps",
+        "/timestamps on|off",
+        "Show when each output line arrived",
+        CommandSection::Terminal,
+        Builtin::Timestamps,
+        &["on", "off"],
+    ),
+    with_arguments(
+        "/collapse",
+        "/collapse on|off",
+        "Show repeated output lines once with a count",
+        CommandSection::Terminal,
+        Builtin::Collapse,
+        &["on", "off"],
+    ),
+    with_arguments(
+        "/line-numbers",
+        "/line-numbers on|off",
+        "Number the output lines of each command",
+        CommandSection::Terminal,
+        Builtin::LineNumbers,
+        &["on", "off"],
+    ),
+    command(
+        "/replay",
+        "/replay [speed]",
+        "Replay the last command's output at its original pace",
+        CommandSection::Terminal,
+        Builtin::Replay,
+    ),
+    command(
+        "/export-html",
+        "/export-html <path>",
+        "Save the command history with its colors as an HTML page",
+        CommandSection::Terminal,
+        Builtin::ExportHtml,
+    ),
+    command(
+        "/history",
+        "/history [N]",
+        "List the last N commands run (default 20)",
+        CommandSection::History,
+        Builtin::History,
+    ),
+    command(
+        "/stats",
+        "/stats",
+        "Show the most-run commands, commands per day and success rate",
+        CommandSection::History,
+        Builtin::Stats,
+    ),
+    command(
+        "/vacuum",
+        "/vacuum",
+        "Compact the database",
+        CommandSection::Data,
+        Builtin::Vacuum,
+    ),
+    command(
+        "/backup",
+        "/backup <path>",
+        "Export all tasks to a JSON file",
+        CommandSection::Data,
+        Builtin::Backup,
+    ),
+    command(
+        "/config",
+        "/config",
+        "Show the effective configuration",
+        CommandSection::Data,
+        Builtin::Config,
+    ),
+    command(
+        "/reload-config",
+        "/reload-config",
+        "Re-read the config file",
+        CommandSection::Data,
+        Builtin::ReloadConfig,
+    ),
+    command(
+        "/open-config",
+        "/open-config",
+        "Open the config file's directory in the file manager",
+        CommandSection::Data,
+        Builtin::OpenConfig,
+    ),
+    command(
+        "/open-data",
+        "/open-data",
+        "Open the database directory in the file manager",
+        CommandSection::Data,
+        Builtin::OpenData,
+    ),
+    command(
+        "/in",
+        "/in <dir> <command>",
+        "Run a command in another directory",
+        CommandSection::Terminal,
+        Builtin::In,
+    ),
+    command(
+        "/cd",
+        "/cd [dir]",
+        "Change the working directory (home if no dir, - for the previous one)",
+        CommandSection::Terminal,
+        Builtin::Cd,
+    ),
+];
+
+/// Entries of the command list: each command's name, or one entry per suggested
+/// argument for commands that have them
+pub fn command_list() -> Vec<String> {
+    BUILTIN_COMMANDS
+        .iter()
+        .flat_map(|command| {
+            if command.arguments.is_empty() {
+                vec![command.name.to_string()]
+            } else {
+                command
+                    .arguments
+                    .iter()
+                    .map(|argument| format!("{} {argument}", command.name))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// The built-in `input` runs: the one with the longest name that is all of
+/// `input`, or is followed by a space in it and takes arguments
+pub fn find_builtin(input: &str) -> Option<&'static BuiltinCommand> {
+    BUILTIN_COMMANDS
+        .iter()
+        .filter(|command| match input.strip_prefix(command.name) {
+            Some("") => true,
+            Some(rest) => command.takes_arguments && rest.starts_with(' '),
+            None => false,
+        })
+        .max_by_key(|command| command.name.len())
+}
@@ -21,6 +21,8 @@ pub struct Completion {
     pub text: String,
     pub completion_type: CompletionType,
     pub display_text: Option<String>, // For showing additional info
+    /// The text replaces the whole word instead of being appended to it
+    pub replaces_word: bool,
 }
 
 impl Completion {
@@ -29,6 +31,7 @@ impl Completion {
             text,
             completion_type,
             display_text: None,
+            replaces_word: false,
         }
     }
 
@@ -37,8 +40,24 @@ impl Completion {
             text,
             completion_type,
             display_text: Some(display),
+            replaces_word: false,
         }
     }
+
+    /// A completion whose text is the full replacement for the word being completed
+    pub fn replacing(text: String, completion_type: CompletionType) -> Self {
+        Self {
+            display_text: Some(text.clone()),
+            text,
+            completion_type,
+            replaces_word: true,
+        }
+    }
+
+    /// Whether this completes to a directory
+    pub fn is_directory(&self) -> bool {
+        self.completion_type == CompletionType::FilePath && self.text.ends_with('/')
+    }
 }
 
 #[derive(Debug)]
@@ -85,13 +104,7 @@ impl CompletionState {
         let completion = &self.completions[self.current_index];
         self.current_index = (self.current_index + 1) % self.completions.len();
 
-        // Get the word that's being completed
-        let word_start = self.prefix.len();
-        let word_end = self.original_input.len();
-        let word = &self.original_input[word_start..word_end];
-
-        // Replace the word with the completed word
-        Some(format!("{}{}{}", self.prefix, word, completion.text))
+        Some(self.apply(completion))
     }
 
     pub fn cycle_previous(&mut self) -> Option<String> {
@@ -107,13 +120,31 @@ impl CompletionState {
 
         let completion = &self.completions[self.current_index];
 
+        Some(self.apply(completion))
+    }
+
+    /// The input with the word being completed replaced by `completion`
+    fn apply(&self, completion: &Completion) -> String {
+        if completion.replaces_word {
+            return format!("{}{}", self.prefix, completion.text);
+        }
+
         // Get the word that's being completed
         let word_start = self.prefix.len();
         let word_end = self.original_input.len();
         let word = &self.original_input[word_start..word_end];
 
         // Replace the word with the completed word
-        Some(format!("{}{}{}", self.prefix, word, completion.text))
+        format!("{}{}{}", self.prefix, word, completion.text)
+    }
+
+    /// Index of the completion most recently applied by `cycle_next`
+    pub fn applied_index(&self) -> usize {
+        if self.completions.is_empty() {
+            0
+        } else {
+            (self.current_index + self.completions.len() - 1) % self.completions.len()
+        }
     }
 
     pub fn current_completion(&self) -> Option<&Completion> {
@@ -137,6 +168,8 @@ pub struct CompletionEngine {
     available_commands: Vec<String>,
     command_cache: Mutex<HashMap<String, CachedCompletion>>,
     path_commands: Mutex<Option<(Vec<String>, Instant)>>,
+    /// Working directory the caches were filled in
+    cache_directory: Mutex<Option<PathBuf>>,
 }
 
 #[derive(Debug, Clone)]
@@ -148,12 +181,22 @@ struct CachedCompletion {
 const CACHE_DURATION: Duration = Duration::from_secs(300); // 5 minutes
 const PATH_CACHE_DURATION: Duration = Duration::from_secs(60); // 1 minute
 
+/// Maximum number of matches offered for a glob pattern
+const GLOB_COMPLETION_LIMIT: usize = 200;
+
+/// Commands whose arguments are completed as file paths
+const PATH_ARGUMENT_COMMANDS: &[&str] = &[
+    "cat", "ls", "cd", "mkdir", "touch", "rm", "cp", "mv", "grep", "find", "less", "head", "tail",
+    "vim", "nano", "chmod", "wc", "diff",
+];
+
 impl CompletionEngine {
     pub fn new(available_commands: Vec<String>) -> Self {
         Self {
             available_commands,
             command_cache: Mutex::new(HashMap::new()),
             path_commands: Mutex::new(None),
+            cache_directory: Mutex::new(std::env::current_dir().ok()),
         }
     }
 
@@ -161,44 +204,75 @@ impl CompletionEngine {
         self.available_commands = commands;
     }
 
+    /// Drop cached results when the working directory differs from the one they were
+    /// computed in. Bash completions (make targets, npm scripts, git branches) and
+    /// relative PATH entries both resolve against the working directory.
+    /// Returns whether the caches were invalidated.
+    pub fn update_working_directory(&self, dir: &Path) -> bool {
+        let mut cache_directory = self.cache_directory.lock().unwrap();
+        if cache_directory.as_deref() == Some(dir) {
+            return false;
+        }
+
+        *cache_directory = Some(dir.to_path_buf());
+        self.command_cache.lock().unwrap().clear();
+        *self.path_commands.lock().unwrap() = None;
+        true
+    }
+
+    /// Whether any completion results are cached
+    pub fn has_cached_results(&self) -> bool {
+        !self.command_cache.lock().unwrap().is_empty()
+            || self.path_commands.lock().unwrap().is_some()
+    }
+
     /// Get all available commands from PATH, with caching
     pub fn get_path_commands(&self) -> Vec<String> {
         let mut path_commands = self.path_commands.lock().unwrap();
 
         // Check if we have cached data and it's still valid
-        if let Some((ref commands, timestamp)) = *path_commands {
-            if timestamp.elapsed() < PATH_CACHE_DURATION {
-                return commands.clone();
-            }
+        if let Some((ref commands, timestamp)) = *path_commands
+            && timestamp.elapsed() < PATH_CACHE_DURATION
+        {
+            return commands.clone();
         }
 
         // Cache is expired or doesn't exist, rebuild it
+        let mut commands = match std::env::var("PATH") {
+            Ok(path_var) => Self::commands_in_path(&path_var),
+            Err(_) => Vec::new(),
+        };
+
+        // Remove duplicates and sort
+        commands.sort();
+        commands.dedup();
+
+        // Cache the result
+        *path_commands = Some((commands.clone(), Instant::now()));
+        commands
+    }
+
+    /// Runnable commands in the directories of a `PATH`-style list
+    pub fn commands_in_path(path_var: &str) -> Vec<String> {
         let mut commands = Vec::new();
 
-        if let Ok(path_var) = std::env::var("PATH") {
-            for path_dir in path_var.split(':') {
-                if let Ok(entries) = std::fs::read_dir(path_dir) {
-                    for entry in entries.flatten() {
-                        if let Ok(file_type) = entry.file_type() {
-                            if file_type.is_file() {
-                                let filename = entry.file_name().to_string_lossy().to_string();
-                                // Filter out files with extensions and hidden files
-                                if !filename.contains('.') && !filename.starts_with('.') {
-                                    commands.push(filename);
-                                }
-                            }
+        for path_dir in path_var.split(':') {
+            if let Ok(entries) = std::fs::read_dir(path_dir) {
+                for entry in entries.flatten() {
+                    if let Ok(metadata) = entry.metadata()
+                        && metadata.is_file()
+                        && is_executable(&metadata)
+                    {
+                        let filename = entry.file_name().to_string_lossy().to_string();
+                        // Filter out files with extensions and hidden files
+                        if !filename.contains('.') && !filename.starts_with('.') {
+                            commands.push(filename);
                         }
                     }
                 }
             }
         }
 
-        // Remove duplicates and sort
-        commands.sort();
-        commands.dedup();
-
-        // Cache the result
-        *path_commands = Some((commands.clone(), Instant::now()));
         commands
     }
 
@@ -214,10 +288,10 @@ impl CompletionEngine {
         // Check cache second
         {
             let cache = self.command_cache.lock().unwrap();
-            if let Some(cached) = cache.get(&cache_key) {
-                if cached.timestamp.elapsed() < CACHE_DURATION {
-                    return Some(cached.completions.clone());
-                }
+            if let Some(cached) = cache.get(&cache_key)
+                && cached.timestamp.elapsed() < CACHE_DURATION
+            {
+                return Some(cached.completions.clone());
             }
         }
 
@@ -389,6 +463,11 @@ fi
     ) -> Vec<Completion> {
         let mut completions = Vec::new();
 
+        // Cached results from another directory would be stale
+        if let Ok(dir) = std::env::current_dir() {
+            self.update_working_directory(&dir);
+        }
+
         // Get the word being completed
         let word_start = self.find_word_start(input, cursor_pos);
         let word = &input[word_start..cursor_pos];
@@ -403,12 +482,13 @@ fi
         } else if self.is_switch_context(input, word_start) {
             // Switch/option completion (for commands like "git checkout --")
             completions.extend(self.complete_switches(input, word, word_start));
+        } else if self.is_file_path_context(input, word_start) {
+            // File path completion, checked before subcommands since path-taking
+            // commands such as ls may also ship a bash completion script
+            completions.extend(self.complete_file_paths(word));
         } else if self.is_subcommand_context(input, word_start) {
             // Bash subcommand completion (for commands like "git checkout")
             completions.extend(self.complete_bash_subcommands(input, word, word_start, cursor_pos));
-        } else if self.is_file_path_context(input, word_start) {
-            // File path completion
-            completions.extend(self.complete_file_paths(word));
         } else {
             // General command completion (bash commands)
             completions.extend(self.complete_bash_commands(word));
@@ -438,19 +518,15 @@ fi
         }
 
         let before_word = &input[..word_start];
+        if !before_word.ends_with(' ') {
+            return false;
+        }
 
-        // Check if the word looks like a path or if the command typically takes file arguments
-        before_word.ends_with(' ')
-            && (before_word.contains("cat ")
-                || before_word.contains("ls ")
-                || before_word.contains("cd ")
-                || before_word.contains("mkdir ")
-                || before_word.contains("touch ")
-                || before_word.contains("rm ")
-                || before_word.contains("cp ")
-                || before_word.contains("mv ")
-                || before_word.contains("grep ")
-                || before_word.contains("find "))
+        // The command typically takes file arguments
+        before_word
+            .split_whitespace()
+            .next()
+            .is_some_and(|command| PATH_ARGUMENT_COMMANDS.contains(&command))
     }
 
     pub fn is_task_context(&self, input: &str) -> bool {
@@ -528,6 +604,13 @@ fi
     }
 
     fn complete_file_paths(&self, word: &str) -> Vec<Completion> {
+        if word.contains(['*', '?']) {
+            return complete_glob(word);
+        }
+        if let Some(completions) = complete_abbreviated_path(word) {
+            return completions;
+        }
+
         let mut completions = Vec::new();
 
         let path = if word.is_empty() {
@@ -536,10 +619,16 @@ fi
             PathBuf::from(word)
         };
 
-        let (dir, filename_prefix) = if path.is_dir() {
+        // A directory name without a trailing slash is still being typed, so it
+        // completes to itself with a slash instead of listing its contents
+        let (dir, filename_prefix) = if path.is_dir() && (word.is_empty() || word.ends_with('/')) {
             (path, String::new())
         } else {
-            let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+            let dir = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+                .to_path_buf();
             let filename_prefix = path
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
@@ -553,18 +642,14 @@ fi
 
                 if filename.starts_with(&filename_prefix) && !filename.starts_with('.') {
                     let mut completion_text = filename[filename_prefix.len()..].to_string();
+                    let mut display_text = filename.clone();
 
-                    // Add trailing slash for directories
-                    if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                    // Add trailing slash for directories, in both the inserted and shown text
+                    if entry.path().is_dir() {
                         completion_text.push('/');
+                        display_text.push('/');
                     }
 
-                    let display_text = if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                        format!("{filename} (dir)")
-                    } else {
-                        filename.clone()
-                    };
-
                     completions.push(Completion::with_display(
                         completion_text,
                         CompletionType::FilePath,
@@ -574,7 +659,12 @@ fi
             }
         }
 
-        completions.sort_by(|a, b| a.text.cmp(&b.text));
+        // Directories first, then files, each alphabetically
+        completions.sort_by(|a, b| {
+            let a_is_file = !a.text.ends_with('/');
+            let b_is_file = !b.text.ends_with('/');
+            a_is_file.cmp(&b_is_file).then_with(|| a.text.cmp(&b.text))
+        });
         completions
     }
 
@@ -2492,28 +2582,261 @@ fi
     }
 }
 
+/// Builtins and keywords of common shells, which are never found on `PATH`
+pub(crate) const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "[[", "alias", "bg", "bind", "break", "builtin", "case", "cd", "command",
+    "continue", "declare", "dirs", "disown", "do", "done", "echo", "elif", "else", "enable",
+    "esac", "eval", "exec", "exit", "export", "false", "fc", "fg", "fi", "for", "function",
+    "getopts", "hash", "help", "history", "if", "jobs", "kill", "let", "local", "logout", "popd",
+    "printf", "pushd", "pwd", "read", "readonly", "return", "select", "set", "shift", "shopt",
+    "source", "test", "then", "time", "times", "trap", "true", "type", "typeset", "ulimit",
+    "umask", "unalias", "unset", "until", "wait", "while",
+];
+
+/// The program a shell command line starts, skipping leading `NAME=value` assignments.
+/// None for empty input and for lines starting with shell syntax such as `(` or `{`.
+pub fn command_program(command: &str) -> Option<&str> {
+    let word = command.split_whitespace().find(|word| {
+        let assignment = word
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && !name.contains(['/', '\'', '"']));
+        !assignment
+    })?;
+
+    let starts_with_syntax =
+        word.starts_with(['(', '{', '!', '$', '`', '\'', '"', '<', '>', '|', '&', ';']);
+    (!starts_with_syntax).then_some(word)
+}
+
+/// Whether `program` names a shell builtin, a command in `path_commands` or, when it
+/// contains a slash, an existing file
+pub fn is_known_program(program: &str, path_commands: &[String]) -> bool {
+    if program.contains('/') {
+        return Path::new(&expand_tilde(program)).exists();
+    }
+    SHELL_BUILTINS.contains(&program) || path_commands.iter().any(|command| command == program)
+}
+
+/// The candidate closest to `name` by edit distance, if any is close enough to be a
+/// likely typo: at most two edits, and fewer than the length of `name`
+pub fn closest_command<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max_distance = 2.min(name.chars().count().saturating_sub(1));
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each count as one edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// `~/rest` with the home directory substituted
+fn expand_tilde(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+/// Whether any of the execute permission bits is set
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+/// Platforms without an execute bit treat every file as runnable
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// Directory to read for a path prefix, where an empty prefix means the current directory
+fn dir_for(prefix: &str) -> &str {
+    if prefix.is_empty() { "." } else { prefix }
+}
+
+/// Non-hidden entry names of `prefix` with whether each is a directory
+fn list_entries(prefix: &str) -> Vec<(String, bool)> {
+    let Ok(entries) = std::fs::read_dir(dir_for(prefix)) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<(String, bool)> = entries
+        .flatten()
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path().is_dir(),
+            )
+        })
+        .filter(|(name, _)| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Sort full-path completions with directories first and turn them into completions
+fn path_completions(mut paths: Vec<(String, bool)>) -> Vec<Completion> {
+    paths.sort_by(|(a, a_dir), (b, b_dir)| b_dir.cmp(a_dir).then_with(|| a.cmp(b)));
+    paths.dedup();
+    paths
+        .into_iter()
+        .map(|(path, is_dir)| {
+            let text = if is_dir { format!("{path}/") } else { path };
+            Completion::replacing(text, CompletionType::FilePath)
+        })
+        .collect()
+}
+
+/// Complete a path whose directory segments are prefixes of real directories, so
+/// `s/t/co` expands to `src/tui/completion.rs`. Returns `None` when the directory
+/// part exists as typed and plain single-level completion applies.
+fn complete_abbreviated_path(word: &str) -> Option<Vec<Completion>> {
+    let (head, name_prefix) = word.rsplit_once('/')?;
+    if head.is_empty() || Path::new(head).is_dir() {
+        return None;
+    }
+
+    let mut bases = vec![if word.starts_with('/') { "/" } else { "" }.to_string()];
+    for segment in head.split('/').filter(|segment| !segment.is_empty()) {
+        let mut next = Vec::new();
+        for base in &bases {
+            let exact = format!("{base}{segment}");
+            if matches!(segment, "." | "..") || Path::new(&exact).is_dir() {
+                next.push(format!("{exact}/"));
+                continue;
+            }
+            for (name, is_dir) in list_entries(base) {
+                if is_dir && name.starts_with(segment) {
+                    next.push(format!("{base}{name}/"));
+                }
+            }
+        }
+        bases = next;
+    }
+
+    let mut paths = Vec::new();
+    for base in &bases {
+        for (name, is_dir) in list_entries(base) {
+            if name.starts_with(name_prefix) {
+                paths.push((format!("{base}{name}"), is_dir));
+            }
+        }
+    }
+    Some(path_completions(paths))
+}
+
+/// Complete a glob pattern such as `src/**/*.rs` to the paths it matches
+fn complete_glob(word: &str) -> Vec<Completion> {
+    let segments: Vec<&str> = word.split('/').filter(|s| !s.is_empty()).collect();
+    let root = if word.starts_with('/') { "/" } else { "" };
+
+    let mut matches = Vec::new();
+    glob_walk(root, &segments, &mut matches);
+    matches.truncate(GLOB_COMPLETION_LIMIT);
+    path_completions(matches)
+}
+
+/// Collect paths under `base` matching the remaining pattern `segments`.
+/// `**` matches any number of directories, including none.
+fn glob_walk(base: &str, segments: &[&str], matches: &mut Vec<(String, bool)>) {
+    if matches.len() >= GLOB_COMPLETION_LIMIT {
+        return;
+    }
+
+    let Some((segment, rest)) = segments.split_first() else {
+        let path = base.trim_end_matches('/');
+        if !path.is_empty() {
+            matches.push((path.to_string(), Path::new(path).is_dir()));
+        }
+        return;
+    };
+
+    if *segment == "**" {
+        glob_walk(base, rest, matches);
+        for (name, is_dir) in list_entries(base) {
+            if is_dir {
+                glob_walk(&format!("{base}{name}/"), segments, matches);
+            }
+        }
+    } else if segment.contains(['*', '?']) {
+        for (name, is_dir) in list_entries(base) {
+            if wildcard_match(segment, &name) && (rest.is_empty() || is_dir) {
+                glob_walk(&format!("{base}{name}/"), rest, matches);
+            }
+        }
+    } else {
+        let path = format!("{base}{segment}");
+        if Path::new(&path).exists() {
+            glob_walk(&format!("{path}/"), rest, matches);
+        }
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any run of characters and `?` one character
+pub(crate) fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last star absorb one more character
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, n));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::models::{Priority, TaskSource, TaskStatus};
-    use std::collections::HashMap;
-    use uuid::Uuid;
 
     fn create_test_task(title: &str) -> Task {
         Task {
-            id: Uuid::new_v4(),
-            external_id: None,
-            source: TaskSource::Markdown,
-            title: title.to_string(),
-            description: None,
-            status: TaskStatus::Open,
-            priority: Priority::Medium,
-            assignee: None,
-            labels: Vec::new(),
-            due_date: None,
             created_at: "2025-01-01T00:00:00Z".to_string(),
             updated_at: "2025-01-01T00:00:00Z".to_string(),
-            custom_fields: HashMap::new(),
+            ..Task::new(title)
         }
     }
 
@@ -0,0 +1,114 @@
+use crate::db::models::TaskStatus;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use uuid::Uuid;
+
+/// Widest the confirmation popup is drawn, including borders
+const DIALOG_MAX_WIDTH: u16 = 60;
+
+/// Something held back until the user answers a confirmation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Run a shell command whose program was not found
+    RunCommand(String),
+    /// Run a shell command that deletes or changes the files its globs match
+    RunDangerousCommand(String),
+    /// Quit, killing the command that is still running
+    Quit,
+    /// Delete the task `command` named
+    DeleteTask {
+        command: String,
+        id: Uuid,
+        title: String,
+    },
+    /// Set the status of every task `command` named
+    UpdateTaskStatuses {
+        command: String,
+        ids: Vec<Uuid>,
+        status: TaskStatus,
+    },
+}
+
+impl ConfirmAction {
+    /// The command that asked for the action, if the user typed one
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            ConfirmAction::RunCommand(command)
+            | ConfirmAction::RunDangerousCommand(command)
+            | ConfirmAction::DeleteTask { command, .. }
+            | ConfirmAction::UpdateTaskStatuses { command, .. } => Some(command),
+            ConfirmAction::Quit => None,
+        }
+    }
+}
+
+/// A yes/no question shown over the current view. While it is open y runs the
+/// action, and n or Esc declines it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    pub prompt: String,
+    pub action: ConfirmAction,
+}
+
+impl Confirmation {
+    pub fn new(prompt: impl Into<String>, action: ConfirmAction) -> Self {
+        Self {
+            prompt: prompt.into(),
+            action,
+        }
+    }
+
+    /// The command held back, if the user typed one
+    pub fn command(&self) -> Option<&str> {
+        self.action.command()
+    }
+
+    /// The popup centered in `bounds`, tall enough for the wrapped prompt
+    pub fn area(&self, bounds: Rect) -> Rect {
+        let width = DIALOG_MAX_WIDTH.min(bounds.width);
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let prompt_rows = self.prompt.chars().count().div_ceil(text_width).max(1) as u16;
+        // Prompt, a blank line, the answer keys and the borders
+        let height = (prompt_rows + 4).min(bounds.height);
+
+        Rect::new(
+            bounds.x + (bounds.width - width) / 2,
+            bounds.y + (bounds.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+pub fn draw_confirmation(f: &mut Frame<'_>, confirmation: &Confirmation) {
+    let area = confirmation.area(f.area());
+
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let text = vec![
+        Line::from(confirmation.prompt.as_str()),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", key_style),
+            Span::raw(" yes   "),
+            Span::styled("n/Esc", key_style),
+            Span::raw(" no"),
+        ]),
+    ];
+
+    let dialog = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title("Confirm"),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
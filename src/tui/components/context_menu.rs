@@ -0,0 +1,120 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem},
+};
+
+/// Width of the context menu popup including borders
+const MENU_WIDTH: u16 = 12;
+
+/// Actions offered by the right-click context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuAction {
+    Copy,
+    Paste,
+    Clear,
+}
+
+impl ContextMenuAction {
+    pub const ALL: [ContextMenuAction; 3] = [
+        ContextMenuAction::Copy,
+        ContextMenuAction::Paste,
+        ContextMenuAction::Clear,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContextMenuAction::Copy => "Copy",
+            ContextMenuAction::Paste => "Paste",
+            ContextMenuAction::Clear => "Clear",
+        }
+    }
+}
+
+/// A small popup menu opened at the mouse position
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenu {
+    /// Top-left corner of the popup
+    pub column: u16,
+    pub row: u16,
+    pub selected_index: usize,
+}
+
+impl ContextMenu {
+    /// Open a menu at the mouse position, shifted so that it fits inside `bounds`
+    pub fn new(column: u16, row: u16, bounds: Rect) -> Self {
+        let max_column = (bounds.x + bounds.width).saturating_sub(MENU_WIDTH);
+        let max_row = (bounds.y + bounds.height).saturating_sub(Self::height());
+
+        Self {
+            column: column.min(max_column).max(bounds.x),
+            row: row.min(max_row).max(bounds.y),
+            selected_index: 0,
+        }
+    }
+
+    fn height() -> u16 {
+        ContextMenuAction::ALL.len() as u16 + 2
+    }
+
+    /// Screen area covered by the popup, including borders
+    pub fn area(&self) -> Rect {
+        Rect::new(self.column, self.row, MENU_WIDTH, Self::height())
+    }
+
+    pub fn selected_action(&self) -> ContextMenuAction {
+        ContextMenuAction::ALL[self.selected_index]
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected_index + 1 < ContextMenuAction::ALL.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    /// Action under the given screen position, if it is on one of the menu entries
+    pub fn action_at(&self, column: u16, row: u16) -> Option<ContextMenuAction> {
+        let area = self.area();
+        let inside_columns = column > area.x && column < area.x + area.width - 1;
+        if !inside_columns || row <= area.y {
+            return None;
+        }
+
+        ContextMenuAction::ALL
+            .get((row - area.y - 1) as usize)
+            .copied()
+    }
+}
+
+pub fn draw_context_menu(f: &mut Frame<'_>, menu: &ContextMenu) {
+    // Keep the popup on screen even if the terminal shrank since it was opened
+    let area = menu.area().intersection(f.area());
+
+    let items: Vec<ListItem> = ContextMenuAction::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let style = if i == menu.selected_index {
+                Style::default().bg(Color::Blue).fg(Color::White)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(Line::from(Span::styled(action.label(), style)))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(list, area);
+}
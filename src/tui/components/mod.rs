@@ -1 +1,3 @@
-
+pub mod confirmation;
+pub mod context_menu;
+pub mod quick_add;
@@ -0,0 +1,65 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// Inline input for adding a task from the task list, opened with `a`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    /// Why the last Enter did not add a task
+    pub error: Option<String>,
+}
+
+impl QuickAdd {
+    pub fn push(&mut self, ch: char) {
+        self.title.push(ch);
+        self.error = None;
+    }
+
+    pub fn backspace(&mut self) {
+        self.title.pop();
+        self.error = None;
+    }
+
+    /// The title to create a task with, or an error if only whitespace was typed
+    pub fn submit(&mut self) -> Result<String, String> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            let error = "Task title cannot be empty".to_string();
+            self.error = Some(error.clone());
+            return Err(error);
+        }
+        Ok(title.to_string())
+    }
+}
+
+/// Draw the quick-add input over the bottom rows of `area`
+pub fn draw_quick_add(f: &mut Frame<'_>, area: Rect, quick_add: &QuickAdd) {
+    let height = 3.min(area.height);
+    let area = Rect::new(area.x, area.y + area.height - height, area.width, height);
+
+    let content = match &quick_add.error {
+        Some(error) => Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(vec![
+            Span::raw(quick_add.title.as_str()),
+            Span::styled("█", Style::default().fg(Color::Gray)),
+        ]),
+    };
+
+    let input = Paragraph::new(content).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title("New task (Enter to add, Esc to cancel)"),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(input, area);
+}
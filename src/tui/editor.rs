@@ -0,0 +1,49 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+use uuid::Uuid;
+
+/// Editor to launch: `$VISUAL`, then `$EDITOR`, then `vi`
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Edit `content` in a temporary file using `run_editor`, which is given the file path.
+/// Returns the saved content, or `None` when the editor exits unsuccessfully so the
+/// edit can be discarded. The trailing newline editors append is dropped.
+pub fn edit_with<F>(content: &str, run_editor: F) -> io::Result<Option<String>>
+where
+    F: FnOnce(&Path) -> io::Result<ExitStatus>,
+{
+    let path = std::env::temp_dir().join(format!("taskhub-edit-{}.sh", Uuid::new_v4()));
+    std::fs::write(&path, content)?;
+
+    let result = run_editor(&path).and_then(|status| {
+        if status.success() {
+            std::fs::read_to_string(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    });
+    std::fs::remove_file(&path).ok();
+
+    Ok(result?.map(|edited| edited.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Edit `content` in the user's editor. The terminal must already be released
+/// from raw mode and the alternate screen.
+pub fn edit_in_external_editor(content: &str) -> io::Result<Option<String>> {
+    edit_with(content, |path| {
+        // Run through the shell so editors configured with arguments, like "code --wait", work
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor_command()))
+            .arg("sh")
+            .arg(path)
+            .status()
+    })
+}
@@ -0,0 +1,174 @@
+/// Points for each query character that matches
+const SCORE_MATCH: i64 = 16;
+/// Penalty for the first skipped character between two matches
+const PENALTY_GAP_START: i64 = 3;
+/// Penalty for each further skipped character
+const PENALTY_GAP_EXTENSION: i64 = 1;
+/// Bonus for matching at the start of a word
+const BONUS_BOUNDARY: i64 = 8;
+/// Bonus for matching right after the previous match
+const BONUS_CONSECUTIVE: i64 = 4;
+/// The first query character counts its boundary bonus this many times
+const FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+/// Most results the fuzzy history search keeps
+pub const FUZZY_RESULT_LIMIT: usize = 50;
+
+/// How well a query matched a candidate, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character indices in the candidate of each matched query character
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` as a subsequence of `candidate`, fzf-style: matches at word starts
+/// and runs of consecutive characters score higher, gaps lower. Matching ignores case
+/// unless the query has an uppercase letter. None when the characters are not all
+/// present in order.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let query: Vec<char> = query.chars().map(fold).collect();
+    let text: Vec<char> = candidate.chars().collect();
+    let folded: Vec<char> = text.iter().copied().map(fold).collect();
+
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    // Try every place the first character matches and keep the best greedy match
+    (0..folded.len())
+        .filter(|&start| folded[start] == query[0])
+        .filter_map(|start| {
+            let mut positions = vec![start];
+            let mut next = start + 1;
+            for &wanted in &query[1..] {
+                let found = (next..folded.len()).find(|&i| folded[i] == wanted)?;
+                positions.push(found);
+                next = found + 1;
+            }
+            Some(FuzzyMatch {
+                score: score_positions(&text, &positions),
+                positions,
+            })
+        })
+        .max_by(|a, b| {
+            a.score
+                .cmp(&b.score)
+                .then(b.positions[0].cmp(&a.positions[0]))
+        })
+}
+
+fn score_positions(text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &position) in positions.iter().enumerate() {
+        let mut bonus = if is_word_start(text, position) {
+            BONUS_BOUNDARY
+        } else {
+            0
+        };
+        if i == 0 {
+            bonus *= FIRST_CHAR_MULTIPLIER;
+        }
+
+        score += SCORE_MATCH + bonus;
+        if let Some(previous) = previous {
+            let gap = (position - previous - 1) as i64;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP_START + (gap - 1) * PENALTY_GAP_EXTENSION;
+            }
+        }
+        previous = Some(position);
+    }
+    score
+}
+
+fn is_word_start(text: &[char], position: usize) -> bool {
+    position == 0
+        || matches!(
+            text[position - 1],
+            ' ' | '/' | '-' | '_' | '.' | ':' | '=' | '\'' | '"'
+        )
+}
+
+/// Candidates that match `query`, best first. Equal scores keep the candidates' order,
+/// so passing the newest history first favors recent commands.
+pub fn rank<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<(&'a str, FuzzyMatch)> {
+    let mut matches: Vec<(&str, FuzzyMatch)> = candidates
+        .into_iter()
+        .filter_map(|candidate| fuzzy_match(query, candidate).map(|m| (candidate, m)))
+        .collect();
+    // A stable sort keeps the original order among equal scores
+    matches.sort_by_key(|m| std::cmp::Reverse(m.1.score));
+    matches
+}
+
+/// A history command found by the fuzzy search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyResult {
+    pub command: String,
+    /// Character indices of the matched query characters, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// State of the fuzzy history search overlay
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FuzzySearch {
+    pub query: String,
+    /// Best match first
+    pub results: Vec<FuzzyResult>,
+    pub selected: usize,
+}
+
+impl FuzzySearch {
+    /// Re-rank `history` (oldest first, as it is stored) against the query. Repeated
+    /// commands are listed once, at their most recent use.
+    pub fn update(&mut self, history: &[String]) {
+        let mut seen = std::collections::HashSet::new();
+        let newest_first = history
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .filter(|command| seen.insert(*command));
+
+        self.results = rank(&self.query, newest_first)
+            .into_iter()
+            .take(FUZZY_RESULT_LIMIT)
+            .map(|(command, m)| FuzzyResult {
+                command: command.to_string(),
+                positions: m.positions,
+            })
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn selected_result(&self) -> Option<&FuzzyResult> {
+        self.results.get(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::tui::completion::{command_program, wildcard_match};
+use std::path::Path;
+
+/// Programs that delete, move or change the files their arguments name
+const DESTRUCTIVE_PROGRAMS: &[&str] = &[
+    "rm", "rmdir", "unlink", "shred", "mv", "truncate", "chmod", "chown", "chgrp",
+];
+/// Matched paths named in a preview before the rest are only counted
+const PREVIEW_NAME_LIMIT: usize = 5;
+
+/// Unquoted arguments of `command` that the shell would expand as globs
+pub fn glob_arguments(command: &str) -> Vec<&str> {
+    command
+        .split_whitespace()
+        .filter(|word| word.contains(['*', '?']) && !word.contains(['\'', '"', '\\', '$', '`']))
+        .collect()
+}
+
+/// Paths relative to `dir` that `pattern` matches, sorted. `*` and `?` match within
+/// a path segment, and hidden files only match a segment that starts with a dot,
+/// as in the shell.
+pub fn expand_glob(pattern: &str, dir: &Path) -> Vec<String> {
+    let absolute = pattern.starts_with('/');
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let mut matches = vec![if absolute {
+        "/".to_string()
+    } else {
+        String::new()
+    }];
+
+    for segment in segments {
+        let mut next = Vec::new();
+        for base in &matches {
+            let base_dir = dir.join(base);
+            if !segment.contains(['*', '?']) {
+                if base_dir.join(segment).exists() {
+                    next.push(format!("{base}{segment}/"));
+                }
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(&base_dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let hidden = name.starts_with('.') && !segment.starts_with('.');
+                if !hidden && wildcard_match(segment, &name) {
+                    next.push(format!("{base}{name}/"));
+                }
+            }
+        }
+        matches = next;
+    }
+
+    let mut matches: Vec<String> = matches
+        .into_iter()
+        .map(|path| path.trim_end_matches('/').to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// "matches 12 files: a, b, c, d, e and 7 more", naming the first few
+pub fn summarize_matches(matches: &[String]) -> String {
+    if matches.is_empty() {
+        return "matches no files".to_string();
+    }
+    let noun = if matches.len() == 1 { "file" } else { "files" };
+
+    let names = matches[..matches.len().min(PREVIEW_NAME_LIMIT)].join(", ");
+    match matches.len().saturating_sub(PREVIEW_NAME_LIMIT) {
+        0 => format!("matches {} {noun}: {names}", matches.len()),
+        more => format!("matches {} {noun}: {names} and {more} more", matches.len()),
+    }
+}
+
+/// What the globs of a destructive command match in `dir`, e.g. "This matches 2
+/// files: a.log, b.log" for `rm *.log`. None for other commands, commands without
+/// globs and globs that match nothing, which the shell passes on unexpanded.
+pub fn glob_preview(command: &str, dir: &Path) -> Option<String> {
+    let program = command_program(command)?;
+    let name = Path::new(program).file_name()?.to_string_lossy();
+    if !DESTRUCTIVE_PROGRAMS.contains(&name.as_ref()) {
+        return None;
+    }
+
+    let mut matches: Vec<String> = glob_arguments(command)
+        .into_iter()
+        .flat_map(|pattern| expand_glob(pattern, dir))
+        .collect();
+    matches.sort();
+    matches.dedup();
+    if matches.is_empty() {
+        return None;
+    }
+    Some(format!("This {}", summarize_matches(&matches)))
+}
@@ -0,0 +1,265 @@
+use crate::tui::commands::{BUILTIN_COMMANDS, CommandSection};
+
+/// One line of help: a command or key combination and what it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HelpEntry {
+    /// What to type, e.g. `/task add <title>` or `Ctrl+R`
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+/// A titled group of help entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: &'static str,
+    pub entries: Vec<HelpEntry>,
+}
+
+impl HelpEntry {
+    /// Whether the usage or description contains `filter`, ignoring case
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.usage.to_lowercase().contains(&filter)
+            || self.description.to_lowercase().contains(&filter)
+    }
+}
+
+const fn key(usage: &'static str, description: &'static str) -> HelpEntry {
+    HelpEntry { usage, description }
+}
+
+/// Syntax handled outside the built-in commands, listed after them in its section
+const EXTRA_COMMAND_HELP: &[(CommandSection, HelpEntry)] = &[(
+    CommandSection::History,
+    HelpEntry {
+        usage: "!N, !!",
+        description: "Re-run history command N, or the last command",
+    },
+)];
+
+/// Built-in commands, shown by /help
+pub fn command_sections() -> Vec<HelpSection> {
+    CommandSection::ALL
+        .iter()
+        .map(|&section| {
+            let commands = BUILTIN_COMMANDS
+                .iter()
+                .filter(|command| command.section == section)
+                .map(|command| HelpEntry {
+                    usage: command.usage,
+                    description: command.description,
+                });
+            let extra = EXTRA_COMMAND_HELP
+                .iter()
+                .filter(|(extra_section, _)| *extra_section == section)
+                .map(|(_, entry)| *entry);
+            HelpSection {
+                title: section.title(),
+                entries: commands.chain(extra).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Keyboard shortcuts, shown by /help keys
+pub fn key_sections() -> Vec<HelpSection> {
+    KEY_HELP
+        .iter()
+        .map(|(title, entries)| HelpSection {
+            title,
+            entries: entries.to_vec(),
+        })
+        .collect()
+}
+
+const KEY_HELP: &[(&str, &[HelpEntry])] = &[
+    (
+        "🔄 Mode Switching",
+        &[
+            key("q", "Switch to Terminal mode (from TaskList)"),
+            key("/task", "Switch to TaskList mode"),
+        ],
+    ),
+    (
+        "✅ Task List",
+        &[
+            key("↑/↓ arrows", "Select a task (while the input is empty)"),
+            key("+/-", "Raise/lower the selected task's priority"),
+            key("a", "Quick-add a task; Enter adds it, Esc cancels"),
+        ],
+    ),
+    (
+        "📝 Text Editing",
+        &[
+            key("Ctrl+A", "Move cursor to beginning of line"),
+            key("Ctrl+E", "Move cursor to end of line"),
+            key("Ctrl+B", "Move cursor backward one character"),
+            key("Ctrl+K", "Delete from cursor to end of line"),
+            key("Ctrl+G", "Clear the whole input line"),
+            key("Ctrl+X Ctrl+E", "Edit the input in $EDITOR"),
+            key("Alt+W", "Insert the current directory"),
+            key("Alt+G", "Insert the git repository root"),
+            key("Backspace", "Delete character before cursor"),
+            key("Delete", "Delete character at cursor"),
+        ],
+    ),
+    (
+        "🧭 Navigation",
+        &[
+            key("↑/↓ arrows", "Navigate command history"),
+            key("←/→ arrows", "Move cursor left/right"),
+            key("Ctrl+←/→", "Move cursor by word"),
+            key(
+                "Home/End",
+                "Move to beginning/end (or scroll history if empty)",
+            ),
+        ],
+    ),
+    (
+        "📜 Scrolling",
+        &[
+            key("Shift+↑/↓", "Scroll through terminal history"),
+            key("Alt+↑/↓", "Jump to the previous/next command"),
+            key("Page Up/Down", "Scroll by 10 lines"),
+        ],
+    ),
+    (
+        "🔍 Search & Completion",
+        &[
+            key("Ctrl+R", "Reverse search through history"),
+            key("Ctrl+Shift+R", "Fuzzy search through history"),
+            key("Ctrl+F", "Search terminal output"),
+            key("Tab", "Accept auto-suggestion or cycle completions"),
+            key("Right arrow", "Accept next character from suggestion"),
+        ],
+    ),
+    (
+        "📋 Copy & Paste",
+        &[
+            key("Ctrl+C", "Copy selected text or interrupt command"),
+            key("Ctrl+Shift+C", "Copy the whole input line"),
+            key(
+                "Alt+M",
+                "Copy the command at the top of the history (or the last one) as markdown",
+            ),
+            key(
+                "Ctrl+S",
+                "Select history text with the keyboard (←↑↓→ extend, v restart, y/Enter copy, Esc cancel)",
+            ),
+            key("Ctrl+V", "Paste from clipboard"),
+            key("Middle Click", "Paste from clipboard"),
+        ],
+    ),
+    (
+        "🖱️ Mouse",
+        &[
+            key("Left Click", "Start text selection"),
+            key("Left Drag", "Extend text selection"),
+            key(
+                "Right Click",
+                "Clear selections (or menu/paste via ui.right_click)",
+            ),
+        ],
+    ),
+    (
+        "⌨️ Command List (when typing /)",
+        &[
+            key("↑/↓ arrows", "Navigate command list"),
+            key("Enter", "Select command"),
+            key("Esc", "Cancel command selection"),
+        ],
+    ),
+    (
+        "🔍 Reverse Search (Ctrl+R)",
+        &[
+            key("↑/↓ arrows", "Navigate search results"),
+            key("Enter", "Accept search result"),
+            key("Tab", "Also search the output of this session's commands"),
+            key("Esc", "Cancel reverse search"),
+        ],
+    ),
+    (
+        "🔍 Fuzzy Search (Ctrl+Shift+R)",
+        &[
+            key("Type text", "Filter history, best match first"),
+            key("↑/↓ arrows", "Navigate matches"),
+            key("Enter", "Accept the selected command"),
+            key("Esc", "Cancel fuzzy search"),
+        ],
+    ),
+    (
+        "🔍 Output Search (Ctrl+F)",
+        &[
+            key("Type text", "Search terminal output"),
+            key("↑/↓ arrows", "Navigate between matches"),
+            key("Tab", "Cycle search mode ([aa]/[Aa]/[.*]/[W])"),
+            key("Enter/Esc", "Exit search mode"),
+        ],
+    ),
+    (
+        "▶️ Running Commands",
+        &[
+            key("Typing", "Sent to the running command's input"),
+            key(
+                "Ctrl+C",
+                "Interrupt the command (passed to full-screen programs)",
+            ),
+            key("Ctrl+D", "End the command's input (EOF)"),
+        ],
+    ),
+    (
+        "🚪 Exit",
+        &[
+            key("/quit", "Exit application"),
+            key("Ctrl+C", "Interrupt running command"),
+            key("Ctrl+L", "Clear terminal screen"),
+        ],
+    ),
+];
+
+/// Column the descriptions of key help start in
+const KEY_COLUMN_WIDTH: usize = 17;
+
+/// The /help text, limited to entries matching `filter` if given. None when the
+/// filter matches nothing.
+pub fn command_help(filter: Option<&str>) -> Option<String> {
+    render(
+        "Available commands:\n",
+        &command_sections(),
+        filter,
+        |entry| format!("  {} - {}", entry.usage, entry.description),
+    )
+}
+
+/// The /help keys text, limited to entries matching `filter` if given. None when
+/// the filter matches nothing.
+pub fn key_help(filter: Option<&str>) -> Option<String> {
+    render(
+        "\n📋 TaskHub Keyboard Shortcuts\n",
+        &key_sections(),
+        filter,
+        |entry| format!("  {:<KEY_COLUMN_WIDTH$}{}", entry.usage, entry.description),
+    )
+}
+
+fn render(
+    heading: &str,
+    sections: &[HelpSection],
+    filter: Option<&str>,
+    format_entry: impl Fn(&HelpEntry) -> String,
+) -> Option<String> {
+    let blocks: Vec<String> = sections
+        .iter()
+        .filter_map(|section| {
+            let lines: Vec<String> = section
+                .entries
+                .iter()
+                .filter(|entry| filter.is_none_or(|filter| entry.matches(filter)))
+                .map(&format_entry)
+                .collect();
+            (!lines.is_empty()).then(|| format!("{}:\n{}", section.title, lines.join("\n")))
+        })
+        .collect();
+
+    (!blocks.is_empty()).then(|| format!("{heading}\n{}", blocks.join("\n\n")))
+}
@@ -0,0 +1,186 @@
+use crate::tui::ansi_parser::AnsiParser;
+use crate::tui::theme::Theme;
+use crate::tui::views::terminal::CommandEntry;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+
+/// Background of the exported page, matching a dark terminal
+const BACKGROUND: Color = Color::Rgb(30, 30, 30);
+
+/// `color` as a CSS color, resolving the 16 base colors through `palette`. None
+/// for `Reset`, which leaves the surrounding color in place.
+pub fn css_color(color: Color, palette: &[Color; 16]) -> Option<String> {
+    let base = |index: usize| match palette[index] {
+        Color::Rgb(r, g, b) => Some(hex(r, g, b)),
+        // A palette entry that is not an RGB value falls back to xterm's
+        _ => css_color(Theme::default().palette[index], palette),
+    };
+    match color {
+        Color::Reset => None,
+        Color::Black => base(0),
+        Color::Red => base(1),
+        Color::Green => base(2),
+        Color::Yellow => base(3),
+        Color::Blue => base(4),
+        Color::Magenta => base(5),
+        Color::Cyan => base(6),
+        Color::Gray => base(7),
+        Color::DarkGray => base(8),
+        Color::LightRed => base(9),
+        Color::LightGreen => base(10),
+        Color::LightYellow => base(11),
+        Color::LightBlue => base(12),
+        Color::LightMagenta => base(13),
+        Color::LightCyan => base(14),
+        Color::White => base(15),
+        Color::Indexed(index @ 0..=15) => base(index as usize),
+        Color::Indexed(index) => {
+            let (r, g, b) = indexed_rgb(index);
+            Some(hex(r, g, b))
+        }
+        Color::Rgb(r, g, b) => Some(hex(r, g, b)),
+    }
+}
+
+fn hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// RGB value of one of the 240 colors of the 256-color palette above the base 16:
+/// a 6x6x6 color cube followed by a grayscale ramp
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    if index >= 232 {
+        let level = 8 + (index - 232) * 10;
+        return (level, level, level);
+    }
+    let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+    let cube = index - 16;
+    (level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+}
+
+/// Inline CSS for `style`, empty for unstyled text. Reversed text swaps its colors,
+/// using `foreground` and `background` for colors it does not set.
+fn css_style(style: Style, palette: &[Color; 16], foreground: Color, background: Color) -> String {
+    let (mut fg, mut bg) = (style.fg, style.bg);
+    if style.add_modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (
+            Some(bg.unwrap_or(background)),
+            Some(fg.unwrap_or(foreground)),
+        );
+    }
+
+    let mut properties = Vec::new();
+    if let Some(color) = fg.and_then(|color| css_color(color, palette)) {
+        properties.push(format!("color:{color}"));
+    }
+    if let Some(color) = bg.and_then(|color| css_color(color, palette)) {
+        properties.push(format!("background-color:{color}"));
+    }
+    let modifiers = style.add_modifier;
+    if modifiers.contains(Modifier::BOLD) {
+        properties.push("font-weight:bold".to_string());
+    }
+    if modifiers.contains(Modifier::DIM) {
+        properties.push("opacity:0.7".to_string());
+    }
+    if modifiers.contains(Modifier::ITALIC) {
+        properties.push("font-style:italic".to_string());
+    }
+    let decorations: Vec<&str> = [
+        (Modifier::UNDERLINED, "underline"),
+        (Modifier::CROSSED_OUT, "line-through"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, decoration)| decoration)
+    .collect();
+    if !decorations.is_empty() {
+        properties.push(format!("text-decoration:{}", decorations.join(" ")));
+    }
+    properties.join(";")
+}
+
+/// `text` with the characters HTML gives a meaning escaped
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// `line` as HTML: each styled span becomes a `<span>` with inline styles, and
+/// unstyled text is written as is
+pub fn line_to_html(line: &Line<'_>, theme: &Theme) -> String {
+    line.spans
+        .iter()
+        .map(|span| {
+            let text = escape_html(&span.content);
+            let style = line.style.patch(span.style);
+            let css = css_style(style, &theme.palette, theme.foreground, BACKGROUND);
+            if css.is_empty() {
+                text
+            } else {
+                format!("<span style=\"{css}\">{text}</span>")
+            }
+        })
+        .collect()
+}
+
+/// Styled lines of an entry's output: the lines the terminal emulator laid out, or
+/// each output line parsed for its escape sequences
+fn styled_output(entry: &CommandEntry) -> Vec<Line<'static>> {
+    if let Some(lines) = &entry.styled_output {
+        return lines.clone();
+    }
+    entry
+        .output
+        .lines()
+        .map(|line| {
+            // The parser expands tabs itself, counting only the columns that show
+            let mut parser = AnsiParser::new(line.chars().count().max(1), 1);
+            parser.parse(line).into_iter().next().unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A standalone HTML page showing `entries` with their output's colors. Styles are
+/// inline so that the page keeps them when pasted into an email.
+pub fn history_to_html(entries: &[CommandEntry], theme: &Theme) -> String {
+    let foreground = css_color(theme.foreground, &theme.palette).unwrap_or_default();
+    let background = css_color(BACKGROUND, &theme.palette).unwrap_or_default();
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>taskhub output</title>\n</head>\n");
+    html.push_str(&format!(
+        "<body style=\"margin:0;padding:1em;background-color:{background};color:{foreground}\">\n"
+    ));
+
+    for entry in entries {
+        let status = if entry.success {
+            theme.success
+        } else {
+            theme.failure
+        };
+        let status = css_color(status, &theme.palette).unwrap_or_default();
+        html.push_str(&format!(
+            "<pre style=\"margin:0 0 1em 0;font-family:monospace\"><span style=\"color:{status};font-weight:bold\">$ {}</span>",
+            escape_html(&entry.command)
+        ));
+        for line in styled_output(entry) {
+            html.push('\n');
+            html.push_str(&line_to_html(&line, theme));
+        }
+        html.push_str("</pre>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
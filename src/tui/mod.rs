@@ -3,13 +3,27 @@ use crossterm::{
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
+use ratatui::{
+    Terminal,
+    backend::{Backend, CrosstermBackend},
+};
 use std::io;
 
 pub mod ansi_parser;
 pub mod app;
+pub mod commands;
 pub mod completion;
 pub mod components;
+pub mod editor;
+pub mod fuzzy;
+pub mod glob_preview;
+pub mod help;
+pub mod html_export;
+pub mod pty_input;
+pub mod pty_output;
+pub mod replay;
+pub mod shell;
+pub mod theme;
 pub mod views;
 
 pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, io::Error> {
@@ -31,3 +45,16 @@ pub fn cleanup_terminal(
     )?;
     terminal.show_cursor()
 }
+
+/// Release the terminal to another full-screen program such as an editor
+pub fn suspend_terminal() -> Result<(), io::Error> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
+}
+
+/// Take the terminal back after `suspend_terminal` and redraw from scratch
+pub fn resume_terminal<B: Backend>(terminal: &mut Terminal<B>) -> Result<(), io::Error> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()
+}
@@ -0,0 +1,79 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// Where a key press should go while a command is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputTarget {
+    /// Handled by the app as usual
+    App,
+    /// Written to the running command's terminal
+    Child,
+}
+
+/// Keys the app keeps for itself while a command runs on the main screen:
+/// Ctrl-C kills the command and Ctrl-Shift-C copies the input line
+pub fn is_app_control_key(key: KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('c' | 'C')) && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Where `key` goes while a command is running. Without a PTY to write to every key
+/// stays with the app; a full-screen program gets every key, including Ctrl-C.
+pub fn route_key(key: KeyEvent, has_pty_input: bool, full_screen: bool) -> InputTarget {
+    if !has_pty_input || (!full_screen && is_app_control_key(key)) {
+        InputTarget::App
+    } else {
+        InputTarget::Child
+    }
+}
+
+/// Bytes a terminal sends for `key`, or `None` for keys with no encoding here.
+/// Key releases reported by some terminals are ignored.
+pub fn key_to_pty_bytes(key: KeyEvent) -> Option<Vec<u8>> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    let mut bytes = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+letter maps to the C0 control codes, e.g. Ctrl-C is 0x03
+            match c.to_ascii_lowercase() {
+                c @ 'a'..='z' => vec![c as u8 - b'a' + 1],
+                ' ' | '@' | '2' => vec![0],
+                '[' | '3' => vec![0x1b],
+                '\\' | '4' => vec![0x1c],
+                ']' | '5' => vec![0x1d],
+                '^' | '6' => vec![0x1e],
+                '_' | '7' | '/' => vec![0x1f],
+                _ => return None,
+            }
+        }
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => b"\x1b[A".to_vec(),
+        KeyCode::Down => b"\x1b[B".to_vec(),
+        KeyCode::Right => b"\x1b[C".to_vec(),
+        KeyCode::Left => b"\x1b[D".to_vec(),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::F(n @ 1..=4) => format!("\x1bO{}", (b'P' + n - 1) as char).into_bytes(),
+        KeyCode::F(n @ 5..=12) => {
+            // The VT220 codes skip 16 and 22
+            let code = [15, 17, 18, 19, 20, 21, 23, 24][(n - 5) as usize];
+            format!("\x1b[{code}~").into_bytes()
+        }
+        _ => return None,
+    };
+
+    // Alt sends the key prefixed with ESC
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
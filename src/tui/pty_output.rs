@@ -0,0 +1,103 @@
+/// Decodes PTY output read in arbitrary chunks. A multibyte character split
+/// across two reads is held back until the rest of it arrives, instead of being
+/// replaced with U+FFFD on both sides of the split.
+#[derive(Debug, Default)]
+pub struct Utf8Decoder {
+    /// Start of an incomplete character at the end of the last chunk
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text for `chunk` and any bytes held back from the previous one. Invalid
+    /// bytes become U+FFFD; an incomplete character at the end is kept for the
+    /// next chunk.
+    pub fn decode(&mut self, chunk: &[u8]) -> String {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+
+        let mut text = String::with_capacity(bytes.len());
+        let mut rest = bytes.as_slice();
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    text.push_str(valid);
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    text.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(len) => {
+                            text.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        None => {
+                            // The chunk ends partway through a character
+                            self.pending = after.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        text
+    }
+
+    /// Text for bytes still held back once the output has ended, which can only
+    /// be a truncated character
+    pub fn finish(&mut self) -> String {
+        String::from_utf8_lossy(&std::mem::take(&mut self.pending)).into_owned()
+    }
+}
+
+/// A piece of PTY output, split at newlines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputPiece {
+    /// The end of a line, or all of it if nothing of it was sent yet
+    Line(String),
+    /// Text of a line whose end has not arrived yet, e.g. a prompt waiting for
+    /// input. More of the line follows in later pieces.
+    Partial(String),
+}
+
+/// Splits decoded PTY output into lines as it is read. The start of an unfinished
+/// line is passed on right away instead of waiting for its newline.
+#[derive(Debug, Default)]
+pub struct LineSplitter {
+    /// The last chunk ended in a carriage return, which may be the first half of
+    /// a CRLF line ending
+    carriage_return: bool,
+}
+
+impl LineSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pieces of `text`, continuing the line the previous chunk left unfinished
+    pub fn split(&mut self, text: &str) -> Vec<OutputPiece> {
+        let mut text = text.to_string();
+        if std::mem::take(&mut self.carriage_return) {
+            text.insert(0, '\r');
+        }
+        if text.ends_with('\r') {
+            text.pop();
+            self.carriage_return = true;
+        }
+
+        let mut lines: Vec<&str> = text.split('\n').collect();
+        let unfinished = lines.pop().unwrap_or_default();
+        let mut pieces: Vec<OutputPiece> = lines
+            .into_iter()
+            .map(|line| OutputPiece::Line(line.strip_suffix('\r').unwrap_or(line).to_string()))
+            .collect();
+        if !unfinished.is_empty() {
+            pieces.push(OutputPiece::Partial(unfinished.to_string()));
+        }
+        pieces
+    }
+}
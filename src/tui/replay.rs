@@ -0,0 +1,102 @@
+use crate::tui::views::terminal::{CommandEntry, OutputStream};
+use std::time::{Duration, Instant};
+
+/// When each recorded output line is due during a replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaySchedule {
+    /// Time from the start of the replay until each line is shown; never decreasing
+    offsets: Vec<Duration>,
+}
+
+impl ReplaySchedule {
+    /// Schedule lines recorded at `times` (relative to their command's start), played
+    /// back `speed` times faster than they arrived. Speeds that are not positive and
+    /// finite play at the original pace.
+    pub fn new(times: &[Duration], speed: f64) -> Self {
+        let speed = if speed.is_finite() && speed > 0.0 {
+            speed
+        } else {
+            1.0
+        };
+        let mut latest = Duration::ZERO;
+        let offsets = times
+            .iter()
+            .map(|time| {
+                latest = latest.max(time.div_f64(speed));
+                latest
+            })
+            .collect();
+        Self { offsets }
+    }
+
+    /// Wait before each line: from the start of the replay for the first line, then
+    /// from the line before it
+    pub fn delays(&self) -> Vec<Duration> {
+        let mut previous = Duration::ZERO;
+        self.offsets
+            .iter()
+            .map(|offset| {
+                let delay = *offset - previous;
+                previous = *offset;
+                delay
+            })
+            .collect()
+    }
+
+    /// Number of lines that should be visible `elapsed` into the replay
+    pub fn lines_due(&self, elapsed: Duration) -> usize {
+        self.offsets.partition_point(|offset| *offset <= elapsed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+}
+
+/// A replay in progress, revealing the recorded output in the history entry named
+/// `command` as its lines come due
+pub struct Replay {
+    pub command: String,
+    lines: Vec<String>,
+    streams: Vec<OutputStream>,
+    times: Vec<Duration>,
+    schedule: ReplaySchedule,
+    started_at: Instant,
+}
+
+impl Replay {
+    /// Replay the output of `source` at `speed`; `None` if it has no recorded timing
+    pub fn new(command: String, source: &CommandEntry, speed: f64) -> Option<Self> {
+        let lines: Vec<String> = source
+            .output
+            .lines()
+            .take(source.output_times.len())
+            .map(str::to_string)
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        let times = source.output_times[..lines.len()].to_vec();
+        Some(Self {
+            command,
+            schedule: ReplaySchedule::new(&times, speed),
+            streams: source.output_streams.clone(),
+            lines,
+            times,
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Show the lines due by now in `entry`. Returns false once every line is shown.
+    pub fn update(&self, entry: &mut CommandEntry) -> bool {
+        let due = self.schedule.lines_due(self.started_at.elapsed());
+        entry.output = self.lines[..due].join("\n");
+        entry.output_streams = self.streams.iter().take(due).copied().collect();
+        entry.output_times = self.times[..due].to_vec();
+        due < self.lines.len()
+    }
+}
@@ -0,0 +1,186 @@
+use crate::tui::completion::SHELL_BUILTINS;
+use portable_pty::CommandBuilder;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Characters that make a command need the shell when they appear unquoted
+const SHELL_METACHARACTERS: &[char] = &[
+    '|', '&', ';', '<', '>', '(', ')', '$', '`', '*', '?', '[', '{', '}', '!', '\n',
+];
+
+/// The shell that commands typed into the terminal run in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shell {
+    program: String,
+    /// Run simple commands as their program directly instead of through the shell
+    direct_exec: bool,
+}
+
+impl Shell {
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            direct_exec: false,
+        }
+    }
+
+    /// Spawn commands without shell syntax directly with their parsed arguments
+    pub fn with_direct_exec(mut self, enabled: bool) -> Self {
+        self.direct_exec = enabled;
+        self
+    }
+
+    pub fn direct_exec(&self) -> bool {
+        self.direct_exec
+    }
+
+    /// The configured shell; without one, `$SHELL` on Unix and `cmd` on Windows,
+    /// falling back to `sh`
+    pub fn from_setting(setting: Option<&str>) -> Self {
+        let configured = setting.map(str::trim).filter(|shell| !shell.is_empty());
+        let program = match configured {
+            Some(shell) => shell.to_string(),
+            None if cfg!(target_os = "windows") => "cmd".to_string(),
+            None => std::env::var("SHELL")
+                .ok()
+                .filter(|shell| !shell.is_empty())
+                .unwrap_or_else(|| "sh".to_string()),
+        };
+        Self::new(program)
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Flag that makes the shell run the command line that follows it and exit
+    pub fn command_flag(&self) -> &'static str {
+        let name = Path::new(&self.program)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match name.as_str() {
+            "cmd" => "/C",
+            "pwsh" | "powershell" => "-Command",
+            _ => "-c",
+        }
+    }
+
+    /// Program and arguments that run `command`: its own argv when direct execution is
+    /// on and the command is simple, otherwise the shell with the command line
+    pub fn argv(&self, command: &str) -> Vec<String> {
+        // Other shells quote differently, so only POSIX-style command lines are split
+        let direct = (self.direct_exec && self.command_flag() == "-c")
+            .then(|| simple_command_argv(command))
+            .flatten();
+        direct.unwrap_or_else(|| {
+            vec![
+                self.program.clone(),
+                self.command_flag().to_string(),
+                command.to_string(),
+            ]
+        })
+    }
+
+    /// PTY command running `command` in this shell, in `cwd` if given
+    pub fn pty_command(&self, command: &str, cwd: Option<&Path>) -> CommandBuilder {
+        let argv = self.argv(command);
+        let mut cmd = CommandBuilder::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        if let Some(cwd) = cwd {
+            cmd.cwd(cwd);
+        }
+        cmd
+    }
+
+    /// Process running `command` in this shell, in `cwd` if given
+    pub fn pipe_command(&self, command: &str, cwd: Option<&Path>) -> Command {
+        let argv = self.argv(command);
+        let mut cmd = Command::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd
+    }
+}
+
+/// Whether `command` can run without a shell: plain words, optionally quoted, with no
+/// pipes, redirects, globs, variables or other shell syntax
+pub fn is_simple_command(command: &str) -> bool {
+    simple_command_argv(command).is_some()
+}
+
+/// Split a simple command into its arguments the way a POSIX shell would, removing
+/// quotes and backslash escapes. None when the command needs the shell: it uses
+/// unquoted metacharacters, expands variables inside double quotes, starts with a
+/// `NAME=value` assignment or a shell builtin, or is unbalanced or empty.
+pub fn simple_command_argv(command: &str) -> Option<Vec<String>> {
+    let mut argv = Vec::new();
+    let mut word = String::new();
+    // A word exists even if it is empty, as with `''`
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => {
+                if in_word {
+                    argv.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+                in_word = true;
+            }
+            '"' => {
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '$' | '`' => return None,
+                        '\\' => match chars.next()? {
+                            escaped @ ('"' | '\\') => word.push(escaped),
+                            '$' | '`' => return None,
+                            other => {
+                                word.push('\\');
+                                word.push(other);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+                in_word = true;
+            }
+            '\\' => {
+                word.push(chars.next()?);
+                in_word = true;
+            }
+            '#' | '~' if !in_word => return None,
+            c if SHELL_METACHARACTERS.contains(&c) => return None,
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        argv.push(word);
+    }
+
+    let program = argv.first()?;
+    let assignment = command
+        .split_whitespace()
+        .next()
+        .and_then(|first| first.split_once('='))
+        .is_some_and(|(name, _)| !name.is_empty() && !name.contains(['\'', '"', '/']));
+    if program.is_empty() || assignment || SHELL_BUILTINS.contains(&program.as_str()) {
+        return None;
+    }
+    Some(argv)
+}
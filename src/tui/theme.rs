@@ -0,0 +1,46 @@
+use ratatui::style::Color;
+
+/// Colors used by the application when rendering terminal output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Text color for output that doesn't set its own foreground
+    pub foreground: Color,
+    /// Text color for output written to stderr
+    pub stderr: Color,
+    /// Color of commands that succeeded, and of the prompt after one
+    pub success: Color,
+    /// Color of commands that failed, and of the prompt after one
+    pub failure: Color,
+    /// RGB values for the 16 base ANSI colors (indices 0-15)
+    pub palette: [Color; 16],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        // xterm's default palette
+        Self {
+            foreground: Color::White,
+            stderr: Color::Red,
+            success: Color::Green,
+            failure: Color::Red,
+            palette: [
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(205, 0, 0),
+                Color::Rgb(0, 205, 0),
+                Color::Rgb(205, 205, 0),
+                Color::Rgb(0, 0, 238),
+                Color::Rgb(205, 0, 205),
+                Color::Rgb(0, 205, 205),
+                Color::Rgb(229, 229, 229),
+                Color::Rgb(127, 127, 127),
+                Color::Rgb(255, 0, 0),
+                Color::Rgb(0, 255, 0),
+                Color::Rgb(255, 255, 0),
+                Color::Rgb(92, 92, 255),
+                Color::Rgb(255, 0, 255),
+                Color::Rgb(0, 255, 255),
+                Color::Rgb(255, 255, 255),
+            ],
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::db::models::{Task, TaskStatus};
+use crate::tui::views::task_list::draw_task_view;
+use crate::tui::views::terminal::TerminalDisplayState;
+use chrono::{Days, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+/// Agenda group a task falls into, relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaBucket {
+    Overdue,
+    Today,
+    Tomorrow,
+    /// Due within the seven days after tomorrow
+    ThisWeek,
+    Later,
+}
+
+impl AgendaBucket {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgendaBucket::Overdue => "Overdue",
+            AgendaBucket::Today => "Today",
+            AgendaBucket::Tomorrow => "Tomorrow",
+            AgendaBucket::ThisWeek => "This week",
+            AgendaBucket::Later => "Later",
+        }
+    }
+
+    /// Bucket for a task due on `due` when it is currently `today`
+    pub fn for_due_date(due: NaiveDate, today: NaiveDate) -> Self {
+        match (due - today).num_days() {
+            ..0 => AgendaBucket::Overdue,
+            0 => AgendaBucket::Today,
+            1 => AgendaBucket::Tomorrow,
+            2..=7 => AgendaBucket::ThisWeek,
+            _ => AgendaBucket::Later,
+        }
+    }
+}
+
+/// Group unfinished tasks with a due date into agenda buckets, earliest first.
+/// Within a bucket tasks are sorted by due date, then title. Empty buckets are
+/// left out.
+pub fn group_by_due_date(tasks: &[Task], today: NaiveDate) -> Vec<(AgendaBucket, Vec<&Task>)> {
+    let mut dated: Vec<(NaiveDate, &Task)> = tasks
+        .iter()
+        .filter(|task| task.status != TaskStatus::Done)
+        .filter_map(|task| Some((task.due_day()?, task)))
+        .collect();
+    dated.sort_by(|(a_due, a), (b_due, b)| a_due.cmp(b_due).then_with(|| a.title.cmp(&b.title)));
+
+    let mut groups: Vec<(AgendaBucket, Vec<&Task>)> = Vec::new();
+    for (due, task) in dated {
+        let bucket = AgendaBucket::for_due_date(due, today);
+        match groups.last_mut() {
+            Some((last, group)) if *last == bucket => group.push(task),
+            _ => groups.push((bucket, vec![task])),
+        }
+    }
+    groups
+}
+
+pub fn draw_agenda(
+    f: &mut Frame<'_>,
+    area: Rect,
+    tasks: &[Task],
+    today: NaiveDate,
+    state: &TerminalDisplayState<'_>,
+) {
+    draw_task_view(f, area, state, |f, area| {
+        draw_agenda_list(f, area, tasks, today)
+    });
+}
+
+fn draw_agenda_list(f: &mut Frame<'_>, area: Rect, tasks: &[Task], today: NaiveDate) {
+    let groups = group_by_due_date(tasks, today);
+    let tomorrow = today.checked_add_days(Days::new(1));
+
+    let mut items = Vec::new();
+    for (bucket, group) in &groups {
+        let color = match bucket {
+            AgendaBucket::Overdue => Color::Red,
+            AgendaBucket::Today => Color::Yellow,
+            _ => Color::Cyan,
+        };
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!("{} ({})", bucket.label(), group.len()),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ))));
+        for task in group {
+            // Today and Tomorrow already say the date, so only show it elsewhere
+            let due = task
+                .due_day()
+                .filter(|due| *due != today && Some(*due) != tomorrow);
+            let due = due.map(|due| format!("{due}  ")).unwrap_or_default();
+            items.push(ListItem::new(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(due, Style::default().fg(Color::DarkGray)),
+                Span::raw(task.title.clone()),
+                Span::styled(
+                    format!("  [{}]", task.status),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])));
+        }
+    }
+    if items.is_empty() {
+        items.push(ListItem::new("No open tasks with a due date"));
+    }
+
+    let list = List::new(items).block(
+        Block::default()
+            .title("Agenda (/task to return to the task list, 'q' to return to terminal)")
+            .borders(Borders::ALL),
+    );
+
+    f.render_widget(list, area);
+}
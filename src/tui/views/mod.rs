@@ -1,2 +1,3 @@
+pub mod agenda;
 pub mod task_list;
 pub mod terminal;
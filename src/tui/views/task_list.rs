@@ -1,32 +1,98 @@
-use crate::db::models::Task;
+use crate::db::models::{Task, TaskStatus};
+use crate::tui::components::context_menu::draw_context_menu;
+use crate::tui::components::quick_add::{QuickAdd, draw_quick_add};
 use crate::tui::views::terminal::TerminalDisplayState;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table},
+    widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Table, TableState},
 };
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
+/// Default number of tasks loaded per page
+pub const DEFAULT_TASK_PAGE_SIZE: usize = 100;
+
+/// Which page of tasks is loaded into memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskPage {
+    /// Zero-based page number
+    pub index: usize,
+    /// Maximum number of tasks on a page
+    pub size: usize,
+    /// Number of tasks matching the current filter across all pages
+    pub total: usize,
+}
+
+impl Default for TaskPage {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            size: DEFAULT_TASK_PAGE_SIZE,
+            total: 0,
+        }
+    }
+}
+
+impl TaskPage {
+    /// Offset of the first task on this page
+    pub fn offset(&self) -> usize {
+        self.index * self.size
+    }
+
+    /// Number of pages needed for all matching tasks; at least one
+    pub fn page_count(&self) -> usize {
+        self.total.div_ceil(self.size).max(1)
+    }
+}
+
+/// Draw the task list with the `selected`th task in display order highlighted,
+/// and the quick-add input below the tasks while it is open
 pub fn draw_task_list(
     f: &mut Frame<'_>,
     area: Rect,
     tasks: &[Task],
+    page: TaskPage,
+    selected: Option<usize>,
+    quick_add: Option<&QuickAdd>,
     state: &TerminalDisplayState<'_>,
+) {
+    draw_task_view(f, area, state, |f, area| {
+        draw_tasks_table(f, area, tasks, page, selected);
+        if let Some(quick_add) = quick_add {
+            draw_quick_add(f, area, quick_add);
+        }
+    });
+}
+
+/// Lay out a task view: `draw_body` fills the top area, with the command list
+/// and input box below it
+pub(crate) fn draw_task_view(
+    f: &mut Frame<'_>,
+    area: Rect,
+    state: &TerminalDisplayState<'_>,
+    draw_body: impl FnOnce(&mut Frame<'_>, Rect),
 ) {
     if state.show_command_list {
-        // Split into three areas: tasks, command list, input
+        // Split into three areas: body, command list, input
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(0),
-                Constraint::Length(state.filtered_commands.len().min(8) as u16 + 2),
-                Constraint::Length(3),
+                Constraint::Length(
+                    state
+                        .filtered_commands
+                        .len()
+                        .min(state.command_list_max_items as usize) as u16
+                        + 2,
+                ),
+                Constraint::Length(state.input_height),
             ])
             .split(area);
 
-        // Task list area
-        draw_tasks_table(f, chunks[0], tasks);
+        draw_body(f, chunks[0]);
 
         // Command list area
         draw_command_list_in_task_view(
@@ -39,27 +105,138 @@ pub fn draw_task_list(
         // Input area
         draw_input_box_in_task_view(f, chunks[2], state.current_input, state.cursor_position);
     } else {
-        // Normal two-area layout: tasks and input
+        // Normal two-area layout: body and input
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .constraints([Constraint::Min(0), Constraint::Length(state.input_height)])
             .split(area);
 
-        // Task list area
-        draw_tasks_table(f, chunks[0], tasks);
+        draw_body(f, chunks[0]);
 
         // Input area
         draw_input_box_in_task_view(f, chunks[1], state.current_input, state.cursor_position);
     }
+
+    // The context menu floats above everything else
+    if let Some(menu) = state.context_menu {
+        draw_context_menu(f, menu);
+    }
 }
 
-fn draw_tasks_table(f: &mut Frame<'_>, area: Rect, tasks: &[Task]) {
-    let rows: Vec<Row> = tasks
-        .iter()
-        .map(|task| {
+/// Order tasks so each subtask follows its parent, paired with its nesting depth.
+/// Subtasks whose parent is not among `tasks` are shown at the top level.
+pub fn order_by_parent(tasks: &[Task]) -> Vec<(&Task, usize)> {
+    let ids: HashSet<Uuid> = tasks.iter().map(|task| task.id).collect();
+    let mut children: HashMap<Uuid, Vec<&Task>> = HashMap::new();
+    let mut roots = Vec::new();
+    for task in tasks {
+        match task.parent_id {
+            Some(parent) if parent != task.id && ids.contains(&parent) => {
+                children.entry(parent).or_default().push(task)
+            }
+            _ => roots.push(task),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(tasks.len());
+    let mut visited = HashSet::new();
+    let mut stack: Vec<(&Task, usize)> = roots.into_iter().rev().map(|task| (task, 0)).collect();
+    while let Some((task, depth)) = stack.pop() {
+        if !visited.insert(task.id) {
+            continue;
+        }
+        ordered.push((task, depth));
+        if let Some(subtasks) = children.get(&task.id) {
+            stack.extend(subtasks.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+
+    // Tasks in a parent cycle are never reached from a root
+    ordered.extend(
+        tasks
+            .iter()
+            .filter(|task| !visited.contains(&task.id))
+            .map(|task| (task, 0)),
+    );
+    ordered
+}
+
+/// Width of the inline progress bar shown after a parent task's title
+const PROGRESS_BAR_WIDTH: usize = 5;
+
+/// How many of a task's direct subtasks are done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubtaskProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl SubtaskProgress {
+    /// Share of subtasks done, rounded down to a whole percent
+    pub fn percent(&self) -> usize {
+        (self.done * 100).checked_div(self.total).unwrap_or(0)
+    }
+
+    /// Compact bar followed by the done/total count, e.g. `■■■□□ 3/5`
+    pub fn render(&self) -> String {
+        let filled = (self.done * PROGRESS_BAR_WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(0);
+        format!(
+            "{}{} {}/{}",
+            "■".repeat(filled),
+            "□".repeat(PROGRESS_BAR_WIDTH - filled),
+            self.done,
+            self.total
+        )
+    }
+}
+
+/// Progress of every task in `tasks` that has subtasks among `tasks`.
+/// Tasks without children have no entry.
+pub fn subtask_progress(tasks: &[Task]) -> HashMap<Uuid, SubtaskProgress> {
+    let mut progress: HashMap<Uuid, SubtaskProgress> = HashMap::new();
+    for task in tasks {
+        if let Some(parent) = task.parent_id.filter(|parent| *parent != task.id) {
+            let entry = progress
+                .entry(parent)
+                .or_insert(SubtaskProgress { done: 0, total: 0 });
+            entry.total += 1;
+            if task.status == TaskStatus::Done {
+                entry.done += 1;
+            }
+        }
+    }
+    progress
+}
+
+/// Title prefixed with tree guides for its nesting depth
+fn indented_title(title: &str, depth: usize) -> String {
+    if depth == 0 {
+        title.to_string()
+    } else {
+        format!("{}└ {title}", "  ".repeat(depth - 1))
+    }
+}
+
+fn draw_tasks_table(
+    f: &mut Frame<'_>,
+    area: Rect,
+    tasks: &[Task],
+    page: TaskPage,
+    selected: Option<usize>,
+) {
+    let progress = subtask_progress(tasks);
+    let rows: Vec<Row> = order_by_parent(tasks)
+        .into_iter()
+        .map(|(task, depth)| {
+            let mut title = indented_title(&task.title, depth);
+            if let Some(progress) = progress.get(&task.id) {
+                title = format!("{title} {}", progress.render());
+            }
             Row::new(vec![
                 Cell::from(task.id.to_string()),
-                Cell::from(task.title.clone()),
+                Cell::from(title),
                 Cell::from(task.source.to_string()),
                 Cell::from(task.status.to_string()),
                 Cell::from(task.priority.to_string()),
@@ -88,11 +265,18 @@ fn draw_tasks_table(f: &mut Frame<'_>, area: Rect, tasks: &[Task]) {
         )
         .block(
             Block::default()
-                .title("Tasks (Use /task add <title> to add new tasks)")
+                .title(format!(
+                    "Tasks ({} total) - page {} of {} (PgUp/PgDn to change page, ↑↓ to select, +/- to change priority, a to add a task)",
+                    page.total,
+                    page.index + 1,
+                    page.page_count()
+                ))
                 .borders(Borders::ALL),
-        );
+        )
+        .row_highlight_style(Style::default().bg(Color::Blue).fg(Color::White));
 
-    f.render_widget(table, area);
+    let mut table_state = TableState::default().with_selected(selected);
+    f.render_stateful_widget(table, area, &mut table_state);
 }
 
 fn draw_command_list_in_task_view(
@@ -1,42 +1,150 @@
-use crate::tui::ansi_parser::AnsiParser;
+use crate::tui::ansi_parser::{AnsiParser, strip_ansi};
+use crate::tui::components::context_menu::{ContextMenu, draw_context_menu};
+use crate::tui::fuzzy::FuzzySearch;
+use crate::tui::theme::Theme;
 use crossterm;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
+use std::borrow::Cow;
+use std::time::Duration;
 
 struct HistoryRenderState<'a> {
     scroll_offset: usize,
+    horizontal_scroll_offset: usize,
     selection_start: Option<(usize, usize)>,
     selection_end: Option<(usize, usize)>,
     search_matches: &'a [(usize, usize, usize)],
     current_search_match: usize,
+    ansi_palette: Option<[Color; 16]>,
+    theme: &'a Theme,
+    show_timestamps: bool,
+    collapse_repeats: bool,
+    show_line_numbers: bool,
 }
 
-/// Create a ListItem with vtparse ANSI parsing
-fn create_vtparse_parsed_line(text: &str, fallback_style: Style) -> ListItem<'static> {
-    // Expand tab characters to spaces (using 8-space tab stops)
-    let expanded_text = expand_tabs(text, 8);
+/// Width of the line number gutter in front of the output lines of a command, including
+/// the space after the numbers
+pub fn line_number_width(output: &str) -> usize {
+    output.lines().count().max(1).to_string().len() + 1
+}
+
+/// Runs of identical consecutive lines, as the index of each run's first line and
+/// the length of the run
+pub fn collapse_repeats(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        match runs.last_mut() {
+            Some((start, count)) if lines[*start] == *line => *count += 1,
+            _ => runs.push((index, 1)),
+        }
+    }
+    runs
+}
 
-    // Use vtparse to parse ANSI sequences with actual terminal width
+/// Output lines as displayed, each with the index of the output line it shows. With
+/// `collapse`, a run of identical lines is shown once followed by ` (xN)`.
+pub fn display_output_lines(output: &str, collapse: bool) -> Vec<(usize, Cow<'_, str>)> {
+    if !collapse {
+        return output.lines().map(Cow::Borrowed).enumerate().collect();
+    }
+    let lines: Vec<&str> = output.lines().collect();
+    collapse_repeats(&lines)
+        .into_iter()
+        .map(|(start, count)| {
+            let line = if count > 1 {
+                Cow::Owned(format!("{} (x{count})", lines[start]))
+            } else {
+                Cow::Borrowed(lines[start])
+            };
+            (start, line)
+        })
+        .collect()
+}
+
+/// The `index`th output line of `entry`, displayed as `text`: the stored styled line
+/// if the entry has one, otherwise `text` parsed for its escape sequences
+fn output_line(
+    entry: &CommandEntry,
+    index: usize,
+    text: &str,
+    fallback_style: Style,
+    render_state: &HistoryRenderState<'_>,
+) -> Line<'static> {
+    let Some(styled) = entry
+        .styled_output
+        .as_ref()
+        .and_then(|lines| lines.get(index))
+    else {
+        return create_vtparse_parsed_line(text, fallback_style, render_state.ansi_palette);
+    };
+
+    let mut line = styled.clone().style(fallback_style);
+    // A collapsed run of repeats shows its count after the text
+    let plain: String = styled
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    if let Some(count) = text.strip_prefix(plain.as_str())
+        && !count.is_empty()
+    {
+        line.spans.push(Span::raw(count.to_string()));
+    }
+    line
+}
+
+/// Create a Line with vtparse ANSI parsing
+fn create_vtparse_parsed_line(
+    text: &str,
+    fallback_style: Style,
+    ansi_palette: Option<[Color; 16]>,
+) -> Line<'static> {
+    // Use vtparse to parse ANSI sequences with actual terminal width. The parser
+    // expands tabs itself, counting only the columns that show.
     let (term_width, _) = crossterm::terminal::size().unwrap_or((120, 24));
     let mut parser = AnsiParser::new(term_width as usize, 1); // Single line parser
-    let parsed_lines = parser.parse(&expanded_text);
+    parser.set_palette(ansi_palette);
+    let parsed_lines = parser.parse(text);
 
     if let Some(parsed_line) = parsed_lines.first() {
-        // Use the parsed line with proper ANSI handling
-        ListItem::new(parsed_line.clone())
+        // Use the parsed line with proper ANSI handling. Spans without their own
+        // colors inherit the line style, so default text follows the theme.
+        parsed_line.clone().style(fallback_style)
     } else {
         // Fallback to styled text
-        ListItem::new(Line::from(Span::styled(expanded_text, fallback_style)))
+        Line::from(Span::styled(expand_tabs(text, 8), fallback_style))
+    }
+}
+
+/// Drop the first `offset` columns of a line, keeping the styling of the remaining text
+fn scroll_line_horizontally(line: Line<'_>, offset: usize) -> Line<'_> {
+    if offset == 0 {
+        return line;
+    }
+
+    let mut remaining = offset;
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let char_count = span.content.chars().count();
+        if remaining >= char_count {
+            remaining -= char_count;
+            continue;
+        }
+        let visible: String = span.content.chars().skip(remaining).collect();
+        remaining = 0;
+        spans.push(Span::styled(visible, span.style));
     }
+
+    Line::from(spans).style(line.style)
 }
 
 /// Expand tab characters to spaces using the specified tab width
-fn expand_tabs(text: &str, tab_width: usize) -> String {
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
     let mut result = String::new();
     let mut column = 0;
 
@@ -55,11 +163,66 @@ fn expand_tabs(text: &str, tab_width: usize) -> String {
     result
 }
 
-#[derive(Debug, Clone)]
+/// Stream a line of command output was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CommandEntry {
     pub command: String,
     pub output: String,
     pub success: bool,
+    /// Stream of each line in `output`; lines past the end are treated as stdout
+    pub output_streams: Vec<OutputStream>,
+    /// Time from the command's start until each line in `output` arrived; empty for
+    /// output that was not streamed from a process
+    pub output_times: Vec<Duration>,
+    /// Exit code of the process; None for builtins, commands still running and
+    /// processes killed by a signal
+    pub exit_code: Option<i32>,
+    /// Styled lines laid out by the terminal emulator, for output that needed one
+    /// (screen clearing, cursor movement). `output` then holds their plain text,
+    /// line for line. None when `output` is rendered from its own escape sequences.
+    pub styled_output: Option<Vec<Line<'static>>>,
+}
+
+/// `entry` as markdown to paste into an issue or chat: the command as a bold title,
+/// with its exit code if it failed, and its output without escape sequences in a
+/// fenced code block
+pub fn entry_markdown(entry: &CommandEntry) -> String {
+    let output: Vec<String> = entry.output.lines().map(strip_ansi).collect();
+    let output = output.join("\n");
+
+    let mut title = format!("**{}**", inline_code(&format!("$ {}", entry.command)));
+    if let Some(code) = entry.exit_code.filter(|code| *code != 0) {
+        title.push_str(&format!(" (exit {code})"));
+    }
+
+    // The fence has to be longer than any run of backticks in the output
+    let fence = "`".repeat(longest_backtick_run(&output).max(2) + 1);
+    if output.is_empty() {
+        format!("{title}\n\n{fence}\n{fence}\n")
+    } else {
+        format!("{title}\n\n{fence}\n{output}\n{fence}\n")
+    }
+}
+
+/// `text` as markdown inline code, delimited by more backticks than it contains
+fn inline_code(text: &str) -> String {
+    let ticks = "`".repeat(longest_backtick_run(text) + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{ticks} {text} {ticks}")
+    } else {
+        format!("{ticks}{text}{ticks}")
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0)
 }
 
 pub struct TerminalDisplayState<'a> {
@@ -67,10 +230,13 @@ pub struct TerminalDisplayState<'a> {
     pub current_input: &'a str,
     pub cursor_position: usize,
     pub scroll_offset: usize,
+    pub horizontal_scroll_offset: usize,
     pub show_command_list: bool,
     pub filtered_commands: &'a [String],
     pub selected_command_index: usize,
     pub is_command_running: bool,
+    /// The running command looks like it is blocked waiting for input
+    pub waiting_for_input: bool,
     pub prompt: &'a str,
     pub selection_start: Option<(usize, usize)>,
     pub selection_end: Option<(usize, usize)>,
@@ -80,42 +246,152 @@ pub struct TerminalDisplayState<'a> {
     pub reverse_search_active: bool,
     pub reverse_search_prompt: &'a str,
     pub current_search_result: Option<&'a str>,
+    /// Output line the current reverse search result matched
+    pub reverse_search_preview: Option<&'a str>,
+    /// Open fuzzy history search, listed in place of the command list
+    pub fuzzy_search: Option<&'a FuzzySearch>,
     pub output_search_active: bool,
     pub output_search_query: &'a str,
     pub output_search_matches: &'a [(usize, usize, usize)],
     pub output_search_current_match: usize,
     pub output_search_status: &'a str,
+    pub visual_selection_active: bool,
+    pub context_menu: Option<&'a ContextMenu>,
+    pub ansi_palette: Option<[Color; 16]>,
+    pub theme: &'a Theme,
+    /// Height of the input box in rows, including its borders
+    pub input_height: u16,
+    /// Maximum number of commands shown in the command-list popup
+    pub command_list_max_items: u16,
+    /// Candidates of an ambiguous Tab completion, shown in place of the command list
+    pub completion_candidates: &'a [String],
+    /// Index of the completion currently applied to the input
+    pub selected_completion: usize,
+    /// Prefix output lines with the time since their command started
+    pub show_timestamps: bool,
+    /// Show runs of identical output lines once, with a repeat count
+    pub collapse_repeats: bool,
+    /// Number the output lines of each command
+    pub show_line_numbers: bool,
+}
+
+impl<'a> TerminalDisplayState<'a> {
+    /// `command_history` drawn with `theme` and nothing else going on: an empty input,
+    /// no command running, and no selection, search or popup open
+    pub fn new(command_history: &'a [CommandEntry], theme: &'a Theme) -> Self {
+        Self {
+            command_history,
+            current_input: "",
+            cursor_position: 0,
+            scroll_offset: 0,
+            horizontal_scroll_offset: 0,
+            show_command_list: false,
+            filtered_commands: &[],
+            selected_command_index: 0,
+            is_command_running: false,
+            waiting_for_input: false,
+            prompt: ">",
+            selection_start: None,
+            selection_end: None,
+            input_selection_start: None,
+            input_selection_end: None,
+            auto_suggestion: None,
+            reverse_search_active: false,
+            reverse_search_prompt: "",
+            current_search_result: None,
+            reverse_search_preview: None,
+            fuzzy_search: None,
+            output_search_active: false,
+            output_search_query: "",
+            output_search_matches: &[],
+            output_search_current_match: 0,
+            output_search_status: "",
+            visual_selection_active: false,
+            context_menu: None,
+            ansi_palette: None,
+            theme,
+            input_height: 3,
+            command_list_max_items: 8,
+            completion_candidates: &[],
+            selected_completion: 0,
+            show_timestamps: false,
+            collapse_repeats: false,
+            show_line_numbers: false,
+        }
+    }
+}
+
+/// The part of `area` content is drawn in: at most `max_width` columns wide, centered.
+/// None or 0 uses the whole area.
+pub fn content_area(area: Rect, max_width: Option<u16>) -> Rect {
+    match max_width {
+        Some(max_width) if max_width > 0 && max_width < area.width => Rect {
+            x: area.x + (area.width - max_width) / 2,
+            width: max_width,
+            ..area
+        },
+        _ => area,
+    }
 }
 
 pub fn draw_terminal(f: &mut Frame<'_>, area: Rect, state: &TerminalDisplayState<'_>) {
-    if state.show_command_list {
+    let show_completions = state.fuzzy_search.is_none()
+        && !state.show_command_list
+        && !state.completion_candidates.is_empty();
+    if state.fuzzy_search.is_some() || state.show_command_list || show_completions {
+        let list_len = if let Some(search) = state.fuzzy_search {
+            // Keep a row for the "no matches" hint
+            search.results.len().max(1)
+        } else if show_completions {
+            state.completion_candidates.len()
+        } else {
+            state.filtered_commands.len()
+        };
+
         // Split into three areas: history, command list, input
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(0),
-                Constraint::Length(state.filtered_commands.len().min(8) as u16 + 2),
-                Constraint::Length(3),
+                Constraint::Length(list_len.min(state.command_list_max_items as usize) as u16 + 2),
+                Constraint::Length(state.input_height),
             ])
             .split(area);
 
         // Command history area
         let history_state = HistoryRenderState {
             scroll_offset: state.scroll_offset,
+            horizontal_scroll_offset: state.horizontal_scroll_offset,
             selection_start: state.selection_start,
             selection_end: state.selection_end,
             search_matches: state.output_search_matches,
             current_search_match: state.output_search_current_match,
+            ansi_palette: state.ansi_palette,
+            theme: state.theme,
+            show_timestamps: state.show_timestamps,
+            collapse_repeats: state.collapse_repeats,
+            show_line_numbers: state.show_line_numbers,
         };
         draw_command_history(f, chunks[0], state.command_history, &history_state);
 
         // Command list area
-        draw_command_list(
-            f,
-            chunks[1],
-            state.filtered_commands,
-            state.selected_command_index,
-        );
+        if let Some(search) = state.fuzzy_search {
+            draw_fuzzy_list(f, chunks[1], search);
+        } else if show_completions {
+            draw_completion_list(
+                f,
+                chunks[1],
+                state.completion_candidates,
+                state.selected_completion,
+            );
+        } else {
+            draw_command_list(
+                f,
+                chunks[1],
+                state.filtered_commands,
+                state.selected_command_index,
+            );
+        }
 
         // Input area
         draw_input_box(f, chunks[2], state);
@@ -123,22 +399,33 @@ pub fn draw_terminal(f: &mut Frame<'_>, area: Rect, state: &TerminalDisplayState
         // Normal two-area layout
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .constraints([Constraint::Min(0), Constraint::Length(state.input_height)])
             .split(area);
 
         // Command history area
         let history_state = HistoryRenderState {
             scroll_offset: state.scroll_offset,
+            horizontal_scroll_offset: state.horizontal_scroll_offset,
             selection_start: state.selection_start,
             selection_end: state.selection_end,
             search_matches: state.output_search_matches,
             current_search_match: state.output_search_current_match,
+            ansi_palette: state.ansi_palette,
+            theme: state.theme,
+            show_timestamps: state.show_timestamps,
+            collapse_repeats: state.collapse_repeats,
+            show_line_numbers: state.show_line_numbers,
         };
         draw_command_history(f, chunks[0], state.command_history, &history_state);
 
         // Input area
         draw_input_box(f, chunks[1], state);
     }
+
+    // The context menu floats above everything else
+    if let Some(menu) = state.context_menu {
+        draw_context_menu(f, menu);
+    }
 }
 
 fn draw_command_history(
@@ -167,11 +454,7 @@ fn draw_command_history(
 
     for entry in command_history.iter() {
         // Add command line
-        let command_style = if entry.success {
-            Style::default().fg(Color::Green)
-        } else {
-            Style::default().fg(Color::Red)
-        };
+        let command_style = Style::default().fg(status_color(render_state.theme, entry.success));
 
         // Check if this line has search matches
         let search_matches_for_line: Vec<(usize, (usize, usize, usize))> = render_state
@@ -183,16 +466,13 @@ fn draw_command_history(
             .collect();
 
         // Check if this line is selected
-        let line_item = if !search_matches_for_line.is_empty() {
+        let mut line_item = if !search_matches_for_line.is_empty() {
             // This line has search matches, create with highlighting
             let command_text = format!("> {}", entry.command);
             create_line_with_search_highlights(
-                command_text,
+                create_vtparse_parsed_line(&command_text, command_style, render_state.ansi_palette),
                 &search_matches_for_line,
                 render_state.current_search_match,
-                command_style,
-                selection_bounds,
-                line_index,
             )
         } else if let Some(((start_line, start_col), (end_line, end_col))) = selection_bounds {
             if line_index >= start_line && line_index <= end_line {
@@ -215,19 +495,29 @@ fn draw_command_history(
                 }
             } else {
                 // Not selected
-                ListItem::new(Line::from(vec![
+                Line::from(vec![
                     Span::styled("> ", Style::default().fg(Color::Cyan)),
                     Span::styled(&entry.command, command_style),
-                ]))
+                ])
             }
         } else {
             // No selection
-            ListItem::new(Line::from(vec![
+            Line::from(vec![
                 Span::styled("> ", Style::default().fg(Color::Cyan)),
                 Span::styled(&entry.command, command_style),
-            ]))
+            ])
         };
 
+        // A failing exit code follows the command, outside the selectable text
+        if let Some(code) = entry.exit_code.filter(|code| *code != 0) {
+            line_item.spans.push(Span::styled(
+                format!(" [exit {code}]"),
+                Style::default()
+                    .fg(render_state.theme.failure)
+                    .add_modifier(Modifier::DIM),
+            ));
+        }
+
         all_items.push(line_item);
         line_index += 1;
 
@@ -236,10 +526,23 @@ fn draw_command_history(
             let output_style = if entry.output == "Running..." {
                 Style::default().fg(Color::Yellow)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(render_state.theme.foreground)
             };
 
-            for line in entry.output.lines() {
+            let stderr_style = Style::default()
+                .fg(render_state.theme.stderr)
+                .add_modifier(Modifier::DIM);
+
+            let number_width = line_number_width(&entry.output) - 1;
+            for (output_line_index, line) in
+                display_output_lines(&entry.output, render_state.collapse_repeats)
+            {
+                let line: &str = &line;
+                let output_style = match entry.output_streams.get(output_line_index) {
+                    Some(OutputStream::Stderr) => stderr_style,
+                    _ => output_style,
+                };
+
                 // Check if this line has search matches
                 let search_matches_for_line: Vec<(usize, (usize, usize, usize))> = render_state
                     .search_matches
@@ -250,15 +553,12 @@ fn draw_command_history(
                     .collect();
 
                 // Check if this line is selected
-                let line_item = if !search_matches_for_line.is_empty() {
+                let mut line_item = if !search_matches_for_line.is_empty() {
                     // This line has search matches, create with highlighting
                     create_line_with_search_highlights(
-                        line.to_string(),
+                        output_line(entry, output_line_index, line, output_style, render_state),
                         &search_matches_for_line,
                         render_state.current_search_match,
-                        output_style,
-                        selection_bounds,
-                        line_index,
                     )
                 } else if let Some(((start_line, start_col), (end_line, end_col))) =
                     selection_bounds
@@ -281,21 +581,44 @@ fn draw_command_history(
                             create_selected_line(line.to_string(), 0, len, output_style)
                         }
                     } else {
-                        // Not selected - use vtparse ANSI parsing
-                        create_vtparse_parsed_line(line, output_style)
+                        // Not selected - use the styled line or vtparse ANSI parsing
+                        output_line(entry, output_line_index, line, output_style, render_state)
                     }
                 } else {
-                    // No selection - use vtparse ANSI parsing
-                    create_vtparse_parsed_line(line, output_style)
+                    // No selection - use the styled line or vtparse ANSI parsing
+                    output_line(entry, output_line_index, line, output_style, render_state)
                 };
 
+                if render_state.show_timestamps
+                    && let Some(time) = entry.output_times.get(output_line_index)
+                {
+                    line_item.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("[{:.3}] ", time.as_secs_f64()),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    );
+                }
+
+                // Numbers count the command's own output lines, starting at 1
+                if render_state.show_line_numbers {
+                    line_item.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("{:>number_width$} ", output_line_index + 1),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    );
+                }
+
                 all_items.push(line_item);
                 line_index += 1;
             }
         }
 
         // Add empty line for spacing
-        all_items.push(ListItem::new(Line::from("")));
+        all_items.push(Line::from(""));
         line_index += 1;
     }
 
@@ -324,8 +647,19 @@ fn draw_command_history(
         all_items[start_index..end_index].to_vec()
     };
 
+    // Apply the horizontal scroll so wide output can be read past the right edge
+    let visible_items: Vec<ListItem> = visible_items
+        .into_iter()
+        .map(|line| {
+            ListItem::new(scroll_line_horizontally(
+                line,
+                render_state.horizontal_scroll_offset,
+            ))
+        })
+        .collect();
+
     // Create scroll indicator text
-    let scroll_info = if render_state.scroll_offset > 0 {
+    let mut scroll_info = if render_state.scroll_offset > 0 {
         format!(
             "Terminal Output (↑{} lines scrolled)",
             render_state.scroll_offset
@@ -336,6 +670,12 @@ fn draw_command_history(
     } else {
         "Terminal Output".to_string()
     };
+    if render_state.horizontal_scroll_offset > 0 {
+        scroll_info.push_str(&format!(
+            " (→{} columns scrolled)",
+            render_state.horizontal_scroll_offset
+        ));
+    }
 
     let list = List::new(visible_items).block(
         Block::default()
@@ -376,20 +716,130 @@ fn draw_command_list(
     f.render_widget(list, area);
 }
 
+fn draw_completion_list(f: &mut Frame<'_>, area: Rect, candidates: &[String], selected: usize) {
+    let items: Vec<ListItem> = candidates
+        .iter()
+        .map(|candidate| {
+            // Directories end in a slash and are set apart from files
+            let style = if candidate.ends_with('/') {
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            ListItem::new(Line::from(Span::styled(candidate.as_str(), style)))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(
+                    "Completions {}/{} (Tab to cycle)",
+                    selected + 1,
+                    candidates.len()
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green)),
+        )
+        .highlight_style(Style::default().bg(Color::Blue).fg(Color::White));
+
+    // Let the list scroll so the applied completion stays visible
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn draw_fuzzy_list(f: &mut Frame<'_>, area: Rect, search: &FuzzySearch) {
+    let items: Vec<ListItem> = if search.results.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "No matching commands",
+            Style::default().fg(Color::DarkGray),
+        )))]
+    } else {
+        search
+            .results
+            .iter()
+            .map(|result| {
+                // Matched characters stand out so it is clear why a command ranked
+                let spans: Vec<Span> = result
+                    .command
+                    .chars()
+                    .enumerate()
+                    .map(|(i, ch)| {
+                        let style = if result.positions.contains(&i) {
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(Color::Green)
+                        };
+                        Span::styled(ch.to_string(), style)
+                    })
+                    .collect();
+                ListItem::new(Line::from(spans))
+            })
+            .collect()
+    };
+
+    let title = if search.results.is_empty() {
+        "History (no matches)".to_string()
+    } else {
+        format!("History {}/{}", search.selected + 1, search.results.len())
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightMagenta)),
+        )
+        .highlight_style(Style::default().bg(Color::Blue));
+
+    // Let the list scroll so the selected command stays visible
+    let selected = (!search.results.is_empty()).then_some(search.selected);
+    let mut list_state = ListState::default().with_selected(selected);
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Theme color for a command that succeeded or failed
+fn status_color(theme: &Theme, success: bool) -> Color {
+    if success {
+        theme.success
+    } else {
+        theme.failure
+    }
+}
+
 fn draw_input_box(f: &mut Frame<'_>, area: Rect, state: &TerminalDisplayState<'_>) {
     let chars: Vec<char> = state.current_input.chars().collect();
     let cursor_pos = state.cursor_position.min(chars.len());
 
+    // The prompt shows how the last command went, like many shells
     let prompt_style = if state.is_command_running {
         Style::default().fg(Color::Yellow)
     } else {
-        Style::default().fg(Color::Green)
+        let succeeded = state
+            .command_history
+            .last()
+            .is_none_or(|entry| entry.success);
+        Style::default().fg(status_color(state.theme, succeeded))
     };
 
-    let input_text = if state.output_search_active {
+    let input_text = if let Some(search) = state.fuzzy_search {
+        Line::from(vec![
+            Span::styled("fuzzy> ", Style::default().fg(Color::LightMagenta)),
+            Span::raw(search.query.as_str()),
+            Span::styled(" ", Style::default().bg(Color::White)),
+        ])
+    } else if state.output_search_active {
         create_output_search_line(state.output_search_query, state.output_search_status)
     } else if state.reverse_search_active {
-        create_reverse_search_line(state.reverse_search_prompt, state.current_search_result)
+        create_reverse_search_line(
+            state.reverse_search_prompt,
+            state.current_search_result,
+            state.reverse_search_preview,
+        )
     } else {
         create_input_line_with_selection(
             state.prompt,
@@ -402,10 +852,16 @@ fn draw_input_box(f: &mut Frame<'_>, area: Rect, state: &TerminalDisplayState<'_
         )
     };
 
-    let title = if state.output_search_active {
+    let title = if state.fuzzy_search.is_some() {
+        "Fuzzy History Search (Type to filter, ↑↓ to navigate, Enter to accept, Esc to cancel)"
+    } else if state.visual_selection_active {
+        "Visual Selection (←↑↓→ to extend, v to restart here, y/Enter to copy, Esc to cancel)"
+    } else if state.output_search_active {
         "Output Search (Type to search, ↑↓ to navigate, Tab for mode, Enter/Esc to exit)"
     } else if state.reverse_search_active {
-        "Reverse Search (Enter to accept, Esc to cancel, ↑↓ to navigate)"
+        "Reverse Search (Enter to accept, Esc to cancel, ↑↓ to navigate, Tab to include output)"
+    } else if state.waiting_for_input {
+        "Command Input (Command may be waiting for input: type a reply, Ctrl-D to send EOF, Ctrl-C to stop)"
     } else if state.is_command_running {
         "Command Input (Command running... Press Ctrl-C to stop)"
     } else if state.current_input.starts_with('/') {
@@ -416,7 +872,11 @@ fn draw_input_box(f: &mut Frame<'_>, area: Rect, state: &TerminalDisplayState<'_
         "Command Input (Type / for commands, /quit to exit, Ctrl-R for search, Ctrl-F for output search)"
     };
 
-    let border_style = if state.output_search_active {
+    let border_style = if state.fuzzy_search.is_some() {
+        Style::default().fg(Color::LightMagenta)
+    } else if state.visual_selection_active {
+        Style::default().fg(Color::LightBlue)
+    } else if state.output_search_active {
         Style::default().fg(Color::Cyan)
     } else if state.reverse_search_active {
         Style::default().fg(Color::Magenta)
@@ -439,7 +899,7 @@ fn create_selected_line(
     start_col: usize,
     end_col: usize,
     base_style: Style,
-) -> ListItem<'static> {
+) -> Line<'static> {
     // Expand tab characters to spaces first
     let expanded_text = expand_tabs(&text, 8);
     let chars: Vec<char> = expanded_text.chars().collect();
@@ -468,7 +928,7 @@ fn create_selected_line(
         spans.push(Span::styled(after_text, base_style));
     }
 
-    ListItem::new(Line::from(spans))
+    Line::from(spans)
 }
 
 fn create_input_line_with_selection<'a>(
@@ -580,6 +1040,7 @@ fn create_input_line_with_selection<'a>(
 fn create_reverse_search_line<'a>(
     search_prompt: &'a str,
     current_result: Option<&'a str>,
+    preview: Option<&'a str>,
 ) -> Line<'a> {
     let mut spans = vec![Span::styled(
         search_prompt,
@@ -592,6 +1053,12 @@ fn create_reverse_search_line<'a>(
             Style::default().fg(Color::Green),
         ));
     }
+    if let Some(preview) = preview {
+        spans.push(Span::styled(
+            format!("  ↳ {preview}"),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
 
     Line::from(spans)
 }
@@ -605,65 +1072,50 @@ fn create_output_search_line<'a>(search_query: &'a str, search_status: &'a str)
     Line::from(vec![Span::styled(prompt, Style::default().fg(Color::Cyan))])
 }
 
+/// Overlay search highlights on a line parsed with its ANSI colors. Match columns
+/// refer to the visible text, so escape sequences never shift a highlight and
+/// colors outside the matches are kept.
 fn create_line_with_search_highlights(
-    text: String,
+    parsed: Line<'static>,
     search_matches: &[(usize, (usize, usize, usize))],
     current_search_match: usize,
-    base_style: Style,
-    _selection_bounds: Option<((usize, usize), (usize, usize))>,
-    _line_index: usize,
-) -> ListItem<'static> {
-    // Expand tab characters to spaces first
-    let expanded_text = expand_tabs(&text, 8);
-    let chars: Vec<char> = expanded_text.chars().collect();
-    let mut spans = Vec::new();
-    let mut pos = 0;
-
-    // Collect all match ranges for this line
-    let mut match_ranges: Vec<(usize, usize, bool)> = search_matches
-        .iter()
-        .map(|(match_idx, (_, start_col, end_col))| {
-            (*start_col, *end_col, *match_idx == current_search_match)
-        })
-        .collect();
-
-    // Sort by start position
-    match_ranges.sort_by_key(|(start, _, _)| *start);
+) -> Line<'static> {
+    // Whether the column is inside a match, and if so whether it is the current one
+    let highlight_at = |col: usize| {
+        search_matches
+            .iter()
+            .find(|(_, (_, start_col, end_col))| *start_col <= col && col < *end_col)
+            .map(|(match_idx, _)| *match_idx == current_search_match)
+    };
 
-    for (start_col, end_col, is_current) in match_ranges {
-        // Add text before match
-        if pos < start_col && start_col <= chars.len() {
-            let before_text: String = chars[pos..start_col].iter().collect();
-            spans.push(Span::styled(before_text, base_style));
-        }
+    let mut spans = Vec::new();
+    let mut col = 0;
+    for span in parsed.spans {
+        let chars: Vec<char> = span.content.chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            // Split the span where the highlight starts or stops
+            let highlight = highlight_at(col + start);
+            let mut end = start + 1;
+            while end < chars.len() && highlight_at(col + end) == highlight {
+                end += 1;
+            }
 
-        // Add highlighted match
-        let match_start = start_col.min(chars.len());
-        let match_end = end_col.min(chars.len());
-        if match_start < match_end {
-            let match_text: String = chars[match_start..match_end].iter().collect();
-            let highlight_style = if is_current {
+            let style = match highlight {
                 // Current match: bright yellow background
-                base_style.bg(Color::Yellow).fg(Color::Black)
-            } else {
+                Some(true) => span.style.bg(Color::Yellow).fg(Color::Black),
                 // Other matches: cyan background
-                base_style.bg(Color::Cyan).fg(Color::Black)
+                Some(false) => span.style.bg(Color::Cyan).fg(Color::Black),
+                None => span.style,
             };
-            spans.push(Span::styled(match_text, highlight_style));
+            spans.push(Span::styled(
+                chars[start..end].iter().collect::<String>(),
+                style,
+            ));
+            start = end;
         }
-
-        pos = match_end;
-    }
-
-    // Add remaining text after last match
-    if pos < chars.len() {
-        let remaining_text: String = chars[pos..].iter().collect();
-        spans.push(Span::styled(remaining_text, base_style));
+        col += chars.len();
     }
 
-    // Handle selection highlighting over search highlights if needed
-    // For simplicity, we'll prioritize search highlighting over selection
-    // A more sophisticated implementation could layer both
-
-    ListItem::new(Line::from(spans))
+    Line::from(spans).style(parsed.style)
 }
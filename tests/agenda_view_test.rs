@@ -0,0 +1,96 @@
+use chrono::NaiveDate;
+use taskhub::db::init_db;
+use taskhub::db::models::{Task, TaskStatus};
+use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::views::agenda::{AgendaBucket, group_by_due_date};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn task(title: &str, due_date: Option<&str>) -> Task {
+    Task {
+        due_date: due_date.map(str::to_string),
+        created_at: "2025-01-01 00:00:00".to_string(),
+        updated_at: "2025-01-01 00:00:00".to_string(),
+        ..Task::new(title)
+    }
+}
+
+#[test]
+fn test_bucket_for_due_date() {
+    // A Wednesday
+    let today = date("2025-06-11");
+
+    let bucket = |due| AgendaBucket::for_due_date(date(due), today);
+    assert_eq!(bucket("2025-05-30"), AgendaBucket::Overdue);
+    assert_eq!(bucket("2025-06-10"), AgendaBucket::Overdue);
+    assert_eq!(bucket("2025-06-11"), AgendaBucket::Today);
+    assert_eq!(bucket("2025-06-12"), AgendaBucket::Tomorrow);
+    assert_eq!(bucket("2025-06-13"), AgendaBucket::ThisWeek);
+    assert_eq!(bucket("2025-06-18"), AgendaBucket::ThisWeek);
+    assert_eq!(bucket("2025-06-19"), AgendaBucket::Later);
+    assert_eq!(bucket("2026-01-01"), AgendaBucket::Later);
+}
+
+#[test]
+fn test_due_day_parses_dates_and_timestamps() {
+    assert_eq!(
+        task("a", Some("2025-06-11")).due_day(),
+        Some(date("2025-06-11"))
+    );
+    assert_eq!(
+        task("b", Some("2025-06-11T09:30:00Z")).due_day(),
+        Some(date("2025-06-11"))
+    );
+    assert_eq!(
+        task("c", Some("2025-06-11 09:30:00")).due_day(),
+        Some(date("2025-06-11"))
+    );
+    assert_eq!(task("d", Some("next friday")).due_day(), None);
+    assert_eq!(task("e", None).due_day(), None);
+}
+
+#[test]
+fn test_group_by_due_date_orders_buckets_and_tasks() {
+    let today = date("2025-06-11");
+    let mut done = task("Shipped", Some("2025-06-11"));
+    done.status = TaskStatus::Done;
+    let tasks = vec![
+        task("Plan Q3", Some("2025-07-01")),
+        task("Standup", Some("2025-06-11")),
+        task("Undated", None),
+        task("Invoice", Some("2025-06-01")),
+        task("Review", Some("2025-06-11T15:00:00Z")),
+        task("Expenses", Some("2025-06-09")),
+        task("Demo", Some("2025-06-15")),
+        done,
+    ];
+
+    let groups: Vec<(AgendaBucket, Vec<&str>)> = group_by_due_date(&tasks, today)
+        .into_iter()
+        .map(|(bucket, group)| (bucket, group.iter().map(|t| t.title.as_str()).collect()))
+        .collect();
+
+    assert_eq!(
+        groups,
+        vec![
+            (AgendaBucket::Overdue, vec!["Invoice", "Expenses"]),
+            (AgendaBucket::Today, vec!["Review", "Standup"]),
+            (AgendaBucket::ThisWeek, vec!["Demo"]),
+            (AgendaBucket::Later, vec!["Plan Q3"]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_task_agenda_command_switches_mode() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    assert!(app.handle_builtin_command("/task agenda").await);
+    assert_eq!(app.mode, AppMode::Agenda);
+
+    app.on_key('q');
+    assert_eq!(app.mode, AppMode::Terminal);
+}
@@ -0,0 +1,86 @@
+use std::time::Instant;
+use taskhub::db::init_db;
+use taskhub::tui::ansi_parser::strip_alternate_screen;
+use taskhub::tui::app::{App, OutputLine, RunningCommand};
+use taskhub::tui::views::terminal::CommandEntry;
+use tokio::sync::mpsc;
+
+/// Stream `lines` from a fake command and return the output shown for it
+async fn streamed_output(lines: &[&str]) -> String {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    for line in lines {
+        sender.send(OutputLine::Stdout(line.to_string())).unwrap();
+    }
+
+    app.command_history.push(CommandEntry {
+        command: "app".to_string(),
+        output: "Running...".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app.running_command = Some(RunningCommand {
+        command: "app".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: Some(receiver),
+        uses_alternate_screen: false,
+        live_ansi_parser: None,
+        pty_writer: None,
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    });
+
+    app.check_running_command().await;
+
+    app.command_history.last().unwrap().output.clone()
+}
+
+#[test]
+fn test_strip_alternate_screen_keeps_main_screen_text() {
+    assert_eq!(
+        strip_alternate_screen("before\n\x1b[?1049hmenu\x1b[2Jframe\n\x1b[?1049lafter"),
+        "before\nafter"
+    );
+    assert_eq!(
+        strip_alternate_screen("a\x1b[?1047hx\x1b[?1047lb\x1b[?1049hy\x1b[?1049lc"),
+        "abc"
+    );
+    // Still on the alternate screen: nothing is dropped yet
+    assert_eq!(
+        strip_alternate_screen("before\n\x1b[?1049hlive"),
+        "before\n\x1b[?1049hlive"
+    );
+    assert_eq!(strip_alternate_screen("plain"), "plain");
+}
+
+#[tokio::test]
+async fn test_only_alternate_screen_output_is_dropped() {
+    let output = streamed_output(&[
+        "building",
+        "\x1b[?1049h\x1b[Hprogress 50%",
+        "progress 100%\x1b[?1049l",
+        "done",
+    ])
+    .await;
+
+    assert_eq!(output, "building\ndone\nRunning...");
+}
+
+#[tokio::test]
+async fn test_alternate_screen_only_output_leaves_nothing() {
+    let output = streamed_output(&["\x1b[?1049h\x1b[Hfull screen", "\x1b[?1049l"]).await;
+
+    assert_eq!(output, "Running...");
+}
@@ -10,6 +10,7 @@ fn test_basic_ansi_color_parsing() {
         command: "ls --color=always".to_string(),
         output: colored_text.to_string(),
         success: true,
+        ..Default::default()
     };
 
     // This test just ensures the parsing doesn't crash
@@ -26,6 +27,7 @@ fn test_git_colored_output() {
         command: "git status --porcelain".to_string(),
         output: git_output.to_string(),
         success: true,
+        ..Default::default()
     };
 
     // Verify the entry contains ANSI codes
@@ -43,6 +45,7 @@ fn test_cargo_colored_output() {
         command: "cargo build".to_string(),
         output: cargo_output.to_string(),
         success: true,
+        ..Default::default()
     };
 
     // Verify the entry contains ANSI codes
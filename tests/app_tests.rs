@@ -1,9 +1,8 @@
-use std::collections::HashMap;
+use crossterm::event::{KeyCode, KeyModifiers};
 use taskhub::db::init_db;
 use taskhub::db::models::{Priority, Task, TaskSource, TaskStatus};
 use taskhub::tui::app::{App, AppMode};
 use taskhub::tui::views::terminal::CommandEntry;
-use uuid::Uuid;
 
 // Helper function to create a test app
 async fn create_test_app() -> App {
@@ -34,9 +33,38 @@ async fn test_app_initialization() {
         "/task",
         "/task add",
         "/task list",
+        "/task label add",
+        "/task label rm",
+        "/task notes",
+        "/task sub",
+        "/task agenda",
+        "/task delete",
+        "/task search",
+        "/done",
+        "/progress",
         "/help",
         "/help keys",
         "/clear",
+        "/stderr split",
+        "/stderr merge",
+        "/timestamps on",
+        "/timestamps off",
+        "/collapse on",
+        "/collapse off",
+        "/line-numbers on",
+        "/line-numbers off",
+        "/replay",
+        "/export-html",
+        "/history",
+        "/stats",
+        "/vacuum",
+        "/backup",
+        "/config",
+        "/reload-config",
+        "/open-config",
+        "/open-data",
+        "/in",
+        "/cd",
     ];
     assert_eq!(app.available_commands, expected_commands);
 }
@@ -54,19 +82,12 @@ async fn test_load_tasks() {
 
     // Add a task manually to database and reload
     let task = Task {
-        id: Uuid::new_v4(),
-        external_id: None,
-        source: TaskSource::Markdown,
-        title: "Test Task".to_string(),
         description: Some("Test Description".to_string()),
-        status: TaskStatus::Open,
         priority: Priority::High,
-        assignee: None,
         labels: vec!["test".to_string()],
-        due_date: None,
         created_at: "2025-01-01T00:00:00Z".to_string(),
         updated_at: "2025-01-01T00:00:00Z".to_string(),
-        custom_fields: HashMap::new(),
+        ..Task::new("Test Task")
     };
 
     taskhub::db::operations::create_task(&app.db_pool, &task)
@@ -124,6 +145,7 @@ async fn test_command_history() {
         command: "test".to_string(),
         output: "test output".to_string(),
         success: true,
+        ..Default::default()
     };
     app.command_history.push(manual_entry);
 
@@ -142,6 +164,7 @@ async fn test_get_total_history_lines() {
         command: "cmd1".to_string(),
         output: "single line".to_string(),
         success: true,
+        ..Default::default()
     };
     app.command_history.push(entry1);
 
@@ -153,6 +176,7 @@ async fn test_get_total_history_lines() {
         command: "cmd2".to_string(),
         output: "line1\nline2\nline3".to_string(),
         success: false,
+        ..Default::default()
     };
     app.command_history.push(entry2);
 
@@ -183,6 +207,94 @@ mod task_operations {
         assert_eq!(pending_task.source, TaskSource::Markdown);
     }
 
+    #[tokio::test]
+    async fn test_handle_task_add_command_with_due_date() {
+        let mut app = create_test_app().await;
+
+        app.handle_task_add_command("/task add Fix bug due:2025-06-01")
+            .await;
+        app.handle_pending_task_add().await;
+
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(app.tasks[0].title, "Fix bug");
+        assert_eq!(app.tasks[0].due_date.as_deref(), Some("2025-06-01"));
+
+        // Stored as given after reloading from the database
+        app.load_tasks().await.unwrap();
+        assert_eq!(app.tasks[0].title, "Fix bug");
+        assert_eq!(app.tasks[0].due_date.as_deref(), Some("2025-06-01"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_task_add_command_invalid_due_date() {
+        let mut app = create_test_app().await;
+
+        app.handle_task_add_command("/task add Fix bug due:2025-02-30")
+            .await;
+
+        assert!(app.pending_task_add.is_none());
+        let entry = app.command_history.last().unwrap();
+        assert_eq!(
+            entry.output,
+            "Invalid due date '2025-02-30': expected YYYY-MM-DD"
+        );
+        assert!(!entry.success);
+
+        // due: words anywhere in the title are dates
+        app.handle_task_add_command("/task add Ask about due:soon policy")
+            .await;
+        assert!(app.pending_task_add.is_none());
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Invalid due date 'soon': expected YYYY-MM-DD"
+        );
+
+        // A due date alone is not a title
+        app.handle_task_add_command("/task add due:2025-06-01")
+            .await;
+        assert!(app.pending_task_add.is_none());
+        assert!(!app.command_history.last().unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_handle_task_add_command_with_flags() {
+        let mut app = create_test_app().await;
+
+        app.handle_task_add_command(
+            "/task add Deploy priority:high the label:ops new service label:backend due:2025-06-01",
+        )
+        .await;
+
+        let pending_task = app.pending_task_add.as_ref().unwrap();
+        assert_eq!(pending_task.title, "Deploy the new service");
+        assert_eq!(pending_task.priority, Priority::High);
+        assert_eq!(pending_task.labels, vec!["ops", "backend"]);
+        assert_eq!(pending_task.due_date.as_deref(), Some("2025-06-01"));
+
+        app.handle_task_add_command("/task add Tidy up priority:LOW")
+            .await;
+        let pending_task = app.pending_task_add.as_ref().unwrap();
+        assert_eq!(pending_task.title, "Tidy up");
+        assert_eq!(pending_task.priority, Priority::Low);
+        assert!(pending_task.labels.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_task_add_command_unknown_priority() {
+        let mut app = create_test_app().await;
+
+        app.handle_task_add_command("/task add Deploy priority:urgent")
+            .await;
+
+        assert!(app.pending_task_add.is_none());
+        let entry = app.command_history.last().unwrap();
+        assert_eq!(
+            entry.output,
+            "Unknown priority 'urgent': expected high, medium or low"
+        );
+        assert!(!entry.success);
+    }
+
     #[tokio::test]
     async fn test_handle_task_add_command_invalid() {
         let mut app = create_test_app().await;
@@ -207,19 +319,9 @@ mod task_operations {
 
         // Create a pending task
         let task = Task {
-            id: Uuid::new_v4(),
-            external_id: None,
-            source: TaskSource::Markdown,
-            title: "Pending Task".to_string(),
-            description: None,
-            status: TaskStatus::Open,
-            priority: Priority::Medium,
-            assignee: None,
-            labels: Vec::new(),
-            due_date: None,
             created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             updated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            custom_fields: HashMap::new(),
+            ..Task::new("Pending Task")
         };
 
         app.pending_task_add = Some(task);
@@ -241,3 +343,64 @@ mod task_operations {
         assert_eq!(app.tasks[0].title, "Pending Task");
     }
 }
+
+#[tokio::test]
+async fn test_task_pagination() {
+    let mut app = create_test_app().await.with_task_page_size(2);
+    app.mode = AppMode::TaskList;
+
+    for i in 0..3 {
+        let task = Task {
+            created_at: format!("2025-01-01T00:00:0{i}Z"),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            ..Task::new(format!("Task {i}"))
+        };
+        taskhub::db::operations::create_task(&app.db_pool, &task)
+            .await
+            .unwrap();
+    }
+
+    // Only one page is held in memory, but the total covers every task
+    app.load_tasks().await.unwrap();
+    assert_eq!(app.tasks.len(), 2);
+    assert_eq!(app.task_page.index, 0);
+    assert_eq!(app.task_page.total, 3);
+    assert_eq!(app.task_page.page_count(), 2);
+
+    app.on_key_code(KeyCode::PageDown, KeyModifiers::NONE);
+    app.handle_pending_commands().await;
+    assert_eq!(app.task_page.index, 1);
+    assert_eq!(app.tasks.len(), 1);
+    assert_eq!(app.tasks[0].title, "Task 0");
+
+    // A partial page is the last one
+    app.on_key_code(KeyCode::PageDown, KeyModifiers::NONE);
+    app.handle_pending_commands().await;
+    assert_eq!(app.task_page.index, 1);
+
+    app.on_key_code(KeyCode::PageUp, KeyModifiers::NONE);
+    app.handle_pending_commands().await;
+    assert_eq!(app.task_page.index, 0);
+    assert_eq!(app.tasks[0].title, "Task 2");
+}
+
+#[tokio::test]
+async fn test_task_pagination_stops_after_full_last_page() {
+    let mut app = create_test_app().await.with_task_page_size(1);
+    let task = Task {
+        created_at: "2025-01-01T00:00:00Z".to_string(),
+        updated_at: "2025-01-01T00:00:00Z".to_string(),
+        ..Task::new("Only Task")
+    };
+    taskhub::db::operations::create_task(&app.db_pool, &task)
+        .await
+        .unwrap();
+    app.load_tasks().await.unwrap();
+
+    app.next_task_page();
+    app.handle_pending_task_page().await.unwrap();
+
+    // The next page is empty, so the full page stays loaded
+    assert_eq!(app.task_page.index, 0);
+    assert_eq!(app.tasks.len(), 1);
+}
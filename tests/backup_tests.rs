@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use taskhub::db::backup::{self, ImportReport, TaskBackup};
+use taskhub::db::models::{Priority, Task, TaskSource, TaskStatus};
+use taskhub::db::{init_db, operations};
+use taskhub::tui::app::App;
+use uuid::Uuid;
+
+async fn create_test_pool() -> sqlx::SqlitePool {
+    init_db(Some(":memory:".into())).await.unwrap()
+}
+
+fn create_test_task(title: &str) -> Task {
+    let mut custom_fields = HashMap::new();
+    custom_fields.insert("epic".to_string(), "user-auth".to_string());
+    custom_fields.insert("story_points".to_string(), "5".to_string());
+
+    Task {
+        external_id: Some("EXT-42".to_string()),
+        source: TaskSource::Jira,
+        description: Some("Carried across machines".to_string()),
+        status: TaskStatus::InProgress,
+        priority: Priority::Low,
+        assignee: Some("alex".to_string()),
+        labels: vec!["backend".to_string(), "migration".to_string()],
+        due_date: Some("2025-06-30".to_string()),
+        created_at: "2025-01-01T00:00:00Z".to_string(),
+        updated_at: "2025-01-02T00:00:00Z".to_string(),
+        custom_fields,
+        ..Task::new(title)
+    }
+}
+
+fn assert_same_task(actual: &Task, expected: &Task) {
+    assert_eq!(actual.id, expected.id);
+    assert_eq!(actual.external_id, expected.external_id);
+    assert_eq!(actual.source, expected.source);
+    assert_eq!(actual.title, expected.title);
+    assert_eq!(actual.description, expected.description);
+    assert_eq!(actual.status, expected.status);
+    assert_eq!(actual.priority, expected.priority);
+    assert_eq!(actual.assignee, expected.assignee);
+    assert_eq!(actual.labels, expected.labels);
+    assert_eq!(actual.due_date, expected.due_date);
+    assert_eq!(actual.created_at, expected.created_at);
+    assert_eq!(actual.updated_at, expected.updated_at);
+    assert_eq!(actual.custom_fields, expected.custom_fields);
+}
+
+#[tokio::test]
+async fn test_json_round_trip_preserves_all_fields() {
+    let source_pool = create_test_pool().await;
+    let task = create_test_task("Move to the new laptop");
+    operations::create_task(&source_pool, &task).await.unwrap();
+
+    let backup = backup::export_tasks(&source_pool).await.unwrap();
+    let json = serde_json::to_string(&backup).unwrap();
+    let restored: TaskBackup = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.version, backup::BACKUP_VERSION);
+
+    let target_pool = create_test_pool().await;
+    let report = backup::import_tasks(&target_pool, &restored).await.unwrap();
+    assert_eq!(
+        report,
+        ImportReport {
+            added: 1,
+            updated: 0,
+            unchanged: 0
+        }
+    );
+
+    let imported = operations::get_task(&target_pool, task.id).await.unwrap();
+    assert_same_task(&imported, &task);
+}
+
+#[tokio::test]
+async fn test_import_merges_by_updated_at() {
+    let pool = create_test_pool().await;
+    let stale = create_test_task("Stale in database");
+    let current = create_test_task("Current in database");
+    operations::create_task(&pool, &stale).await.unwrap();
+    operations::create_task(&pool, &current).await.unwrap();
+
+    let mut newer = create_test_task("Newer in backup");
+    newer.id = stale.id;
+    newer.updated_at = "2025-03-01T00:00:00Z".to_string();
+    let mut older = create_test_task("Older in backup");
+    older.id = current.id;
+    older.updated_at = "2024-12-01T00:00:00Z".to_string();
+    let added = create_test_task("Only in backup");
+
+    let backup = TaskBackup {
+        version: backup::BACKUP_VERSION,
+        exported_at: "2025-03-02T00:00:00Z".to_string(),
+        tasks: vec![newer, older, added],
+    };
+    let report = backup::import_tasks(&pool, &backup).await.unwrap();
+
+    assert_eq!(
+        report,
+        ImportReport {
+            added: 1,
+            updated: 1,
+            unchanged: 1
+        }
+    );
+    let titles: Vec<String> = operations::list_tasks(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| task.title)
+        .collect();
+    assert_eq!(titles.len(), 3);
+    assert!(titles.contains(&"Newer in backup".to_string()));
+    assert!(titles.contains(&"Current in database".to_string()));
+    assert!(titles.contains(&"Only in backup".to_string()));
+}
+
+#[tokio::test]
+async fn test_backup_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("taskhub_backup_{}.json", Uuid::new_v4()));
+    let source_pool = create_test_pool().await;
+    let task = create_test_task("Backed up to disk");
+    operations::create_task(&source_pool, &task).await.unwrap();
+
+    let count = backup::backup_to_file(&source_pool, &path).await.unwrap();
+    assert_eq!(count, 1);
+
+    let target_pool = create_test_pool().await;
+    let report = backup::import_from_file(&target_pool, &path).await.unwrap();
+    assert_eq!(report.added, 1);
+    assert_same_task(
+        &operations::get_task(&target_pool, task.id).await.unwrap(),
+        &task,
+    );
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_backup_command_writes_file() {
+    let path = std::env::temp_dir().join(format!("taskhub_backup_{}.json", Uuid::new_v4()));
+    let pool = create_test_pool().await;
+    operations::create_task(&pool, &create_test_task("From the TUI"))
+        .await
+        .unwrap();
+    let mut app = App::new(pool);
+
+    let command = format!("/backup {}", path.display());
+    assert!(app.handle_builtin_command(&command).await);
+
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+    assert!(entry.output.starts_with("Backed up 1 tasks"));
+    assert!(path.exists());
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_backup_command_requires_path() {
+    let mut app = App::new(create_test_pool().await);
+
+    assert!(app.handle_builtin_command("/backup").await);
+
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Usage: /backup <path>");
+}
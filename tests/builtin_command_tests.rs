@@ -21,6 +21,19 @@ mod builtin_commands {
         assert!(app.should_quit);
     }
 
+    #[tokio::test]
+    async fn test_handle_builtin_command_vacuum() {
+        let mut app = create_test_app().await;
+
+        let result = app.handle_builtin_command("/vacuum").await;
+
+        assert!(result);
+        let entry = app.command_history.last().unwrap();
+        assert_eq!(entry.command, "/vacuum");
+        assert!(entry.success);
+        assert!(entry.output.starts_with("Database size: "));
+    }
+
     #[tokio::test]
     async fn test_handle_builtin_command_task() {
         let mut app = create_test_app().await;
@@ -241,6 +254,55 @@ mod pending_commands {
                 || entry.output == "(no output)"
         ); // Depends on shell
     }
+
+    #[tokio::test]
+    async fn test_mistyped_builtin_suggests_closest() {
+        let mut app = create_test_app().await;
+
+        for (typo, suggestion) in [
+            ("/tsak", "/task"),
+            ("/hlep", "/help"),
+            ("/qiut", "/quit"),
+            ("/clera", "/clear"),
+            ("/dnoe", "/done"),
+            ("/conifg", "/config"),
+        ] {
+            app.pending_command = Some(format!("{typo} some args"));
+            app.handle_pending_commands().await;
+
+            assert!(app.running_command.is_none(), "{typo} was run");
+            let entry = app.command_history.last().unwrap();
+            assert_eq!(entry.command, format!("{typo} some args"));
+            assert_eq!(
+                entry.output,
+                format!("Unknown command {typo}. Did you mean {suggestion}?")
+            );
+            assert!(!entry.success);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_builtin_without_close_match() {
+        let mut app = create_test_app().await;
+
+        app.pending_command = Some("/frobnicate".to_string());
+        app.handle_pending_commands().await;
+
+        assert!(app.running_command.is_none());
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Unknown command /frobnicate. Type /help for a list of commands"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_absolute_program_path_still_runs() {
+        let app = create_test_app().await;
+
+        assert_eq!(app.unknown_builtin_message("/bin/sh -c true"), None);
+        assert_eq!(app.unknown_builtin_message("/task list"), None);
+        assert_eq!(app.unknown_builtin_message("echo /tsak"), None);
+    }
 }
 
 #[cfg(test)]
@@ -280,6 +342,7 @@ mod command_execution {
                 command: format!("echo {i}"),
                 output: format!("{i}"),
                 success: true,
+                ..Default::default()
             };
             app.command_history.push(entry);
         }
@@ -309,3 +372,428 @@ mod command_execution {
         assert_eq!(app.scroll_offset, 0);
     }
 }
+
+#[cfg(test)]
+mod task_labels {
+    use super::*;
+    use taskhub::db::models::Task;
+    use taskhub::db::operations;
+    use uuid::Uuid;
+
+    async fn create_app_with_task(labels: &[&str]) -> (App, Uuid) {
+        let app = create_test_app().await;
+        let task = Task {
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            created_at: "2025-01-01 00:00:00".to_string(),
+            updated_at: "2025-01-01 00:00:00".to_string(),
+            ..Task::new("Label me")
+        };
+        operations::create_task(&app.db_pool, &task).await.unwrap();
+        (app, task.id)
+    }
+
+    async fn stored_labels(app: &App, id: Uuid) -> Vec<String> {
+        operations::get_task(&app.db_pool, id).await.unwrap().labels
+    }
+
+    #[tokio::test]
+    async fn test_task_label_add() {
+        let (mut app, id) = create_app_with_task(&["bug"]).await;
+
+        assert!(
+            app.handle_builtin_command(&format!("/task label add {id} urgent"))
+                .await
+        );
+
+        assert!(app.command_history.last().unwrap().success);
+        assert_eq!(stored_labels(&app, id).await, vec!["bug", "urgent"]);
+        assert_eq!(app.tasks[0].labels, vec!["bug", "urgent"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_label_add_existing_is_noop() {
+        let (mut app, id) = create_app_with_task(&["bug"]).await;
+
+        app.handle_builtin_command(&format!("/task label add {id} bug"))
+            .await;
+
+        let entry = app.command_history.last().unwrap();
+        assert!(!entry.success);
+        assert!(entry.output.contains("already has label 'bug'"));
+        assert_eq!(stored_labels(&app, id).await, vec!["bug"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_label_rm() {
+        let (mut app, id) = create_app_with_task(&["bug", "urgent"]).await;
+
+        app.handle_builtin_command(&format!("/task label rm {id} bug"))
+            .await;
+
+        assert!(app.command_history.last().unwrap().success);
+        assert_eq!(stored_labels(&app, id).await, vec!["urgent"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_label_rm_missing_is_noop() {
+        let (mut app, id) = create_app_with_task(&["bug"]).await;
+
+        app.handle_builtin_command(&format!("/task label rm {id} urgent"))
+            .await;
+
+        let entry = app.command_history.last().unwrap();
+        assert!(!entry.success);
+        assert!(entry.output.contains("has no label 'urgent'"));
+        assert_eq!(stored_labels(&app, id).await, vec!["bug"]);
+    }
+
+    #[tokio::test]
+    async fn test_task_label_rejects_bad_input() {
+        let mut app = create_test_app().await;
+
+        app.handle_builtin_command("/task label add").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Usage: /task label add|rm <id-prefix> <label>"
+        );
+
+        app.handle_builtin_command("/task label add not-a-uuid bug")
+            .await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Invalid task id 'not-a-uuid'"
+        );
+
+        let missing = Uuid::new_v4();
+        app.handle_builtin_command(&format!("/task label add {missing} bug"))
+            .await;
+        let entry = app.command_history.last().unwrap();
+        assert!(!entry.success);
+        assert_eq!(entry.output, format!("No task id starts with '{missing}'"));
+    }
+
+    #[tokio::test]
+    async fn test_task_label_accepts_id_prefix() {
+        let (mut app, id) = create_app_with_task(&["bug"]).await;
+        let prefix = &id.to_string()[..8];
+
+        app.handle_builtin_command(&format!("/task label add {prefix} urgent"))
+            .await;
+
+        assert!(app.command_history.last().unwrap().success);
+        assert_eq!(stored_labels(&app, id).await, vec!["bug", "urgent"]);
+    }
+}
+
+#[cfg(test)]
+mod task_notes {
+    use super::*;
+    use taskhub::db::models::{NOTES_FIELD, Task};
+    use taskhub::db::operations;
+    use uuid::Uuid;
+
+    async fn create_app_with_task() -> (App, Task) {
+        let app = create_test_app().await;
+        let task = Task {
+            created_at: "2025-01-01 00:00:00".to_string(),
+            updated_at: "2025-01-01 00:00:00".to_string(),
+            ..Task::new("Write notes")
+        };
+        operations::create_task(&app.db_pool, &task).await.unwrap();
+        (app, task)
+    }
+
+    #[tokio::test]
+    async fn test_blank_notes_remove_field() {
+        let (_, mut task) = create_app_with_task().await;
+        assert_eq!(task.notes(), "");
+
+        task.set_notes("first line\nsecond line");
+        assert_eq!(task.notes(), "first line\nsecond line");
+
+        task.set_notes("  \n");
+        assert!(!task.custom_fields.contains_key(NOTES_FIELD));
+    }
+
+    #[tokio::test]
+    async fn test_task_notes_queues_editor() {
+        let (mut app, task) = create_app_with_task().await;
+
+        assert!(
+            app.handle_builtin_command(&format!("/task notes {}", task.id))
+                .await
+        );
+
+        assert_eq!(app.pending_note_edit.map(|t| t.id), Some(task.id));
+    }
+
+    #[tokio::test]
+    async fn test_task_notes_rejects_bad_ids() {
+        let mut app = create_test_app().await;
+
+        app.handle_builtin_command("/task notes").await;
+        assert!(
+            app.command_history
+                .last()
+                .unwrap()
+                .output
+                .starts_with("Usage")
+        );
+
+        app.handle_builtin_command("/task notes nope").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Invalid task id 'nope'"
+        );
+
+        let id = Uuid::new_v4();
+        app.handle_builtin_command(&format!("/task notes {id}"))
+            .await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            format!("No task id starts with '{id}'")
+        );
+        assert!(app.pending_note_edit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_task_notes_accepts_id_prefix() {
+        let (mut app, task) = create_app_with_task().await;
+
+        app.handle_builtin_command(&format!("/task notes {}", &task.id.to_string()[..8]))
+            .await;
+
+        assert_eq!(app.pending_note_edit.map(|t| t.id), Some(task.id));
+    }
+
+    #[tokio::test]
+    async fn test_finish_note_edit_persists_notes() {
+        let (mut app, task) = create_app_with_task().await;
+        let id = task.id;
+
+        app.finish_note_edit(task, Ok(Some("- check logs".to_string())))
+            .await;
+
+        let stored = operations::get_task(&app.db_pool, id).await.unwrap();
+        assert_eq!(stored.notes(), "- check logs");
+        assert!(app.command_history.last().unwrap().success);
+
+        // A cancelled edit leaves the stored notes alone
+        app.finish_note_edit(stored, Ok(None)).await;
+        let stored = operations::get_task(&app.db_pool, id).await.unwrap();
+        assert_eq!(stored.notes(), "- check logs");
+    }
+}
+
+#[cfg(test)]
+mod task_subtasks {
+    use super::*;
+    use taskhub::db::operations;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_task_sub_adds_child_of_parent() {
+        let mut app = create_test_app().await;
+        app.handle_builtin_command("/task add Ship release").await;
+        app.handle_pending_task_add().await;
+        let parent_id = app.tasks[0].id;
+
+        assert!(
+            app.handle_builtin_command(&format!("/task sub {parent_id} Write changelog"))
+                .await
+        );
+
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success, "{}", entry.output);
+        let children = operations::list_subtasks(&app.db_pool, parent_id)
+            .await
+            .unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].title, "Write changelog");
+        assert_eq!(app.mode, AppMode::TaskList);
+    }
+
+    #[tokio::test]
+    async fn test_task_sub_rejects_bad_input() {
+        let mut app = create_test_app().await;
+
+        app.handle_builtin_command("/task sub").await;
+        assert!(
+            app.command_history
+                .last()
+                .unwrap()
+                .output
+                .starts_with("Usage")
+        );
+
+        app.handle_builtin_command("/task sub nope Title").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Invalid task id 'nope'"
+        );
+
+        let id = Uuid::new_v4();
+        app.handle_builtin_command(&format!("/task sub {id} Title"))
+            .await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            format!("No task id starts with '{id}'")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_sub_accepts_parent_id_prefix() {
+        let mut app = create_test_app().await;
+        app.handle_builtin_command("/task add Ship release").await;
+        app.handle_pending_task_add().await;
+        let parent_id = app.tasks[0].id;
+        let prefix = &parent_id.to_string()[..8];
+
+        app.handle_builtin_command(&format!("/task sub {prefix} Tag it"))
+            .await;
+
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success, "{}", entry.output);
+        let children = operations::list_subtasks(&app.db_pool, parent_id)
+            .await
+            .unwrap();
+        assert_eq!(children.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod task_delete {
+    use super::*;
+    use taskhub::db::models::{Task, TaskStatus};
+    use taskhub::db::operations;
+    use uuid::Uuid;
+
+    async fn create_app_with_tasks(ids: &[&str]) -> App {
+        let app = create_test_app().await;
+        for (i, id) in ids.iter().enumerate() {
+            let task = Task {
+                id: Uuid::parse_str(id).unwrap(),
+                created_at: "2025-01-01 00:00:00".to_string(),
+                updated_at: "2025-01-01 00:00:00".to_string(),
+                ..Task::new(format!("Task {i}"))
+            };
+            operations::create_task(&app.db_pool, &task).await.unwrap();
+        }
+        app
+    }
+
+    const FIRST: &str = "1234abcd-0000-4000-8000-000000000001";
+    const SECOND: &str = "1234abce-0000-4000-8000-000000000002";
+
+    #[tokio::test]
+    async fn test_task_delete_by_unique_prefix() {
+        let mut app = create_app_with_tasks(&[FIRST, SECOND]).await;
+        app.load_tasks().await.unwrap();
+        assert_eq!(app.tasks.len(), 2);
+
+        assert!(app.handle_builtin_command("/task delete 1234ABCD").await);
+        // Nothing is deleted until the user confirms
+        assert_eq!(app.tasks.len(), 2);
+        app.answer_confirmation(true);
+        app.handle_pending_commands().await;
+
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success, "{}", entry.output);
+        assert_eq!(entry.output, format!("Deleted task 'Task 0' ({FIRST})"));
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(app.tasks[0].id.to_string(), SECOND);
+    }
+
+    #[tokio::test]
+    async fn test_task_delete_reports_ambiguous_prefix() {
+        let mut app = create_app_with_tasks(&[FIRST, SECOND]).await;
+
+        app.handle_builtin_command("/task delete 1234abc").await;
+
+        let entry = app.command_history.last().unwrap();
+        assert!(!entry.success);
+        assert!(
+            entry
+                .output
+                .starts_with("Task id prefix '1234abc' matches 2 tasks")
+        );
+        assert!(entry.output.contains(FIRST) && entry.output.contains(SECOND));
+        let remaining = operations::list_tasks(&app.db_pool).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_task_delete_rejects_missing_and_invalid_ids() {
+        let mut app = create_app_with_tasks(&[FIRST]).await;
+
+        app.handle_builtin_command("/task delete").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Usage: /task delete <id-prefix>"
+        );
+
+        app.handle_builtin_command("/task delete ffff").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "No task id starts with 'ffff'"
+        );
+
+        app.handle_builtin_command("/task delete 12%").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Invalid task id '12%'"
+        );
+        assert_eq!(operations::list_tasks(&app.db_pool).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_task_search_lists_matches() {
+        let mut app = create_app_with_tasks(&[FIRST, SECOND]).await;
+
+        assert!(app.handle_builtin_command("/task search task 1").await);
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success);
+        assert_eq!(
+            entry.output,
+            format!("1 task(s) match 'task 1':\n  {SECOND}  [Open] Task 1")
+        );
+
+        app.handle_builtin_command("/task search 50%").await;
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success);
+        assert_eq!(entry.output, "No tasks match '50%'");
+
+        app.handle_builtin_command("/task search").await;
+        let entry = app.command_history.last().unwrap();
+        assert!(!entry.success);
+        assert_eq!(entry.output, "Usage: /task search <query>");
+    }
+
+    #[tokio::test]
+    async fn test_done_and_progress_set_task_status() {
+        let mut app = create_app_with_tasks(&[FIRST]).await;
+
+        assert!(app.handle_builtin_command("/progress 1234abcd").await);
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success, "{}", entry.output);
+        assert_eq!(app.tasks[0].status, TaskStatus::InProgress);
+
+        assert!(app.handle_builtin_command("/done 1234abcd").await);
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Task 'Task 0' is now Done"
+        );
+        let task = operations::get_task(&app.db_pool, Uuid::parse_str(FIRST).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Done);
+
+        app.handle_builtin_command("/done").await;
+        assert_eq!(
+            app.command_history.last().unwrap().output,
+            "Usage: /done <id-prefix>..."
+        );
+        app.handle_builtin_command("/progress ffff").await;
+        assert!(!app.command_history.last().unwrap().success);
+    }
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, cd_argument};
+use uuid::Uuid;
+
+async fn run(app: &mut App, command: &str) -> String {
+    app.pending_command = Some(command.to_string());
+    app.handle_pending_commands().await;
+
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, command);
+    entry.output.trim_end().to_string()
+}
+
+#[test]
+fn test_cd_argument() {
+    assert_eq!(cd_argument("cd /tmp"), Some("/tmp"));
+    assert_eq!(cd_argument("  cd   src  "), Some("src"));
+    assert_eq!(cd_argument("cd"), Some(""));
+    assert_eq!(cd_argument("cd -"), Some("-"));
+    assert_eq!(cd_argument("cd ~/work"), Some("~/work"));
+    assert_eq!(cd_argument("cd 'My Projects'"), Some("My Projects"));
+    assert_eq!(cd_argument("cd \"My Projects\""), Some("My Projects"));
+
+    // Left to the shell
+    assert_eq!(cd_argument("cd src && make"), None);
+    assert_eq!(cd_argument("cd $HOME"), None);
+    assert_eq!(cd_argument("cd \"$HOME/src\""), None);
+    assert_eq!(cd_argument("cd a b"), None);
+    assert_eq!(cd_argument("cd My\\ Projects"), None);
+    assert_eq!(cd_argument("cdrecord"), None);
+    assert_eq!(cd_argument("echo cd"), None);
+}
+
+// The working directory is process-wide, so the steps run in one test
+#[tokio::test]
+async fn test_cd_persists_between_commands() {
+    let original = std::env::current_dir().unwrap();
+    let tmp = std::env::temp_dir().canonicalize().unwrap();
+    let scratch = tmp.join(format!("taskhub-cd-{}", Uuid::new_v4()));
+    fs::create_dir_all(scratch.join("sub dir")).unwrap();
+
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    let tmp_str = tmp.to_string_lossy().into_owned();
+    assert_eq!(run(&mut app, &format!("cd {tmp_str}")).await, tmp_str);
+    assert_eq!(app.current_working_dir, tmp);
+    assert_eq!(run(&mut app, "pwd").await, tmp_str);
+
+    // Relative to the tracked directory, with quotes
+    let name = scratch.file_name().unwrap().to_string_lossy().into_owned();
+    run(&mut app, &format!("cd '{name}/sub dir'")).await;
+    assert_eq!(
+        run(&mut app, "pwd").await,
+        scratch.join("sub dir").to_string_lossy()
+    );
+
+    // Back to the previous directory
+    assert_eq!(run(&mut app, "cd -").await, tmp_str);
+    assert_eq!(run(&mut app, "pwd").await, tmp_str);
+
+    // A missing directory is an error and changes nothing
+    let output = run(&mut app, "cd no-such-dir-for-taskhub").await;
+    assert_eq!(output, "Not a directory: no-such-dir-for-taskhub");
+    assert!(!app.command_history.last().unwrap().success);
+    assert_eq!(app.current_working_dir, tmp);
+
+    // No argument goes home
+    if let Some(home) = dirs::home_dir() {
+        run(&mut app, "cd").await;
+        assert_eq!(app.current_working_dir, home);
+    }
+
+    // /cd shares the same history of directories
+    run(&mut app, &format!("/cd {}", scratch.display())).await;
+    assert_eq!(app.current_working_dir, scratch);
+
+    std::env::set_current_dir(&original).unwrap();
+    fs::remove_dir_all(&scratch).unwrap();
+}
@@ -13,11 +13,13 @@ async fn test_clear_command_user_experience() {
         command: "ls".to_string(),
         output: "file1.txt\nfile2.txt\nfile3.txt".to_string(),
         success: true,
+        ..Default::default()
     });
     app.command_history.push(CommandEntry {
         command: "echo hello world".to_string(),
         output: "hello world".to_string(),
         success: true,
+        ..Default::default()
     });
 
     // Simulate typing "/clear" and pressing Enter
@@ -61,6 +63,7 @@ async fn test_ctrl_l_user_experience() {
         command: "pwd".to_string(),
         output: "/home/user".to_string(),
         success: true,
+        ..Default::default()
     });
 
     // Type some input
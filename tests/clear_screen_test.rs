@@ -13,11 +13,13 @@ async fn test_clear_screen_functionality() {
         command: "ls".to_string(),
         output: "file1.txt\nfile2.txt".to_string(),
         success: true,
+        ..Default::default()
     });
     app.command_history.push(CommandEntry {
         command: "echo hello".to_string(),
         output: "hello".to_string(),
         success: true,
+        ..Default::default()
     });
 
     // Set some state that should be cleared
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, CLIPBOARD_READ_TIMEOUT, read_clipboard_with_timeout};
+
+async fn create_test_app(read: impl Fn() -> Result<String, String> + Send + Sync + 'static) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool).with_clipboard_reader(Arc::new(read))
+}
+
+#[test]
+fn test_slow_clipboard_read_times_out() {
+    let started = Instant::now();
+
+    let result = read_clipboard_with_timeout(
+        || {
+            std::thread::sleep(Duration::from_secs(5));
+            Ok("late".to_string())
+        },
+        Duration::from_millis(50),
+    );
+
+    assert_eq!(result.unwrap_err(), "Timed out reading the clipboard");
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn test_paste_inserts_text_at_cursor() {
+    let mut app = create_test_app(|| Ok("-la ".to_string())).await;
+    app.current_input = "ls /tmp".to_string();
+    app.cursor_position = 3;
+
+    app.paste_from_clipboard().unwrap();
+
+    assert_eq!(app.current_input, "ls -la /tmp");
+    assert_eq!(app.cursor_position, 7);
+}
+
+#[tokio::test]
+async fn test_stuck_clipboard_does_not_block_paste() {
+    let mut app = create_test_app(|| {
+        std::thread::sleep(Duration::from_secs(10));
+        Ok("never".to_string())
+    })
+    .await;
+    app.current_input = "echo".to_string();
+    let started = Instant::now();
+
+    app.paste_and_report().await;
+
+    assert!(started.elapsed() < CLIPBOARD_READ_TIMEOUT + Duration::from_secs(2));
+    assert_eq!(app.current_input, "echo");
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Timed out reading the clipboard");
+}
+
+#[tokio::test]
+async fn test_failing_clipboard_read_is_reported() {
+    let mut app = create_test_app(|| Err("Failed to get clipboard contents".to_string())).await;
+
+    assert!(app.paste_from_clipboard().is_err());
+    app.paste_and_report().await;
+
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "Failed to get clipboard contents"
+    );
+}
+
+#[tokio::test]
+async fn test_paste_keeps_newlines_only_in_multiline_input() {
+    let mut app = create_test_app(|| Ok("a\nb".to_string())).await;
+    app.paste_from_clipboard().unwrap();
+    assert_eq!(app.current_input, "ab");
+
+    let mut app = create_test_app(|| Ok("a\nb".to_string()))
+        .await
+        .with_multiline_input(true);
+    app.paste_from_clipboard().unwrap();
+    assert_eq!(app.current_input, "a\nb");
+    assert_eq!(app.current_input.lines().count(), 2);
+    assert_eq!(app.cursor_position, 3);
+
+    // Other control characters are still dropped
+    let mut app = create_test_app(|| Ok("a\r\nb".to_string()))
+        .await
+        .with_multiline_input(true);
+    app.paste_from_clipboard().unwrap();
+    assert_eq!(app.current_input, "a\nb");
+}
@@ -0,0 +1,90 @@
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::{CommandEntry, collapse_repeats, display_output_lines};
+
+#[test]
+fn test_collapse_runs_of_identical_lines() {
+    let lines = [
+        "retrying",
+        "retrying",
+        "retrying",
+        "connected",
+        "ok",
+        "ok",
+        "retrying",
+    ];
+    assert_eq!(
+        collapse_repeats(&lines),
+        vec![(0, 3), (3, 1), (4, 2), (6, 1)]
+    );
+
+    assert!(collapse_repeats(&[]).is_empty());
+    assert_eq!(
+        collapse_repeats(&["a", "b", "c"]),
+        vec![(0, 1), (1, 1), (2, 1)]
+    );
+    // Lines differing only in whitespace are different lines
+    assert_eq!(
+        collapse_repeats(&["x", "x ", "x"]),
+        vec![(0, 1), (1, 1), (2, 1)]
+    );
+}
+
+#[test]
+fn test_display_output_lines() {
+    let output = "waiting\nwaiting\nwaiting\ndone\n\n";
+    let collapsed: Vec<(usize, String)> = display_output_lines(output, true)
+        .into_iter()
+        .map(|(index, line)| (index, line.into_owned()))
+        .collect();
+    assert_eq!(
+        collapsed,
+        vec![
+            (0, "waiting (x3)".to_string()),
+            (3, "done".to_string()),
+            (4, String::new()),
+        ]
+    );
+
+    // Without collapsing every line is shown
+    assert_eq!(display_output_lines(output, false).len(), 5);
+}
+
+#[tokio::test]
+async fn test_collapse_command_changes_history_layout() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(CommandEntry {
+        command: "poll".to_string(),
+        output: "try\ntry\ntry\ntry\nok".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    // Command line, five output lines and the spacer
+    assert_eq!(app.get_total_history_lines(), 7);
+
+    assert!(app.handle_builtin_command("/collapse on").await);
+    assert!(app.collapse_repeats);
+    assert!(app.command_history.last().unwrap().success);
+    // "poll" now takes two output lines; the /collapse entry adds three more
+    assert_eq!(app.get_total_history_lines(), 4 + 3);
+
+    // Search sees the lines as displayed
+    app.start_output_search();
+    for ch in "(x4)".chars() {
+        app.handle_terminal_input(ch);
+    }
+    assert_eq!(app.get_output_search_matches(), &[(1, 4, 8)]);
+    app.cancel_output_search();
+
+    app.handle_builtin_command("/collapse off").await;
+    assert!(!app.collapse_repeats);
+
+    app.handle_builtin_command("/collapse sometimes").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(
+        entry.output,
+        "Unknown collapse setting 'sometimes'\nUsage: /collapse on|off"
+    );
+}
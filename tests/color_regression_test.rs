@@ -1,3 +1,4 @@
+use ratatui::style::Color;
 use std::time::Duration;
 use taskhub::db::init_db;
 use taskhub::tui::app::App;
@@ -40,16 +41,19 @@ async fn test_ansi_parser_preserves_colors_in_complex_processing() {
         "Complex printf command should succeed"
     );
 
-    // The output should contain color codes (red and green)
-    // Even though this goes through complex ANSI processing, colors should be preserved
-    let has_red_color =
-        command_entry.output.contains("\x1b[31m") || command_entry.output.contains("\\x1b[31m");
-    let has_green_color =
-        command_entry.output.contains("\x1b[32m") || command_entry.output.contains("\\x1b[32m");
+    // Even though this goes through complex ANSI processing, the colors are kept
+    // in the styled lines laid out by the terminal emulator
+    let styled = command_entry
+        .styled_output
+        .as_ref()
+        .expect("Complex ANSI processing should keep styled lines");
+    let has_green_color = styled
+        .iter()
+        .flat_map(|line| &line.spans)
+        .any(|span| span.content.contains("green") && span.style.fg == Some(Color::Green));
 
     assert!(
-        has_red_color || has_green_color,
-        "Complex ANSI processing should preserve color codes in output: '{}'",
-        command_entry.output.replace('\x1b', "\\x1b")
+        has_green_color,
+        "Complex ANSI processing should preserve colors in output: {styled:?}"
     );
 }
@@ -19,7 +19,26 @@ mod command_filtering {
         let filtered = app.get_filtered_commands();
 
         // Should exclude "/task add", "/task list", and "/help keys" (they contain spaces)
-        let expected = vec!["/quit", "/task", "/help", "/clear"];
+        let expected = vec![
+            "/quit",
+            "/task",
+            "/done",
+            "/progress",
+            "/help",
+            "/clear",
+            "/replay",
+            "/export-html",
+            "/history",
+            "/stats",
+            "/vacuum",
+            "/backup",
+            "/config",
+            "/reload-config",
+            "/open-config",
+            "/open-data",
+            "/in",
+            "/cd",
+        ];
         assert_eq!(filtered, expected);
     }
 
@@ -33,7 +52,18 @@ mod command_filtering {
         let filtered = app.get_filtered_commands();
 
         // Should include all task-related commands
-        let expected = vec!["/task", "/task add", "/task list"];
+        let expected = vec![
+            "/task",
+            "/task add",
+            "/task list",
+            "/task label add",
+            "/task label rm",
+            "/task notes",
+            "/task sub",
+            "/task agenda",
+            "/task delete",
+            "/task search",
+        ];
         assert_eq!(filtered, expected);
     }
 
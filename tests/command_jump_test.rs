@@ -0,0 +1,114 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
+
+fn entry(command: &str, output_lines: usize) -> CommandEntry {
+    CommandEntry {
+        command: command.to_string(),
+        output: (0..output_lines)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    }
+}
+
+/// App in a 24-row terminal, so the history area shows 19 lines. The commands
+/// start on lines 0, 32 and 36 of 58: the first one's output is longer than the
+/// visible area.
+async fn create_app_with_commands() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(entry("long", 30));
+    app.command_history.push(entry("short", 2));
+    app.command_history.push(entry("last", 20));
+    app.update_layout_areas(24, false, 0);
+    assert_eq!(app.get_total_history_lines(), 58);
+    app
+}
+
+/// First visible line for the current scroll offset
+fn top_line(app: &App) -> usize {
+    58 - 19 - app.scroll_offset
+}
+
+#[tokio::test]
+async fn test_jump_to_command_puts_it_at_the_top() {
+    let mut app = create_app_with_commands().await;
+
+    app.jump_to_command(1);
+    assert_eq!(top_line(&app), 32);
+    app.jump_to_command(2);
+    assert_eq!(top_line(&app), 36);
+
+    // Output longer than the area runs off the bottom
+    app.jump_to_command(0);
+    assert_eq!(top_line(&app), 0);
+
+    // Past the end jumps to the last command
+    app.jump_to_command(99);
+    assert_eq!(top_line(&app), 36);
+}
+
+#[tokio::test]
+async fn test_jump_near_the_end_scrolls_to_bottom() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    for i in 0..3 {
+        app.command_history.push(entry(&format!("cmd_{i}"), 10));
+    }
+    app.update_layout_areas(24, false, 0);
+
+    app.jump_to_command(2);
+    assert_eq!(app.scroll_offset, 0);
+
+    // Everything fits, so there is nothing to scroll
+    app.command_history.truncate(1);
+    app.scroll_offset = 5;
+    app.jump_to_command(0);
+    assert_eq!(app.scroll_offset, 0);
+}
+
+#[tokio::test]
+async fn test_jump_with_empty_history_does_nothing() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.jump_to_command(0);
+    app.on_key_code(KeyCode::Up, KeyModifiers::ALT);
+    app.on_key_code(KeyCode::Down, KeyModifiers::ALT);
+    assert_eq!(app.scroll_offset, 0);
+    assert!(app.current_input.is_empty());
+}
+
+#[tokio::test]
+async fn test_alt_arrows_hop_between_commands() {
+    let mut app = create_app_with_commands().await;
+    assert_eq!(top_line(&app), 39);
+
+    app.on_key_code(KeyCode::Up, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 36);
+    app.on_key_code(KeyCode::Up, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 32);
+    app.on_key_code(KeyCode::Up, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 0);
+
+    // Already at the first command
+    app.on_key_code(KeyCode::Up, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 0);
+
+    app.on_key_code(KeyCode::Down, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 32);
+    app.on_key_code(KeyCode::Down, KeyModifiers::ALT);
+    assert_eq!(top_line(&app), 36);
+    app.on_key_code(KeyCode::Down, KeyModifiers::ALT);
+    assert_eq!(app.scroll_offset, 0);
+
+    // History navigation is untouched
+    assert!(app.current_input.is_empty());
+}
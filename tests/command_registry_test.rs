@@ -0,0 +1,83 @@
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::commands::{BUILTIN_COMMANDS, Builtin, command_list, find_builtin};
+use taskhub::tui::help::command_help;
+
+#[test]
+fn test_command_list_expands_suggested_arguments() {
+    let list = command_list();
+    assert!(list.contains(&"/stderr split".to_string()));
+    assert!(list.contains(&"/stderr merge".to_string()));
+    assert!(list.contains(&"/task label rm".to_string()));
+    // Commands with suggested arguments are only listed with them
+    assert!(!list.contains(&"/stderr".to_string()));
+    assert!(list.contains(&"/task".to_string()));
+}
+
+#[test]
+fn test_find_builtin_picks_longest_name() {
+    let found = |input: &str| find_builtin(input).map(|command| command.name);
+
+    assert_eq!(found("/task"), Some("/task"));
+    assert_eq!(found("/task add Buy milk"), Some("/task add"));
+    assert_eq!(found("/help keys copy"), Some("/help keys"));
+    assert_eq!(found("/help copy"), Some("/help"));
+    assert_eq!(found("/stderr merge"), Some("/stderr"));
+    // Commands without arguments only match exactly
+    assert_eq!(found("/task bogus"), None);
+    assert_eq!(found("/quit now"), None);
+    assert_eq!(found("/cdx"), None);
+    assert_eq!(found("ls"), None);
+}
+
+#[tokio::test]
+async fn test_list_completion_help_and_dispatch_share_the_registry() {
+    let db_pool = init_db(None).await.unwrap();
+    let app = App::new(db_pool);
+    let help = command_help(None).unwrap();
+
+    // Command list and completion
+    assert_eq!(app.available_commands, command_list());
+    let completions = app.completion_engine.get_completions("/line", 5, &[]);
+    let texts: Vec<&str> = completions.iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(texts, vec!["-numbers on", "-numbers off"]);
+
+    for command in BUILTIN_COMMANDS {
+        // Help
+        assert!(
+            help.contains(&format!("  {} - {}", command.usage, command.description)),
+            "{} is missing from /help",
+            command.name
+        );
+        // Dispatch
+        assert_eq!(find_builtin(command.name), Some(command));
+    }
+    for entry in &app.available_commands {
+        assert!(find_builtin(entry).is_some(), "{entry} is not dispatched");
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_runs_the_registered_builtin() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    assert_eq!(
+        find_builtin("/task agenda").unwrap().builtin,
+        Builtin::TaskAgenda
+    );
+    assert!(app.handle_builtin_command("/task agenda").await);
+    assert_eq!(app.mode, AppMode::Agenda);
+
+    assert!(app.handle_builtin_command("/stderr").await);
+    assert!(
+        app.command_history
+            .last()
+            .unwrap()
+            .output
+            .starts_with("Stderr mode: split")
+    );
+
+    assert!(!app.handle_builtin_command("/not-a-builtin").await);
+    assert!(!app.handle_builtin_command("/task bogus").await);
+}
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn run_to_completion(app: &mut App, command: &str) -> Duration {
+    let start = Instant::now();
+    app.execute_command(command.to_string()).await;
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(10) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    start.elapsed()
+}
+
+#[tokio::test]
+async fn test_command_killed_after_timeout() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_command_timeout(Some(Duration::from_secs(1)));
+
+    let elapsed = run_to_completion(&mut app, "echo started; sleep 5").await;
+
+    assert!(app.running_command.is_none());
+    assert!(elapsed < Duration::from_secs(4), "took {elapsed:?}");
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert!(entry.output.starts_with("started"));
+    assert!(entry.output.ends_with("\nTimed out after 1s"));
+}
+
+#[tokio::test]
+async fn test_quick_command_unaffected_by_timeout() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_command_timeout(Some(Duration::from_secs(5)));
+
+    run_to_completion(&mut app, "echo done").await;
+
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+    assert_eq!(entry.output.trim_end(), "done");
+}
+
+#[tokio::test]
+async fn test_no_timeout_by_default() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let app = App::new(db_pool);
+    assert_eq!(app.command_timeout, None);
+}
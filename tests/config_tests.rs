@@ -123,9 +123,13 @@ mod config_integration {
     async fn test_database_path_scenarios() {
         // Test various database path scenarios
 
+        let file_path = std::env::temp_dir()
+            .join(format!("taskhub_test_{}.db", uuid::Uuid::new_v4()))
+            .display()
+            .to_string();
         let scenarios = vec![
-            (":memory:", true),    // Memory database should always work
-            ("./test1.db", false), // File path may fail in test environment
+            (":memory:", true),          // Memory database should always work
+            (file_path.as_str(), false), // File path may fail in test environment
         ];
 
         for (path_str, should_succeed) in scenarios {
@@ -145,11 +149,14 @@ mod config_integration {
                 }
             }
 
-            // Cleanup if it's a file
+            // Cleanup if it's a file, including the WAL sidecar files, once the
+            // pool is closed so that it does not write them again
+            if let Ok(pool) = result {
+                pool.close().await;
+            }
             if path_str != ":memory:" {
-                let path = PathBuf::from(path_str);
-                if path.exists() {
-                    std::fs::remove_file(path).ok();
+                for suffix in ["", "-wal", "-shm"] {
+                    std::fs::remove_file(format!("{path_str}{suffix}")).ok();
                 }
             }
         }
@@ -250,3 +257,407 @@ theme = "dark"
         }
     }
 }
+
+#[cfg(test)]
+mod integration_targets {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use taskhub::config::secrets::{SecretRef, SecretStore};
+    use taskhub::config::settings::{
+        IntegrationKind, IntegrationTarget, SearchScrollMode, Settings,
+    };
+    use taskhub::db::init_db;
+    use taskhub::db::models::{Priority, Task, TaskSource};
+    use taskhub::db::operations;
+    use taskhub::integrations::github::GitHubClient;
+    use taskhub::sync::engine;
+
+    /// In-memory stand-in for the environment and the OS keyring
+    #[derive(Default)]
+    struct FakeSecrets {
+        env: HashMap<String, String>,
+        keyring: HashMap<(String, String), String>,
+        /// Error every keyring read fails with, as when no keyring is available
+        keyring_error: Option<String>,
+    }
+
+    impl SecretStore for FakeSecrets {
+        fn env(&self, name: &str) -> Option<String> {
+            self.env.get(name).cloned()
+        }
+
+        fn keyring(&self, service: &str, account: &str) -> Result<Option<String>, String> {
+            if let Some(error) = &self.keyring_error {
+                return Err(error.clone());
+            }
+            Ok(self
+                .keyring
+                .get(&(service.to_string(), account.to_string()))
+                .cloned())
+        }
+    }
+
+    fn github_target(token_env: &str) -> IntegrationTarget {
+        IntegrationTarget {
+            kind: IntegrationKind::Github,
+            owner: "taskhub-sh".to_string(),
+            repo: "taskhub".to_string(),
+            token_env: Some(token_env.to_string()),
+            token_keyring: None,
+            max_pages: None,
+            max_retries: 0,
+            label_priorities: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_integrations_section() {
+        let settings = Settings::from_toml(
+            r#"
+[[integrations]]
+type = "github"
+owner = "taskhub-sh"
+repo = "taskhub"
+token_env = "TASKHUB_GITHUB_TOKEN"
+
+[[integrations]]
+type = "gitlab"
+owner = "group"
+repo = "project"
+token_keyring = "taskhub/gitlab"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.integrations.len(), 2);
+        assert_eq!(
+            settings.integrations[0],
+            github_target("TASKHUB_GITHUB_TOKEN")
+        );
+        assert_eq!(settings.integrations[1].kind, IntegrationKind::Gitlab);
+        assert_eq!(
+            settings.integrations[1].token_ref().unwrap(),
+            SecretRef::Keyring {
+                service: "taskhub".to_string(),
+                account: "gitlab".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_label_priorities() {
+        let settings = Settings::from_toml(
+            r#"
+[[integrations]]
+type = "github"
+owner = "taskhub-sh"
+repo = "taskhub"
+token_env = "TASKHUB_GITHUB_TOKEN"
+max_pages = 3
+
+[integrations.label_priorities]
+P0 = "High"
+"priority/low" = "Low"
+"#,
+        )
+        .unwrap();
+
+        let target = &settings.integrations[0];
+        assert_eq!(target.max_pages, Some(3));
+        assert_eq!(target.label_priorities.get("P0"), Some(&Priority::High));
+        assert_eq!(
+            target.label_priorities.get("priority/low"),
+            Some(&Priority::Low)
+        );
+    }
+
+    #[test]
+    fn test_parse_restore_directory() {
+        assert!(!Settings::from_toml("").unwrap().history.restore_directory);
+
+        let settings = Settings::from_toml("[history]\nrestore_directory = true").unwrap();
+        assert!(settings.history.restore_directory);
+        // Other history settings keep their defaults
+        assert!(settings.history.persist);
+    }
+
+    #[test]
+    fn test_parse_confirm_unknown_commands() {
+        assert!(!Settings::from_toml("").unwrap().ui.confirm_unknown_commands);
+
+        let settings = Settings::from_toml("[ui]\nconfirm_unknown_commands = true").unwrap();
+        assert!(settings.ui.confirm_unknown_commands);
+    }
+
+    #[test]
+    fn test_parse_preview_globs() {
+        assert!(!Settings::from_toml("").unwrap().ui.preview_globs);
+
+        let settings = Settings::from_toml("[ui]\npreview_globs = true").unwrap();
+        assert!(settings.ui.preview_globs);
+    }
+
+    #[test]
+    fn test_parse_max_scrollback_lines() {
+        assert_eq!(
+            Settings::from_toml("").unwrap().ui.max_scrollback_lines,
+            100_000
+        );
+
+        let settings = Settings::from_toml("[ui]\nmax_scrollback_lines = 500").unwrap();
+        assert_eq!(settings.ui.max_scrollback_lines, 500);
+    }
+
+    #[test]
+    fn test_parse_search_scroll_mode() {
+        assert_eq!(
+            Settings::from_toml("").unwrap().ui.search_scroll_mode,
+            SearchScrollMode::Center
+        );
+
+        let settings = Settings::from_toml("[ui]\nsearch_scroll_mode = \"minimal\"").unwrap();
+        assert_eq!(settings.ui.search_scroll_mode, SearchScrollMode::Minimal);
+        assert!(Settings::from_toml("[ui]\nsearch_scroll_mode = \"bottom\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_custom_commands() {
+        assert!(Settings::from_toml("").unwrap().commands.is_empty());
+
+        let settings = Settings::from_toml(
+            "[commands]\ndeploy = \"~/bin/deploy.sh\"\nlint = \"./scripts/lint\"",
+        )
+        .unwrap();
+        let commands: Vec<(&str, &str)> = settings
+            .commands
+            .iter()
+            .map(|(name, script)| (name.as_str(), script.as_str()))
+            .collect();
+        assert_eq!(
+            commands,
+            vec![("deploy", "~/bin/deploy.sh"), ("lint", "./scripts/lint")]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_timeout() {
+        let settings = Settings::from_toml("").unwrap();
+        assert_eq!(settings.command_timeout_secs, None);
+        assert_eq!(settings.command_timeout(), None);
+
+        let settings = Settings::from_toml("command_timeout_secs = 30").unwrap();
+        assert_eq!(settings.command_timeout(), Some(Duration::from_secs(30)));
+
+        // Zero disables the timeout
+        let settings = Settings::from_toml("command_timeout_secs = 0").unwrap();
+        assert_eq!(settings.command_timeout(), None);
+    }
+
+    #[test]
+    fn test_integrations_default_to_empty() {
+        let settings = Settings::from_toml("").unwrap();
+        assert!(settings.integrations.is_empty());
+        assert_eq!(settings.tasks.page_size, 100);
+    }
+
+    #[test]
+    fn test_resolve_token_from_referenced_variable() {
+        let target = github_target("TASKHUB_GITHUB_TOKEN");
+        let mut secrets = FakeSecrets::default();
+        secrets.env.insert(
+            "TASKHUB_GITHUB_TOKEN".to_string(),
+            "secret-token".to_string(),
+        );
+
+        assert_eq!(target.resolve_token_with(&secrets).unwrap(), "secret-token");
+    }
+
+    #[test]
+    fn test_resolve_token_reads_process_environment() {
+        // PATH is set in every test environment, unlike a real token variable
+        let target = github_target("PATH");
+        assert_eq!(
+            target.resolve_token().unwrap(),
+            std::env::var("PATH").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resolve_token_from_keyring() {
+        let mut target = github_target("unused");
+        target.token_env = None;
+        target.token_keyring = Some("taskhub/github".to_string());
+        let mut secrets = FakeSecrets::default();
+        secrets.keyring.insert(
+            ("taskhub".to_string(), "github".to_string()),
+            "keyring-token".to_string(),
+        );
+
+        assert_eq!(
+            target.resolve_token_with(&secrets).unwrap(),
+            "keyring-token"
+        );
+    }
+
+    #[test]
+    fn test_resolve_token_reports_keyring_errors() {
+        let mut target = github_target("unused");
+        target.token_env = None;
+        target.token_keyring = Some("taskhub/github".to_string());
+
+        let error = target
+            .resolve_token_with(&FakeSecrets::default())
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Secret not found in keyring entry taskhub/github")
+        );
+
+        let secrets = FakeSecrets {
+            keyring_error: Some("Platform secure storage failure".to_string()),
+            ..FakeSecrets::default()
+        };
+        let error = target.resolve_token_with(&secrets).unwrap_err();
+        assert!(error.to_string().contains(
+            "Could not read keyring entry taskhub/github: Platform secure storage failure"
+        ));
+    }
+
+    #[test]
+    fn test_resolve_token_missing_variable() {
+        let target = github_target("TASKHUB_GITHUB_TOKEN");
+
+        let error = target
+            .resolve_token_with(&FakeSecrets::default())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Token for taskhub-sh/taskhub: Secret not found in environment variable TASKHUB_GITHUB_TOKEN"
+        );
+
+        let mut secrets = FakeSecrets::default();
+        secrets
+            .env
+            .insert("TASKHUB_GITHUB_TOKEN".to_string(), "  ".to_string());
+        assert!(target.resolve_token_with(&secrets).is_err());
+    }
+
+    #[test]
+    fn test_missing_or_invalid_token_reference() {
+        let mut target = github_target("unused");
+        target.token_env = None;
+        let error = target.token_ref().unwrap_err();
+        assert!(error.to_string().contains("set token_env or token_keyring"));
+
+        target.token_keyring = Some("no-account".to_string());
+        let error = target.token_ref().unwrap_err();
+        assert!(error.to_string().contains("expected service/account"));
+    }
+
+    #[test]
+    fn test_github_client_reports_missing_secret() {
+        let secret = SecretRef::Env("TASKHUB_TEST_TOKEN_THAT_IS_NEVER_SET".to_string());
+        let error = GitHubClient::from_secret(&secret).err().unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("TASKHUB_TEST_TOKEN_THAT_IS_NEVER_SET")
+        );
+    }
+
+    fn imported_task(external_id: &str, updated_at: &str) -> Task {
+        Task {
+            external_id: Some(external_id.to_string()),
+            source: TaskSource::GitHub,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: updated_at.to_string(),
+            ..Task::new(format!("Issue {external_id}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_sync_updates_in_place() {
+        let pool = init_db(Some(":memory:".into())).await.unwrap();
+
+        let first = vec![imported_task("1", "2025-01-01T00:00:00Z")];
+        let report = engine::store_fetched_tasks(&pool, first).await.unwrap();
+        assert_eq!(report.added, 1);
+
+        let second = vec![
+            imported_task("1", "2025-02-01T00:00:00Z"),
+            imported_task("2", "2025-02-01T00:00:00Z"),
+        ];
+        let report = engine::store_fetched_tasks(&pool, second).await.unwrap();
+        assert_eq!((report.added, report.updated), (1, 1));
+
+        let tasks = operations::list_tasks(&pool).await.unwrap();
+        assert_eq!(tasks.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod show_config {
+    use taskhub::config::settings::{Settings, redact_secrets};
+    use taskhub::db::init_db;
+    use taskhub::tui::app::App;
+
+    #[test]
+    fn test_redact_secrets_masks_token_fields() {
+        let mut value = serde_json::json!({
+            "github_token": "ghp_plaintext",
+            "database_path": "/tmp/taskhub.db",
+            "integrations": [
+                { "owner": "taskhub-sh", "token_env": "TASKHUB_GITHUB_TOKEN", "api_secret": "hunter2" }
+            ]
+        });
+
+        redact_secrets(&mut value);
+
+        assert_eq!(value["github_token"], "********");
+        assert_eq!(value["database_path"], "/tmp/taskhub.db");
+        assert_eq!(value["integrations"][0]["api_secret"], "********");
+        // References to where a secret lives stay readable
+        assert_eq!(
+            value["integrations"][0]["token_env"],
+            "TASKHUB_GITHUB_TOKEN"
+        );
+    }
+
+    #[test]
+    fn test_describe_shows_effective_settings() {
+        let settings = Settings::from_toml(
+            r#"
+[ui]
+input_height = 5
+
+[[integrations]]
+type = "github"
+owner = "taskhub-sh"
+repo = "taskhub"
+token_env = "TASKHUB_GITHUB_TOKEN"
+"#,
+        )
+        .unwrap();
+
+        let description = settings.describe();
+
+        assert!(description.starts_with("Config file: "));
+        assert!(description.contains("\"input_height\": 5"));
+        assert!(description.contains("\"page_size\": 100"));
+        assert!(description.contains("TASKHUB_GITHUB_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn test_config_command_prints_settings() {
+        let pool = init_db(Some(":memory:".into())).await.unwrap();
+        let mut app = App::new(pool).with_settings(Settings::default());
+
+        assert!(app.handle_builtin_command("/config").await);
+
+        let entry = app.command_history.last().unwrap();
+        assert!(entry.success);
+        assert!(entry.output.contains("\"history\""));
+        assert!(entry.output.contains("\"max_entries\": 1000"));
+    }
+}
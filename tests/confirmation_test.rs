@@ -0,0 +1,261 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+use std::fs;
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::db::models::{Task, TaskStatus};
+use taskhub::db::operations;
+use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::components::confirmation::{ConfirmAction, Confirmation, draw_confirmation};
+use uuid::Uuid;
+
+const COMMAND: &str = "echo confirmed";
+
+async fn create_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+async fn finish_running_command(app: &mut App) {
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Number of times the confirmed command has run
+fn runs(app: &App) -> usize {
+    app.command_history
+        .iter()
+        .filter(|entry| entry.command == COMMAND)
+        .count()
+}
+
+/// Answer with `key` and let the app process the answer a few times over
+async fn answer(app: &mut App, key: char) {
+    app.on_key(key);
+    for _ in 0..3 {
+        app.handle_pending_commands().await;
+        finish_running_command(app).await;
+    }
+}
+
+#[tokio::test]
+async fn test_confirming_runs_action_once() {
+    let mut app = create_app().await;
+    app.confirm("Run it?", ConfirmAction::RunCommand(COMMAND.to_string()));
+
+    answer(&mut app, 'y').await;
+    assert!(app.confirmation.is_none());
+    assert_eq!(runs(&app), 1);
+    assert_eq!(
+        app.command_history.last().unwrap().output.trim_end(),
+        "confirmed"
+    );
+
+    // A second y has nothing left to confirm
+    answer(&mut app, 'y').await;
+    assert_eq!(runs(&app), 1);
+}
+
+#[tokio::test]
+async fn test_declining_never_runs_action() {
+    for decline in ['n', 'N'] {
+        let mut app = create_app().await;
+        app.confirm("Run it?", ConfirmAction::RunCommand(COMMAND.to_string()));
+
+        answer(&mut app, decline).await;
+        assert!(app.confirmation.is_none());
+        assert_eq!(runs(&app), 0);
+        // The command is put back to edit
+        assert_eq!(app.current_input, COMMAND);
+
+        answer(&mut app, 'y').await;
+        assert_eq!(runs(&app), 0);
+    }
+}
+
+#[tokio::test]
+async fn test_esc_and_enter_decline() {
+    for key_code in [KeyCode::Esc, KeyCode::Enter] {
+        let mut app = create_app().await;
+        app.confirm("Run it?", ConfirmAction::RunCommand(COMMAND.to_string()));
+
+        app.on_key_code(key_code, KeyModifiers::NONE);
+        assert!(app.confirmation.is_none());
+        app.handle_pending_commands().await;
+        finish_running_command(&mut app).await;
+        assert_eq!(runs(&app), 0);
+    }
+}
+
+#[tokio::test]
+async fn test_other_keys_are_ignored_while_open() {
+    let mut app = create_app().await;
+    app.mode = AppMode::TaskList;
+    app.confirm("Run it?", ConfirmAction::RunCommand(COMMAND.to_string()));
+
+    // q would otherwise leave the task list, and a would open the quick-add input
+    for key in ['x', 'q', 'a'] {
+        app.on_key(key);
+    }
+    app.on_key_code(KeyCode::Up, KeyModifiers::NONE);
+
+    assert_eq!(app.mode, AppMode::TaskList);
+    assert!(app.quick_add.is_none());
+    assert!(app.current_input.is_empty());
+    assert!(app.confirmation.is_some());
+
+    answer(&mut app, 'y').await;
+    assert_eq!(runs(&app), 1);
+}
+
+/// App holding a task for each of `titles`, with their ids
+async fn create_app_with_tasks(titles: &[&str]) -> (App, Vec<Uuid>) {
+    let mut app = create_app().await;
+    let mut ids = Vec::new();
+    for title in titles {
+        let task = Task::new(title.to_string());
+        operations::create_task(&app.db_pool, &task).await.unwrap();
+        ids.push(task.id);
+    }
+    app.load_tasks().await.unwrap();
+    (app, ids)
+}
+
+/// Submit `command` as if typed and entered
+async fn submit(app: &mut App, command: &str) {
+    app.pending_command = Some(command.to_string());
+    app.handle_pending_commands().await;
+}
+
+#[tokio::test]
+async fn test_quit_with_running_command_asks_first() {
+    let mut app = create_app().await;
+    app.execute_command("sleep 5".to_string()).await;
+    assert!(app.running_command.is_some());
+
+    // /quit is accepted while the command runs
+    app.current_input = "/quit".to_string();
+    app.cursor_position = 5;
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+    app.handle_pending_commands().await;
+    assert_eq!(
+        app.confirmation.as_ref().map(|c| &c.action),
+        Some(&ConfirmAction::Quit)
+    );
+    assert!(!app.should_quit);
+
+    answer(&mut app, 'y').await;
+    assert!(app.should_quit);
+    assert!(app.running_command.is_none());
+}
+
+#[tokio::test]
+async fn test_task_delete_asks_first() {
+    let (mut app, ids) = create_app_with_tasks(&["Old task"]).await;
+    let prefix = &ids[0].to_string()[..8];
+
+    submit(&mut app, &format!("/task delete {prefix}")).await;
+    assert_eq!(
+        app.confirmation.as_ref().unwrap().prompt,
+        "Delete task 'Old task'? [y/N]"
+    );
+    assert_eq!(app.tasks.len(), 1);
+
+    answer(&mut app, 'y').await;
+    assert!(app.tasks.is_empty());
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        format!("Deleted task 'Old task' ({})", ids[0])
+    );
+}
+
+#[tokio::test]
+async fn test_bulk_status_change_asks_first() {
+    let (mut app, ids) = create_app_with_tasks(&["One", "Two"]).await;
+    let prefixes: Vec<String> = ids
+        .iter()
+        .map(|id| id.to_string()[..8].to_string())
+        .collect();
+
+    submit(&mut app, &format!("/done {}", prefixes.join(" "))).await;
+    assert_eq!(
+        app.confirmation.as_ref().unwrap().prompt,
+        "Mark 2 tasks as Done? [y/N]"
+    );
+    assert!(app.tasks.iter().all(|task| task.status == TaskStatus::Open));
+
+    answer(&mut app, 'y').await;
+    assert!(app.tasks.iter().all(|task| task.status == TaskStatus::Done));
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "2 tasks are now Done"
+    );
+}
+
+#[tokio::test]
+async fn test_dangerous_command_runs_once_confirmed() {
+    let dir = std::env::temp_dir().join(format!("taskhub-confirm-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.log"), "").unwrap();
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_preview_globs(true);
+    app.current_working_dir = dir.clone();
+
+    submit(&mut app, "rm *.log").await;
+    assert_eq!(
+        app.confirmation.as_ref().map(|c| &c.action),
+        Some(&ConfirmAction::RunDangerousCommand("rm *.log".to_string()))
+    );
+    assert!(dir.join("a.log").exists());
+
+    answer(&mut app, 'y').await;
+    assert!(!dir.join("a.log").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_dialog_is_centered_and_fits_prompt() {
+    let confirmation = Confirmation::new(
+        "x".repeat(100),
+        ConfirmAction::RunCommand(COMMAND.to_string()),
+    );
+    // 58 columns inside the borders, so the prompt takes two rows
+    assert_eq!(
+        confirmation.area(Rect::new(0, 0, 80, 24)),
+        Rect::new(10, 9, 60, 6)
+    );
+    // Narrow terminals get a narrower dialog
+    assert_eq!(
+        confirmation.area(Rect::new(0, 0, 30, 4)),
+        Rect::new(0, 0, 30, 4)
+    );
+}
+
+#[test]
+fn test_dialog_shows_prompt_and_keys() {
+    let confirmation = Confirmation::new(
+        "Delete 3 tasks?",
+        ConfirmAction::RunCommand(COMMAND.to_string()),
+    );
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal
+        .draw(|f| draw_confirmation(f, &confirmation))
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect();
+    assert!(rows[9].contains("Confirm"));
+    assert!(rows[10].contains("Delete 3 tasks?"));
+    assert!(rows[12].contains("y yes   n/Esc no"));
+}
@@ -13,16 +13,19 @@ async fn test_coordinate_mapping_with_content() {
             command: "echo hello".to_string(),
             output: "hello".to_string(),
             success: true,
+            ..Default::default()
         },
         CommandEntry {
             command: "ls -la".to_string(),
             output: "file1.txt\nfile2.txt\nfile3.txt".to_string(),
             success: true,
+            ..Default::default()
         },
         CommandEntry {
             command: "pwd".to_string(),
             output: "/home/user".to_string(),
             success: true,
+            ..Default::default()
         },
     ];
 
@@ -39,14 +42,14 @@ async fn test_coordinate_mapping_with_content() {
     let mut all_items_count = 0;
     let mut content_map = Vec::new();
 
-    for (_entry_idx, entry) in app.command_history.iter().enumerate() {
+    for entry in app.command_history.iter() {
         // Command line
         content_map.push(format!("Line {}: > {}", all_items_count, entry.command));
         all_items_count += 1;
 
         // Output lines
         if !entry.output.is_empty() {
-            for (_line_idx, line) in entry.output.lines().enumerate() {
+            for line in entry.output.lines() {
                 content_map.push(format!("Line {}: {}", all_items_count, line));
                 all_items_count += 1;
             }
@@ -58,7 +61,7 @@ async fn test_coordinate_mapping_with_content() {
     }
 
     println!("Total content lines: {}", all_items_count);
-    for (_i, line) in content_map.iter().enumerate() {
+    for line in content_map.iter() {
         println!("  {}", line);
     }
 
@@ -124,6 +127,7 @@ async fn test_coordinate_mapping_edge_cases() {
         command: "test".to_string(),
         output: "output".to_string(),
         success: true,
+        ..Default::default()
     });
 
     app.update_layout_areas(24, false, 0);
@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+/// App whose clipboard writes are recorded in the returned list
+async fn create_test_app() -> (App, Arc<Mutex<Vec<String>>>) {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let copied = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&copied);
+    let app = App::new(db_pool).with_clipboard_writer(Box::new(move |text| {
+        sink.lock().unwrap().push(text.to_string());
+        Ok(())
+    }));
+    (app, copied)
+}
+
+#[tokio::test]
+async fn test_copy_input_line_sends_whole_input() {
+    let (mut app, copied) = create_test_app().await;
+    app.current_input = "git log --oneline -5".to_string();
+    app.cursor_position = 3;
+
+    app.copy_input_line().await;
+
+    assert_eq!(*copied.lock().unwrap(), vec!["git log --oneline -5"]);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+    assert_eq!(entry.output, "Copied 20 characters to the clipboard");
+    // The input itself is left untouched
+    assert_eq!(app.current_input, "git log --oneline -5");
+}
+
+#[tokio::test]
+async fn test_copy_empty_input_is_reported() {
+    let (mut app, copied) = create_test_app().await;
+
+    app.copy_input_line().await;
+
+    assert!(copied.lock().unwrap().is_empty());
+    assert!(!app.command_history.last().unwrap().success);
+}
+
+#[tokio::test]
+async fn test_copy_input_line_reports_clipboard_errors() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool)
+        .with_clipboard_writer(Box::new(|_| Err("clipboard unavailable".to_string())));
+    app.current_input = "ls".to_string();
+
+    app.copy_input_line().await;
+
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "clipboard unavailable");
+}
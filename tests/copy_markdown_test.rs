@@ -0,0 +1,120 @@
+use std::sync::{Arc, Mutex};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::{CommandEntry, entry_markdown};
+
+fn entry(command: &str, output: &str, exit_code: Option<i32>) -> CommandEntry {
+    CommandEntry {
+        command: command.to_string(),
+        output: output.to_string(),
+        success: exit_code.is_none_or(|code| code == 0),
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code,
+        styled_output: None,
+    }
+}
+
+/// App whose clipboard writes are recorded in the returned list
+async fn create_test_app() -> (App, Arc<Mutex<Vec<String>>>) {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let copied = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&copied);
+    let app = App::new(db_pool).with_clipboard_writer(Box::new(move |text| {
+        sink.lock().unwrap().push(text.to_string());
+        Ok(())
+    }));
+    (app, copied)
+}
+
+#[test]
+fn test_markdown_of_sample_entry() {
+    let sample = entry(
+        "cargo test",
+        "\x1b[32mrunning 2 tests\x1b[0m\ntest ok ... \x1b[1;31mFAILED\x1b[0m",
+        Some(101),
+    );
+    assert_eq!(
+        entry_markdown(&sample),
+        "**`$ cargo test`** (exit 101)\n\n```\nrunning 2 tests\ntest ok ... FAILED\n```\n"
+    );
+}
+
+#[test]
+fn test_markdown_leaves_out_successful_exit_code() {
+    assert_eq!(
+        entry_markdown(&entry("ls", "a\nb", Some(0))),
+        "**`$ ls`**\n\n```\na\nb\n```\n"
+    );
+    // Builtins have no exit code, and may have no output
+    assert_eq!(
+        entry_markdown(&entry("/clear", "", None)),
+        "**`$ /clear`**\n\n```\n```\n"
+    );
+}
+
+#[test]
+fn test_markdown_with_backticks() {
+    let sample = entry("echo '`x`'", "```rust\nfn main() {}\n```", Some(0));
+    assert_eq!(
+        entry_markdown(&sample),
+        "**``$ echo '`x`'``**\n\n````\n```rust\nfn main() {}\n```\n````\n"
+    );
+    // Inline code may not start or end with its delimiter
+    assert_eq!(
+        entry_markdown(&entry("`pwd`", "/tmp", Some(0)))
+            .lines()
+            .next(),
+        Some("**`` $ `pwd` ``**")
+    );
+}
+
+#[tokio::test]
+async fn test_copies_last_entry_by_default() {
+    let (mut app, copied) = create_test_app().await;
+    app.command_history.push(entry("echo one", "one", Some(0)));
+    app.command_history.push(entry("echo two", "two", Some(0)));
+
+    app.copy_focused_entry_as_markdown().await;
+
+    assert_eq!(
+        *copied.lock().unwrap(),
+        vec!["**`$ echo two`**\n\n```\ntwo\n```\n"]
+    );
+    let report = app.command_history.last().unwrap();
+    assert!(report.success);
+    assert_eq!(report.output, "Copied 'echo two' as markdown");
+}
+
+#[tokio::test]
+async fn test_copies_entry_at_top_while_scrolled() {
+    let (mut app, copied) = create_test_app().await;
+    for i in 0..10 {
+        app.command_history
+            .push(entry(&format!("seq {i}"), "1\n2\n3\n4\n5", Some(0)));
+    }
+    app.handle_resize(80, 24);
+    assert_eq!(app.focused_entry_index(), Some(9));
+
+    app.jump_to_command(3);
+    assert_eq!(app.focused_entry_index(), Some(3));
+    // Scrolling a little further keeps the entry whose output is at the top
+    app.scroll_offset -= 2;
+    assert_eq!(app.focused_entry_index(), Some(3));
+
+    app.copy_focused_entry_as_markdown().await;
+    assert!(copied.lock().unwrap()[0].starts_with("**`$ seq 3`**"));
+}
+
+#[tokio::test]
+async fn test_empty_history_is_reported() {
+    let (mut app, copied) = create_test_app().await;
+    assert_eq!(app.focused_entry_index(), None);
+
+    app.copy_focused_entry_as_markdown().await;
+
+    assert!(copied.lock().unwrap().is_empty());
+    let report = app.command_history.last().unwrap();
+    assert!(!report.success);
+    assert_eq!(report.output, "Nothing to copy: the history is empty");
+}
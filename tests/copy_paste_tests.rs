@@ -53,12 +53,14 @@ async fn test_get_selected_text_with_history() {
         command: "echo hello".to_string(),
         output: "hello".to_string(),
         success: true,
+        ..Default::default()
     };
 
     let entry2 = CommandEntry {
         command: "ls".to_string(),
         output: "file1.txt\nfile2.txt".to_string(),
         success: true,
+        ..Default::default()
     };
 
     app.command_history.push(entry1);
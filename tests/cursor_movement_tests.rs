@@ -1,6 +1,7 @@
 use crossterm::event::{KeyCode, KeyModifiers};
 use taskhub::db::init_db;
 use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
 
 async fn create_test_app() -> App {
     let pool = init_db(Some(":memory:".into())).await.unwrap();
@@ -117,6 +118,35 @@ async fn test_ctrl_k_at_beginning_clears_all() {
     assert_eq!(app.current_input, ""); // All text removed
 }
 
+#[tokio::test]
+async fn test_ctrl_g_clears_input_but_keeps_history() {
+    let mut app = create_test_app().await;
+    app.command_history.push(CommandEntry {
+        command: "echo hi".to_string(),
+        output: "hi".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app.scroll_offset = 1;
+    for ch in "/he".chars() {
+        app.on_key(ch);
+    }
+    app.cursor_position = 1;
+    assert!(app.show_command_list);
+
+    app.on_key_code(KeyCode::Char('g'), KeyModifiers::CONTROL);
+
+    assert_eq!(app.current_input, "");
+    assert_eq!(app.cursor_position, 0);
+    assert!(!app.show_command_list);
+    assert!(app.command_filter.is_empty());
+    assert!(app.auto_suggestion.is_none());
+    // Scrollback is untouched, unlike /clear
+    assert_eq!(app.command_history.len(), 1);
+    assert_eq!(app.command_history[0].output, "hi");
+    assert_eq!(app.scroll_offset, 1);
+}
+
 #[tokio::test]
 async fn test_ctrl_left_moves_backward_by_word() {
     let mut app = create_test_app().await;
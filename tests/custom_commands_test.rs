@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn create_test_app(commands: &[(&str, &str)]) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let commands: BTreeMap<String, String> = commands
+        .iter()
+        .map(|(name, script)| (name.to_string(), script.to_string()))
+        .collect();
+    App::new(db_pool).with_custom_commands(commands)
+}
+
+async fn wait_for_command_completion(app: &mut App) {
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_custom_command_lookup() {
+    let app = create_test_app(&[
+        ("deploy", "/opt/scripts/deploy.sh"),
+        ("greet", "/tmp/my scripts/greet.sh"),
+    ])
+    .await;
+
+    assert_eq!(
+        app.custom_command_line("/deploy").as_deref(),
+        Some("/opt/scripts/deploy.sh")
+    );
+    assert_eq!(app.custom_command_line("/other"), None);
+    assert_eq!(app.custom_command_line("deploy"), None);
+    assert_eq!(app.custom_command_line("/deployx"), None);
+
+    // Custom commands are offered after the built-in ones
+    let listed = &app.available_commands[app.available_commands.len() - 2..];
+    assert_eq!(listed, ["/deploy", "/greet"]);
+}
+
+#[tokio::test]
+async fn test_custom_command_arguments_are_passed_on() {
+    let app = create_test_app(&[
+        ("deploy", "/opt/scripts/deploy.sh"),
+        ("greet", "/tmp/my scripts/greet.sh"),
+    ])
+    .await;
+
+    assert_eq!(
+        app.custom_command_line("/deploy prod --fast").as_deref(),
+        Some("/opt/scripts/deploy.sh prod --fast")
+    );
+    // The script path is quoted; the arguments keep the user's quoting
+    assert_eq!(
+        app.custom_command_line("/greet 'Ada Lovelace'").as_deref(),
+        Some("'/tmp/my scripts/greet.sh' 'Ada Lovelace'")
+    );
+}
+
+#[tokio::test]
+async fn test_builtins_take_precedence() {
+    let mut app = create_test_app(&[("history", "/bin/false")]).await;
+    assert!(app.handle_builtin_command("/history").await);
+    assert!(app.running_command.is_none());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_custom_command_runs_script_with_arguments() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("taskhub-custom-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("args.sh");
+    std::fs::write(&script, "#!/bin/sh\nprintf '%s\\n' \"$@\"\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut app = create_test_app(&[("args", script.to_str().unwrap())]).await;
+    assert!(app.handle_builtin_command("/args one 'two words'").await);
+    wait_for_command_completion(&mut app).await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, "/args one 'two words'");
+    assert!(entry.success);
+    let lines: Vec<&str> = entry.output.lines().map(str::trim_end).collect();
+    assert_eq!(lines, vec!["one", "two words"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,8 +1,6 @@
-use std::collections::HashMap;
 use taskhub::db::init_db;
-use taskhub::db::models::{Priority, Task, TaskSource, TaskStatus};
+use taskhub::db::models::{Task, TaskStatus};
 use taskhub::db::operations::{create_task, delete_task, get_task, list_tasks, update_task};
-use uuid::Uuid;
 
 #[tokio::test]
 async fn test_db_operations() -> Result<(), Box<dyn std::error::Error>> {
@@ -10,19 +8,11 @@ async fn test_db_operations() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create a task
     let new_task = Task {
-        id: Uuid::new_v4(),
-        external_id: None,
-        source: TaskSource::Markdown,
-        title: "Test Task".to_string(),
         description: Some("This is a test task.".to_string()),
-        status: TaskStatus::Open,
-        priority: Priority::Medium,
-        assignee: None,
         labels: vec!["test".to_string()],
-        due_date: None,
         created_at: "2025-01-01T00:00:00Z".to_string(),
         updated_at: "2025-01-01T00:00:00Z".to_string(),
-        custom_fields: HashMap::new(),
+        ..Task::new("Test Task")
     };
     create_task(&pool, &new_task).await?;
 
@@ -0,0 +1,149 @@
+use std::time::Duration;
+use taskhub::config::settings::Settings;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::shell::{Shell, is_simple_command, simple_command_argv};
+
+fn argv(command: &str) -> Vec<String> {
+    simple_command_argv(command).expect("command should be simple")
+}
+
+#[test]
+fn test_plain_words_are_simple() {
+    assert!(is_simple_command("ls -la /tmp"));
+    assert!(is_simple_command("git commit -m 'fix: a bug'"));
+    assert!(is_simple_command("cargo test -- --nocapture"));
+    assert!(is_simple_command("curl https://example.com/a=b"));
+}
+
+#[test]
+fn test_shell_syntax_is_not_simple() {
+    for command in [
+        "ls | wc -l",
+        "make && make install",
+        "sleep 1; ls",
+        "ls > out.txt",
+        "sort < input",
+        "ls *.rs",
+        "ls file?.txt",
+        "ls [ab].txt",
+        "ls $HOME",
+        "ls `pwd`",
+        "ls \"$HOME\"",
+        "touch {a,b}",
+        "(ls src)",
+        "sleep 10 &",
+        "ls ~/src",
+        "ls # comment",
+        "FOO=bar env",
+    ] {
+        assert!(
+            !is_simple_command(command),
+            "{command} should need the shell"
+        );
+    }
+}
+
+#[test]
+fn test_builtins_and_empty_commands_need_the_shell() {
+    assert!(!is_simple_command("export FOO=bar"));
+    assert!(!is_simple_command("source ~/.profile"));
+    assert!(!is_simple_command("alias ll"));
+    assert!(!is_simple_command(""));
+    assert!(!is_simple_command("   "));
+    assert!(!is_simple_command("''"));
+}
+
+#[test]
+fn test_unbalanced_quotes_need_the_shell() {
+    assert!(!is_simple_command("cat 'unterminated"));
+    assert!(!is_simple_command("cat \"unterminated"));
+    assert!(!is_simple_command("cat trailing\\"));
+}
+
+#[test]
+fn test_tokenize_plain_arguments() {
+    assert_eq!(argv("ls   -la\t/tmp"), ["ls", "-la", "/tmp"]);
+}
+
+#[test]
+fn test_tokenize_quoted_arguments() {
+    assert_eq!(
+        argv("git commit -m 'fix: the *real* bug'"),
+        ["git", "commit", "-m", "fix: the *real* bug"]
+    );
+    assert_eq!(
+        argv("grep \"two words\" notes.txt"),
+        ["grep", "two words", "notes.txt"]
+    );
+    // Quotes join with the text around them into one argument
+    assert_eq!(argv("curl --user='a b'\"c\"d"), ["curl", "--user=a bcd"]);
+    // Empty quotes still make an argument
+    assert_eq!(argv("touch '' x"), ["touch", "", "x"]);
+}
+
+#[test]
+fn test_tokenize_escapes() {
+    assert_eq!(argv("ls my\\ file"), ["ls", "my file"]);
+    assert_eq!(argv("ls \\$HOME"), ["ls", "$HOME"]);
+    // Inside double quotes a backslash only escapes quotes and backslashes
+    assert_eq!(
+        argv("grep \"say \\\"hi\\\" \\n\""),
+        ["grep", "say \"hi\" \\n"]
+    );
+    assert_eq!(argv("cat 'it''s'"), ["cat", "its"]);
+}
+
+#[test]
+fn test_direct_exec_spawns_the_program() {
+    let shell = Shell::new("/bin/sh").with_direct_exec(true);
+    assert_eq!(shell.argv("ls -la 'my dir'"), ["ls", "-la", "my dir"]);
+    assert_eq!(shell.argv("ls | wc -l"), ["/bin/sh", "-c", "ls | wc -l"]);
+
+    let pty = shell.pty_command("git log --oneline", None);
+    assert_eq!(pty.get_argv(), &["git", "log", "--oneline"]);
+    let pipe = shell.pipe_command("git log --oneline", None);
+    assert_eq!(pipe.as_std().get_program(), "git");
+}
+
+#[test]
+fn test_direct_exec_is_off_by_default() {
+    let shell = Shell::new("/bin/sh");
+    assert!(!shell.direct_exec());
+    assert_eq!(shell.argv("ls -la"), ["/bin/sh", "-c", "ls -la"]);
+}
+
+#[test]
+fn test_direct_exec_only_splits_posix_command_lines() {
+    let shell = Shell::new("cmd").with_direct_exec(true);
+    assert_eq!(shell.argv("dir C:\\"), ["cmd", "/C", "dir C:\\"]);
+}
+
+#[test]
+fn test_parse_direct_exec_setting() {
+    assert!(!Settings::from_toml("").unwrap().direct_exec);
+    assert!(
+        Settings::from_toml("direct_exec = true")
+            .unwrap()
+            .direct_exec
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_direct_exec_passes_arguments_unchanged() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_shell(Shell::new("/bin/sh").with_direct_exec(true));
+
+    // printf is a shell builtin, so name the program by path to run it directly
+    app.execute_command("/usr/bin/printf '%s|' 'a  b' c".to_string())
+        .await;
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.output.trim(), "a  b|c|");
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn run(command: &str) -> (bool, Option<i32>) {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.execute_command(command.to_string()).await;
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let entry = app.command_history.last().unwrap();
+    (entry.success, entry.exit_code)
+}
+
+#[tokio::test]
+async fn test_exit_code_is_recorded() {
+    assert_eq!(run("sh -c 'exit 3'").await, (false, Some(3)));
+    assert_eq!(run("echo ok").await, (true, Some(0)));
+    assert_eq!(run("taskhub-no-such-program").await, (false, Some(127)));
+}
+
+#[tokio::test]
+async fn test_signal_terminated_command_has_no_exit_code() {
+    assert_eq!(run("kill -9 $$").await, (false, None));
+}
+
+#[tokio::test]
+async fn test_builtins_have_no_exit_code() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.handle_builtin_command("/help").await;
+    assert_eq!(app.command_history.last().unwrap().exit_code, None);
+}
@@ -0,0 +1,97 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::editor::edit_with;
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+#[test]
+fn test_edit_round_trips_through_temp_file() {
+    let mut edited_path = None;
+
+    let result = edit_with("echo hello", |path| {
+        // Stand-in for the editor: check the input, then save a new command
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "echo hello");
+        std::fs::write(path, "echo hello world\n").unwrap();
+        edited_path = Some(path.to_path_buf());
+        Ok(ExitStatus::from_raw(0))
+    })
+    .unwrap();
+
+    assert_eq!(result, Some("echo hello world".to_string()));
+    // The temp file is cleaned up afterwards
+    assert!(!edited_path.unwrap().exists());
+}
+
+#[test]
+fn test_failed_editor_discards_edit() {
+    let result = edit_with("ls", |path| {
+        std::fs::write(path, "rm -rf build").unwrap();
+        Ok(ExitStatus::from_raw(1 << 8))
+    })
+    .unwrap();
+
+    assert_eq!(result, None);
+}
+
+#[test]
+fn test_editor_launch_error_is_reported() {
+    let result = edit_with("ls", |_| {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no editor",
+        ))
+    });
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_ctrl_x_ctrl_e_requests_editor() {
+    let mut app = create_test_app().await;
+
+    app.on_key_code(KeyCode::Char('x'), KeyModifiers::CONTROL);
+    assert!(!app.pending_editor);
+    app.on_key_code(KeyCode::Char('e'), KeyModifiers::CONTROL);
+
+    assert!(app.pending_editor);
+}
+
+#[tokio::test]
+async fn test_ctrl_e_alone_still_moves_to_end() {
+    let mut app = create_test_app().await;
+    app.current_input = "echo".to_string();
+    app.cursor_position = 0;
+
+    app.on_key_code(KeyCode::Char('x'), KeyModifiers::CONTROL);
+    app.on_key('a');
+    app.on_key_code(KeyCode::Char('e'), KeyModifiers::CONTROL);
+
+    assert!(!app.pending_editor);
+    assert_eq!(app.cursor_position, app.current_input.chars().count());
+}
+
+#[tokio::test]
+async fn test_finish_external_edit_replaces_input() {
+    let mut app = create_test_app().await;
+    app.current_input = "ls".to_string();
+
+    app.finish_external_edit(Ok(Some("ls -la".to_string())))
+        .await;
+    assert_eq!(app.current_input, "ls -la");
+    assert_eq!(app.cursor_position, 6);
+
+    app.finish_external_edit(Ok(None)).await;
+    assert_eq!(app.current_input, "ls -la");
+
+    app.finish_external_edit(Err(std::io::Error::other("boom")))
+        .await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert!(entry.output.contains("boom"));
+}
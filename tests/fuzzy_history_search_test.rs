@@ -0,0 +1,119 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::fuzzy::{fuzzy_match, rank};
+
+fn ranked<'a>(query: &str, candidates: &[&'a str]) -> Vec<&'a str> {
+    rank(query, candidates.iter().copied())
+        .into_iter()
+        .map(|(candidate, _)| candidate)
+        .collect()
+}
+
+#[test]
+fn test_characters_must_appear_in_order() {
+    assert!(fuzzy_match("gst", "git status").is_some());
+    assert!(fuzzy_match("tsg", "git status").is_none());
+    assert!(fuzzy_match("xyz", "git status").is_none());
+}
+
+#[test]
+fn test_match_positions_are_reported() {
+    let m = fuzzy_match("gs", "git status").unwrap();
+    assert_eq!(m.positions, vec![0, 4]);
+}
+
+#[test]
+fn test_word_starts_rank_above_scattered_matches() {
+    let results = ranked("gc", &["log cat", "git commit -m 'x'"]);
+    assert_eq!(results, vec!["git commit -m 'x'", "log cat"]);
+}
+
+#[test]
+fn test_consecutive_matches_rank_above_gaps() {
+    let results = ranked("build", &["burrito island", "cargo build"]);
+    assert_eq!(results[0], "cargo build");
+}
+
+#[test]
+fn test_tighter_match_ranks_first() {
+    let results = ranked("cb", &["cargo --verbose build", "cargo build"]);
+    assert_eq!(results, vec!["cargo build", "cargo --verbose build"]);
+}
+
+#[test]
+fn test_non_matching_candidates_are_dropped() {
+    let results = ranked("dock", &["docker ps", "git log", "cd docs"]);
+    assert_eq!(results, vec!["docker ps"]);
+}
+
+#[test]
+fn test_equal_scores_keep_candidate_order() {
+    let results = ranked("ls", &["ls /tmp", "ls /var"]);
+    assert_eq!(results, vec!["ls /tmp", "ls /var"]);
+}
+
+#[test]
+fn test_matching_is_smart_case() {
+    assert!(fuzzy_match("readme", "cat README.md").is_some());
+    assert!(fuzzy_match("README", "cat readme.md").is_none());
+}
+
+#[tokio::test]
+async fn test_ctrl_shift_r_opens_search_and_enter_accepts_selection() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = vec![
+        "cargo test".to_string(),
+        "git status".to_string(),
+        "cargo build --release".to_string(),
+        "git status".to_string(),
+    ];
+
+    app.on_key_code(
+        KeyCode::Char('R'),
+        KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+    );
+    let search = app.fuzzy_search.as_ref().unwrap();
+    // With no query every command is listed once, newest first
+    let listed: Vec<&str> = search.results.iter().map(|r| r.command.as_str()).collect();
+    assert_eq!(
+        listed,
+        vec!["git status", "cargo build --release", "cargo test"]
+    );
+
+    for ch in "cbr".chars() {
+        app.on_key(ch);
+    }
+    let search = app.fuzzy_search.as_ref().unwrap();
+    assert_eq!(search.results[0].command, "cargo build --release");
+
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(app.fuzzy_search.is_none());
+    assert_eq!(app.current_input, "cargo build --release");
+    assert_eq!(app.cursor_position, app.current_input.chars().count());
+}
+
+#[tokio::test]
+async fn test_navigation_and_escape() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = vec!["ls".to_string(), "ls -la".to_string()];
+    app.current_input = "typed".to_string();
+
+    app.start_fuzzy_search();
+    app.on_key_code(KeyCode::Down, KeyModifiers::NONE);
+    app.on_key_code(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(app.fuzzy_search.as_ref().unwrap().selected, 1);
+    app.on_key_code(KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(app.fuzzy_search.as_ref().unwrap().selected, 0);
+
+    app.on_key('x');
+    assert!(app.fuzzy_search.as_ref().unwrap().results.is_empty());
+    app.on_key_code(KeyCode::Backspace, KeyModifiers::NONE);
+    assert_eq!(app.fuzzy_search.as_ref().unwrap().results.len(), 2);
+
+    app.on_key_code(KeyCode::Esc, KeyModifiers::NONE);
+    assert!(app.fuzzy_search.is_none());
+    assert_eq!(app.current_input, "typed");
+}
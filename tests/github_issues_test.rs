@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use taskhub::db::models::Priority;
+use taskhub::integrations::github::{GitHubClient, GitHubError, next_page_url};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn issue_json(number: u64) -> String {
+    format!(
+        r#"{{"number":{number},"title":"Issue {number}","body":null,"state":"open","assignee":null,"labels":[],"created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-01T00:00:00Z"}}"#
+    )
+}
+
+fn pull_request_json(number: u64) -> String {
+    format!(
+        r#"{{"number":{number},"title":"PR {number}","body":null,"state":"open","assignee":null,"labels":[],"created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-01T00:00:00Z","pull_request":{{"url":"https://api.github.com/repos/owner/repo/pulls/{number}"}}}}"#
+    )
+}
+
+fn labeled_issue_json(number: u64, labels: &[&str]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|label| format!(r#"{{"name":"{label}"}}"#))
+        .collect();
+    issue_json(number).replace(
+        r#""labels":[]"#,
+        &format!(r#""labels":[{}]"#, labels.join(",")),
+    )
+}
+
+fn issues(numbers: &[u64]) -> Vec<String> {
+    numbers.iter().copied().map(issue_json).collect()
+}
+
+/// Serve `pages` of issues, linking each page to the next. Returns the base URL and
+/// the request paths seen so far.
+async fn start_mock_github(pages: Vec<Vec<String>>) -> (String, Arc<Mutex<Vec<String>>>) {
+    start_rate_limited_github(pages, 0, 0).await
+}
+
+/// Like `start_mock_github`, but the first `limited_requests` requests are rejected
+/// as rate limited until the Unix time `reset_at`
+async fn start_rate_limited_github(
+    pages: Vec<Vec<String>>,
+    limited_requests: usize,
+    reset_at: u64,
+) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let server_url = base_url.clone();
+    let seen = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let request = String::from_utf8_lossy(&request);
+            let path = request.split_whitespace().nth(1).unwrap_or("").to_string();
+            let request_count = {
+                let mut seen = seen.lock().unwrap();
+                seen.push(path.clone());
+                seen.len()
+            };
+            if request_count <= limited_requests {
+                let body = r#"{"message":"API rate limit exceeded"}"#;
+                let response = format!(
+                    "HTTP/1.1 403 Forbidden\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-RateLimit-Remaining: 0\r\nX-RateLimit-Reset: {reset_at}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+                continue;
+            }
+
+            // The first request has no page parameter; later ones come from the Link header
+            let page: usize = path
+                .split_once("&page=")
+                .and_then(|(_, page)| page.parse().ok())
+                .unwrap_or(1);
+            let body = format!(
+                "[{}]",
+                pages.get(page - 1).cloned().unwrap_or_default().join(",")
+            );
+            let link = if page < pages.len() {
+                format!(
+                    "Link: <{server_url}/repos/owner/repo/issues?per_page=100&page={}>; rel=\"next\", <{server_url}/repos/owner/repo/issues?per_page=100&page={}>; rel=\"last\"\r\n",
+                    page + 1,
+                    pages.len()
+                )
+            } else {
+                String::new()
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{link}Connection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+    });
+
+    (base_url, requests)
+}
+
+#[tokio::test]
+async fn test_fetch_issues_follows_link_header_across_pages() {
+    let (base_url, requests) = start_mock_github(vec![issues(&[1, 2]), issues(&[3])]).await;
+    let client = GitHubClient::new("test-token").with_base_url(base_url);
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    let numbers: Vec<_> = tasks
+        .iter()
+        .map(|task| task.external_id.clone().unwrap())
+        .collect();
+    assert_eq!(numbers, ["1", "2", "3"]);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_stops_at_empty_page() {
+    let (base_url, requests) =
+        start_mock_github(vec![issues(&[1]), issues(&[]), issues(&[3])]).await;
+    let client = GitHubClient::new("test-token").with_base_url(base_url);
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_respects_max_pages() {
+    let (base_url, requests) =
+        start_mock_github(vec![issues(&[1]), issues(&[2]), issues(&[3])]).await;
+    let client = GitHubClient::new("test-token")
+        .with_base_url(base_url)
+        .with_max_pages(2);
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_skips_pull_requests() {
+    let page = vec![
+        issue_json(1),
+        pull_request_json(2),
+        issue_json(3),
+        pull_request_json(4),
+    ];
+    let (base_url, _) = start_mock_github(vec![page]).await;
+    let client = GitHubClient::new("test-token").with_base_url(base_url);
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    let titles: Vec<_> = tasks.iter().map(|task| task.title.as_str()).collect();
+    assert_eq!(titles, ["Issue 1", "Issue 3"]);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_maps_labels_to_priority() {
+    let page = vec![
+        labeled_issue_json(1, &["bug", "P0"]),
+        labeled_issue_json(2, &["priority/LOW", "P0"]),
+        labeled_issue_json(3, &["bug"]),
+    ];
+    let (base_url, _) = start_mock_github(vec![page]).await;
+    let client = GitHubClient::new("test-token")
+        .with_base_url(base_url)
+        .with_label_priorities(HashMap::from([
+            ("P0".to_string(), Priority::High),
+            ("priority/low".to_string(), Priority::Low),
+        ]));
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    let priorities: Vec<_> = tasks.iter().map(|task| task.priority).collect();
+    assert_eq!(
+        priorities,
+        [Priority::High, Priority::Low, Priority::Medium]
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_issues_retries_after_rate_limit() {
+    // A reset time in the past means the limit has already been lifted
+    let (base_url, requests) = start_rate_limited_github(vec![issues(&[1])], 1, 1).await;
+    let client = GitHubClient::new("test-token")
+        .with_base_url(base_url)
+        .with_retry(1);
+
+    let tasks = client.fetch_issues("owner", "repo").await.unwrap();
+
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_reports_rate_limit_when_out_of_retries() {
+    let (base_url, requests) = start_rate_limited_github(vec![issues(&[1])], 2, 1).await;
+    let client = GitHubClient::new("test-token")
+        .with_base_url(base_url)
+        .with_retry(1);
+
+    let error = client.fetch_issues("owner", "repo").await.unwrap_err();
+
+    assert!(matches!(
+        error,
+        GitHubError::RateLimited { reset_at: Some(1) }
+    ));
+    assert!(error.to_string().contains("rate limit exceeded"));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fetch_issues_does_not_wait_for_a_distant_reset() {
+    let far_future = u64::MAX / 2;
+    let (base_url, requests) = start_rate_limited_github(vec![issues(&[1])], 1, far_future).await;
+    let client = GitHubClient::new("test-token")
+        .with_base_url(base_url)
+        .with_retry(3);
+
+    let error = client.fetch_issues("owner", "repo").await.unwrap_err();
+
+    assert!(matches!(error, GitHubError::RateLimited { .. }));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_next_page_url_picks_the_next_relation() {
+    let link = r#"<https://api.github.com/repositories/1/issues?page=2>; rel="next", <https://api.github.com/repositories/1/issues?page=5>; rel="last""#;
+    assert_eq!(
+        next_page_url(link).as_deref(),
+        Some("https://api.github.com/repositories/1/issues?page=2")
+    );
+
+    let last_page = r#"<https://api.github.com/repositories/1/issues?page=1>; rel="prev""#;
+    assert_eq!(next_page_url(last_page), None);
+}
@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::components::confirmation::Confirmation;
+use taskhub::tui::glob_preview::{expand_glob, glob_arguments, glob_preview, summarize_matches};
+use uuid::Uuid;
+
+/// A fresh scratch directory holding empty `files`
+fn dir_with(files: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("taskhub-glob-{}", Uuid::new_v4()));
+    for file in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+    dir
+}
+
+fn names(count: usize) -> Vec<String> {
+    (1..=count).map(|i| format!("{i}.log")).collect()
+}
+
+#[test]
+fn test_glob_arguments() {
+    assert_eq!(
+        glob_arguments("rm -f *.log build/?.o"),
+        ["*.log", "build/?.o"]
+    );
+    assert!(glob_arguments("rm notes.txt").is_empty());
+    // Quoted globs reach the program unexpanded
+    assert!(glob_arguments("rm '*.log' \"*.tmp\" \\*.bak").is_empty());
+}
+
+#[test]
+fn test_expand_glob_in_directory() {
+    let dir = dir_with(&["a.log", "b.log", "notes.txt", ".hidden.log", "logs/c.log"]);
+
+    assert_eq!(expand_glob("*.log", &dir), ["a.log", "b.log"]);
+    assert_eq!(expand_glob("?.log", &dir), ["a.log", "b.log"]);
+    assert_eq!(expand_glob("logs/*.log", &dir), ["logs/c.log"]);
+    assert_eq!(expand_glob("*/*.log", &dir), ["logs/c.log"]);
+    assert_eq!(
+        expand_glob("*", &dir),
+        ["a.log", "b.log", "logs", "notes.txt"]
+    );
+    // Hidden files only match a pattern that starts with a dot
+    assert_eq!(expand_glob(".*.log", &dir), [".hidden.log"]);
+    assert!(expand_glob("*.rs", &dir).is_empty());
+    assert!(expand_glob("missing/*", &dir).is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_summarize_matches() {
+    assert_eq!(summarize_matches(&[]), "matches no files");
+    assert_eq!(summarize_matches(&names(1)), "matches 1 file: 1.log");
+    assert_eq!(
+        summarize_matches(&names(5)),
+        "matches 5 files: 1.log, 2.log, 3.log, 4.log, 5.log"
+    );
+    assert_eq!(
+        summarize_matches(&names(12)),
+        "matches 12 files: 1.log, 2.log, 3.log, 4.log, 5.log and 7 more"
+    );
+}
+
+#[test]
+fn test_preview_only_for_destructive_commands_with_matches() {
+    let dir = dir_with(&["a.log", "b.log", "c.txt"]);
+
+    assert_eq!(
+        glob_preview("rm *.log", &dir).as_deref(),
+        Some("This matches 2 files: a.log, b.log")
+    );
+    // Matches of several globs are counted once
+    assert_eq!(
+        glob_preview("/bin/rm -f *.log a.* *.txt", &dir).as_deref(),
+        Some("This matches 3 files: a.log, b.log, c.txt")
+    );
+    assert_eq!(glob_preview("ls *.log", &dir), None);
+    assert_eq!(glob_preview("rm notes.txt", &dir), None);
+    assert_eq!(glob_preview("rm *.rs", &dir), None);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_destructive_glob_waits_for_confirmation() {
+    let dir = dir_with(&["a.log", "b.log"]);
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_preview_globs(true);
+    app.current_working_dir = dir.clone();
+
+    app.pending_command = Some("rm *.log".to_string());
+    app.handle_pending_commands().await;
+
+    assert!(app.running_command.is_none());
+    assert_eq!(
+        app.confirmation.as_ref().and_then(Confirmation::command),
+        Some("rm *.log")
+    );
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "This matches 2 files: a.log, b.log - run anyway? [y/N]"
+    );
+
+    // Declining keeps the files
+    app.handle_terminal_input('n');
+    app.handle_pending_commands().await;
+    assert!(app.running_command.is_none());
+    assert!(dir.join("a.log").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_preview_is_off_by_default() {
+    let dir = dir_with(&["a.log"]);
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.current_working_dir = dir.clone();
+
+    app.pending_command = Some("rm *.log".to_string());
+    app.handle_pending_commands().await;
+    assert!(app.confirmation.is_none());
+
+    // The command ran without asking; let it finish before cleaning up
+    let start = Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
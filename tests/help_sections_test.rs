@@ -0,0 +1,79 @@
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::commands::find_builtin;
+use taskhub::tui::help::{command_help, command_sections, key_help, key_sections};
+
+#[tokio::test]
+async fn test_every_listed_command_has_help() {
+    let db_pool = init_db(None).await.unwrap();
+    let app = App::new(db_pool);
+    let text = command_help(None).unwrap();
+
+    for command in &app.available_commands {
+        let builtin = find_builtin(command).unwrap();
+        assert!(
+            text.contains(&format!("{} - {}", builtin.usage, builtin.description)),
+            "{command} has no /help entry"
+        );
+    }
+}
+
+#[test]
+fn test_sections_are_not_empty() {
+    for section in command_sections().iter().chain(&key_sections()) {
+        assert!(!section.entries.is_empty(), "{} is empty", section.title);
+    }
+}
+
+#[test]
+fn test_unfiltered_help_lists_every_section() {
+    let text = command_help(None).unwrap();
+    assert!(text.starts_with("Available commands:"));
+    for section in command_sections() {
+        assert!(text.contains(&format!("{}:", section.title)));
+    }
+    assert!(text.contains("  /quit - Exit the application"));
+
+    let keys = key_help(None).unwrap();
+    assert!(keys.contains("TaskHub Keyboard Shortcuts"));
+    assert!(keys.contains("  Ctrl+A           Move cursor to beginning of line"));
+}
+
+#[test]
+fn test_filter_keeps_matching_entries_and_sections() {
+    let text = command_help(Some("HISTORY")).unwrap();
+    assert!(text.contains("/history [N]"));
+    assert!(text.contains("Re-run history command N"));
+    assert!(!text.contains("/quit"));
+    // Sections without a match are left out
+    assert!(!text.contains("Tasks:"));
+
+    let keys = key_help(Some("clipboard")).unwrap();
+    assert!(keys.contains("Ctrl+V"));
+    assert!(keys.contains("Middle Click"));
+    assert!(!keys.contains("Ctrl+A"));
+
+    assert_eq!(command_help(Some("no such thing")), None);
+}
+
+#[tokio::test]
+async fn test_help_command_with_filter() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    assert!(app.handle_builtin_command("/help task").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+    assert!(entry.output.contains("/task agenda"));
+    assert!(!entry.output.contains("/vacuum"));
+
+    app.handle_builtin_command("/help keys search").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.output.contains("Ctrl+F"));
+    assert!(!entry.output.contains("Ctrl+E"));
+
+    app.handle_builtin_command("/help xyzzy").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "No help entries match 'xyzzy'");
+}
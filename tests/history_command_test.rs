@@ -0,0 +1,90 @@
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn app_with_history(count: usize) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_history_manager(Some(100));
+    for i in 1..=count {
+        app.append_to_persistent_history(&format!("echo {i}")).await;
+    }
+    app
+}
+
+/// (number, time, command) of each listed line
+fn parse(output: &str) -> Vec<(usize, String, String)> {
+    output
+        .lines()
+        .map(|line| {
+            let mut parts = line.trim_start().splitn(3, "  ");
+            let number = parts.next().unwrap().parse().unwrap();
+            let time = parts.next().unwrap().to_string();
+            let command = parts.next().unwrap().to_string();
+            (number, time, command)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_history_lists_newest_last_with_default_count() {
+    let mut app = app_with_history(25).await;
+
+    assert!(app.handle_builtin_command("/history").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+
+    let lines = parse(&entry.output);
+    assert_eq!(lines.len(), 20);
+    assert_eq!(lines[0].0, 6);
+    assert_eq!(lines[0].2, "echo 6");
+    assert_eq!(lines[19].0, 25);
+    assert_eq!(lines[19].2, "echo 25");
+    // Numbers are right-aligned to the widest one
+    assert!(entry.output.starts_with(" 6  "));
+
+    // Times are HH:MM:SS
+    for (_, time, _) in &lines {
+        let fields: Vec<&str> = time.split(':').collect();
+        assert_eq!(fields.len(), 3, "{time}");
+        assert!(
+            fields
+                .iter()
+                .all(|f| f.len() == 2 && f.parse::<u32>().is_ok())
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_history_with_count() {
+    let mut app = app_with_history(5).await;
+
+    app.handle_builtin_command("/history 3").await;
+    let lines = parse(&app.command_history.last().unwrap().output);
+    let commands: Vec<_> = lines.iter().map(|(n, _, c)| (*n, c.as_str())).collect();
+    assert_eq!(commands, vec![(3, "echo 3"), (4, "echo 4"), (5, "echo 5")]);
+
+    // More than are stored lists them all, including the earlier /history
+    app.handle_builtin_command("/history 50").await;
+    let lines = parse(&app.command_history.last().unwrap().output);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5].2, "/history 3");
+
+    app.handle_builtin_command("/history zero").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Invalid count 'zero'\nUsage: /history [N]");
+}
+
+#[tokio::test]
+async fn test_history_empty_and_not_persisted() {
+    let mut app = app_with_history(0).await;
+    app.handle_builtin_command("/history").await;
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "No commands in history"
+    );
+
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.handle_builtin_command("/history").await;
+    assert!(!app.command_history.last().unwrap().success);
+}
@@ -0,0 +1,111 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, expand_history_reference};
+
+fn history() -> Vec<String> {
+    vec![
+        "ls -la".to_string(),
+        "git status".to_string(),
+        "cargo build".to_string(),
+    ]
+}
+
+#[test]
+fn test_expand_numbered_reference() {
+    let history = history();
+    assert_eq!(
+        expand_history_reference("!1", &history),
+        Some(Ok("ls -la".to_string()))
+    );
+    assert_eq!(
+        expand_history_reference("!3", &history),
+        Some(Ok("cargo build".to_string()))
+    );
+}
+
+#[test]
+fn test_expand_last_command() {
+    assert_eq!(
+        expand_history_reference("!!", &history()),
+        Some(Ok("cargo build".to_string()))
+    );
+    assert_eq!(
+        expand_history_reference("!!", &[]),
+        Some(Err("!!: no previous command".to_string()))
+    );
+}
+
+#[test]
+fn test_words_after_reference_are_appended() {
+    assert_eq!(
+        expand_history_reference("!3 --release", &history()),
+        Some(Ok("cargo build --release".to_string()))
+    );
+}
+
+#[test]
+fn test_out_of_range_reference_is_an_error() {
+    let history = history();
+    for reference in ["!0", "!4", "!99999999999999999999999"] {
+        let result = expand_history_reference(reference, &history);
+        assert!(matches!(result, Some(Err(_))), "{reference}: {result:?}");
+    }
+    assert_eq!(
+        expand_history_reference("!4", &history),
+        Some(Err("!4: no such command in history (1-3)".to_string()))
+    );
+    assert_eq!(
+        expand_history_reference("!1", &[]),
+        Some(Err("!1: history is empty".to_string()))
+    );
+}
+
+#[test]
+fn test_other_commands_are_not_references() {
+    let history = history();
+    for command in ["ls", "!", "!git", "!1a", "echo !1", "/history"] {
+        assert_eq!(
+            expand_history_reference(command, &history),
+            None,
+            "{command}"
+        );
+    }
+}
+
+async fn submit(app: &mut App, input: &str) {
+    app.current_input = input.to_string();
+    app.cursor_position = input.chars().count();
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+}
+
+#[tokio::test]
+async fn test_enter_substitutes_the_referenced_command() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = history();
+
+    submit(&mut app, "!2").await;
+    assert_eq!(app.pending_command.as_deref(), Some("git status"));
+    assert!(app.current_input.is_empty());
+
+    app.pending_command = None;
+    submit(&mut app, "!!").await;
+    assert_eq!(app.pending_command.as_deref(), Some("cargo build"));
+}
+
+#[tokio::test]
+async fn test_out_of_range_reference_records_an_error_entry() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = history();
+
+    submit(&mut app, "!42").await;
+    assert_eq!(app.pending_command, None);
+
+    app.handle_pending_commands().await;
+    assert!(app.running_command.is_none());
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, "!42");
+    assert!(!entry.success);
+    assert_eq!(entry.output, "!42: no such command in history (1-3)");
+}
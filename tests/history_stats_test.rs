@@ -0,0 +1,108 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use taskhub::db::init_db;
+use taskhub::history::{HistoryEntry, commands_per_day, exit_code_counts, most_run_commands};
+use taskhub::tui::app::App;
+
+fn entry(number: usize, command: &str, executed_at: Option<DateTime<Utc>>) -> HistoryEntry {
+    HistoryEntry {
+        number,
+        command: command.to_string(),
+        executed_at,
+    }
+}
+
+fn at(day: u32, hour: u32) -> Option<DateTime<Utc>> {
+    Some(Utc.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap())
+}
+
+fn sample_history() -> Vec<HistoryEntry> {
+    vec![
+        entry(1, "git status", at(13, 9)),
+        entry(2, "cargo test", at(13, 10)),
+        entry(3, "git status", at(14, 8)),
+        entry(4, "ls", at(14, 12)),
+        entry(5, "cargo test", at(14, 23)),
+        entry(6, "git status", at(15, 0)),
+        entry(7, "make", None),
+    ]
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+#[test]
+fn test_most_run_commands_are_counted_and_ranked() {
+    let top = most_run_commands(&sample_history(), 10);
+    let top: Vec<(&str, usize)> = top.iter().map(|(c, n)| (c.as_str(), *n)).collect();
+    // Ties are alphabetical
+    assert_eq!(
+        top,
+        vec![("git status", 3), ("cargo test", 2), ("ls", 1), ("make", 1)]
+    );
+}
+
+#[test]
+fn test_most_run_commands_respects_limit() {
+    let top = most_run_commands(&sample_history(), 2);
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[1], ("cargo test".to_string(), 2));
+    assert!(most_run_commands(&[], 5).is_empty());
+}
+
+#[test]
+fn test_surrounding_whitespace_does_not_split_counts() {
+    let history = vec![entry(1, "ls", None), entry(2, "  ls ", None)];
+    assert_eq!(most_run_commands(&history, 5), vec![("ls".to_string(), 2)]);
+}
+
+#[test]
+fn test_commands_per_day() {
+    let days = commands_per_day(&sample_history(), &Utc);
+    // The command without a recorded time is left out
+    assert_eq!(days, vec![(date(13), 2), (date(14), 3), (date(15), 1)]);
+}
+
+#[test]
+fn test_commands_per_day_uses_the_given_time_zone() {
+    let tz = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+    let days = commands_per_day(&sample_history(), &tz);
+    // 23:00 UTC on the 14th is already the 15th two hours east
+    assert_eq!(days, vec![(date(13), 2), (date(14), 2), (date(15), 2)]);
+}
+
+#[test]
+fn test_exit_code_counts() {
+    assert_eq!(exit_code_counts([0, 0, 1, 127, 0]), (3, 2));
+    assert_eq!(exit_code_counts([]), (0, 0));
+}
+
+#[tokio::test]
+async fn test_stats_command_summarizes_history() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_history_manager(Some(100));
+    for command in ["git status", "cargo test", "git status"] {
+        app.append_to_persistent_history(command).await;
+    }
+
+    assert!(app.handle_builtin_command("/stats").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success);
+    assert!(entry.output.starts_with("Commands in history: 3\n"));
+    assert!(
+        entry
+            .output
+            .contains("Most run:\n  2  git status\n  1  cargo test")
+    );
+    assert!(entry.output.contains("Per day:\n"));
+    // No process has exited in this session yet
+    assert!(!entry.output.contains("This session"));
+}
+
+#[tokio::test]
+async fn test_stats_without_saved_history() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.handle_builtin_command("/stats").await;
+    assert!(!app.command_history.last().unwrap().success);
+}
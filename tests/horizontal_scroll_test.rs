@@ -0,0 +1,115 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
+
+async fn create_app_with_output(output: &str) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(CommandEntry {
+        command: "ls".to_string(),
+        output: output.to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app
+}
+
+#[tokio::test]
+async fn test_max_line_width_ignores_ansi_sequences() {
+    let mut app = create_app_with_output("short\n\x1b[31mred text of width 20\x1b[0m").await;
+    assert_eq!(app.get_max_history_line_width(), 20);
+}
+
+#[tokio::test]
+async fn test_horizontal_scroll_clamped_to_widest_line() {
+    let mut app = create_app_with_output(&"x".repeat(30)).await;
+
+    app.scroll_horizontally(10);
+    assert_eq!(app.horizontal_scroll_offset, 10);
+
+    // Scrolling past the widest line stops at its last column
+    app.scroll_horizontally(100);
+    assert_eq!(app.horizontal_scroll_offset, 29);
+
+    // Scrolling back never goes below zero
+    app.scroll_horizontally(-100);
+    assert_eq!(app.horizontal_scroll_offset, 0);
+}
+
+#[tokio::test]
+async fn test_max_line_width_follows_history_changes() {
+    let mut app = create_app_with_output(&"x".repeat(30)).await;
+    app.command_history.push(CommandEntry {
+        command: "pwd".to_string(),
+        output: "/tmp".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    assert_eq!(app.get_max_history_line_width(), 30);
+
+    // The last entry is measured again as its output grows
+    app.command_history.last_mut().unwrap().output = "y".repeat(40);
+    assert_eq!(app.get_max_history_line_width(), 40);
+
+    app.clear_screen();
+    app.command_history.push(CommandEntry {
+        command: "ls".to_string(),
+        output: "a.txt".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    assert_eq!(app.get_max_history_line_width(), "a.txt".len());
+}
+
+#[tokio::test]
+async fn test_horizontal_scroll_without_history() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.scroll_horizontally(5);
+    assert_eq!(app.horizontal_scroll_offset, 0);
+}
+
+#[tokio::test]
+async fn test_shift_arrows_scroll_history() {
+    let mut app = create_app_with_output(&"y".repeat(50)).await;
+    app.current_input = "echo".to_string();
+    app.cursor_position = 2;
+
+    app.on_key_code(KeyCode::Right, KeyModifiers::SHIFT);
+    assert_eq!(app.horizontal_scroll_offset, 4);
+    // The input cursor is left alone
+    assert_eq!(app.cursor_position, 2);
+
+    app.on_key_code(KeyCode::Left, KeyModifiers::SHIFT);
+    assert_eq!(app.horizontal_scroll_offset, 0);
+    assert_eq!(app.cursor_position, 2);
+}
+
+#[tokio::test]
+async fn test_plain_arrows_scroll_only_when_input_empty() {
+    let mut app = create_app_with_output(&"z".repeat(50)).await;
+
+    app.on_key_code(KeyCode::Right, KeyModifiers::NONE);
+    assert_eq!(app.horizontal_scroll_offset, 4);
+
+    app.current_input = "ab".to_string();
+    app.cursor_position = 2;
+    app.on_key_code(KeyCode::Left, KeyModifiers::NONE);
+    assert_eq!(app.horizontal_scroll_offset, 4);
+    assert_eq!(app.cursor_position, 1);
+}
+
+#[tokio::test]
+async fn test_mouse_mapping_accounts_for_horizontal_scroll() {
+    let mut app = create_app_with_output(&"w".repeat(50)).await;
+    app.update_layout_areas(20, false, 0);
+
+    let before = app.map_mouse_to_content_line(2, 5).unwrap();
+    app.scroll_horizontally(8);
+    let after = app.map_mouse_to_content_line(2, 5).unwrap();
+
+    assert_eq!(before.0, after.0);
+    assert_eq!(after.1, before.1 + 8);
+}
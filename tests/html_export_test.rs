@@ -0,0 +1,138 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use std::fs;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::html_export::{css_color, escape_html, history_to_html, line_to_html};
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::CommandEntry;
+use uuid::Uuid;
+
+fn entry(command: &str, output: &str, success: bool) -> CommandEntry {
+    CommandEntry {
+        command: command.to_string(),
+        output: output.to_string(),
+        success,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    }
+}
+
+#[test]
+fn test_colored_line_to_html_spans() {
+    let line = Line::from(vec![
+        Span::styled("error", Style::default().fg(Color::Red)),
+        Span::raw(": "),
+        Span::styled(
+            "a < b",
+            Style::default()
+                .fg(Color::Rgb(255, 128, 0))
+                .bg(Color::Indexed(21))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        ),
+    ]);
+
+    assert_eq!(
+        line_to_html(&line, &Theme::default()),
+        "<span style=\"color:#cd0000\">error</span>: \
+         <span style=\"color:#ff8000;background-color:#0000ff;font-weight:bold;text-decoration:underline\">a &lt; b</span>"
+    );
+}
+
+#[test]
+fn test_base_colors_follow_theme_palette() {
+    let mut theme = Theme::default();
+    theme.palette[2] = Color::Rgb(1, 2, 3);
+    let line = Line::from(Span::styled("ok", Style::default().fg(Color::Green)));
+    assert_eq!(
+        line_to_html(&line, &theme),
+        "<span style=\"color:#010203\">ok</span>"
+    );
+
+    let palette = Theme::default().palette;
+    assert_eq!(css_color(Color::Indexed(9), &palette).unwrap(), "#ff0000");
+    assert_eq!(css_color(Color::Indexed(16), &palette).unwrap(), "#000000");
+    assert_eq!(css_color(Color::Indexed(231), &palette).unwrap(), "#ffffff");
+    assert_eq!(css_color(Color::Indexed(244), &palette).unwrap(), "#808080");
+    assert_eq!(css_color(Color::Reset, &palette), None);
+}
+
+#[test]
+fn test_reversed_text_swaps_colors() {
+    let line = Line::from(Span::styled(
+        "sel",
+        Style::default()
+            .fg(Color::Rgb(0, 0, 255))
+            .add_modifier(Modifier::REVERSED),
+    ));
+    assert_eq!(
+        line_to_html(&line, &Theme::default()),
+        "<span style=\"color:#1e1e1e;background-color:#0000ff\">sel</span>"
+    );
+}
+
+#[test]
+fn test_escape_html() {
+    assert_eq!(
+        escape_html("<a href=\"x\">Tom & Jerry's</a>"),
+        "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+    );
+}
+
+#[test]
+fn test_history_page_keeps_escape_sequence_colors() {
+    let history = vec![
+        entry("ls", "\x1b[34mdir\x1b[0m\tfile", true),
+        entry("cat <missing>", "no such file", false),
+    ];
+    let html = history_to_html(&history, &Theme::default());
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains(
+        "<span style=\"color:#00cd00;font-weight:bold\">$ ls</span>\n\
+         <span style=\"color:#0000ee\">dir</span>     file</pre>"
+    ));
+    assert!(html.contains(
+        "<span style=\"color:#cd0000;font-weight:bold\">$ cat &lt;missing&gt;</span>\nno such file</pre>"
+    ));
+}
+
+#[test]
+fn test_history_page_uses_emulated_styled_output() {
+    let mut emulated = entry("progress", "100%", true);
+    emulated.styled_output = Some(vec![Line::from(Span::styled(
+        "100%",
+        Style::default().fg(Color::Rgb(0, 200, 0)),
+    ))]);
+    let html = history_to_html(&[emulated], &Theme::default());
+    assert!(html.contains("<span style=\"color:#00c800\">100%</span>"));
+}
+
+#[tokio::test]
+async fn test_export_html_command_writes_file() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history
+        .push(entry("echo hi", "\x1b[31mhi\x1b[0m", true));
+
+    let path = std::env::temp_dir().join(format!("taskhub-export-{}.html", Uuid::new_v4()));
+    let command = format!("/export-html {}", path.display());
+    assert!(app.handle_builtin_command(&command).await);
+
+    let report = app.command_history.last().unwrap();
+    assert!(report.success, "{}", report.output);
+    assert_eq!(
+        report.output,
+        format!("Exported 1 commands to {}", path.display())
+    );
+    let html = fs::read_to_string(&path).unwrap();
+    assert!(html.contains("<span style=\"color:#cd0000\">hi</span>"));
+    fs::remove_file(&path).unwrap();
+
+    assert!(app.handle_builtin_command("/export-html").await);
+    let report = app.command_history.last().unwrap();
+    assert!(!report.success);
+    assert_eq!(report.output, "Usage: /export-html <path>");
+}
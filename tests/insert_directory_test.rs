@@ -0,0 +1,72 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::fs;
+use std::path::PathBuf;
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, find_git_root, shell_quote};
+use uuid::Uuid;
+
+/// Fresh, empty directory under the system temp directory
+fn scratch_dir() -> PathBuf {
+    let dir = std::env::temp_dir()
+        .canonicalize()
+        .unwrap()
+        .join(format!("taskhub-git-root-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_git_root_found_from_nested_directory() {
+    let scratch = scratch_dir();
+    let repo = scratch.join("repo");
+    let nested = repo.join("src").join("deeply").join("nested");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::create_dir_all(&nested).unwrap();
+
+    assert_eq!(find_git_root(&nested), Some(repo.clone()));
+    assert_eq!(find_git_root(&repo), Some(repo.clone()));
+
+    // The nearest repository wins, e.g. for submodules, whose .git is a file
+    let submodule = nested.join("vendor");
+    fs::create_dir_all(&submodule).unwrap();
+    fs::write(submodule.join(".git"), "gitdir: ../../.git/modules/vendor").unwrap();
+    assert_eq!(find_git_root(&submodule), Some(submodule.clone()));
+
+    fs::remove_dir_all(&scratch).unwrap();
+}
+
+#[test]
+fn test_no_git_root_outside_a_repository() {
+    let scratch = scratch_dir();
+    let plain = scratch.join("plain").join("dir");
+    fs::create_dir_all(&plain).unwrap();
+
+    // Skip if the temp directory itself happens to sit inside a repository
+    if find_git_root(&scratch).is_none() {
+        assert_eq!(find_git_root(&plain), None);
+    }
+
+    fs::remove_dir_all(&scratch).unwrap();
+}
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("/home/me/src/taskhub"), "/home/me/src/taskhub");
+    assert_eq!(shell_quote("/tmp/my dir"), "'/tmp/my dir'");
+    assert_eq!(shell_quote("/tmp/it's"), "'/tmp/it'\\''s'");
+    assert_eq!(shell_quote("~/x"), "'~/x'");
+}
+
+#[tokio::test]
+async fn test_alt_w_inserts_working_directory_at_cursor() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.current_input = "ls  -l".to_string();
+    app.cursor_position = 3;
+
+    app.on_key_code(KeyCode::Char('w'), KeyModifiers::ALT);
+
+    let cwd = shell_quote(&std::env::current_dir().unwrap().to_string_lossy());
+    assert_eq!(app.current_input, format!("ls {cwd} -l"));
+    assert_eq!(app.cursor_position, 3 + cwd.chars().count());
+}
@@ -0,0 +1,280 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::io::{self, Write};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, INPUT_WAIT_THRESHOLD, RunningCommand, likely_waiting_for_input};
+use taskhub::tui::pty_input::{InputTarget, key_to_pty_bytes, route_key};
+
+/// PTY input side that records what was written to it
+#[derive(Clone, Default)]
+struct RecordingWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn running_command(alternate_screen: bool, writer: Option<RecordingWriter>) -> RunningCommand {
+    RunningCommand {
+        command: "vim".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: None,
+        uses_alternate_screen: alternate_screen,
+        live_ansi_parser: None,
+        pty_writer: writer.map(|writer| Box::new(writer) as Box<dyn Write + Send>),
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    }
+}
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+fn ctrl_c() -> KeyEvent {
+    key(KeyCode::Char('c'), KeyModifiers::CONTROL)
+}
+
+#[tokio::test]
+async fn test_keys_go_to_app_without_pty_command() {
+    let mut app = create_test_app().await;
+    let letter = key(KeyCode::Char('a'), KeyModifiers::NONE);
+    assert_eq!(app.key_input_target(letter), InputTarget::App);
+
+    // Commands running through pipes cannot take input
+    app.running_command = Some(running_command(false, None));
+    assert_eq!(app.key_input_target(letter), InputTarget::App);
+    app.running_command = Some(running_command(true, None));
+    assert_eq!(app.key_input_target(letter), InputTarget::App);
+    assert!(!app.send_input(b"x"));
+}
+
+#[tokio::test]
+async fn test_typing_goes_to_pty_command_but_ctrl_c_kills_it() {
+    let mut app = create_test_app().await;
+    let writer = RecordingWriter::default();
+    app.running_command = Some(running_command(false, Some(writer.clone())));
+
+    assert_eq!(
+        app.key_input_target(key(KeyCode::Char('y'), KeyModifiers::NONE)),
+        InputTarget::Child
+    );
+    assert_eq!(
+        app.key_input_target(key(KeyCode::Enter, KeyModifiers::NONE)),
+        InputTarget::Child
+    );
+    assert_eq!(app.key_input_target(ctrl_c()), InputTarget::App);
+    assert_eq!(
+        app.key_input_target(key(
+            KeyCode::Char('C'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )),
+        InputTarget::App
+    );
+
+    assert!(app.send_input(b"answer\r"));
+    assert_eq!(writer.0.lock().unwrap().as_slice(), b"answer\r");
+}
+
+#[test]
+fn test_route_key_state_machine() {
+    let letter = key(KeyCode::Char('q'), KeyModifiers::NONE);
+    // (has PTY input, full screen) -> target for a letter and for Ctrl-C
+    let cases = [
+        ((false, false), InputTarget::App, InputTarget::App),
+        ((false, true), InputTarget::App, InputTarget::App),
+        ((true, false), InputTarget::Child, InputTarget::App),
+        ((true, true), InputTarget::Child, InputTarget::Child),
+    ];
+    for ((has_pty_input, full_screen), letter_target, ctrl_c_target) in cases {
+        assert_eq!(route_key(letter, has_pty_input, full_screen), letter_target);
+        assert_eq!(
+            route_key(ctrl_c(), has_pty_input, full_screen),
+            ctrl_c_target
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_keys_are_forwarded_to_full_screen_program() {
+    let mut app = create_test_app().await;
+    let writer = RecordingWriter::default();
+    app.running_command = Some(running_command(true, Some(writer.clone())));
+    assert_eq!(app.key_input_target(ctrl_c()), InputTarget::Child);
+
+    assert!(app.forward_key_to_child(key(KeyCode::Char(':'), KeyModifiers::NONE)));
+    assert!(app.forward_key_to_child(key(KeyCode::Char('q'), KeyModifiers::NONE)));
+    assert!(app.forward_key_to_child(key(KeyCode::Enter, KeyModifiers::NONE)));
+
+    assert_eq!(writer.0.lock().unwrap().as_slice(), b":q\r");
+    // Forwarded keys do not reach the input line
+    assert_eq!(app.current_input, "");
+}
+
+#[tokio::test]
+async fn test_ctrl_d_sends_eof_character_to_pty() {
+    let mut app = create_test_app().await;
+    assert!(!app.send_eof());
+
+    let writer = RecordingWriter::default();
+    app.running_command = Some(running_command(false, Some(writer.clone())));
+
+    assert!(app.send_eof());
+    assert_eq!(writer.0.lock().unwrap().as_slice(), [0x04]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_ctrl_d_closes_piped_stdin() {
+    let mut app = create_test_app().await;
+    let mut child = tokio::process::Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut running = running_command(false, None);
+    running.stdin = child.stdin.take();
+    app.running_command = Some(running);
+
+    assert!(app.send_eof());
+    assert!(app.running_command.as_ref().unwrap().stdin.is_none());
+    // cat only exits once its input has ended
+    let status = tokio::time::timeout(Duration::from_secs(5), child.wait())
+        .await
+        .expect("cat did not see end of input")
+        .unwrap();
+    assert!(status.success());
+
+    // Nothing left to close
+    assert!(!app.send_eof());
+}
+
+#[test]
+fn test_idle_detection_timing() {
+    assert!(!likely_waiting_for_input(Duration::ZERO, true));
+    assert!(!likely_waiting_for_input(
+        INPUT_WAIT_THRESHOLD - Duration::from_millis(1),
+        true
+    ));
+    assert!(likely_waiting_for_input(INPUT_WAIT_THRESHOLD, true));
+    assert!(likely_waiting_for_input(Duration::from_secs(60), true));
+
+    // A command whose input is closed cannot be waiting on it
+    assert!(!likely_waiting_for_input(Duration::from_secs(60), false));
+}
+
+#[tokio::test]
+async fn test_waiting_for_input_hint_follows_output() {
+    let mut app = create_test_app().await;
+    assert!(!app.command_may_be_waiting_for_input());
+
+    let mut running = running_command(false, Some(RecordingWriter::default()));
+    running.last_output_at = Instant::now() - INPUT_WAIT_THRESHOLD;
+    app.running_command = Some(running);
+    assert!(app.command_may_be_waiting_for_input());
+
+    // Fresh output means it is still working
+    app.running_command.as_mut().unwrap().last_output_at = Instant::now();
+    assert!(!app.command_may_be_waiting_for_input());
+
+    // Once the input is closed there is nothing to type into
+    let mut running = running_command(false, None);
+    running.last_output_at = Instant::now() - INPUT_WAIT_THRESHOLD;
+    app.running_command = Some(running);
+    assert!(!app.command_may_be_waiting_for_input());
+}
+
+#[test]
+fn test_key_encoding() {
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        Some(vec![0x03])
+    );
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::Up, KeyModifiers::NONE)),
+        Some(b"\x1b[A".to_vec())
+    );
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::Char('x'), KeyModifiers::ALT)),
+        Some(b"\x1bx".to_vec())
+    );
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::Char('é'), KeyModifiers::NONE)),
+        Some("é".as_bytes().to_vec())
+    );
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::F(5), KeyModifiers::NONE)),
+        Some(b"\x1b[15~".to_vec())
+    );
+    assert_eq!(
+        key_to_pty_bytes(key(KeyCode::Backspace, KeyModifiers::NONE)),
+        Some(vec![0x7f])
+    );
+
+    let mut release = key(KeyCode::Char('a'), KeyModifiers::NONE);
+    release.kind = KeyEventKind::Release;
+    assert_eq!(key_to_pty_bytes(release), None);
+}
+
+#[test]
+fn test_write_to_pty_needs_a_pty() {
+    let writer = RecordingWriter::default();
+    let mut running = running_command(false, Some(writer.clone()));
+    running.write_to_pty(b"print(1)\r").unwrap();
+    running.write_to_pty(&[0x04]).unwrap();
+    assert_eq!(writer.0.lock().unwrap().as_slice(), b"print(1)\r\x04");
+
+    let mut piped = running_command(false, None);
+    let error = piped.write_to_pty(b"x").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotConnected);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_typed_line_reaches_command_in_pty() {
+    let mut app = create_test_app().await;
+    app.execute_command("read line; echo \"got $line\"".to_string())
+        .await;
+    assert!(app.running_command.is_some());
+
+    // Typed keys go to the command rather than the input line
+    for code in [KeyCode::Char('h'), KeyCode::Char('i'), KeyCode::Enter] {
+        let typed = key(code, KeyModifiers::NONE);
+        assert_eq!(app.key_input_target(typed), InputTarget::Child);
+        assert!(app.forward_key_to_child(typed));
+    }
+    assert_eq!(app.current_input, "");
+
+    // read only returns once the line has reached it
+    let start = Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(app.running_command.is_none(), "command never got its input");
+    let output = &app.command_history.last().unwrap().output;
+    assert!(output.contains("got hi"), "unexpected output: {output:?}");
+}
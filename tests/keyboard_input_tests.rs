@@ -84,6 +84,38 @@ mod key_handling {
         assert_eq!(app.scroll_offset, 0);
     }
 
+    #[tokio::test]
+    async fn test_on_key_code_enter_keeps_leading_whitespace() {
+        let mut app = create_test_app().await;
+        app.current_input = "  echo hi  ".to_string();
+
+        app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+
+        // Only the trailing whitespace goes
+        assert_eq!(app.pending_command, Some("  echo hi".to_string()));
+        assert_eq!(app.current_input, "");
+
+        // Builtins are still recognised behind leading whitespace
+        app.pending_command = None;
+        app.current_input = " /help ".to_string();
+        app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.pending_command, Some("/help".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_on_key_code_enter_runs_command_with_arguments() {
+        let mut app = create_test_app().await;
+        app.current_input = "/cd /tmp".to_string();
+        app.update_command_filtering();
+        assert!(app.show_command_list);
+
+        app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+
+        // Arguments after a known command make it complete
+        assert_eq!(app.pending_command, Some("/cd /tmp".to_string()));
+        assert!(!app.show_command_list);
+    }
+
     #[tokio::test]
     async fn test_on_key_code_enter_with_command_list_complete_command() {
         let mut app = create_test_app().await;
@@ -264,12 +296,14 @@ mod key_handling {
                 command: "test1".to_string(),
                 output: "output1".to_string(),
                 success: true,
+                ..Default::default()
             });
         app.command_history
             .push(taskhub::tui::views::terminal::CommandEntry {
                 command: "test2".to_string(),
                 output: "output2".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         app.on_key_code(KeyCode::PageUp, KeyModifiers::NONE);
@@ -348,18 +382,21 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "file1.txt".to_string(),
                 success: true,
+                ..Default::default()
             });
         app.command_history
             .push(taskhub::tui::views::terminal::CommandEntry {
                 command: "pwd".to_string(),
                 output: "/home/user".to_string(),
                 success: true,
+                ..Default::default()
             });
         app.command_history
             .push(taskhub::tui::views::terminal::CommandEntry {
                 command: "echo hello".to_string(),
                 output: "hello".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start with empty input
@@ -402,12 +439,14 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "file1.txt".to_string(),
                 success: true,
+                ..Default::default()
             });
         app.command_history
             .push(taskhub::tui::views::terminal::CommandEntry {
                 command: "pwd".to_string(),
                 output: "/home/user".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start navigation from oldest command
@@ -443,6 +482,7 @@ mod key_handling {
                 command: "ls -la".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start with partial input
@@ -473,6 +513,7 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start history navigation
@@ -497,6 +538,7 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start history navigation
@@ -521,6 +563,7 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start history navigation
@@ -546,6 +589,7 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Start history navigation
@@ -591,12 +635,14 @@ mod key_handling {
                 command: "test1".to_string(),
                 output: "output1".to_string(),
                 success: true,
+                ..Default::default()
             });
         app.command_history
             .push(taskhub::tui::views::terminal::CommandEntry {
                 command: "test2".to_string(),
                 output: "output2".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         app.scroll_offset = 0;
@@ -648,6 +694,7 @@ mod key_handling {
                 command: "ls".to_string(),
                 output: "files".to_string(),
                 success: true,
+                ..Default::default()
             });
 
         // Activate command list
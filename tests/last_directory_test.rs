@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::PathBuf;
+use taskhub::config::state::{load_last_directory, save_last_directory};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use uuid::Uuid;
+
+/// Fresh, empty directory under the system temp directory
+fn scratch_dir() -> PathBuf {
+    let dir = std::env::temp_dir()
+        .canonicalize()
+        .unwrap()
+        .join(format!("taskhub-last-dir-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_save_and_restore_last_directory() {
+    let scratch = scratch_dir();
+    let dir = scratch.join("my projects").join("taskhub");
+    fs::create_dir_all(&dir).unwrap();
+    // The state directory is created on first save
+    let file = scratch.join("state").join("last_directory");
+
+    save_last_directory(&file, &dir).unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        dir.to_string_lossy().as_ref()
+    );
+    assert_eq!(load_last_directory(&file), Some(dir.clone()));
+
+    // Saving again replaces the remembered directory
+    save_last_directory(&file, &scratch).unwrap();
+    assert_eq!(load_last_directory(&file), Some(scratch.clone()));
+
+    fs::remove_dir_all(&scratch).unwrap();
+}
+
+#[test]
+fn test_restore_skips_missing_directory() {
+    let scratch = scratch_dir();
+    let file = scratch.join("last_directory");
+    assert_eq!(load_last_directory(&file), None);
+
+    let gone = scratch.join("removed");
+    fs::create_dir(&gone).unwrap();
+    save_last_directory(&file, &gone).unwrap();
+    fs::remove_dir(&gone).unwrap();
+    assert_eq!(load_last_directory(&file), None);
+
+    fs::remove_dir_all(&scratch).unwrap();
+}
+
+// The only test in this file that changes the process's working directory
+#[tokio::test]
+async fn test_cd_changes_and_remembers_directory() {
+    let scratch = scratch_dir();
+    let file = scratch.join("last_directory");
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_last_directory_file(file.clone());
+    let original = std::env::current_dir().unwrap();
+
+    let command = format!("/cd {}", scratch.display());
+    assert!(app.handle_builtin_command(&command).await);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.success, "{}", entry.output);
+    assert_eq!(std::env::current_dir().unwrap(), scratch);
+    assert_eq!(load_last_directory(&file), Some(scratch.clone()));
+
+    app.handle_builtin_command("/cd no-such-dir").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Not a directory: no-such-dir");
+    assert_eq!(load_last_directory(&file), Some(scratch.clone()));
+
+    std::env::set_current_dir(&original).unwrap();
+    fs::remove_dir_all(&scratch).unwrap();
+}
@@ -0,0 +1,167 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::layout::Rect;
+use taskhub::config::settings::{Settings, UiConfig};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::{
+    CommandEntry, TerminalDisplayState, content_area, draw_terminal,
+};
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+#[test]
+fn test_ui_config_layout_defaults() {
+    let ui = UiConfig::default();
+    assert_eq!(ui.input_height, 3);
+    assert_eq!(ui.command_list_max_items, 8);
+}
+
+#[tokio::test]
+async fn test_default_layout_matches_previous_behavior() {
+    let mut app = create_test_app().await;
+    app.update_layout_areas(30, false, 0);
+
+    assert_eq!(app.history_area_start, 0);
+    assert_eq!(app.history_area_height, 27);
+    assert_eq!(app.input_area_start, 27);
+}
+
+#[tokio::test]
+async fn test_layout_with_taller_input_area() {
+    let mut app = create_test_app().await.with_layout(6, 8);
+    app.update_layout_areas(30, false, 0);
+
+    assert_eq!(app.history_area_height, 24);
+    assert_eq!(app.input_area_start, 24);
+
+    app.update_layout_areas(30, true, 5);
+    assert_eq!(app.history_area_height, 19);
+    assert_eq!(app.input_area_start, 24);
+}
+
+#[tokio::test]
+async fn test_input_height_keeps_room_for_borders() {
+    let app = create_test_app().await.with_layout(1, 0);
+    assert_eq!(app.input_area_height, 3);
+    assert_eq!(app.command_list_max_items, 1);
+}
+
+#[tokio::test]
+async fn test_command_list_size_uses_configured_limit() {
+    let mut app = create_test_app().await.with_layout(3, 3);
+    assert_eq!(app.command_list_size(), 0);
+
+    // "/" matches every built-in command, more than the configured limit
+    app.current_input = "/".to_string();
+    app.update_command_filtering();
+    assert!(app.get_filtered_commands().len() > 3);
+    assert_eq!(app.command_list_size(), 5);
+
+    let mut app = app.with_layout(3, 20);
+    app.update_command_filtering();
+    let expected = app.get_filtered_commands().len() as u16 + 2;
+    assert_eq!(app.command_list_size(), expected);
+}
+
+#[tokio::test]
+async fn test_mouse_mapping_with_taller_input_area() {
+    let mut app = create_test_app().await.with_layout(8, 8);
+    for i in 0..20 {
+        app.command_history.push(CommandEntry {
+            command: format!("cmd_{i}"),
+            output: String::new(),
+            success: true,
+            ..Default::default()
+        });
+    }
+
+    // 40 content lines, history area of 30 - 8 = 22 rows with 20 visible lines
+    app.update_layout_areas(30, false, 0);
+    assert_eq!(app.history_area_height, 22);
+
+    // The first visible row maps to the line 20 lines above the bottom
+    assert_eq!(app.map_mouse_to_content_line(1, 1), Some((20, 0)));
+    assert_eq!(app.map_mouse_to_content_line(20, 1), Some((39, 0)));
+}
+
+#[test]
+fn test_content_area_is_capped_and_centered() {
+    let area = Rect::new(0, 0, 200, 40);
+
+    assert_eq!(content_area(area, Some(80)), Rect::new(60, 0, 80, 40));
+    assert_eq!(content_area(area, Some(199)), Rect::new(0, 0, 199, 40));
+    // Narrow terminals and unset or zero widths use the whole area
+    assert_eq!(content_area(area, Some(300)), area);
+    assert_eq!(content_area(area, Some(0)), area);
+    assert_eq!(content_area(area, None), area);
+
+    let settings = Settings::from_toml("[ui]\nmax_content_width = 100").unwrap();
+    assert_eq!(settings.ui.max_content_width, Some(100));
+    assert_eq!(UiConfig::default().max_content_width, None);
+}
+
+#[test]
+fn test_terminal_constrained_to_max_width_on_wide_screen() {
+    let history = vec![CommandEntry {
+        command: "cat wide.log".to_string(),
+        output: "x".repeat(150),
+        success: true,
+        ..Default::default()
+    }];
+    let theme = Theme::default();
+    let mut terminal = Terminal::new(TestBackend::new(200, 12)).unwrap();
+
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState::new(&history, &theme);
+            draw_terminal(f, content_area(f.area(), Some(80)), &state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    for row in 0..12 {
+        // Nothing is drawn outside columns 60..140
+        for column in (0..60).chain(140..200) {
+            assert_eq!(buffer[(column, row)].symbol(), " ", "({column}, {row})");
+        }
+    }
+    // The borders sit at the edges of the content area
+    assert_eq!(buffer[(60, 0)].symbol(), "┌");
+    assert_eq!(buffer[(139, 0)].symbol(), "┐");
+    assert_eq!(buffer[(61, 1)].symbol(), ">");
+    // The long output line is cut off at the right border
+    assert_eq!(buffer[(138, 2)].symbol(), "x");
+    assert_eq!(buffer[(139, 2)].symbol(), "│");
+}
+
+#[tokio::test]
+async fn test_mouse_columns_relative_to_content_area() {
+    let mut app = create_test_app().await.with_max_content_width(Some(80));
+    app.content_left = 60;
+    app.command_history.push(CommandEntry {
+        command: "echo hello".to_string(),
+        output: "hello".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app.update_layout_areas(30, false, 0);
+
+    let click = |kind, column| MouseEvent {
+        kind,
+        column,
+        row: 2,
+        modifiers: KeyModifiers::NONE,
+    };
+    // Column 63 is the fourth column of the history text: "hello" from its 'l'
+    app.on_mouse_event(click(MouseEventKind::Down(MouseButton::Left), 63));
+    app.on_mouse_event(click(MouseEventKind::Drag(MouseButton::Left), 65));
+
+    assert_eq!(app.selection_start, Some((1, 2)));
+    assert_eq!(app.selection_end, Some((1, 4)));
+}
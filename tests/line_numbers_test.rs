@@ -0,0 +1,118 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::{
+    CommandEntry, TerminalDisplayState, draw_terminal, line_number_width,
+};
+
+fn entry(command: &str, output: &str) -> CommandEntry {
+    CommandEntry {
+        command: command.to_string(),
+        output: output.to_string(),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    }
+}
+
+fn ten_lines() -> String {
+    (1..=10)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render(history: &[CommandEntry], show_line_numbers: bool) -> Buffer {
+    let theme = Theme::default();
+    let mut terminal = Terminal::new(TestBackend::new(40, 22)).unwrap();
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState {
+                show_line_numbers,
+                ..TerminalDisplayState::new(history, &theme)
+            };
+            draw_terminal(f, f.area(), &state);
+        })
+        .unwrap();
+    terminal.backend().buffer().clone()
+}
+
+/// Text of a row inside the history border
+fn row_text(buffer: &Buffer, y: u16) -> String {
+    (1..buffer.area.width - 1)
+        .map(|x| buffer[(x, y)].symbol())
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_line_number_width() {
+    assert_eq!(line_number_width("one"), 2);
+    assert_eq!(line_number_width(""), 2);
+    assert_eq!(line_number_width(&ten_lines()), 3);
+    assert_eq!(line_number_width(&"x\n".repeat(100)), 4);
+}
+
+#[test]
+fn test_numbers_restart_for_each_command() {
+    let history = vec![entry("seq", &ten_lines()), entry("ls", "a\nb")];
+
+    let buffer = render(&history, true);
+    assert_eq!(row_text(&buffer, 1), "> seq");
+    assert_eq!(row_text(&buffer, 2), " 1 line 1");
+    assert_eq!(row_text(&buffer, 11), "10 line 10");
+    assert_eq!(row_text(&buffer, 13), "> ls");
+    assert_eq!(row_text(&buffer, 14), "1 a");
+    assert_eq!(row_text(&buffer, 15), "2 b");
+
+    let buffer = render(&history, false);
+    assert_eq!(row_text(&buffer, 2), "line 1");
+    assert_eq!(row_text(&buffer, 14), "a");
+}
+
+#[tokio::test]
+async fn test_line_numbers_widen_history_and_keep_mouse_mapping() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(entry("seq", &ten_lines()));
+    app.update_layout_areas(30, false, 0);
+
+    assert_eq!(app.get_max_history_line_width(), "line 10".len());
+    assert_eq!(app.map_mouse_to_content_line(2, 1), Some((1, 0)));
+
+    app.show_line_numbers = true;
+    assert_eq!(app.get_max_history_line_width(), "10 line 10".len());
+
+    // Command lines are not numbered, output lines start after the 3-column gutter
+    assert_eq!(app.map_mouse_to_content_line(1, 3), Some((0, 2)));
+    assert_eq!(app.map_mouse_to_content_line(2, 4), Some((1, 0)));
+    assert_eq!(app.map_mouse_to_content_line(2, 9), Some((1, 5)));
+    // Clicks on the gutter go to the start of the line
+    assert_eq!(app.map_mouse_to_content_line(2, 1), Some((1, 0)));
+
+    // Dragging over "line" on the first output line selects exactly that word
+    let mouse = |kind, column| MouseEvent {
+        kind,
+        column,
+        row: 2,
+        modifiers: KeyModifiers::NONE,
+    };
+    app.on_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left), 4));
+    app.on_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 8));
+    assert_eq!(app.selection_start, Some((1, 0)));
+    assert_eq!(app.selection_end, Some((1, 4)));
+
+    app.handle_builtin_command("/line-numbers off").await;
+    assert!(!app.show_line_numbers);
+    assert!(app.handle_builtin_command("/line-numbers on").await);
+    assert!(app.show_line_numbers);
+    app.handle_builtin_command("/line-numbers maybe").await;
+    assert!(!app.command_history.last().unwrap().success);
+}
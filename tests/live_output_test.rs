@@ -0,0 +1,224 @@
+use std::time::Instant;
+use taskhub::config::settings::StderrMode;
+use taskhub::db::init_db;
+use taskhub::tui::ansi_parser::AnsiParser;
+use taskhub::tui::app::{App, OutputLine, RunningCommand};
+use taskhub::tui::views::terminal::{CommandEntry, OutputStream};
+use tokio::sync::mpsc;
+
+/// App running a fake command with a live terminal, and the sender its output
+/// arrives on
+async fn app_with_running_command() -> (App, mpsc::UnboundedSender<OutputLine>) {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    app.command_history.push(CommandEntry {
+        command: "download".to_string(),
+        output: "Running...".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app.running_command = Some(RunningCommand {
+        command: "download".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: Some(receiver),
+        uses_alternate_screen: false,
+        live_ansi_parser: Some(AnsiParser::new(80, 24)),
+        pty_writer: None,
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    });
+
+    (app, sender)
+}
+
+fn send(sender: &mpsc::UnboundedSender<OutputLine>, lines: &[&str]) {
+    for line in lines {
+        sender.send(OutputLine::Stdout(line.to_string())).unwrap();
+    }
+}
+
+fn shown_output(app: &App) -> &str {
+    &app.command_history.last().unwrap().output
+}
+
+#[tokio::test]
+async fn test_carriage_return_progress_bar_updates_one_line() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    // Each update arrives in its own read, ending in a carriage return
+    send(&sender, &["Downloading", "[##        ] 20%\r"]);
+    app.check_running_command().await;
+    assert_eq!(
+        shown_output(&app),
+        "Downloading\n[##        ] 20%\nRunning..."
+    );
+
+    send(&sender, &["[#####     ] 50%\r", "[##########] 100%"]);
+    app.check_running_command().await;
+    assert_eq!(
+        shown_output(&app),
+        "Downloading\n[##########] 100%\nRunning..."
+    );
+
+    send(&sender, &["done"]);
+    app.check_running_command().await;
+    assert_eq!(
+        shown_output(&app),
+        "Downloading\n[##########] 100%\ndone\nRunning..."
+    );
+}
+
+#[tokio::test]
+async fn test_leading_carriage_return_redraws_line() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    send(&sender, &["\r 10%", "\r 60%", "\r 90%"]);
+    app.check_running_command().await;
+    assert_eq!(shown_output(&app), " 90%\nRunning...");
+}
+
+#[tokio::test]
+async fn test_live_output_keeps_colors() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    send(
+        &sender,
+        &["\x1b[32mok\x1b[0m 1/2\r", "\x1b[32mok\x1b[0m 2/2"],
+    );
+    app.check_running_command().await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.output, "ok 2/2\nRunning...");
+    let styled = entry.styled_output.as_ref().unwrap();
+    assert_eq!(styled[0].spans[0].content, "ok");
+    assert_eq!(
+        styled[0].spans[0].style.fg,
+        Some(ratatui::style::Color::Green)
+    );
+}
+
+#[tokio::test]
+async fn test_output_without_redraws_is_shown_line_by_line() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    send(&sender, &["one", "two"]);
+    app.check_running_command().await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.output, "one\ntwo\nRunning...");
+    // Lines keep their arrival times
+    assert_eq!(entry.output_times.len(), 2);
+    assert!(entry.styled_output.is_none());
+}
+
+#[tokio::test]
+async fn test_partial_line_is_replaced_when_rest_arrives() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    // A prompt with no newline yet is shown right away
+    sender
+        .send(OutputLine::PartialStdout("foo".to_string()))
+        .unwrap();
+    app.check_running_command().await;
+    assert_eq!(shown_output(&app), "foo\nRunning...");
+
+    sender.send(OutputLine::Stdout("bar".to_string())).unwrap();
+    app.check_running_command().await;
+    assert_eq!(shown_output(&app), "foobar\nRunning...");
+
+    send(&sender, &["next"]);
+    app.check_running_command().await;
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.output, "foobar\nnext\nRunning...");
+    assert_eq!(entry.output_times.len(), 2);
+}
+
+#[tokio::test]
+async fn test_partial_line_redrawn_in_pieces() {
+    let (mut app, sender) = app_with_running_command().await;
+
+    for piece in ["10%", "\r20%", "\r30%"] {
+        sender
+            .send(OutputLine::PartialStdout(piece.to_string()))
+            .unwrap();
+    }
+    app.check_running_command().await;
+    assert_eq!(shown_output(&app), "30%\nRunning...");
+
+    sender
+        .send(OutputLine::Stdout(" done".to_string()))
+        .unwrap();
+    send(&sender, &["next"]);
+    app.check_running_command().await;
+    assert_eq!(shown_output(&app), "30% done\nnext\nRunning...");
+}
+
+/// Progress bar output on stdout with a warning on stderr part way through
+async fn app_with_progress_and_warning(mode: StderrMode) -> App {
+    let (app, sender) = app_with_running_command().await;
+    let mut app = app.with_stderr_mode(mode);
+
+    send(&sender, &["Downloading", "[##        ] 20%\r"]);
+    sender
+        .send(OutputLine::Stderr("warning: slow mirror".to_string()))
+        .unwrap();
+    send(&sender, &["[##########] 100%", "done"]);
+    app.check_running_command().await;
+    app
+}
+
+#[tokio::test]
+async fn test_live_output_splits_stderr_after_stdout() {
+    let app = app_with_progress_and_warning(StderrMode::Split).await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(
+        entry.output,
+        "Downloading\n[##########] 100%\ndone\nwarning: slow mirror\nRunning..."
+    );
+    assert_eq!(
+        entry.output_streams,
+        [
+            OutputStream::Stdout,
+            OutputStream::Stdout,
+            OutputStream::Stdout,
+            OutputStream::Stderr
+        ]
+    );
+    assert_eq!(entry.styled_output.as_ref().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_live_output_merges_stderr_where_it_arrived() {
+    let app = app_with_progress_and_warning(StderrMode::Merge).await;
+
+    // The warning arrived while the progress bar was being redrawn, so it shows
+    // above the bar's final state
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(
+        entry.output,
+        "Downloading\nwarning: slow mirror\n[##########] 100%\ndone\nRunning..."
+    );
+    assert_eq!(
+        entry.output_streams,
+        [
+            OutputStream::Stdout,
+            OutputStream::Stderr,
+            OutputStream::Stdout,
+            OutputStream::Stdout
+        ]
+    );
+    assert_eq!(entry.styled_output.as_ref().unwrap().len(), 4);
+}
@@ -104,3 +104,58 @@ async fn test_scroll_offset_coordinate_mapping() {
     // With scroll offset of 10, clicking on row 5 should map to content row 14
     assert_eq!(content_row, 14);
 }
+
+async fn create_app_with_history(entries: usize) -> App {
+    let db_pool = init_db(None).await.expect("Failed to initialize database");
+    let mut app = App::new(db_pool);
+    for i in 0..entries {
+        app.command_history
+            .push(taskhub::tui::views::terminal::CommandEntry {
+                command: format!("cmd_{i}"),
+                output: String::new(),
+                success: true,
+                ..Default::default()
+            });
+    }
+    app
+}
+
+#[tokio::test]
+async fn test_same_click_maps_per_layout() {
+    // 20 entries without output make 40 content lines
+    let mut app = create_app_with_history(20).await;
+
+    // Two-area layout: 21 history rows, 19 visible lines starting at line 21
+    app.update_layout_areas(24, false, 0);
+    assert_eq!(app.map_mouse_to_content_line(5, 3), Some((25, 2)));
+    assert_eq!(app.map_mouse_to_content_line(17, 3), Some((37, 2)));
+
+    // Three-area layout: 16 history rows, 14 visible lines starting at line 26
+    app.update_layout_areas(24, true, 5);
+    assert_eq!(app.map_mouse_to_content_line(5, 3), Some((30, 2)));
+
+    // Row 17 is inside the command list, not the history
+    assert_eq!(app.map_mouse_to_content_line(17, 3), None);
+    // Row 15 is the bottom border of the shrunken history area
+    assert_eq!(app.map_mouse_to_content_line(15, 3), None);
+    // Row 14 is the last visible history line
+    assert_eq!(app.map_mouse_to_content_line(14, 3), Some((39, 2)));
+}
+
+#[tokio::test]
+async fn test_click_in_command_list_does_not_start_selection() {
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    let mut app = create_app_with_history(20).await;
+    app.update_layout_areas(24, true, 5);
+
+    app.on_mouse_event(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 3,
+        row: 17,
+        modifiers: KeyModifiers::NONE,
+    });
+
+    assert!(!app.is_selecting);
+    assert_eq!(app.selection_start, None);
+}
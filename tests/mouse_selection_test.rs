@@ -13,11 +13,13 @@ async fn test_mouse_selection_behavior() {
         command: "echo hello world".to_string(),
         output: "hello world".to_string(),
         success: true,
+        ..Default::default()
     });
     app.command_history.push(CommandEntry {
         command: "ls -la".to_string(),
         output: "file1.txt\nfile2.txt\ndirectory/".to_string(),
         success: true,
+        ..Default::default()
     });
 
     // Set up terminal layout
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+use taskhub::config::settings::Settings;
+use taskhub::db::{database_directory, init_db};
+use taskhub::tui::app::App;
+
+#[test]
+fn test_database_directory_of_configured_path() {
+    assert_eq!(
+        database_directory(Some(Path::new("/var/lib/taskhub/tasks.db"))),
+        Some(PathBuf::from("/var/lib/taskhub"))
+    );
+    assert_eq!(
+        database_directory(Some(Path::new("tasks.db"))),
+        Some(PathBuf::from("."))
+    );
+    assert_eq!(database_directory(Some(Path::new(":memory:"))), None);
+}
+
+#[test]
+fn test_database_directory_defaults_to_data_dir() {
+    let dir = database_directory(None).unwrap();
+    if let Some(data_dir) = dirs::data_dir() {
+        assert_eq!(dir, data_dir.join("taskhub"));
+    }
+}
+
+#[test]
+fn test_config_directory() {
+    // Tests run from the crate root, which has config/Settings.toml
+    assert_eq!(Settings::config_directory(), PathBuf::from("config"));
+}
+
+#[tokio::test]
+async fn test_open_data_reports_unopenable_directories() {
+    let db_pool = init_db(None).await.unwrap();
+    let settings = Settings {
+        database_path: Some(":memory:".to_string()),
+        ..Settings::default()
+    };
+    let mut app = App::new(db_pool).with_settings(settings);
+
+    assert!(app.handle_builtin_command("/open-data").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "The database is in memory");
+
+    app.settings.database_path = Some("/no/such/dir/tasks.db".to_string());
+    app.handle_builtin_command("/open-data").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Directory not found: /no/such/dir");
+}
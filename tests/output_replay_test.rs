@@ -0,0 +1,103 @@
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::replay::ReplaySchedule;
+use taskhub::tui::views::terminal::{CommandEntry, OutputStream};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_schedule_delays_follow_original_timing() {
+    let schedule = ReplaySchedule::new(&[ms(100), ms(150), ms(400)], 1.0);
+    assert_eq!(schedule.delays(), [ms(100), ms(50), ms(250)]);
+}
+
+#[test]
+fn test_schedule_speed_multiplier_scales_delays() {
+    let schedule = ReplaySchedule::new(&[ms(100), ms(150), ms(400)], 2.0);
+    assert_eq!(schedule.delays(), [ms(50), ms(25), ms(125)]);
+
+    let slow = ReplaySchedule::new(&[ms(100)], 0.5);
+    assert_eq!(slow.delays(), [ms(200)]);
+
+    // Nonsense speeds fall back to the original pace
+    let invalid = ReplaySchedule::new(&[ms(100)], 0.0);
+    assert_eq!(invalid.delays(), [ms(100)]);
+}
+
+#[test]
+fn test_schedule_never_goes_back_in_time() {
+    // Stderr lines can be recorded earlier than the stdout lines shown before them
+    let schedule = ReplaySchedule::new(&[ms(300), ms(100), ms(400)], 1.0);
+    assert_eq!(schedule.delays(), [ms(300), ms(0), ms(100)]);
+}
+
+#[test]
+fn test_schedule_lines_due() {
+    let schedule = ReplaySchedule::new(&[ms(0), ms(100), ms(200)], 1.0);
+    assert_eq!(schedule.len(), 3);
+    assert_eq!(schedule.lines_due(ms(0)), 1);
+    assert_eq!(schedule.lines_due(ms(99)), 1);
+    assert_eq!(schedule.lines_due(ms(100)), 2);
+    assert_eq!(schedule.lines_due(ms(1000)), 3);
+}
+
+async fn app_with_recorded_output() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(CommandEntry {
+        command: "build".to_string(),
+        output: "compiling\nwarning\ndone".to_string(),
+        success: true,
+        output_streams: vec![
+            OutputStream::Stdout,
+            OutputStream::Stderr,
+            OutputStream::Stdout,
+        ],
+        output_times: vec![ms(0), ms(0), ms(150)],
+        ..Default::default()
+    });
+    app
+}
+
+#[tokio::test]
+async fn test_replay_reveals_lines_as_they_come_due() {
+    let mut app = app_with_recorded_output().await;
+
+    assert!(app.handle_builtin_command("/replay").await);
+    assert!(app.replay.is_some());
+
+    app.advance_replay();
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, "/replay");
+    assert_eq!(entry.output, "compiling\nwarning");
+    assert_eq!(
+        entry.output_streams,
+        [OutputStream::Stdout, OutputStream::Stderr]
+    );
+
+    tokio::time::sleep(ms(200)).await;
+    app.advance_replay();
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "compiling\nwarning\ndone"
+    );
+    assert!(app.replay.is_none());
+}
+
+#[tokio::test]
+async fn test_replay_rejects_bad_speed_and_missing_output() {
+    let mut app = app_with_recorded_output().await;
+    app.handle_builtin_command("/replay fast").await;
+    assert!(!app.command_history.last().unwrap().success);
+    assert!(app.replay.is_none());
+
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut empty = App::new(db_pool);
+    empty.handle_builtin_command("/replay 2x").await;
+    let entry = empty.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "No command output to replay");
+}
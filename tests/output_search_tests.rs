@@ -1,6 +1,7 @@
 use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::config::settings::SearchScrollMode;
 use taskhub::db::init_db;
-use taskhub::tui::app::{App, SearchMode};
+use taskhub::tui::app::{App, SearchMode, build_search_regex};
 use taskhub::tui::views::terminal::CommandEntry;
 
 async fn create_test_app() -> App {
@@ -17,11 +18,13 @@ async fn test_output_search_case_sensitivity_toggle() {
         command: "echo Hello".to_string(),
         output: "Hello World\nGoodbye world".to_string(),
         success: true,
+        ..Default::default()
     };
     let entry2 = CommandEntry {
         command: "echo WORLD".to_string(),
         output: "WORLD of testing".to_string(),
         success: true,
+        ..Default::default()
     };
 
     app.command_history.push(entry1);
@@ -62,6 +65,10 @@ async fn test_output_search_case_sensitivity_toggle() {
     app.on_key_code(KeyCode::Tab, KeyModifiers::NONE);
     assert_eq!(app.output_search_mode, SearchMode::Regex);
 
+    // Toggle to whole-word mode
+    app.on_key_code(KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(app.output_search_mode, SearchMode::WholeWord);
+
     // Toggle back to case-insensitive
     app.on_key_code(KeyCode::Tab, KeyModifiers::NONE);
     assert_eq!(app.output_search_mode, SearchMode::CaseInsensitive);
@@ -113,11 +120,13 @@ async fn test_output_search_regex_mode() {
         command: "echo test123".to_string(),
         output: "test123\ntest456\nNumber: 789\nEmail: user@example.com".to_string(),
         success: true,
+        ..Default::default()
     };
     let entry2 = CommandEntry {
         command: "cat file.txt".to_string(),
         output: "line1: hello\nline2: world123\nline3: abc123def".to_string(),
         success: true,
+        ..Default::default()
     };
 
     app.command_history.push(entry1);
@@ -189,3 +198,184 @@ async fn test_output_search_regex_mode() {
     let status = app.get_output_search_status();
     assert!(status.contains("[.*]")); // Regex indicator
 }
+
+#[test]
+fn test_search_regex_rejects_oversized_pattern() {
+    // Nested counted repetition compiles to an enormous program
+    let result = build_search_regex(r"(\w{100}){100}", 1 << 20);
+    assert_eq!(result.unwrap_err(), "pattern too complex");
+
+    assert_eq!(
+        build_search_regex("(unclosed", 1 << 20).unwrap_err(),
+        "invalid pattern"
+    );
+    assert!(build_search_regex(r"\d+", 1 << 20).is_ok());
+}
+
+#[tokio::test]
+async fn test_output_search_reports_too_complex_pattern() {
+    let mut app = create_test_app()
+        .await
+        .with_search_regex_size_limit(1 << 16);
+    app.command_history.push(CommandEntry {
+        command: "yes".to_string(),
+        output: "a".repeat(10_000),
+        success: true,
+        ..Default::default()
+    });
+
+    app.start_output_search();
+    app.output_search_mode = SearchMode::Regex;
+    for ch in "(a{50}){50}".chars() {
+        app.on_key(ch);
+    }
+
+    assert!(app.output_search_matches.is_empty());
+    assert_eq!(
+        app.output_search_error.as_deref(),
+        Some("pattern too complex")
+    );
+    assert!(
+        app.get_output_search_status()
+            .ends_with("pattern too complex")
+    );
+
+    // A simpler pattern clears the error
+    for _ in 0.."{50}".len() {
+        app.on_key_code(KeyCode::Backspace, KeyModifiers::NONE);
+    }
+    assert_eq!(app.output_search_query, "(a{50})");
+    assert!(app.output_search_error.is_none());
+    assert_eq!(app.output_search_matches.len(), 200);
+}
+
+#[tokio::test]
+async fn test_output_search_finds_text_in_input_line() {
+    let mut app = create_test_app().await;
+    app.command_history.push(CommandEntry {
+        command: "ls".to_string(),
+        output: "build.log\nsrc".to_string(),
+        success: true,
+        ..Default::default()
+    });
+    app.current_input = "tail -f build.log".to_string();
+
+    app.start_output_search();
+    for ch in "build".chars() {
+        app.on_key(ch);
+    }
+
+    // History is "> ls", two output lines and a spacer, so the input is line 4
+    assert_eq!(app.input_search_line_index(), 4);
+    assert_eq!(app.output_search_matches, vec![(1, 0, 5), (4, 8, 13)]);
+    assert!(!app.current_search_match_in_input());
+
+    app.output_search_next_match();
+    assert!(app.current_search_match_in_input());
+    assert!(
+        app.get_output_search_status()
+            .ends_with("Match 2/2 (in input)")
+    );
+}
+
+#[tokio::test]
+async fn test_output_search_whole_word_mode() {
+    let mut app = create_test_app().await;
+    app.command_history.push(CommandEntry {
+        command: "ls".to_string(),
+        output: "cat\ncategory\nthe Cat sat\nbobcat\ncat-food\nconcatenate".to_string(),
+        success: true,
+        ..Default::default()
+    });
+
+    app.start_output_search();
+    app.output_search_mode = SearchMode::WholeWord;
+    for ch in "cat".chars() {
+        app.on_key(ch);
+    }
+
+    // Lines: 0 "> ls", 1 "cat", 2 "category", 3 "the Cat sat", 4 "bobcat", 5 "cat-food"
+    assert_eq!(
+        app.output_search_matches,
+        vec![(1, 0, 3), (3, 4, 7), (5, 0, 3)]
+    );
+    assert!(app.get_output_search_status().contains("[W]"));
+
+    // The other modes find the substrings too
+    app.output_search_mode = SearchMode::CaseInsensitive;
+    app.update_output_search();
+    assert_eq!(app.output_search_matches.len(), 6);
+}
+
+/// App in a 24-row terminal showing 19 history lines, with 52 lines of history and
+/// "target" on line 21
+async fn create_scrolling_app(mode: SearchScrollMode) -> App {
+    let mut app = create_test_app().await.with_search_scroll_mode(mode);
+    let output: Vec<String> = (0..50)
+        .map(|i| {
+            if i == 20 {
+                "target".to_string()
+            } else {
+                format!("line {i}")
+            }
+        })
+        .collect();
+    app.command_history.push(CommandEntry {
+        command: "seq".to_string(),
+        output: output.join("\n"),
+        success: true,
+        ..Default::default()
+    });
+    app.update_layout_areas(24, false, 0);
+    assert_eq!(app.get_total_history_lines(), 52);
+    app
+}
+
+fn search_for_target(app: &mut App) {
+    app.start_output_search();
+    app.output_search_query = "target".to_string();
+    app.update_output_search();
+    assert_eq!(app.output_search_matches, vec![(21, 0, 6)]);
+}
+
+/// First visible line for the current scroll offset
+fn top_line(app: &App) -> usize {
+    52 - 19 - app.scroll_offset
+}
+
+#[tokio::test]
+async fn test_search_scroll_center() {
+    let mut app = create_scrolling_app(SearchScrollMode::Center).await;
+    search_for_target(&mut app);
+    assert_eq!(app.scroll_offset, 22);
+    assert_eq!(top_line(&app), 11);
+}
+
+#[tokio::test]
+async fn test_search_scroll_top() {
+    let mut app = create_scrolling_app(SearchScrollMode::Top).await;
+    search_for_target(&mut app);
+    assert_eq!(app.scroll_offset, 12);
+    assert_eq!(top_line(&app), 21);
+}
+
+#[tokio::test]
+async fn test_search_scroll_minimal() {
+    // Already visible: the view stays put
+    let mut app = create_scrolling_app(SearchScrollMode::Minimal).await;
+    app.scroll_offset = 18;
+    search_for_target(&mut app);
+    assert_eq!(app.scroll_offset, 18);
+
+    // Below the view: scrolled in at the bottom
+    app.scroll_offset = 33;
+    app.update_output_search();
+    assert_eq!(app.scroll_offset, 30);
+    assert_eq!(top_line(&app) + 18, 21);
+
+    // Above the view: scrolled in at the top
+    app.scroll_offset = 0;
+    app.update_output_search();
+    assert_eq!(app.scroll_offset, 12);
+    assert_eq!(top_line(&app), 21);
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+async fn wait_for_command_completion(app: &mut App) {
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_output_times_are_recorded_in_arrival_order() {
+    let mut app = create_test_app().await;
+
+    app.execute_command("echo first; sleep 0.2; echo second".to_string())
+        .await;
+    wait_for_command_completion(&mut app).await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(
+        entry.output.lines().collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+    assert_eq!(entry.output_times.len(), 2);
+    assert!(entry.output_times[0] <= entry.output_times[1]);
+    assert!(entry.output_times[1] - entry.output_times[0] >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_timestamps_command_toggles_display() {
+    let mut app = create_test_app().await;
+    assert!(!app.show_timestamps);
+
+    assert!(app.handle_builtin_command("/timestamps on").await);
+    assert!(app.show_timestamps);
+
+    assert!(app.handle_builtin_command("/timestamps off").await);
+    assert!(!app.show_timestamps);
+
+    assert!(app.handle_builtin_command("/timestamps sometimes").await);
+    assert!(!app.command_history.last().unwrap().success);
+}
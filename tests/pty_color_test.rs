@@ -73,11 +73,7 @@ async fn test_pty_fallback_to_pipes() {
     let mut app = App::new(db_pool.clone());
 
     // Test a simple command that should work in both PTY and pipe modes
-    let test_command = if cfg!(target_os = "windows") {
-        "echo hello world".to_string()
-    } else {
-        "echo hello world".to_string()
-    };
+    let test_command = "echo hello world".to_string();
 
     // Execute the command
     app.execute_command(test_command.clone()).await;
@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::pty_output::{LineSplitter, OutputPiece, Utf8Decoder};
+
+#[test]
+fn test_character_split_across_reads_decodes_once() {
+    // "é" is 0xC3 0xA9 and "🙂" is 0xF0 0x9F 0x99 0x82
+    let mut decoder = Utf8Decoder::new();
+    assert_eq!(decoder.decode(b"caf\xC3"), "caf");
+    assert_eq!(decoder.decode(b"\xA9 \xF0\x9F"), "é ");
+    assert_eq!(decoder.decode(b"\x99"), "");
+    assert_eq!(decoder.decode(b"\x82 done"), "🙂 done");
+    assert_eq!(decoder.finish(), "");
+}
+
+#[test]
+fn test_box_drawing_split_in_every_position() {
+    let text = "├── src ─ 日本語";
+    let bytes = text.as_bytes();
+    for split in 0..=bytes.len() {
+        let mut decoder = Utf8Decoder::new();
+        let mut decoded = decoder.decode(&bytes[..split]);
+        decoded.push_str(&decoder.decode(&bytes[split..]));
+        assert_eq!(decoded, text, "split at byte {split}");
+    }
+}
+
+#[test]
+fn test_invalid_bytes_are_replaced() {
+    let mut decoder = Utf8Decoder::new();
+    // A lone continuation byte is invalid wherever it appears
+    assert_eq!(decoder.decode(b"a\x80b"), "a\u{FFFD}b");
+    // A lead byte followed by something other than a continuation byte
+    assert_eq!(decoder.decode(b"\xE2x"), "\u{FFFD}x");
+}
+
+#[test]
+fn test_truncated_character_at_end_of_output() {
+    let mut decoder = Utf8Decoder::new();
+    assert_eq!(decoder.decode(b"end \xE2\x94"), "end ");
+    assert_eq!(decoder.finish(), "\u{FFFD}");
+    // Nothing is held back after finishing
+    assert_eq!(decoder.decode(b"x"), "x");
+}
+
+#[test]
+fn test_unfinished_line_is_sent_as_partial() {
+    let mut splitter = LineSplitter::new();
+    assert_eq!(
+        splitter.split("one\r\ntwo\r\nName? "),
+        vec![
+            OutputPiece::Line("one".to_string()),
+            OutputPiece::Line("two".to_string()),
+            OutputPiece::Partial("Name? ".to_string()),
+        ]
+    );
+    // The rest of the line ends it
+    assert_eq!(
+        splitter.split("bob\r\n"),
+        vec![OutputPiece::Line("bob".to_string())]
+    );
+    assert_eq!(splitter.split(""), vec![]);
+}
+
+#[test]
+fn test_line_ending_split_across_reads() {
+    let mut splitter = LineSplitter::new();
+    // The carriage return may start a CRLF, so it waits for the next read
+    assert_eq!(
+        splitter.split("foo\r"),
+        vec![OutputPiece::Partial("foo".to_string())]
+    );
+    assert_eq!(
+        splitter.split("\nbar"),
+        vec![
+            OutputPiece::Line(String::new()),
+            OutputPiece::Partial("bar".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_carriage_return_redraw_stays_in_line() {
+    let mut splitter = LineSplitter::new();
+    assert_eq!(
+        splitter.split(" 10%\r"),
+        vec![OutputPiece::Partial(" 10%".to_string())]
+    );
+    assert_eq!(
+        splitter.split(" 20%\r"),
+        vec![OutputPiece::Partial("\r 20%".to_string())]
+    );
+    // The held back carriage return is part of the line ending
+    assert_eq!(splitter.split("\n"), vec![OutputPiece::Line(String::new())]);
+}
+
+#[tokio::test]
+async fn test_prompt_without_newline_shows_before_rest_arrives() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.execute_command("printf foo; sleep 1; printf 'bar\\n'".to_string())
+        .await;
+
+    // "foo" shows while the command is still sleeping
+    let start = Instant::now();
+    let mut saw_partial = false;
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        let output = &app.command_history.last().unwrap().output;
+        saw_partial |= app.running_command.is_some() && output.starts_with("foo\n");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert!(saw_partial, "partial line should show before its newline");
+    assert_eq!(
+        app.command_history.last().unwrap().output.trim_end(),
+        "foobar"
+    );
+}
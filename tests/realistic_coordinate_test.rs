@@ -12,11 +12,13 @@ async fn test_realistic_coordinate_mapping() {
         command: "echo hello".to_string(),
         output: "hello".to_string(),
         success: true,
+        ..Default::default()
     });
     app.command_history.push(CommandEntry {
         command: "ls".to_string(),
         output: "file1.txt\nfile2.txt".to_string(),
         success: true,
+        ..Default::default()
     });
 
     // Set up realistic terminal size and layout
@@ -0,0 +1,116 @@
+use taskhub::config::settings::{
+    RightClickAction, SettingChange, Settings, StderrMode, format_setting_changes,
+};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool).with_settings(Settings::default())
+}
+
+#[test]
+fn test_diff_of_identical_settings_is_empty() {
+    let settings = Settings::default();
+    assert!(settings.diff(&settings.clone()).is_empty());
+}
+
+#[test]
+fn test_diff_marks_live_and_restart_settings() {
+    let old = Settings::default();
+    let mut new = old.clone();
+    new.ui.stderr = StderrMode::Merge;
+    new.history.max_entries = 50;
+    new.database_path = Some("/tmp/other.db".to_string());
+
+    assert_eq!(
+        old.diff(&new),
+        vec![
+            SettingChange {
+                key: "database_path",
+                live: false
+            },
+            SettingChange {
+                key: "history.max_entries",
+                live: false
+            },
+            SettingChange {
+                key: "ui.stderr",
+                live: true
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_format_setting_changes() {
+    assert_eq!(
+        format_setting_changes(&[]),
+        "Config reloaded: no settings changed"
+    );
+
+    let changes = [
+        SettingChange {
+            key: "history.persist",
+            live: false,
+        },
+        SettingChange {
+            key: "ui.right_click",
+            live: true,
+        },
+    ];
+    assert_eq!(
+        format_setting_changes(&changes),
+        "Config reloaded\nApplied: ui.right_click\nRequires restart: history.persist"
+    );
+}
+
+#[tokio::test]
+async fn test_apply_settings_updates_live_values() {
+    let mut app = create_test_app().await;
+    let mut new = Settings::default();
+    new.ui.stderr = StderrMode::Merge;
+    new.ui.right_click = RightClickAction::ContextMenu;
+    new.ui.input_height = 6;
+    new.tasks.page_size = 25;
+
+    let changes = app.apply_settings(new);
+
+    assert!(changes.iter().all(|change| change.live));
+    assert_eq!(app.stderr_mode, StderrMode::Merge);
+    assert_eq!(app.right_click_action, RightClickAction::ContextMenu);
+    assert_eq!(app.input_area_height, 6);
+    assert_eq!(app.task_page.size, 25);
+    assert_eq!(app.pending_task_page, Some(0));
+    assert_eq!(app.settings.ui.stderr, StderrMode::Merge);
+}
+
+#[tokio::test]
+async fn test_apply_settings_keeps_restart_only_values() {
+    let mut app = create_test_app().await;
+    let mut new = Settings::default();
+    new.history.persist = false;
+
+    let changes = app.apply_settings(new.clone());
+    assert_eq!(
+        changes,
+        vec![SettingChange {
+            key: "history.persist",
+            live: false
+        }]
+    );
+    // The running app still uses the old value, so a second reload reports it again
+    assert!(app.settings.history.persist);
+    assert_eq!(app.apply_settings(new).len(), 1);
+}
+
+#[tokio::test]
+async fn test_reload_config_command_reports_result() {
+    let mut app = create_test_app().await;
+
+    assert!(app.handle_builtin_command("/reload-config").await);
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, "/reload-config");
+    assert!(entry.output.starts_with("Config reloaded"));
+}
@@ -0,0 +1,89 @@
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
+
+/// App in a 24-row terminal, so the history area shows 19 lines, holding 50
+/// history lines
+async fn create_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(CommandEntry {
+        command: "seq 48".to_string(),
+        output: (1..=48)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        success: true,
+        ..Default::default()
+    });
+    app.handle_resize(80, 24);
+    assert_eq!(app.get_total_history_lines(), 50);
+    app
+}
+
+#[tokio::test]
+async fn test_resize_lays_out_areas_for_new_size() {
+    let mut app = create_app().await;
+    app.handle_resize(100, 14);
+    assert_eq!(app.terminal_area_width, 100);
+    assert_eq!(app.terminal_area_height, 14);
+    assert_eq!(app.history_area_height, 11);
+}
+
+#[tokio::test]
+async fn test_bottom_stays_at_bottom_after_resize() {
+    let mut app = create_app().await;
+    app.handle_resize(80, 14);
+    assert_eq!(app.scroll_offset, 0);
+    app.handle_resize(80, 40);
+    assert_eq!(app.scroll_offset, 0);
+}
+
+#[tokio::test]
+async fn test_height_reduction_keeps_top_line() {
+    let mut app = create_app().await;
+    // First visible line is 50 - 19 - 10 = 21
+    app.scroll_offset = 10;
+
+    app.handle_resize(80, 14);
+    // Nine lines are shown now, still starting at line 21
+    assert_eq!(app.scroll_offset, 50 - 9 - 21);
+}
+
+#[tokio::test]
+async fn test_height_reduction_reclamps_scroll_offset() {
+    let mut app = create_app().await;
+    // Scrolled to the top of the history, as Home does
+    app.scroll_offset = 49;
+
+    app.handle_resize(80, 14);
+    // The first line is at the top and the area is filled
+    assert_eq!(app.scroll_offset, 50 - 9);
+
+    app.handle_resize(80, 6);
+    assert_eq!(app.history_area_height, 3);
+    assert_eq!(app.scroll_offset, 50 - 1);
+}
+
+#[tokio::test]
+async fn test_height_increase_reclamps_scroll_offset() {
+    let mut app = create_app().await;
+    app.scroll_offset = 31;
+
+    // All 50 lines fit, so there is nothing to scroll
+    app.handle_resize(80, 60);
+    assert_eq!(app.scroll_offset, 0);
+}
+
+#[tokio::test]
+async fn test_resize_clears_selection() {
+    let mut app = create_app().await;
+    app.start_selection(3, 0);
+    app.update_selection(5, 2);
+    app.start_visual_selection();
+
+    app.handle_resize(80, 14);
+    assert_eq!(app.selection_start, None);
+    assert_eq!(app.selection_end, None);
+    assert!(!app.visual_selection_active);
+}
@@ -1,5 +1,19 @@
+use crossterm::event::{KeyCode, KeyModifiers};
 use taskhub::db::init_db;
 use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
+
+fn entry(command: &str, output: &str) -> CommandEntry {
+    CommandEntry {
+        command: command.to_string(),
+        output: output.to_string(),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    }
+}
 
 #[tokio::test]
 async fn test_reverse_search_functionality() {
@@ -125,3 +139,72 @@ async fn test_reverse_search_prompt_generation() {
     assert!(prompt.contains("1/1"));
     assert!(prompt.contains("test"));
 }
+
+#[tokio::test]
+async fn test_reverse_search_finds_output_only_match() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = vec!["cargo build".to_string()];
+    app.command_history = vec![
+        entry(
+            "make",
+            "gcc main.c\n\x1b[31merror: undefined reference to `foo'\x1b[0m",
+        ),
+        entry("ls", "Cargo.toml\nsrc"),
+        entry("make clean", "error: nothing to clean"),
+    ];
+
+    app.on_key_code(KeyCode::Char('r'), KeyModifiers::CONTROL);
+    for ch in "undefined".chars() {
+        app.on_key(ch);
+    }
+    // Only commands are searched at first
+    assert!(app.reverse_search_results.is_empty());
+
+    app.on_key_code(KeyCode::Tab, KeyModifiers::NONE);
+    assert!(app.reverse_search_output);
+    assert_eq!(app.reverse_search_results, vec!["make".to_string()]);
+    assert_eq!(
+        app.get_current_search_preview(),
+        Some("error: undefined reference to `foo'")
+    );
+    assert!(app.get_reverse_search_prompt().contains("in output"));
+
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.current_input, "make");
+}
+
+#[tokio::test]
+async fn test_reverse_search_output_mode_keeps_most_recent_first() {
+    let db_pool = init_db(None).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.persistent_command_history = vec!["echo error".to_string()];
+    app.command_history = vec![
+        entry("make", "error: first"),
+        entry("ls", "src"),
+        entry("make test", "error: second"),
+    ];
+
+    app.start_reverse_search();
+    app.toggle_reverse_search_output();
+    app.reverse_search_query = "error".to_string();
+    app.update_reverse_search();
+
+    assert_eq!(
+        app.reverse_search_results,
+        vec![
+            "make test".to_string(),
+            "make".to_string(),
+            "echo error".to_string()
+        ]
+    );
+    assert_eq!(app.get_current_search_preview(), Some("error: second"));
+    app.reverse_search_next();
+    app.reverse_search_next();
+    // Matched by the command itself, so there is no output line to show
+    assert_eq!(app.get_current_search_preview(), None);
+
+    // Toggling back searches commands only
+    app.toggle_reverse_search_output();
+    assert_eq!(app.reverse_search_results, vec!["echo error".to_string()]);
+}
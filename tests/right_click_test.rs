@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use taskhub::config::settings::{RightClickAction, UiConfig};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::components::context_menu::{ContextMenu, ContextMenuAction};
+
+async fn create_test_app(action: RightClickAction) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_right_click_action(action);
+    app.set_terminal_area_width(80);
+    app.update_layout_areas(24, false, 0);
+    app.selection_start = Some((1, 0));
+    app.selection_end = Some((1, 5));
+    app
+}
+
+fn click(app: &mut App, button: MouseButton, column: u16, row: u16) {
+    app.on_mouse_event(MouseEvent {
+        kind: MouseEventKind::Down(button),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    });
+}
+
+#[test]
+fn test_right_click_defaults_to_clear() {
+    assert_eq!(UiConfig::default().right_click, RightClickAction::Clear);
+}
+
+#[tokio::test]
+async fn test_right_click_clears_selection() {
+    let mut app = create_test_app(RightClickAction::Clear).await;
+
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    assert_eq!(app.selection_start, None);
+    assert_eq!(app.selection_end, None);
+    assert!(app.context_menu.is_none());
+}
+
+#[tokio::test]
+async fn test_right_click_paste_keeps_selection() {
+    let mut app = create_test_app(RightClickAction::Paste).await;
+
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    // Pasting doesn't touch the history selection or open a menu
+    assert_eq!(app.selection_start, Some((1, 0)));
+    assert!(app.context_menu.is_none());
+}
+
+#[tokio::test]
+async fn test_right_click_opens_context_menu() {
+    let mut app = create_test_app(RightClickAction::ContextMenu).await;
+
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    let menu = app.context_menu.as_ref().expect("menu should be open");
+    assert_eq!((menu.column, menu.row), (10, 5));
+    assert_eq!(menu.selected_action(), ContextMenuAction::Copy);
+    // Opening the menu keeps the selection so it can be copied
+    assert_eq!(app.selection_start, Some((1, 0)));
+}
+
+#[tokio::test]
+async fn test_context_menu_click_runs_clear() {
+    let mut app = create_test_app(RightClickAction::ContextMenu).await;
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    // Entries start one row below the top border: Copy, Paste, Clear
+    click(&mut app, MouseButton::Left, 12, 8);
+
+    assert!(app.context_menu.is_none());
+    assert_eq!(app.selection_start, None);
+    assert_eq!(app.selection_end, None);
+}
+
+#[tokio::test]
+async fn test_click_outside_context_menu_dismisses_it() {
+    let mut app = create_test_app(RightClickAction::ContextMenu).await;
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    click(&mut app, MouseButton::Left, 50, 2);
+
+    assert!(app.context_menu.is_none());
+    // The dismissing click doesn't start a new selection
+    assert_eq!(app.selection_start, Some((1, 0)));
+    assert!(!app.is_selecting);
+}
+
+#[tokio::test]
+async fn test_context_menu_keyboard_navigation() {
+    let mut app = create_test_app(RightClickAction::ContextMenu).await;
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    app.on_key_code(KeyCode::Down, KeyModifiers::NONE);
+    app.on_key_code(KeyCode::Down, KeyModifiers::NONE);
+    app.on_key_code(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(
+        app.context_menu.as_ref().unwrap().selected_action(),
+        ContextMenuAction::Clear
+    );
+
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(app.context_menu.is_none());
+    assert_eq!(app.selection_start, None);
+}
+
+#[tokio::test]
+async fn test_escape_closes_context_menu() {
+    let mut app = create_test_app(RightClickAction::ContextMenu).await;
+    click(&mut app, MouseButton::Right, 10, 5);
+
+    app.on_key_code(KeyCode::Esc, KeyModifiers::NONE);
+
+    assert!(app.context_menu.is_none());
+    assert_eq!(app.selection_start, Some((1, 0)));
+}
+
+#[test]
+fn test_context_menu_stays_inside_bounds() {
+    let menu = ContextMenu::new(78, 23, Rect::new(0, 0, 80, 24));
+    let area = menu.area();
+
+    assert!(area.x + area.width <= 80);
+    assert!(area.y + area.height <= 24);
+    assert_eq!(
+        menu.action_at(area.x + 1, area.y + 1),
+        Some(ContextMenuAction::Copy)
+    );
+    assert_eq!(menu.action_at(area.x, area.y + 1), None);
+}
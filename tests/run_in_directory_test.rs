@@ -0,0 +1,64 @@
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, resolve_directory};
+
+async fn create_test_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool)
+}
+
+async fn wait_for_command_completion(app: &mut App) {
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_in_runs_command_in_given_directory() {
+    let mut app = create_test_app().await;
+    let dir = std::env::temp_dir().canonicalize().unwrap();
+    let cwd_before = std::env::current_dir().unwrap();
+    let command = format!("/in {} pwd", dir.display());
+
+    assert!(app.handle_builtin_command(&command).await);
+    wait_for_command_completion(&mut app).await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, command);
+    assert!(entry.success);
+    assert_eq!(entry.output.trim(), dir.to_string_lossy());
+    // The session's own directory is unchanged
+    assert_eq!(std::env::current_dir().unwrap(), cwd_before);
+}
+
+#[tokio::test]
+async fn test_in_rejects_missing_directory() {
+    let mut app = create_test_app().await;
+
+    app.handle_builtin_command("/in /no/such/dir ls").await;
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(entry.output, "Not a directory: /no/such/dir");
+    assert!(app.running_command.is_none());
+
+    app.handle_builtin_command("/in /tmp").await;
+    assert_eq!(
+        app.command_history.last().unwrap().output,
+        "Usage: /in <dir> <command>"
+    );
+}
+
+#[test]
+fn test_resolve_directory_expands_home_and_relative_paths() {
+    let home = dirs::home_dir().unwrap();
+    if home.is_dir() {
+        assert_eq!(resolve_directory("~").unwrap(), home);
+    }
+
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(resolve_directory("src").unwrap(), cwd.join("src"));
+    assert_eq!(resolve_directory(".").unwrap(), cwd.join("."));
+}
@@ -14,6 +14,7 @@ async fn test_mouse_with_scrolled_content() {
             command: format!("command_{}", i),
             output: format!("output_{}_line1\noutput_{}_line2", i, i),
             success: true,
+            ..Default::default()
         });
     }
 
@@ -0,0 +1,177 @@
+use std::time::{Duration, Instant};
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, OutputLine, RunningCommand};
+use taskhub::tui::views::terminal::{CommandEntry, OutputStream};
+
+fn entry(command: &str, line_count: usize) -> CommandEntry {
+    let output: Vec<String> = (1..=line_count).map(|n| format!("line {n}")).collect();
+    CommandEntry {
+        command: command.to_string(),
+        output: output.join("\n"),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: Some(0),
+        styled_output: None,
+    }
+}
+
+async fn create_app(limit: usize) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    App::new(db_pool).with_max_scrollback_lines(limit)
+}
+
+#[tokio::test]
+async fn test_history_stays_within_limit() {
+    let mut app = create_app(50).await;
+    // Each entry takes 12 lines: the command, 10 output lines and a blank line
+    for i in 0..10 {
+        app.add_command_entry(entry(&format!("seq {i}"), 10)).await;
+        assert!(app.get_total_history_lines() <= 50);
+    }
+
+    // Trimming an entry down to its marker would gain little, so the oldest
+    // entries are dropped whole
+    let commands: Vec<&str> = app
+        .command_history
+        .iter()
+        .map(|entry| entry.command.as_str())
+        .collect();
+    assert_eq!(commands, ["seq 6", "seq 7", "seq 8", "seq 9"]);
+    assert!(
+        app.command_history
+            .iter()
+            .all(|kept| kept.output == entry("", 10).output)
+    );
+}
+
+#[tokio::test]
+async fn test_long_output_keeps_newest_lines() {
+    let mut app = create_app(50).await;
+    app.add_command_entry(entry("seq 1000", 1000)).await;
+
+    assert_eq!(app.get_total_history_lines(), 50);
+    let lines: Vec<&str> = app.command_history[0].output.lines().collect();
+    assert_eq!(lines[0], "[953 earlier lines trimmed]");
+    assert_eq!(lines[1], "line 954");
+    assert_eq!(lines.last(), Some(&"line 1000"));
+
+    // Trimming again adds to the count instead of stacking markers
+    app.add_command_entry(entry("seq 10", 10)).await;
+    assert_eq!(app.get_total_history_lines(), 50);
+    let lines: Vec<&str> = app.command_history[0].output.lines().collect();
+    assert_eq!(lines[0], "[965 earlier lines trimmed]");
+    assert_eq!(lines[1], "line 966");
+}
+
+#[tokio::test]
+async fn test_trimmed_entry_keeps_line_metadata_aligned() {
+    let mut app = create_app(10).await;
+    let mut long = entry("mixed", 20);
+    long.output_streams = (0..20)
+        .map(|n| {
+            if n % 2 == 0 {
+                OutputStream::Stdout
+            } else {
+                OutputStream::Stderr
+            }
+        })
+        .collect();
+    long.output_times = (0..20).map(Duration::from_secs).collect();
+    app.add_command_entry(long).await;
+
+    let trimmed = &app.command_history[0];
+    let lines: Vec<&str> = trimmed.output.lines().collect();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], "[13 earlier lines trimmed]");
+    assert_eq!(lines[1], "line 14");
+    assert_eq!(trimmed.output_streams.len(), lines.len());
+    assert_eq!(trimmed.output_times.len(), lines.len());
+    assert_eq!(trimmed.output_streams[0], OutputStream::Stdout);
+    // "line 14" was the 14th line, written to stderr at 13s
+    assert_eq!(trimmed.output_streams[1], OutputStream::Stderr);
+    assert_eq!(trimmed.output_times[1], Duration::from_secs(13));
+}
+
+#[tokio::test]
+async fn test_running_command_output_is_trimmed() {
+    let mut app = create_app(100).await;
+    app.execute_command("seq 1 500".to_string()).await;
+
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        assert!(app.get_total_history_lines() <= 100);
+        if let Some(running) = &app.running_command {
+            assert!(running.output_order.len() <= 100);
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let output = &app.command_history.last().unwrap().output;
+    assert!(output.starts_with('['), "{output}");
+    assert!(output.contains(" earlier lines trimmed]"));
+    assert_eq!(output.lines().last().map(str::trim), Some("500"));
+    assert!(app.get_total_history_lines() <= 100);
+}
+
+#[tokio::test]
+async fn test_running_output_buffer_is_capped() {
+    let mut app = create_app(10).await;
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    app.command_history.push(entry("build", 0));
+    app.running_command = Some(RunningCommand {
+        command: "build".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: Some(receiver),
+        uses_alternate_screen: false,
+        live_ansi_parser: None,
+        pty_writer: None,
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    });
+
+    for n in 1..=20 {
+        let line = format!("line {n}");
+        if n % 4 == 0 {
+            sender.send(OutputLine::Stderr(line)).unwrap();
+        } else {
+            sender.send(OutputLine::Stdout(line)).unwrap();
+        }
+    }
+    app.check_running_command().await;
+
+    let running = app.running_command.as_ref().unwrap();
+    assert_eq!(running.output_order.len(), 10);
+    assert_eq!(running.stdout_buffer[0], "[11 earlier lines trimmed]");
+    assert_eq!(running.stdout_buffer[1], "line 13");
+    assert_eq!(running.stderr_buffer, ["line 12", "line 16", "line 20"]);
+    assert_eq!(running.stdout_times.len(), running.stdout_buffer.len());
+    assert_eq!(running.stderr_times.len(), running.stderr_buffer.len());
+
+    // Later drops add to the marker already there
+    for n in 21..=25 {
+        sender
+            .send(OutputLine::Stdout(format!("line {n}")))
+            .unwrap();
+    }
+    app.check_running_command().await;
+
+    let running = app.running_command.as_ref().unwrap();
+    assert_eq!(running.output_order.len(), 10);
+    assert_eq!(running.stdout_buffer[0], "[16 earlier lines trimmed]");
+    assert!(app.get_total_history_lines() <= 10);
+    let shown = &app.command_history.last().unwrap().output;
+    assert!(shown.starts_with('['), "{shown}");
+    assert!(shown.ends_with("Running..."), "{shown}");
+}
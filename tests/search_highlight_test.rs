@@ -0,0 +1,99 @@
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::{CommandEntry, TerminalDisplayState, draw_terminal};
+
+async fn create_app_with_output(output: &str) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.command_history.push(CommandEntry {
+        command: "make".to_string(),
+        output: output.to_string(),
+        success: false,
+        ..Default::default()
+    });
+    app
+}
+
+fn search(app: &mut App, query: &str) {
+    app.start_output_search();
+    for ch in query.chars() {
+        app.on_key(ch);
+    }
+}
+
+fn render(app: &App) -> Buffer {
+    let backend = TestBackend::new(60, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let theme = Theme::default();
+
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState {
+                output_search_active: true,
+                output_search_query: &app.output_search_query,
+                output_search_matches: &app.output_search_matches,
+                output_search_current_match: app.output_search_current_match,
+                ..TerminalDisplayState::new(&app.command_history, &theme)
+            };
+            draw_terminal(f, f.area(), &state);
+        })
+        .unwrap();
+
+    terminal.backend().buffer().clone()
+}
+
+/// Position of the first cell of `text` in the rendered buffer
+fn find(buffer: &Buffer, text: &str) -> (u16, u16) {
+    for y in 0..buffer.area.height {
+        let row: String = (0..buffer.area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect();
+        if let Some(byte) = row.find(text) {
+            return (row[..byte].chars().count() as u16, y);
+        }
+    }
+    panic!("'{text}' not rendered");
+}
+
+#[tokio::test]
+async fn test_match_columns_ignore_escape_sequences() {
+    let mut app = create_app_with_output("\x1b[1;31merror\x1b[0m: disk full").await;
+
+    search(&mut app, "disk");
+
+    // "error: " is 7 visible characters; the escapes add none
+    assert_eq!(app.output_search_matches, vec![(1, 7, 11)]);
+}
+
+#[tokio::test]
+async fn test_highlight_keeps_colors_outside_match() {
+    let mut app = create_app_with_output("\x1b[31merror\x1b[0m: \x1b[32mdisk\x1b[0m full").await;
+    search(&mut app, "disk");
+
+    let buffer = render(&app);
+
+    // No raw escape text leaks into the rendered line
+    let (x, y) = find(&buffer, "error: disk full");
+    for i in 0..5 {
+        assert_eq!(buffer[(x + i, y)].fg, Color::Red);
+    }
+    for i in 7..11 {
+        assert_eq!(buffer[(x + i, y)].bg, Color::Yellow, "column {i}");
+    }
+    assert_ne!(buffer[(x + 6, y)].bg, Color::Yellow);
+    assert_ne!(buffer[(x + 12, y)].bg, Color::Yellow);
+}
+
+#[tokio::test]
+async fn test_case_insensitive_match_after_multibyte_text() {
+    let mut app = create_app_with_output("Größe: \x1b[33mWARN\x1b[0m").await;
+
+    search(&mut app, "warn");
+
+    assert_eq!(app.output_search_matches, vec![(1, 7, 11)]);
+}
@@ -0,0 +1,104 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::views::terminal::CommandEntry;
+
+/// App with 40 content lines in a 24-row terminal: the history area has
+/// 21 rows, showing 19 lines that start at line 21 when not scrolled.
+async fn create_scrollable_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    for i in 0..20 {
+        app.command_history.push(CommandEntry {
+            command: format!("cmd_{i}"),
+            output: String::new(),
+            success: true,
+            ..Default::default()
+        });
+    }
+    app.update_layout_areas(24, false, 0);
+    app
+}
+
+fn mouse(app: &mut App, kind: MouseEventKind, row: u16, column: u16) {
+    app.on_mouse_event(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    });
+}
+
+#[tokio::test]
+async fn test_anchor_survives_scroll_before_drag() {
+    let mut app = create_scrollable_app().await;
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 5, 3);
+    assert_eq!(app.selection_start, Some((25, 2)));
+
+    // Scroll three lines up with the wheel, then keep dragging
+    mouse(&mut app, MouseEventKind::ScrollUp, 5, 3);
+    assert_eq!(app.scroll_offset, 3);
+    assert_eq!(app.selection_start, Some((25, 2)));
+
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 5, 3);
+    assert_eq!(app.selection_start, Some((25, 2)));
+    assert_eq!(app.selection_end, Some((22, 2)));
+}
+
+#[tokio::test]
+async fn test_scroll_during_drag_remaps_selection_end() {
+    let mut app = create_scrollable_app().await;
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 5, 3);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 10, 3);
+    assert_eq!(app.selection_end, Some((30, 2)));
+
+    // The mouse hasn't moved, so the end follows the content now under it
+    mouse(&mut app, MouseEventKind::ScrollUp, 10, 3);
+    assert_eq!(app.selection_start, Some((25, 2)));
+    assert_eq!(app.selection_end, Some((27, 2)));
+
+    mouse(&mut app, MouseEventKind::ScrollDown, 10, 3);
+    assert_eq!(app.selection_end, Some((30, 2)));
+}
+
+#[tokio::test]
+async fn test_drag_past_top_edge_auto_scrolls() {
+    let mut app = create_scrollable_app().await;
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 5, 3);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 0, 3);
+    assert_eq!(app.scroll_offset, 1);
+    assert_eq!(app.selection_end, Some((20, 2)));
+
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 0, 3);
+    assert_eq!(app.scroll_offset, 2);
+    assert_eq!(app.selection_start, Some((25, 2)));
+    assert_eq!(app.selection_end, Some((19, 2)));
+}
+
+#[tokio::test]
+async fn test_drag_past_bottom_edge_auto_scrolls() {
+    let mut app = create_scrollable_app().await;
+    app.scroll_offset = 5;
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 5, 3);
+    assert_eq!(app.selection_start, Some((20, 2)));
+
+    // Dragging into the input area scrolls newer content into view
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 22, 3);
+    assert_eq!(app.scroll_offset, 4);
+    assert_eq!(app.selection_end, Some((35, 2)));
+    assert!(app.is_selecting);
+}
+
+#[tokio::test]
+async fn test_drag_at_bottom_without_scrollback_stays_on_last_line() {
+    let mut app = create_scrollable_app().await;
+
+    mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 5, 3);
+    mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), 23, 3);
+    assert_eq!(app.scroll_offset, 0);
+    assert_eq!(app.selection_end, Some((39, 2)));
+}
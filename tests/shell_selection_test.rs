@@ -0,0 +1,72 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use std::time::Duration;
+use taskhub::config::settings::Settings;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::shell::Shell;
+
+#[test]
+fn test_configured_shell_is_invoked() {
+    let shell = Shell::from_setting(Some("/bin/zsh"));
+    assert_eq!(shell.program(), "/bin/zsh");
+
+    let pty = shell.pty_command("echo hi", Some(Path::new("/tmp")));
+    assert_eq!(pty.get_argv(), &["/bin/zsh", "-c", "echo hi"]);
+    assert_eq!(
+        pty.get_cwd().map(|cwd| cwd.as_os_str()),
+        Some(OsStr::new("/tmp"))
+    );
+
+    let pipe = shell.pipe_command("echo hi", None);
+    let pipe = pipe.as_std();
+    assert_eq!(pipe.get_program(), "/bin/zsh");
+    assert_eq!(pipe.get_args().collect::<Vec<_>>(), ["-c", "echo hi"]);
+}
+
+#[test]
+fn test_command_flag_per_shell() {
+    assert_eq!(Shell::new("bash").command_flag(), "-c");
+    assert_eq!(Shell::new("/usr/bin/fish").command_flag(), "-c");
+    assert_eq!(Shell::new("pwsh").command_flag(), "-Command");
+    assert_eq!(Shell::new("powershell.exe").command_flag(), "-Command");
+    assert_eq!(Shell::new("cmd.exe").command_flag(), "/C");
+    assert_eq!(Shell::new("CMD").command_flag(), "/C");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_default_shell_comes_from_environment() {
+    // A blank setting counts as unset
+    let expected = std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "sh".to_string());
+    assert_eq!(Shell::from_setting(None).program(), expected);
+    assert_eq!(Shell::from_setting(Some("  ")).program(), expected);
+}
+
+#[test]
+fn test_parse_shell_setting() {
+    assert_eq!(Settings::from_toml("").unwrap().shell, None);
+    let settings = Settings::from_toml("shell = \"bash\"").unwrap();
+    assert_eq!(settings.shell.as_deref(), Some("bash"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_commands_run_in_configured_shell() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_shell(Shell::new("/bin/sh"));
+
+    // $0 names the shell running the command
+    app.execute_command("echo \"$0\"".to_string()).await;
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.output.trim(), "/bin/sh");
+}
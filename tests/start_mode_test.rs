@@ -0,0 +1,42 @@
+use taskhub::config::settings::{Settings, StartMode};
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, AppMode};
+
+#[test]
+fn test_parse_start_mode() {
+    assert_eq!(
+        Settings::from_toml("").unwrap().ui.start_mode,
+        StartMode::Terminal
+    );
+
+    let settings = Settings::from_toml("[ui]\nstart_mode = \"task_list\"").unwrap();
+    assert_eq!(settings.ui.start_mode, StartMode::TaskList);
+    assert!(Settings::from_toml("[ui]\nstart_mode = \"agenda_view\"").is_err());
+}
+
+#[tokio::test]
+async fn test_configured_start_mode_is_applied() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let app = App::new(db_pool.clone());
+    assert_eq!(app.mode, AppMode::Terminal);
+
+    let settings = Settings::from_toml("[ui]\nstart_mode = \"task_list\"").unwrap();
+    let mut app = App::new(db_pool).with_start_mode(settings.ui.start_mode);
+    assert_eq!(app.mode, AppMode::TaskList);
+
+    // The task list loads and leaves as usual when it is the first view
+    app.load_tasks().await.unwrap();
+    assert!(app.tasks.is_empty());
+    app.on_key('q');
+    assert_eq!(app.mode, AppMode::Terminal);
+}
+
+#[test]
+fn test_start_mode_change_needs_restart() {
+    let before = Settings::from_toml("").unwrap();
+    let after = Settings::from_toml("[ui]\nstart_mode = \"task_list\"").unwrap();
+    let changes = before.diff(&after);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].key, "ui.start_mode");
+    assert!(!changes[0].live);
+}
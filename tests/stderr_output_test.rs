@@ -0,0 +1,114 @@
+use std::time::Instant;
+use taskhub::config::settings::StderrMode;
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, OutputLine, RunningCommand};
+use taskhub::tui::views::terminal::{CommandEntry, OutputStream};
+use tokio::sync::mpsc;
+
+/// Run a fake command that writes a mixed stdout/stderr sequence and return its entry
+async fn run_mixed_output(mode: StderrMode) -> CommandEntry {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_stderr_mode(mode);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    sender
+        .send(OutputLine::Stdout("compiling".to_string()))
+        .unwrap();
+    sender
+        .send(OutputLine::Stderr("warning: unused".to_string()))
+        .unwrap();
+    sender.send(OutputLine::Stdout("done".to_string())).unwrap();
+
+    app.command_history.push(CommandEntry {
+        command: "build".to_string(),
+        output: "Running...".to_string(),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    });
+    app.running_command = Some(RunningCommand {
+        command: "build".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: Some(receiver),
+        uses_alternate_screen: false,
+        live_ansi_parser: None,
+        pty_writer: None,
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    });
+
+    app.check_running_command().await;
+
+    app.command_history.last().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_split_mode_groups_stderr_after_stdout() {
+    let entry = run_mixed_output(StderrMode::Split).await;
+
+    assert_eq!(entry.output, "compiling\ndone\nwarning: unused\nRunning...");
+    assert_eq!(
+        entry.output_streams,
+        vec![
+            OutputStream::Stdout,
+            OutputStream::Stdout,
+            OutputStream::Stderr
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_merge_mode_interleaves_stderr() {
+    let entry = run_mixed_output(StderrMode::Merge).await;
+
+    assert_eq!(entry.output, "compiling\nwarning: unused\ndone\nRunning...");
+    assert_eq!(
+        entry.output_streams,
+        vec![
+            OutputStream::Stdout,
+            OutputStream::Stderr,
+            OutputStream::Stdout
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_stderr_command_switches_mode() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    assert_eq!(app.stderr_mode, StderrMode::Split);
+
+    assert!(app.handle_builtin_command("/stderr merge").await);
+    assert_eq!(app.stderr_mode, StderrMode::Merge);
+    assert!(app.command_history.last().unwrap().success);
+
+    assert!(app.handle_builtin_command("/stderr split").await);
+    assert_eq!(app.stderr_mode, StderrMode::Split);
+}
+
+#[tokio::test]
+async fn test_stderr_command_reports_current_mode_and_rejects_unknown() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    assert!(app.handle_builtin_command("/stderr").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(entry.output.contains("Stderr mode: split"));
+
+    assert!(app.handle_builtin_command("/stderr sideways").await);
+    let entry = app.command_history.last().unwrap();
+    assert!(!entry.success);
+    assert_eq!(app.stderr_mode, StderrMode::Split);
+}
@@ -0,0 +1,129 @@
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::text::{Line, Span};
+use std::time::Instant;
+use taskhub::db::init_db;
+use taskhub::tui::app::{App, OutputLine, RunningCommand};
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::{CommandEntry, TerminalDisplayState, draw_terminal};
+use tokio::sync::mpsc;
+
+/// Stream `lines` from a fake command and return its history entry
+async fn streamed_entry(lines: &[&str]) -> CommandEntry {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    for line in lines {
+        sender.send(OutputLine::Stdout(line.to_string())).unwrap();
+    }
+
+    app.command_history.push(CommandEntry {
+        command: "bat".to_string(),
+        output: "Running...".to_string(),
+        success: true,
+        output_streams: Vec::new(),
+        output_times: Vec::new(),
+        exit_code: None,
+        styled_output: None,
+    });
+    app.running_command = Some(RunningCommand {
+        command: "bat".to_string(),
+        child: None,
+        pty_child: None,
+        stdout_buffer: Vec::new(),
+        stderr_buffer: Vec::new(),
+        output_order: Vec::new(),
+        output_changed: false,
+        output_receiver: Some(receiver),
+        uses_alternate_screen: false,
+        live_ansi_parser: None,
+        pty_writer: None,
+        stdin: None,
+        started_at: Instant::now(),
+        last_output_at: Instant::now(),
+        stdout_times: Vec::new(),
+        stderr_times: Vec::new(),
+        stderr_rows: Vec::new(),
+        stdout_line_open: false,
+    });
+
+    app.check_running_command().await;
+
+    app.command_history.last().unwrap().clone()
+}
+
+fn render_history(history: &[CommandEntry]) -> Buffer {
+    let backend = TestBackend::new(60, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let theme = Theme::default();
+
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState::new(history, &theme);
+            draw_terminal(f, f.area(), &state);
+        })
+        .unwrap();
+
+    terminal.backend().buffer().clone()
+}
+
+#[tokio::test]
+async fn test_rgb_color_survives_emulated_output() {
+    // Clearing the screen sends the output through the terminal emulator
+    let entry =
+        streamed_entry(&["\x1b[2J\x1b[H\x1b[38;2;12;34;56mtrue\x1b[48;2;1;2;3mcolor\x1b[0m"]).await;
+
+    // The text holds only what is shown, for search and copying
+    assert_eq!(entry.output, "truecolor\nRunning...");
+
+    let styled = entry
+        .styled_output
+        .expect("output should keep its styled lines");
+    assert_eq!(styled.len(), 1);
+    assert_eq!(styled[0].spans[0].content, "true");
+    assert_eq!(styled[0].spans[0].style.fg, Some(Color::Rgb(12, 34, 56)));
+    assert_eq!(styled[0].spans[1].content, "color");
+    assert_eq!(styled[0].spans[1].style.fg, Some(Color::Rgb(12, 34, 56)));
+    assert_eq!(styled[0].spans[1].style.bg, Some(Color::Rgb(1, 2, 3)));
+}
+
+#[tokio::test]
+async fn test_plain_color_output_is_kept_as_text() {
+    let entry = streamed_entry(&["\x1b[38;2;12;34;56mtruecolor\x1b[0m"]).await;
+    assert_eq!(
+        entry.output,
+        "\x1b[38;2;12;34;56mtruecolor\x1b[0m\nRunning..."
+    );
+    assert!(entry.styled_output.is_none());
+}
+
+#[test]
+fn test_styled_lines_are_rendered_directly() {
+    let history = vec![CommandEntry {
+        command: "bat".to_string(),
+        output: "truecolor\nRunning...".to_string(),
+        success: true,
+        styled_output: Some(vec![Line::from(vec![
+            Span::styled(
+                "true",
+                ratatui::style::Style::default().fg(Color::Rgb(12, 34, 56)),
+            ),
+            Span::raw("color"),
+        ])]),
+        ..Default::default()
+    }];
+
+    let buffer = render_history(&history);
+
+    // Row 0 is the border, row 1 the command line, row 2 the output line.
+    // Column 0 is the left border.
+    assert_eq!(buffer[(1, 2)].symbol(), "t");
+    assert_eq!(buffer[(1, 2)].fg, Color::Rgb(12, 34, 56));
+    assert_eq!(buffer[(5, 2)].symbol(), "c");
+    assert_eq!(buffer[(5, 2)].fg, Theme::default().foreground);
+    // Lines past the styled ones are rendered from the text
+    assert_eq!(buffer[(1, 3)].symbol(), "R");
+}
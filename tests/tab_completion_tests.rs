@@ -1,6 +1,5 @@
-use std::collections::HashMap;
 use taskhub::db::init_db;
-use taskhub::db::models::{Priority, Task, TaskSource, TaskStatus};
+use taskhub::db::models::Task;
 use taskhub::tui::app::App;
 use taskhub::tui::completion::{Completion, CompletionEngine, CompletionState, CompletionType};
 use uuid::Uuid;
@@ -13,19 +12,9 @@ async fn create_test_app() -> App {
 
 fn create_test_task(title: &str) -> Task {
     Task {
-        id: Uuid::new_v4(),
-        external_id: None,
-        source: TaskSource::Markdown,
-        title: title.to_string(),
-        description: None,
-        status: TaskStatus::Open,
-        priority: Priority::Medium,
-        assignee: None,
-        labels: Vec::new(),
-        due_date: None,
         created_at: "2025-01-01T00:00:00Z".to_string(),
         updated_at: "2025-01-01T00:00:00Z".to_string(),
-        custom_fields: HashMap::new(),
+        ..Task::new(title)
     }
 }
 
@@ -663,3 +652,240 @@ mod subcommand_completion_tests {
         assert!(npm_subs.iter().any(|c| c.text == "run"));
     }
 }
+
+#[cfg(test)]
+mod file_path_completion_tests {
+    use super::*;
+
+    /// Temp directory holding two directories and two files with interleaved names
+    fn create_listing_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("taskhub_completion_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("beta")).unwrap();
+        std::fs::create_dir_all(dir.join("delta")).unwrap();
+        std::fs::write(dir.join("alpha.txt"), "").unwrap();
+        std::fs::write(dir.join("gamma.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_empty_word_lists_directories_first() {
+        let dir = create_listing_dir();
+        let engine = CompletionEngine::new(Vec::new());
+        let input = format!("ls {}/", dir.display());
+
+        let completions = engine.get_completions(&input, input.len(), &[]);
+        let texts: Vec<&str> = completions.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["beta/", "delta/", "alpha.txt", "gamma.txt"]);
+
+        let labels: Vec<&str> = completions
+            .iter()
+            .map(|c| c.display_text.as_deref().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["beta/", "delta/", "alpha.txt", "gamma.txt"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_empty_argument_lists_current_directory() {
+        let engine = CompletionEngine::new(Vec::new());
+
+        // Tests run from the crate root, which has both directories and files
+        let completions = engine.get_completions("ls ", 3, &[]);
+        let texts: Vec<&str> = completions.iter().map(|c| c.text.as_str()).collect();
+
+        assert!(texts.contains(&"src/"));
+        assert!(texts.contains(&"Cargo.toml"));
+        let first_file = texts.iter().position(|t| !t.ends_with('/')).unwrap();
+        assert!(texts[first_file..].iter().all(|t| !t.ends_with('/')));
+    }
+
+    #[test]
+    fn test_directory_name_completes_with_slash() {
+        let engine = CompletionEngine::new(Vec::new());
+
+        let completions = engine.get_completions("cat src", 7, &[]);
+        let texts: Vec<&str> = completions.iter().map(|c| c.text.as_str()).collect();
+
+        assert_eq!(texts, vec!["/"]);
+    }
+
+    #[tokio::test]
+    async fn test_tab_on_empty_argument_opens_candidate_list() {
+        let dir = create_listing_dir();
+        let mut app = create_test_app().await;
+        app.current_input = format!("ls {}/", dir.display());
+        app.cursor_position = app.current_input.chars().count();
+
+        app.handle_tab_completion();
+
+        assert_eq!(
+            app.completion_candidates(),
+            vec!["beta/", "delta/", "alpha.txt", "gamma.txt"]
+        );
+        assert_eq!(app.completion_state.applied_index(), 0);
+        assert!(app.current_input.ends_with("/beta/"));
+        assert_eq!(app.command_list_size(), 6);
+
+        app.handle_tab_completion();
+        assert_eq!(app.completion_state.applied_index(), 1);
+        assert!(app.current_input.ends_with("/delta/"));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod nested_path_completion_tests {
+    use super::*;
+
+    fn completion_texts(engine: &CompletionEngine, input: &str) -> Vec<String> {
+        engine
+            .get_completions(input, input.len(), &[])
+            .into_iter()
+            .map(|c| c.text)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tab_completes_through_to_files_under_directory() {
+        let mut app = create_test_app().await;
+        app.current_input = "cat src/tu".to_string();
+        app.cursor_position = app.current_input.len();
+
+        app.handle_tab_completion();
+        assert_eq!(app.current_input, "cat src/tui/");
+
+        // The lone directory match descends, so the next Tab lists its contents
+        app.handle_tab_completion();
+        let candidates = app.completion_candidates();
+        assert!(candidates.contains(&"completion.rs".to_string()));
+        assert!(candidates.contains(&"views/".to_string()));
+        assert!(app.current_input.starts_with("cat src/tui/"));
+    }
+
+    #[test]
+    fn test_multi_segment_prefix_completion() {
+        let dir = std::env::temp_dir().join(format!("taskhub_completion_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src/tui/components")).unwrap();
+        std::fs::write(dir.join("src/tui/completion.rs"), "").unwrap();
+        std::fs::write(dir.join("src/tui/app.rs"), "").unwrap();
+        let engine = CompletionEngine::new(Vec::new());
+        let root = dir.display();
+
+        assert_eq!(
+            completion_texts(&engine, &format!("cat {root}/src/tui/compl")),
+            vec!["etion.rs"]
+        );
+        assert_eq!(
+            completion_texts(&engine, &format!("cat {root}/s/tu/compl")),
+            vec![format!("{root}/src/tui/completion.rs")]
+        );
+
+        let texts = completion_texts(&engine, &format!("cat {root}/sr/t/co"));
+        assert_eq!(
+            texts,
+            vec![
+                format!("{root}/src/tui/components/"),
+                format!("{root}/src/tui/completion.rs")
+            ]
+        );
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_abbreviated_completion_replaces_word() {
+        let mut state = CompletionState::new();
+        let engine = CompletionEngine::new(Vec::new());
+        let input = "cat s/tu/compl";
+        let completions = engine.get_completions(input, input.len(), &[]);
+
+        state.start(input, completions, 4);
+
+        assert_eq!(
+            state.cycle_next(),
+            Some("cat src/tui/completion.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_glob_completion() {
+        let engine = CompletionEngine::new(Vec::new());
+
+        let texts = completion_texts(&engine, "ls src/**/comp*.rs");
+        assert_eq!(texts, vec!["src/tui/completion.rs"]);
+
+        let texts = completion_texts(&engine, "ls src/tui/*");
+        assert!(texts.contains(&"src/tui/views/".to_string()));
+        assert!(texts.contains(&"src/tui/app.rs".to_string()));
+        let first_file = texts.iter().position(|t| !t.ends_with('/')).unwrap();
+        assert!(texts[first_file..].iter().all(|t| !t.ends_with('/')));
+
+        let texts = completion_texts(&engine, "ls src/db/?odels.rs");
+        assert_eq!(texts, vec!["src/db/models.rs"]);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod executable_completion_tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_only_executable_files_are_offered() {
+        let dir = std::env::temp_dir().join(format!("taskhub_path_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let runnable = dir.join("runnable");
+        std::fs::write(&runnable, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&runnable, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let plain = dir.join("plain");
+        std::fs::write(&plain, "not a program").unwrap();
+        std::fs::set_permissions(&plain, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let commands = CompletionEngine::commands_in_path(dir.to_str().unwrap());
+        assert_eq!(commands, vec!["runnable"]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod completion_cache_tests {
+    use super::*;
+
+    #[test]
+    fn test_changing_directory_invalidates_caches() {
+        let engine = CompletionEngine::new(Vec::new());
+        let cwd = std::env::current_dir().unwrap();
+
+        engine.get_path_commands();
+        assert!(engine.has_cached_results());
+
+        // Same directory keeps the cache
+        assert!(!engine.update_working_directory(&cwd));
+        assert!(engine.has_cached_results());
+
+        // A different directory drops it
+        assert!(engine.update_working_directory(&std::env::temp_dir()));
+        assert!(!engine.has_cached_results());
+
+        // Results computed afterwards are cached for the new directory
+        engine.get_path_commands();
+        assert!(!engine.update_working_directory(&std::env::temp_dir()));
+        assert!(engine.has_cached_results());
+    }
+
+    #[test]
+    fn test_completions_refresh_cache_directory() {
+        let engine = CompletionEngine::new(Vec::new());
+        engine.update_working_directory(&std::env::temp_dir());
+        engine.get_path_commands();
+
+        // Completing from the real working directory invalidates the stale entries
+        engine.get_completions("/he", 3, &[]);
+        assert!(!engine.has_cached_results());
+    }
+}
@@ -0,0 +1,133 @@
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use taskhub::db::models::{Task, TaskStatus};
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::task_list::{
+    SubtaskProgress, TaskPage, draw_task_list, order_by_parent, subtask_progress,
+};
+use taskhub::tui::views::terminal::TerminalDisplayState;
+
+fn render_header(page: TaskPage) -> String {
+    let backend = TestBackend::new(120, 12);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let theme = Theme::default();
+
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState::new(&[], &theme);
+            draw_task_list(f, f.area(), &[], page, None, None, &state);
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.width)
+        .map(|x| buffer[(x, 0)].symbol())
+        .collect()
+}
+
+#[test]
+fn test_header_shows_total_and_page_count() {
+    let header = render_header(TaskPage {
+        index: 1,
+        size: 100,
+        total: 250,
+    });
+
+    assert!(
+        header.contains("Tasks (250 total) - page 2 of 3"),
+        "{header}"
+    );
+}
+
+#[test]
+fn test_header_with_no_tasks_shows_single_page() {
+    let header = render_header(TaskPage::default());
+
+    assert!(header.contains("Tasks (0 total) - page 1 of 1"), "{header}");
+}
+
+fn task(title: &str, parent: Option<&Task>) -> Task {
+    Task {
+        parent_id: parent.map(|parent| parent.id),
+        created_at: "2025-01-01 00:00:00".to_string(),
+        updated_at: "2025-01-01 00:00:00".to_string(),
+        ..Task::new(title)
+    }
+}
+
+fn ordered_titles(tasks: &[Task]) -> Vec<(String, usize)> {
+    order_by_parent(tasks)
+        .into_iter()
+        .map(|(task, depth)| (task.title.clone(), depth))
+        .collect()
+}
+
+#[test]
+fn test_subtasks_follow_their_parent() {
+    let release = task("Release", None);
+    let docs = task("Docs", None);
+    let tag = task("Tag", Some(&release));
+    let notes = task("Notes", Some(&tag));
+    let build = task("Build", Some(&release));
+    // Listed newest first, so children come before their parent
+    let tasks = vec![build, notes, docs, tag, release];
+
+    assert_eq!(
+        ordered_titles(&tasks),
+        vec![
+            ("Docs".to_string(), 0),
+            ("Release".to_string(), 0),
+            ("Build".to_string(), 1),
+            ("Tag".to_string(), 1),
+            ("Notes".to_string(), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_subtask_of_task_on_other_page_is_top_level() {
+    let parent = task("Elsewhere", None);
+    let tasks = vec![task("Orphan", Some(&parent))];
+
+    assert_eq!(ordered_titles(&tasks), vec![("Orphan".to_string(), 0)]);
+}
+
+#[test]
+fn test_parent_cycle_still_lists_every_task() {
+    let mut a = task("A", None);
+    let b = task("B", Some(&a));
+    a.parent_id = Some(b.id);
+    let tasks = vec![a, b];
+
+    assert_eq!(ordered_titles(&tasks).len(), 2);
+}
+
+#[test]
+fn test_subtask_progress_counts_done_children() {
+    let release = task("Release", None);
+    let docs = task("Docs", None);
+    let mut build = task("Build", Some(&release));
+    build.status = TaskStatus::Done;
+    let mut tag = task("Tag", Some(&release));
+    tag.status = TaskStatus::InProgress;
+    let announce = task("Announce", Some(&release));
+    let tasks = vec![release, docs, build, tag, announce];
+
+    let progress = subtask_progress(&tasks);
+
+    let release_progress = progress[&tasks[0].id];
+    assert_eq!(release_progress, SubtaskProgress { done: 1, total: 3 });
+    assert_eq!(release_progress.percent(), 33);
+    assert_eq!(release_progress.render(), "■□□□□ 1/3");
+    // Tasks without subtasks get no progress
+    assert!(!progress.contains_key(&tasks[1].id));
+    assert!(!progress.contains_key(&tasks[2].id));
+}
+
+#[test]
+fn test_subtask_progress_percent_bounds() {
+    assert_eq!(SubtaskProgress { done: 0, total: 4 }.percent(), 0);
+    assert_eq!(SubtaskProgress { done: 4, total: 4 }.percent(), 100);
+    assert_eq!(SubtaskProgress { done: 4, total: 4 }.render(), "■■■■■ 4/4");
+    assert_eq!(SubtaskProgress { done: 0, total: 0 }.percent(), 0);
+}
@@ -0,0 +1,124 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::db::models::{Priority, Task};
+use taskhub::db::operations;
+use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::views::task_list::order_by_parent;
+use uuid::Uuid;
+
+fn task(title: &str, priority: Priority) -> Task {
+    Task {
+        priority,
+        created_at: "2025-01-01 00:00:00".to_string(),
+        updated_at: "2025-01-01 00:00:00".to_string(),
+        ..Task::new(title)
+    }
+}
+
+/// App showing the task list with `tasks` stored and loaded
+async fn app_with_tasks(tasks: &[Task]) -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    for task in tasks {
+        operations::create_task(&db_pool, task).await.unwrap();
+    }
+    let mut app = App::new(db_pool);
+    app.mode = AppMode::TaskList;
+    app.load_tasks().await.unwrap();
+    app
+}
+
+async fn press(app: &mut App, key: char) {
+    app.on_key(key);
+    app.handle_pending_commands().await;
+}
+
+async fn stored_priority(app: &App, id: Uuid) -> Priority {
+    operations::get_task(&app.db_pool, id)
+        .await
+        .unwrap()
+        .priority
+}
+
+#[test]
+fn test_priority_steps_stop_at_the_ends() {
+    assert_eq!(Priority::Low.raised(), Priority::Medium);
+    assert_eq!(Priority::Medium.raised(), Priority::High);
+    assert_eq!(Priority::High.raised(), Priority::High);
+
+    assert_eq!(Priority::High.lowered(), Priority::Medium);
+    assert_eq!(Priority::Medium.lowered(), Priority::Low);
+    assert_eq!(Priority::Low.lowered(), Priority::Low);
+}
+
+#[tokio::test]
+async fn test_plus_raises_priority_up_to_high() {
+    let low = task("Triage me", Priority::Low);
+    let id = low.id;
+    let mut app = app_with_tasks(&[low]).await;
+
+    press(&mut app, '+').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::Medium);
+    press(&mut app, '+').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::High);
+
+    // Cannot go above High
+    press(&mut app, '+').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::High);
+    assert_eq!(app.tasks[0].priority, Priority::High);
+    assert!(app.current_input.is_empty());
+}
+
+#[tokio::test]
+async fn test_minus_lowers_priority_down_to_low() {
+    let high = task("Urgent", Priority::High);
+    let id = high.id;
+    let mut app = app_with_tasks(&[high]).await;
+
+    press(&mut app, '-').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::Medium);
+    press(&mut app, '-').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::Low);
+
+    // Cannot go below Low
+    press(&mut app, '-').await;
+    assert_eq!(stored_priority(&app, id).await, Priority::Low);
+}
+
+#[tokio::test]
+async fn test_arrows_choose_the_task_to_change() {
+    let mut app = app_with_tasks(&[
+        task("First", Priority::Medium),
+        task("Second", Priority::Medium),
+    ])
+    .await;
+    let displayed: Vec<Uuid> = order_by_parent(&app.tasks)
+        .into_iter()
+        .map(|(task, _)| task.id)
+        .collect();
+
+    assert_eq!(app.selected_task(), Some(0));
+    app.on_key_code(KeyCode::Down, KeyModifiers::empty());
+    assert_eq!(app.selected_task(), Some(1));
+    // The selection stops at the last task
+    app.on_key_code(KeyCode::Down, KeyModifiers::empty());
+    assert_eq!(app.selected_task(), Some(1));
+
+    press(&mut app, '+').await;
+    assert_eq!(stored_priority(&app, displayed[1]).await, Priority::High);
+    assert_eq!(stored_priority(&app, displayed[0]).await, Priority::Medium);
+
+    app.on_key_code(KeyCode::Up, KeyModifiers::empty());
+    assert_eq!(app.selected_task(), Some(0));
+}
+
+#[tokio::test]
+async fn test_plus_is_typed_while_input_is_not_empty() {
+    let medium = task("Keep", Priority::Medium);
+    let id = medium.id;
+    let mut app = app_with_tasks(&[medium]).await;
+
+    press(&mut app, 'x').await;
+    press(&mut app, '+').await;
+    assert_eq!(app.current_input, "x+");
+    assert_eq!(stored_priority(&app, id).await, Priority::Medium);
+}
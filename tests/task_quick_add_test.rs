@@ -0,0 +1,117 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use taskhub::db::init_db;
+use taskhub::db::operations;
+use taskhub::tui::app::{App, AppMode};
+use taskhub::tui::components::quick_add::QuickAdd;
+
+/// App showing an empty task list
+async fn create_app() -> App {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+    app.mode = AppMode::TaskList;
+    app.load_tasks().await.unwrap();
+    app
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for ch in text.chars() {
+        app.on_key(ch);
+    }
+}
+
+fn press(app: &mut App, key_code: KeyCode) {
+    app.on_key_code(key_code, KeyModifiers::empty());
+}
+
+#[tokio::test]
+async fn test_a_opens_quick_add_in_task_list() {
+    let mut app = create_app().await;
+    app.on_key('a');
+    assert_eq!(app.quick_add, Some(QuickAdd::default()));
+    // The key opens the input rather than being typed at the prompt
+    assert!(app.current_input.is_empty());
+}
+
+#[tokio::test]
+async fn test_a_is_typed_when_not_in_task_list() {
+    let mut app = create_app().await;
+    app.mode = AppMode::Terminal;
+    app.on_key('a');
+    assert_eq!(app.quick_add, None);
+    assert_eq!(app.current_input, "a");
+
+    // Or while something is typed at the task list prompt
+    let mut app = create_app().await;
+    type_text(&mut app, "ba");
+    assert_eq!(app.quick_add, None);
+    assert_eq!(app.current_input, "ba");
+}
+
+#[tokio::test]
+async fn test_quick_add_captures_typed_keys() {
+    let mut app = create_app().await;
+    app.on_key('a');
+    // q and t switch back to the terminal outside the input
+    type_text(&mut app, "quit tea");
+    press(&mut app, KeyCode::Backspace);
+
+    assert_eq!(app.mode, AppMode::TaskList);
+    assert_eq!(app.quick_add.as_ref().unwrap().title, "quit te");
+    assert!(app.current_input.is_empty());
+}
+
+#[tokio::test]
+async fn test_enter_creates_task() {
+    let mut app = create_app().await;
+    app.on_key('a');
+    type_text(&mut app, "  Write release notes ");
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.quick_add, None);
+
+    app.handle_pending_commands().await;
+    assert_eq!(app.tasks.len(), 1);
+    assert_eq!(app.tasks[0].title, "Write release notes");
+
+    let stored = operations::get_task(&app.db_pool, app.tasks[0].id)
+        .await
+        .expect("task should be stored");
+    assert_eq!(stored.title, "Write release notes");
+}
+
+#[tokio::test]
+async fn test_esc_cancels_quick_add() {
+    let mut app = create_app().await;
+    app.on_key('a');
+    type_text(&mut app, "Never mind");
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.quick_add, None);
+    assert!(app.pending_task_add.is_none());
+
+    app.handle_pending_commands().await;
+    assert!(app.tasks.is_empty());
+}
+
+#[tokio::test]
+async fn test_empty_title_is_rejected() {
+    let mut app = create_app().await;
+    app.on_key('a');
+    press(&mut app, KeyCode::Enter);
+    type_text(&mut app, "   ");
+    press(&mut app, KeyCode::Enter);
+
+    // The input stays open and says why
+    let quick_add = app.quick_add.as_ref().expect("input should stay open");
+    assert_eq!(
+        quick_add.error.as_deref(),
+        Some("Task title cannot be empty")
+    );
+    assert!(app.pending_task_add.is_none());
+
+    // Typing again clears the error
+    app.on_key('x');
+    assert_eq!(app.quick_add.as_ref().unwrap().error, None);
+
+    press(&mut app, KeyCode::Esc);
+    app.handle_pending_commands().await;
+    assert!(app.tasks.is_empty());
+}
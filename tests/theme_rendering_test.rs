@@ -0,0 +1,168 @@
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use taskhub::tui::theme::Theme;
+use taskhub::tui::views::terminal::{
+    CommandEntry, OutputStream, TerminalDisplayState, draw_terminal,
+};
+
+fn render_history(history: &[CommandEntry], theme: &Theme) -> Buffer {
+    let backend = TestBackend::new(60, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal
+        .draw(|f| {
+            let state = TerminalDisplayState::new(history, theme);
+            draw_terminal(f, f.area(), &state);
+        })
+        .unwrap();
+
+    terminal.backend().buffer().clone()
+}
+
+#[test]
+fn test_default_foreground_uses_theme_color() {
+    let theme = Theme {
+        foreground: Color::Rgb(200, 200, 180),
+        ..Theme::default()
+    };
+    let history = vec![CommandEntry {
+        command: "ls".to_string(),
+        output: "\x1b[31mred\x1b[39m plain".to_string(),
+        success: true,
+        ..Default::default()
+    }];
+
+    let buffer = render_history(&history, &theme);
+
+    // Row 0 is the border, row 1 the command line, row 2 the output line.
+    // Column 0 is the left border.
+    assert_eq!(buffer[(1, 2)].symbol(), "r");
+    assert_eq!(buffer[(1, 2)].fg, Color::Red);
+
+    // Text after SGR 39 renders with the theme's foreground, not the terminal default
+    assert_eq!(buffer[(5, 2)].symbol(), "p");
+    assert_eq!(buffer[(5, 2)].fg, Color::Rgb(200, 200, 180));
+}
+
+#[test]
+fn test_plain_output_uses_theme_color() {
+    let theme = Theme {
+        foreground: Color::Rgb(10, 20, 30),
+        ..Theme::default()
+    };
+    let history = vec![CommandEntry {
+        command: "echo hi".to_string(),
+        output: "hi".to_string(),
+        success: true,
+        ..Default::default()
+    }];
+
+    let buffer = render_history(&history, &theme);
+
+    assert_eq!(buffer[(1, 2)].symbol(), "h");
+    assert_eq!(buffer[(1, 2)].fg, Color::Rgb(10, 20, 30));
+}
+
+#[test]
+fn test_stderr_lines_use_stderr_style() {
+    let theme = Theme {
+        foreground: Color::Rgb(200, 200, 180),
+        stderr: Color::Rgb(180, 60, 60),
+        ..Theme::default()
+    };
+    let history = vec![CommandEntry {
+        command: "make".to_string(),
+        output: "building\nerror: failed".to_string(),
+        success: false,
+        output_streams: vec![OutputStream::Stdout, OutputStream::Stderr],
+        ..Default::default()
+    }];
+
+    let buffer = render_history(&history, &theme);
+
+    assert_eq!(buffer[(1, 2)].symbol(), "b");
+    assert_eq!(buffer[(1, 2)].fg, Color::Rgb(200, 200, 180));
+    assert!(!buffer[(1, 2)].modifier.contains(Modifier::DIM));
+
+    assert_eq!(buffer[(1, 3)].symbol(), "e");
+    assert_eq!(buffer[(1, 3)].fg, Color::Rgb(180, 60, 60));
+    assert!(buffer[(1, 3)].modifier.contains(Modifier::DIM));
+}
+
+/// Color of the `>` prompt in the input box at the bottom of the screen
+fn prompt_color(buffer: &Buffer) -> Color {
+    let area = buffer.area;
+    (area.height - 3..area.height)
+        .find_map(|row| (buffer[(1, row)].symbol() == ">").then(|| buffer[(1, row)].fg))
+        .expect("prompt not found")
+}
+
+#[test]
+fn test_prompt_color_follows_last_command_status() {
+    let theme = Theme {
+        success: Color::Rgb(0, 180, 0),
+        failure: Color::Rgb(180, 0, 0),
+        ..Theme::default()
+    };
+    let entry = |command: &str, success| CommandEntry {
+        command: command.to_string(),
+        output: String::new(),
+        success,
+        ..Default::default()
+    };
+
+    // Nothing has failed yet
+    assert_eq!(
+        prompt_color(&render_history(&[], &theme)),
+        Color::Rgb(0, 180, 0)
+    );
+
+    let history = vec![entry("true", true), entry("false", false)];
+    let buffer = render_history(&history, &theme);
+    assert_eq!(prompt_color(&buffer), Color::Rgb(180, 0, 0));
+    // Command lines in the history use the same colors
+    assert_eq!(buffer[(3, 1)].symbol(), "t");
+    assert_eq!(buffer[(3, 1)].fg, Color::Rgb(0, 180, 0));
+
+    let history = vec![entry("false", false), entry("true", true)];
+    assert_eq!(
+        prompt_color(&render_history(&history, &theme)),
+        Color::Rgb(0, 180, 0)
+    );
+}
+
+#[test]
+fn test_nonzero_exit_code_shown_after_command() {
+    let theme = Theme {
+        failure: Color::Rgb(180, 0, 0),
+        ..Theme::default()
+    };
+    let entry = |command: &str, success, exit_code| CommandEntry {
+        command: command.to_string(),
+        output: String::new(),
+        success,
+        exit_code,
+        ..Default::default()
+    };
+    let history = vec![entry("gti", false, Some(127)), entry("true", true, Some(0))];
+
+    let buffer = render_history(&history, &theme);
+    // Each entry is a command line followed by a spacer
+    assert_eq!(row_text(&buffer, 1).trim_end(), "> gti [exit 127]");
+    assert_eq!(buffer[(7, 1)].fg, Color::Rgb(180, 0, 0));
+    assert!(buffer[(7, 1)].modifier.contains(Modifier::DIM));
+    assert_eq!(row_text(&buffer, 3).trim_end(), "> true");
+
+    // Killed by a signal, so there is no code to show
+    let buffer = render_history(&[entry("sleep 9", false, None)], &theme);
+    assert_eq!(row_text(&buffer, 1).trim_end(), "> sleep 9");
+}
+
+/// Text of a row inside the history border
+fn row_text(buffer: &Buffer, y: u16) -> String {
+    (1..buffer.area.width - 1)
+        .map(|x| buffer[(x, y)].symbol())
+        .collect()
+}
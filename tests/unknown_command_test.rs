@@ -0,0 +1,150 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::time::Duration;
+use taskhub::db::init_db;
+use taskhub::tui::app::App;
+use taskhub::tui::completion::{closest_command, command_program, edit_distance, is_known_program};
+use taskhub::tui::components::confirmation::Confirmation;
+
+const MISSING: &str = "taskhub-no-such-program";
+
+fn commands(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("git", "git"), 0);
+    assert_eq!(edit_distance("gti", "git"), 1);
+    assert_eq!(edit_distance("gi", "git"), 1);
+    assert_eq!(edit_distance("gitt", "git"), 1);
+    assert_eq!(edit_distance("car", "cat"), 1);
+    assert_eq!(edit_distance("pyhton3", "python3"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "ls"), 2);
+}
+
+#[test]
+fn test_closest_command_suggests_nearest_match() {
+    let path = commands(&["cat", "git", "gitk", "grep", "ls", "python3"]);
+
+    assert_eq!(closest_command("gti", &path), Some("git"));
+    assert_eq!(closest_command("gitt", &path), Some("git"));
+    assert_eq!(closest_command("grpe", &path), Some("grep"));
+    assert_eq!(closest_command("pyhton3", &path), Some("python3"));
+    // Nothing within two edits
+    assert_eq!(closest_command("kubectl", &path), None);
+    // A one-letter name is never a typo of another command
+    assert_eq!(closest_command("l", &path), None);
+}
+
+#[test]
+fn test_command_program() {
+    assert_eq!(command_program("gti status"), Some("gti"));
+    assert_eq!(command_program("  ls -la"), Some("ls"));
+    assert_eq!(command_program("RUST_LOG=debug cargo run"), Some("cargo"));
+    assert_eq!(command_program("./build.sh --release"), Some("./build.sh"));
+    assert_eq!(command_program("(cd src && ls)"), None);
+    assert_eq!(command_program("FOO=1"), None);
+    assert_eq!(command_program(""), None);
+}
+
+#[test]
+fn test_not_found_detection() {
+    let path = commands(&["git", "ls"]);
+
+    assert!(is_known_program("git", &path));
+    assert!(!is_known_program("gti", &path));
+    // Builtins and keywords are never on PATH
+    assert!(is_known_program("cd", &path));
+    assert!(is_known_program("export", &path));
+    assert!(is_known_program("for", &path));
+    // Paths are checked on disk
+    assert!(!is_known_program("Cargo.toml", &path));
+    assert!(is_known_program("./Cargo.toml", &path));
+    assert!(!is_known_program("./no-such-script.sh", &path));
+}
+
+async fn finish_running_command(app: &mut App) {
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_command_waits_for_confirmation() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_confirm_unknown_commands(true);
+
+    assert_eq!(
+        app.unknown_program(&format!("{MISSING} --flag")).as_deref(),
+        Some(MISSING)
+    );
+    assert_eq!(app.unknown_program("echo hi"), None);
+
+    app.pending_command = Some(format!("{MISSING} --flag"));
+    app.handle_pending_commands().await;
+
+    assert!(app.running_command.is_none());
+    assert_eq!(
+        app.confirmation.as_ref().and_then(Confirmation::command),
+        Some(&*format!("{MISSING} --flag"))
+    );
+    let entry = app.command_history.last().unwrap();
+    assert!(
+        entry
+            .output
+            .starts_with(&format!("Command '{MISSING}' not found"))
+    );
+    assert!(entry.output.ends_with("run anyway? [y/N]"));
+
+    // Other keys are ignored while waiting for an answer
+    app.on_key('x');
+    assert!(app.confirmation.is_some());
+    assert_eq!(app.current_input, "");
+
+    // Declining puts the command back in the input to fix
+    app.on_key_code(KeyCode::Esc, KeyModifiers::NONE);
+    assert!(app.confirmation.is_none());
+    assert_eq!(app.current_input, format!("{MISSING} --flag"));
+    app.handle_pending_commands().await;
+    assert!(app.running_command.is_none());
+
+    // Confirming runs it
+    app.current_input.clear();
+    app.pending_command = Some(format!("{MISSING} --flag"));
+    app.handle_pending_commands().await;
+    app.on_key('y');
+    app.handle_pending_commands().await;
+    finish_running_command(&mut app).await;
+
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, format!("{MISSING} --flag"));
+    assert!(!entry.success);
+    assert!(!entry.output.contains("run anyway"));
+}
+
+#[tokio::test]
+async fn test_known_commands_run_without_confirmation() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool).with_confirm_unknown_commands(true);
+
+    app.pending_command = Some("echo ok".to_string());
+    app.handle_pending_commands().await;
+    assert!(app.confirmation.is_none());
+    finish_running_command(&mut app).await;
+    assert_eq!(app.command_history.last().unwrap().output.trim_end(), "ok");
+}
+
+#[tokio::test]
+async fn test_unknown_commands_run_directly_when_disabled() {
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.pending_command = Some(MISSING.to_string());
+    app.handle_pending_commands().await;
+    assert!(app.confirmation.is_none());
+    finish_running_command(&mut app).await;
+    assert!(!app.command_history.last().unwrap().success);
+}
@@ -50,3 +50,27 @@ async fn test_whitespace_preservation_simple() {
         "Output should contain red color code"
     );
 }
+
+#[tokio::test]
+async fn test_command_with_leading_space_executes() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let db_pool = init_db(Some(":memory:".into())).await.unwrap();
+    let mut app = App::new(db_pool);
+
+    app.current_input = " printf 'leading space\\n'".to_string();
+    app.on_key_code(KeyCode::Enter, KeyModifiers::NONE);
+    app.handle_pending_commands().await;
+
+    let start = std::time::Instant::now();
+    while app.running_command.is_some() && start.elapsed() < Duration::from_secs(5) {
+        app.check_running_command().await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Recorded as typed, and run rather than rejected
+    let entry = app.command_history.last().unwrap();
+    assert_eq!(entry.command, " printf 'leading space\\n'");
+    assert!(entry.success);
+    assert_eq!(entry.output.trim_end(), "leading space");
+}